@@ -0,0 +1,20 @@
+mod place_a_bet;
+use place_a_bet::place_a_bet;
+
+use crate::fixtures;
+
+/// run bet and ticket testing options
+pub async fn run(argument: Option<&str>) -> anyhow::Result<()> {
+    let pool = fixtures::pool().await?;
+    match argument {
+        Some(arg) => match arg {
+            "place-bet" => {
+                place_a_bet(&pool).await?;
+            }
+            _ => anyhow::bail!("Wrong argument specified"),
+        },
+        None => anyhow::bail!("NO ARGUMENT SPECIFIED"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+    },
+    db_models::{bet::CreateBet, bet_market::BetMarket, game_match_event::GameMatchEventType},
+};
+
+use crate::fixtures::{create_game_with_teams, create_match, create_user, open_ticket, unique_run};
+
+/// Bets placed on a ticket at the same time are counted one after another,
+/// so the ticket never holds more bets than allowed
+pub async fn place_a_bet(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let matches = PgMatchRepo::new(pool);
+    let tickets = PgBetAndTicketRepo::new(pool);
+
+    let run = unique_run();
+    let (game_id, team_ids) = create_game_with_teams(pool, "Racing", run, &["one", "two"]).await?;
+    let (one, two) = (team_ids[0], team_ids[1]);
+    let mut match_ids = Vec::new();
+    for _ in 0..2 {
+        // bets can only be placed on live matches
+        let match_id = create_match(pool, game_id, one, two).await?;
+        matches
+            .create_event(match_id, GameMatchEventType::Live)
+            .await?;
+        match_ids.push(match_id);
+    }
+
+    let racer = create_user(pool, "Racer", run).await?;
+    let ticket = open_ticket(pool, racer).await?;
+    let (first, second) = tokio::join!(
+        tickets.place_a_bet(
+            ticket.id,
+            CreateBet::new(match_ids[0], ticket.id, one, BetMarket::MatchWinner),
+            1,
+        ),
+        tickets.place_a_bet(
+            ticket.id,
+            CreateBet::new(match_ids[1], ticket.id, two, BetMarket::MatchWinner),
+            1,
+        ),
+    );
+    assert!(first.is_ok() != second.is_ok());
+    assert_eq!(tickets.get_bets(ticket.id).await?.len(), 1);
+    println!("ticket limited to its number of bets");
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+mod add;
+mod get_match_followers;
+mod remove;
+use add::add;
+use get_match_followers::get_match_followers;
+use remove::remove;
+
+use crate::fixtures;
+
+/// run favourite testing options
+pub async fn run(argument: Option<&str>) -> anyhow::Result<()> {
+    let pool = fixtures::pool().await?;
+    match argument {
+        Some(arg) => match arg {
+            "add" => {
+                add(&pool).await?;
+            }
+            "followers" => {
+                get_match_followers(&pool).await?;
+            }
+            "remove" => {
+                remove(&pool).await?;
+            }
+            _ => anyhow::bail!("Wrong argument specified"),
+        },
+        None => anyhow::bail!("NO ARGUMENT SPECIFIED"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        favourite::{FavouriteRepo, PgFavouriteRepo},
+        repo::Repo,
+    },
+    db_models::user_favourite::Favourite,
+    error::DomainError,
+};
+
+use crate::fixtures::{create_game_with_teams, create_match, create_user, unique_run};
+
+/// A user follows a team, a game and a match, each of them only once
+pub async fn add(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let favourites = PgFavouriteRepo::new(pool);
+
+    let run = unique_run();
+    let user_id = create_user(pool, "Follower", run).await?;
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Followed", run, &["one", "two"]).await?;
+    let match_id = create_match(pool, game_id, team_ids[0], team_ids[1]).await?;
+
+    favourites
+        .add(user_id, Favourite::Team(team_ids[0]))
+        .await?;
+    let err = favourites
+        .add(user_id, Favourite::Team(team_ids[0]))
+        .await
+        .unwrap_err();
+    assert!(matches!(DomainError::of(&err), DomainError::Conflict(_)));
+    favourites.add(user_id, Favourite::Game(game_id)).await?;
+    favourites.add(user_id, Favourite::Match(match_id)).await?;
+    assert_eq!(favourites.get_all(user_id).await?.len(), 3);
+    println!("team, game and match followed once");
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        favourite::{FavouriteRepo, PgFavouriteRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+        team::{PgTeamRepo, TeamRepo},
+    },
+    db_models::user_favourite::Favourite,
+};
+
+use crate::fixtures::{create_game_with_teams, create_match, create_user, unique_run};
+
+/// A user becomes a follower of the matches covered by their favourites,
+/// which are the ones kept by the listing of the favourite matches
+pub async fn get_match_followers(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let teams = PgTeamRepo::new(pool);
+    let matches = PgMatchRepo::new(pool);
+    let favourites = PgFavouriteRepo::new(pool);
+
+    let run = unique_run();
+    let user_id = create_user(pool, "Follower", run).await?;
+    let (followed_game, team_ids) =
+        create_game_with_teams(pool, "Followers", run, &["followed", "other", "third"]).await?;
+    let (followed_team, other, third) = (team_ids[0], team_ids[1], team_ids[2]);
+    let (other_game, _) = create_game_with_teams(pool, "Other followers", run, &[]).await?;
+    for team_id in team_ids.iter() {
+        teams.add_to_game(*team_id, other_game).await?;
+    }
+
+    let of_team = create_match(pool, other_game, followed_team, other).await?;
+    let of_game = create_match(pool, followed_game, other, third).await?;
+    let followed_match = create_match(pool, other_game, third, other).await?;
+    let unfollowed = create_match(pool, other_game, other, third).await?;
+    favourites
+        .add(user_id, Favourite::Team(followed_team))
+        .await?;
+    favourites
+        .add(user_id, Favourite::Game(followed_game))
+        .await?;
+    favourites
+        .add(user_id, Favourite::Match(followed_match))
+        .await?;
+
+    for match_id in [of_team, of_game, followed_match] {
+        assert!(favourites
+            .get_match_followers(match_id)
+            .await?
+            .contains(&user_id));
+    }
+    assert!(!favourites
+        .get_match_followers(unfollowed)
+        .await?
+        .contains(&user_id));
+    println!("followers of the matches covered by the favourites found");
+
+    // the same filter as the one of the listing of the favourite matches
+    let user_favourites = favourites.get_all(user_id).await?;
+    let mut listed: Vec<i32> = Vec::new();
+    for match_id in [of_team, of_game, followed_match, unfollowed] {
+        let game_match = matches.get(match_id).await?;
+        if user_favourites
+            .iter()
+            .any(|favourite| favourite.covers(&game_match))
+        {
+            listed.push(match_id);
+        }
+    }
+    assert_eq!(listed, [of_team, of_game, followed_match]);
+    println!("listing of the favourites kept only the followed matches");
+
+    Ok(())
+}
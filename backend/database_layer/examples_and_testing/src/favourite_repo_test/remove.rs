@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        favourite::{FavouriteRepo, PgFavouriteRepo},
+        repo::Repo,
+    },
+    db_models::user_favourite::Favourite,
+    error::DomainError,
+};
+
+use crate::fixtures::{create_game_with_teams, create_match, create_user, unique_run};
+
+/// An unfollowed team no longer makes the user a follower of its matches,
+/// and it can be followed again
+pub async fn remove(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let favourites = PgFavouriteRepo::new(pool);
+
+    let run = unique_run();
+    let user_id = create_user(pool, "Unfollower", run).await?;
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Unfollowed", run, &["one", "two"]).await?;
+    let followed_team = team_ids[0];
+    let match_id = create_match(pool, game_id, followed_team, team_ids[1]).await?;
+
+    favourites
+        .add(user_id, Favourite::Team(followed_team))
+        .await?;
+    favourites
+        .remove(user_id, Favourite::Team(followed_team))
+        .await?;
+    assert!(!favourites
+        .get_match_followers(match_id)
+        .await?
+        .contains(&user_id));
+    let err = favourites
+        .remove(user_id, Favourite::Team(followed_team))
+        .await
+        .unwrap_err();
+    assert!(matches!(DomainError::of(&err), DomainError::NotFound(_)));
+    favourites
+        .add(user_id, Favourite::Team(followed_team))
+        .await?;
+    println!("team unfollowed and followed again");
+
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use chrono::{Duration, Utc};
+use dotenv::dotenv;
+use std::env;
+use std::sync::Arc;
+
+use database_layer::{
+    connection::*,
+    db_access::{
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        game::{GameRepo, PgGameRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+        team::{PgTeamRepo, TeamRepo},
+        user::{PgUserRepo, UserRepo},
+    },
+    db_models::{
+        game::CreateGame,
+        game_match::CreateGameMatch,
+        team::CreateTeam,
+        ticket::{ObtainedTicket, Ticket},
+        user::CreateUser,
+        user_address::CreateUserAddress,
+    },
+};
+
+/// The number of bets on a ticket allowed by the default betting limits of the server
+pub const MAX_SELECTIONS: usize = 20;
+
+/// Create the pool of database connections from the `DATABASE_URL`
+pub async fn pool() -> anyhow::Result<Arc<PgPool>> {
+    // for development purposes only, using dotenv to retrieve the connection string
+    dotenv().ok();
+    let database_url = env::var("DATABASE_URL")?;
+    Ok(Arc::new(db_connect_create_pool(&database_url).await?))
+}
+
+/// Suffix of the names, emails and civil IDs created by a check,
+/// unique so the checks can be run repeatedly against the same database
+pub fn unique_run() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+/// Create the game `<name> game <run>` along with the teams `<name> <team> <run>` playing it
+///
+/// Returns
+/// ---
+/// - ID of the game and the IDs of the teams, in the order of their names
+pub async fn create_game_with_teams(
+    pool: &Arc<PgPool>,
+    name: &str,
+    run: i64,
+    team_names: &[&str],
+) -> anyhow::Result<(i32, Vec<i32>)> {
+    let games = PgGameRepo::new(pool);
+    let teams = PgTeamRepo::new(pool);
+
+    let game_id = games
+        .create(CreateGame::new(&format!("{} game {}", name, run), "", ""))
+        .await?;
+    let mut team_ids = Vec::new();
+    for team_name in team_names {
+        let team_id = teams
+            .create(CreateTeam::new(
+                &format!("{} {} {}", name, team_name, run),
+                "",
+                "",
+            ))
+            .await?;
+        teams.add_to_game(team_id, game_id).await?;
+        team_ids.push(team_id);
+    }
+    Ok((game_id, team_ids))
+}
+
+/// Create a match of the two teams starting in an hour
+pub async fn create_match(
+    pool: &Arc<PgPool>,
+    game_id: i32,
+    team_one_id: i32,
+    team_two_id: i32,
+) -> anyhow::Result<i32> {
+    PgMatchRepo::new(pool)
+        .create(CreateGameMatch::new(
+            game_id,
+            team_one_id,
+            team_two_id,
+            "1.8",
+            "2.1",
+            Utc::now() + Duration::hours(1),
+            "repository check",
+        ))
+        .await
+}
+
+/// Create a user named `<name>` with a unique email address and civil ID
+pub async fn create_user(pool: &Arc<PgPool>, name: &str, run: i64) -> anyhow::Result<i32> {
+    let (user_id, _) = PgUserRepo::new(pool)
+        .create(
+            CreateUser::new(
+                name,
+                "Check",
+                "-",
+                &format!("{}{}", name, run),
+                "2000-01-01",
+                &format!("{}.{}@repository.check", name.to_lowercase(), run),
+                "0900000000",
+                None,
+            ),
+            CreateUserAddress::new("Street", "1", "City", None, "00000", "Slovakia"),
+        )
+        .await?;
+    Ok(user_id)
+}
+
+/// Get the open ticket of the user, creating it if necessary
+pub async fn open_ticket(pool: &Arc<PgPool>, user_id: i32) -> anyhow::Result<Ticket> {
+    Ok(
+        match PgBetAndTicketRepo::new(pool)
+            .get_user_current_ticket(user_id)
+            .await?
+        {
+            ObtainedTicket::NoTicketFound(ticket)
+            | ObtainedTicket::NewAfterInvalid(ticket)
+            | ObtainedTicket::StillValid(ticket) => ticket,
+        },
+    )
+}
@@ -0,0 +1,30 @@
+mod delete;
+mod edit;
+mod set_archived;
+use delete::delete;
+use edit::edit;
+use set_archived::set_archived;
+
+use crate::fixtures;
+
+/// run game testing options
+pub async fn run(argument: Option<&str>) -> anyhow::Result<()> {
+    let pool = fixtures::pool().await?;
+    match argument {
+        Some(arg) => match arg {
+            "edit" => {
+                edit(&pool).await?;
+            }
+            "archive" => {
+                set_archived(&pool).await?;
+            }
+            "delete" => {
+                delete(&pool).await?;
+            }
+            _ => anyhow::bail!("Wrong argument specified"),
+        },
+        None => anyhow::bail!("NO ARGUMENT SPECIFIED"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game::{GameRepo, PgGameRepo},
+        repo::Repo,
+    },
+    db_models::game::CreateGame,
+};
+
+use crate::fixtures::{create_game_with_teams, create_match, unique_run};
+
+/// A game referenced by a match is kept, an unused one is deleted
+pub async fn delete(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let games = PgGameRepo::new(pool);
+
+    let run = unique_run();
+    let (game_id, team_ids) = create_game_with_teams(pool, "Deleted", run, &["one", "two"]).await?;
+    create_match(pool, game_id, team_ids[0], team_ids[1]).await?;
+    assert!(games.delete(game_id).await.is_err());
+    assert!(games.get(game_id).await.is_ok());
+    println!("game with a match kept");
+
+    let unused_game = games
+        .create(CreateGame::new(&format!("Unused game {}", run), "", ""))
+        .await?;
+    games.delete(unused_game).await?;
+    assert!(games.get(unused_game).await.is_err());
+    assert!(games.set_archived(unused_game, true).await.is_err());
+    println!("unused game deleted");
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game::{GameRepo, PgGameRepo},
+        repo::Repo,
+    },
+    db_models::game::CreateGame,
+};
+
+use crate::fixtures::unique_run;
+
+/// An edit of a game which leaves out the expected duration keeps the previous one
+pub async fn edit(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let games = PgGameRepo::new(pool);
+
+    let name = format!("Edited game {}", unique_run());
+    let game_id = games.create(CreateGame::new(&name, "", "")).await?;
+
+    let mut edited_game = CreateGame::new(&name, "Edited", "");
+    edited_game.expected_duration_minutes = Some(45);
+    games.edit(game_id, edited_game).await?;
+    games
+        .edit(game_id, CreateGame::new(&name, "Edited again", ""))
+        .await?;
+    let game = games.get(game_id).await?;
+    assert_eq!(
+        (game.description.as_str(), game.expected_duration_minutes),
+        ("Edited again", 45)
+    );
+    println!("game edited, the unset duration kept");
+
+    Ok(())
+}
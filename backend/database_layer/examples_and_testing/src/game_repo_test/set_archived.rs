@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game::{GameRepo, PgGameRepo},
+        repo::Repo,
+        team::{PgTeamRepo, TeamRepo},
+    },
+    db_models::team::CreateTeam,
+};
+
+use crate::fixtures::{create_game_with_teams, create_match, unique_run};
+
+/// An archived game is left out of the listing and gets no new matches or teams
+pub async fn set_archived(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let games = PgGameRepo::new(pool);
+    let teams = PgTeamRepo::new(pool);
+
+    let run = unique_run();
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Archived", run, &["one", "two"]).await?;
+    let (one, two) = (team_ids[0], team_ids[1]);
+
+    games.set_archived(game_id, true).await?;
+    assert!(games.get(game_id).await?.archived);
+    assert!(!games
+        .get_all(false)
+        .await?
+        .iter()
+        .any(|game| game.id == game_id));
+    assert!(games
+        .get_all(true)
+        .await?
+        .iter()
+        .any(|game| game.id == game_id));
+    assert!(create_match(pool, game_id, one, two).await.is_err());
+    let newcomer = teams
+        .create(CreateTeam::new(
+            &format!("Archived newcomer {}", run),
+            "",
+            "",
+        ))
+        .await?;
+    assert!(teams.add_to_game(newcomer, game_id).await.is_err());
+    println!("archived game left out of the listing and new matches");
+
+    games.set_archived(game_id, false).await?;
+    create_match(pool, game_id, one, two).await?;
+    teams.add_to_game(newcomer, game_id).await?;
+    println!("game unarchived");
+
+    Ok(())
+}
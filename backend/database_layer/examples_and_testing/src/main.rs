@@ -2,9 +2,18 @@
 
 use clap::{App, Arg};
 
+mod bet_and_ticket_repo_test;
+mod favourite_repo_test;
+mod fixtures;
 mod game_match_repo_test;
+mod game_repo_test;
+mod outbox_test;
+mod player_repo_test;
 mod seed;
 mod team_plays_game_test;
+mod team_repo_test;
+mod ticket_share_repo_test;
+mod timeline_test;
 mod user_repo_test;
 
 use seed::seed;
@@ -47,6 +56,62 @@ async fn main() -> anyhow::Result<()> {
                 .value_name("game_match_value")
                 .help("Usage: --game_match create / get / get all"),
         )
+        .arg(
+            Arg::new("outbox")
+                .long("outbox")
+                .takes_value(false)
+                .help("Store an event in the outbox and wait for its notification")
+                .required(false),
+        )
+        .arg(
+            Arg::new("timeline")
+                .long("timeline")
+                .takes_value(false)
+                .help("Record in-play events of a new match")
+                .required(false),
+        )
+        .arg(
+            Arg::new("game")
+                .long("game")
+                .takes_value(true)
+                .value_name("game_value")
+                .help("Usage: --game edit / archive / delete"),
+        )
+        .arg(
+            Arg::new("team")
+                .long("team")
+                .takes_value(true)
+                .value_name("team_value")
+                .help("Usage: --team archive / remove-from-game / delete"),
+        )
+        .arg(
+            Arg::new("favourite")
+                .long("favourite")
+                .takes_value(true)
+                .value_name("favourite_value")
+                .help("Usage: --favourite add / followers / remove"),
+        )
+        .arg(
+            Arg::new("player")
+                .long("player")
+                .takes_value(true)
+                .value_name("player_value")
+                .help("Usage: --player create / join-team / leave-team / roster / delete"),
+        )
+        .arg(
+            Arg::new("share")
+                .long("share")
+                .takes_value(true)
+                .value_name("share_value")
+                .help("Usage: --share share / selections / copy"),
+        )
+        .arg(
+            Arg::new("ticket")
+                .long("ticket")
+                .takes_value(true)
+                .value_name("ticket_value")
+                .help("Usage: --ticket place-bet"),
+        )
         .get_matches();
 
     if testing_app.is_present("seed") {
@@ -59,6 +124,22 @@ async fn main() -> anyhow::Result<()> {
         team_plays_game_test::run(testing_app.value_of("team_plays_game")).await?;
     } else if testing_app.is_present("game_match") {
         game_match_repo_test::run(testing_app.value_of("game_match")).await?;
+    } else if testing_app.is_present("outbox") {
+        outbox_test::run().await?;
+    } else if testing_app.is_present("timeline") {
+        timeline_test::run().await?;
+    } else if testing_app.is_present("game") {
+        game_repo_test::run(testing_app.value_of("game")).await?;
+    } else if testing_app.is_present("team") {
+        team_repo_test::run(testing_app.value_of("team")).await?;
+    } else if testing_app.is_present("favourite") {
+        favourite_repo_test::run(testing_app.value_of("favourite")).await?;
+    } else if testing_app.is_present("player") {
+        player_repo_test::run(testing_app.value_of("player")).await?;
+    } else if testing_app.is_present("share") {
+        ticket_share_repo_test::run(testing_app.value_of("share")).await?;
+    } else if testing_app.is_present("ticket") {
+        bet_and_ticket_repo_test::run(testing_app.value_of("ticket")).await?;
     }
 
     println!("\n\n=================\nApp ran successfully\n=================");
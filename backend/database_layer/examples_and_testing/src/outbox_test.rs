@@ -0,0 +1,52 @@
+use dotenv::dotenv;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use database_layer::{
+    connection::*,
+    db_access::{
+        event_outbox::{OutboxRepo, PgOutboxRepo},
+        repo::Repo,
+    },
+    listener::{listen, OUTBOX_CHANNEL},
+};
+
+/// Store an event in the outbox and check the listener gets notified about it
+pub async fn run() -> anyhow::Result<()> {
+    // for development purposes only, using dotenv to retrieve the connection string
+    dotenv().ok();
+    let database_url = env::var("DATABASE_URL")?;
+    let database_connection_pool = Arc::new(db_connect_create_pool(&database_url).await?);
+    let pg_outbox = PgOutboxRepo::new(&database_connection_pool);
+
+    let (wake_up, mut wake_ups) = mpsc::channel(1);
+    let listener_url = database_url.clone();
+    tokio::spawn(async move { listen(&listener_url, OUTBOX_CHANNEL, wake_up).await });
+
+    // the listener wakes up once it starts listening
+    tokio::time::timeout(Duration::from_secs(5), wake_ups.recv()).await?;
+
+    let last_id = pg_outbox.get_last_id().await?;
+    let id = pg_outbox
+        .append("{\"BalanceChanged\":{\"user_id\":1}}")
+        .await?;
+    println!("Stored outbox event {}", id);
+
+    tokio::time::timeout(Duration::from_secs(5), wake_ups.recv()).await?;
+    let events = pg_outbox.get_after(last_id, 10).await?;
+    anyhow::ensure!(
+        events.len() == 1 && events[0].id == id,
+        "unexpected events after {}: {:?}",
+        last_id,
+        events
+    );
+    println!("Listener notified about {:?}", events[0]);
+
+    let pruned = pg_outbox
+        .delete_created_before(&events[0].created_at)
+        .await?;
+    println!("Pruned {} older events", pruned);
+    Ok(())
+}
@@ -0,0 +1,40 @@
+mod create;
+mod delete;
+mod get_roster;
+mod join_team;
+mod leave_team;
+use create::create;
+use delete::delete;
+use get_roster::get_roster;
+use join_team::join_team;
+use leave_team::leave_team;
+
+use crate::fixtures;
+
+/// run player testing options
+pub async fn run(argument: Option<&str>) -> anyhow::Result<()> {
+    let pool = fixtures::pool().await?;
+    match argument {
+        Some(arg) => match arg {
+            "create" => {
+                create(&pool).await?;
+            }
+            "join-team" => {
+                join_team(&pool).await?;
+            }
+            "leave-team" => {
+                leave_team(&pool).await?;
+            }
+            "roster" => {
+                get_roster(&pool).await?;
+            }
+            "delete" => {
+                delete(&pool).await?;
+            }
+            _ => anyhow::bail!("Wrong argument specified"),
+        },
+        None => anyhow::bail!("NO ARGUMENT SPECIFIED"),
+    }
+
+    Ok(())
+}
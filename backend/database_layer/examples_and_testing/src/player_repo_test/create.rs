@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        player::{PgPlayerRepo, PlayerRepo},
+        repo::Repo,
+    },
+    db_models::player::CreatePlayer,
+};
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// Players are listed by the game they play, ordered by their nicknames
+pub async fn create(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let players = PgPlayerRepo::new(pool);
+
+    let (game_id, _) = create_game_with_teams(pool, "Players", unique_run(), &[]).await?;
+    let star = players
+        .create(CreatePlayer::new(game_id, "star", "Star Player", "CZ", ""))
+        .await?;
+    let rookie = players
+        .create(CreatePlayer::new(
+            game_id,
+            "rookie",
+            "Rookie Player",
+            "SK",
+            "",
+        ))
+        .await?;
+    let listed: Vec<i32> = players
+        .get_all(Some(game_id))
+        .await?
+        .iter()
+        .map(|player| player.id)
+        .collect();
+    assert_eq!(listed, vec![rookie, star]);
+    println!("players created");
+
+    Ok(())
+}
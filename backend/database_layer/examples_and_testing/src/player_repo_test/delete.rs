@@ -0,0 +1,49 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        player::{PgPlayerRepo, PlayerRepo},
+        repo::Repo,
+    },
+    db_models::{player::CreatePlayer, roster_membership::CreateRosterMembership},
+};
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// A deleted player is removed along with their roster history
+pub async fn delete(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let players = PgPlayerRepo::new(pool);
+
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Deleted player", unique_run(), &["one"]).await?;
+    let rookie = players
+        .create(CreatePlayer::new(
+            game_id,
+            "rookie",
+            "Rookie Player",
+            "SK",
+            "",
+        ))
+        .await?;
+    players
+        .join_team(CreateRosterMembership::new(
+            team_ids[0],
+            rookie,
+            "support",
+            Utc::now() - Duration::days(1),
+        ))
+        .await?;
+
+    players.delete(rookie).await?;
+    assert!(players.get(rookie).await.is_err());
+    assert!(players
+        .get_roster(team_ids[0], Utc::now())
+        .await?
+        .is_empty());
+    assert!(players.get_team_history(team_ids[0]).await?.is_empty());
+    println!("player deleted along with their roster history");
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        player::{PgPlayerRepo, PlayerRepo},
+        repo::Repo,
+    },
+    db_models::{
+        player::{CreatePlayer, Player},
+        roster_membership::CreateRosterMembership,
+    },
+};
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// The lineup of a team can be looked up at any time
+pub async fn get_roster(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let players = PgPlayerRepo::new(pool);
+
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Roster", unique_run(), &["one", "two"]).await?;
+    let (one, two) = (team_ids[0], team_ids[1]);
+    let star = players
+        .create(CreatePlayer::new(game_id, "star", "Star Player", "CZ", ""))
+        .await?;
+    let rookie = players
+        .create(CreatePlayer::new(
+            game_id,
+            "rookie",
+            "Rookie Player",
+            "SK",
+            "",
+        ))
+        .await?;
+
+    // the star moves to the second team after ten days
+    let start = Utc::now() - Duration::days(30);
+    let transfer = start + Duration::days(10);
+    players
+        .join_team(CreateRosterMembership::new(one, star, "captain", start))
+        .await?;
+    players
+        .join_team(CreateRosterMembership::new(one, rookie, "support", start))
+        .await?;
+    players.leave_team(star, transfer).await?;
+    players
+        .join_team(CreateRosterMembership::new(two, star, "captain", transfer))
+        .await?;
+
+    let lineup = |members: Vec<(_, Player)>| {
+        members
+            .into_iter()
+            .map(|(_, player)| player.id)
+            .collect::<Vec<i32>>()
+    };
+    assert_eq!(
+        lineup(players.get_roster(one, start + Duration::days(5)).await?),
+        vec![star, rookie]
+    );
+    assert_eq!(
+        lineup(players.get_roster(one, Utc::now()).await?),
+        vec![rookie]
+    );
+    assert_eq!(
+        lineup(players.get_roster(two, Utc::now()).await?),
+        vec![star]
+    );
+    assert!(players
+        .get_roster(two, start + Duration::days(5))
+        .await?
+        .is_empty());
+    println!("rosters looked up over time");
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        player::{PgPlayerRepo, PlayerRepo},
+        repo::Repo,
+    },
+    db_models::{player::CreatePlayer, roster_membership::CreateRosterMembership},
+};
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// A player joins a single roster at a time, of a team playing their game,
+/// and cannot change their game while on it
+pub async fn join_team(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let players = PgPlayerRepo::new(pool);
+
+    let run = unique_run();
+    let (game_id, team_ids) = create_game_with_teams(pool, "Joined", run, &["one", "two"]).await?;
+    let (one, two) = (team_ids[0], team_ids[1]);
+    let (other_game_id, other_team_ids) =
+        create_game_with_teams(pool, "Other joined", run, &["elsewhere"]).await?;
+    let elsewhere = other_team_ids[0];
+
+    let star = players
+        .create(CreatePlayer::new(game_id, "star", "Star Player", "CZ", ""))
+        .await?;
+    let start = Utc::now() - Duration::days(30);
+    players
+        .join_team(CreateRosterMembership::new(one, star, "captain", start))
+        .await?;
+    assert!(players
+        .join_team(CreateRosterMembership::new(two, star, "captain", start))
+        .await
+        .is_err());
+    assert!(players
+        .join_team(CreateRosterMembership::new(
+            elsewhere, star, "captain", start
+        ))
+        .await
+        .is_err());
+    assert!(players
+        .edit(
+            star,
+            CreatePlayer::new(other_game_id, "star", "Star Player", "CZ", "")
+        )
+        .await
+        .is_err());
+    println!("player joined a roster of their game only");
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        player::{PgPlayerRepo, PlayerRepo},
+        repo::Repo,
+    },
+    db_models::{player::CreatePlayer, roster_membership::CreateRosterMembership},
+};
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// A player leaves a roster after joining it and joins another one
+/// no sooner than they left, their history keeps every membership
+pub async fn leave_team(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let players = PgPlayerRepo::new(pool);
+
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Left", unique_run(), &["one", "two"]).await?;
+    let (one, two) = (team_ids[0], team_ids[1]);
+    let star = players
+        .create(CreatePlayer::new(game_id, "star", "Star Player", "CZ", ""))
+        .await?;
+    let start = Utc::now() - Duration::days(30);
+    players
+        .join_team(CreateRosterMembership::new(one, star, "captain", start))
+        .await?;
+
+    // the star moves to the second team after ten days
+    let transfer = start + Duration::days(10);
+    assert!(players
+        .leave_team(star, start - Duration::days(1))
+        .await
+        .is_err());
+    let left = players.leave_team(star, transfer).await?;
+    assert_eq!(left.team_id, one);
+    assert!(players
+        .join_team(CreateRosterMembership::new(
+            two,
+            star,
+            "captain",
+            transfer - Duration::days(1)
+        ))
+        .await
+        .is_err());
+    players
+        .join_team(CreateRosterMembership::new(two, star, "captain", transfer))
+        .await?;
+    assert!(players.leave_team(star, transfer).await.is_ok());
+    players
+        .join_team(CreateRosterMembership::new(two, star, "coach", transfer))
+        .await?;
+
+    let roles: Vec<String> = players
+        .get_player_history(star)
+        .await?
+        .into_iter()
+        .map(|membership| membership.role)
+        .collect();
+    assert_eq!(roles, vec!["captain", "captain", "coach"]);
+    assert_eq!(players.get_team_history(two).await?.len(), 2);
+    println!("player moved over to another roster");
+
+    Ok(())
+}
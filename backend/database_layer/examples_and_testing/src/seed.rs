@@ -24,7 +24,9 @@ pub fn users_and_addresses() -> Vec<(CreateUser, CreateUserAddress)> {
                 "Sedlacek",
                 "-",
                 "ER748503",
-                &Utc.ymd(2000, 2, 7).to_string(),
+                &Utc.with_ymd_and_hms(2000, 2, 7, 0, 0, 0)
+                    .unwrap()
+                    .to_string(),
                 "sedlacekt@gmail.com",
                 "0912345698",
                 None,
@@ -44,7 +46,9 @@ pub fn users_and_addresses() -> Vec<(CreateUser, CreateUserAddress)> {
                 "Mrkvicka",
                 "-",
                 "ER741254",
-                &Utc.ymd(1995, 12, 4).to_string(),
+                &Utc.with_ymd_and_hms(1995, 12, 4, 0, 0, 0)
+                    .unwrap()
+                    .to_string(),
                 "mrkvickajesef@gmail.com",
                 "0914789541",
                 None,
@@ -64,7 +68,9 @@ pub fn users_and_addresses() -> Vec<(CreateUser, CreateUserAddress)> {
                 "Hlina",
                 "-",
                 "ER548503",
-                &Utc.ymd(1960, 6, 12).to_string(),
+                &Utc.with_ymd_and_hms(1960, 6, 12, 0, 0, 0)
+                    .unwrap()
+                    .to_string(),
                 "hlina@gmail.com",
                 "0915987654",
                 None,
@@ -84,7 +90,9 @@ pub fn users_and_addresses() -> Vec<(CreateUser, CreateUserAddress)> {
                 "Toth",
                 "-",
                 "ER113226",
-                &Utc.ymd(2000, 5, 15).to_string(),
+                &Utc.with_ymd_and_hms(2000, 5, 15, 0, 0, 0)
+                    .unwrap()
+                    .to_string(),
                 "tothovasandra@gmail.com",
                 "0912345658",
                 None,
@@ -0,0 +1,30 @@
+mod delete;
+mod remove_from_game;
+mod set_archived;
+use delete::delete;
+use remove_from_game::remove_from_game;
+use set_archived::set_archived;
+
+use crate::fixtures;
+
+/// run team testing options
+pub async fn run(argument: Option<&str>) -> anyhow::Result<()> {
+    let pool = fixtures::pool().await?;
+    match argument {
+        Some(arg) => match arg {
+            "archive" => {
+                set_archived(&pool).await?;
+            }
+            "remove-from-game" => {
+                remove_from_game(&pool).await?;
+            }
+            "delete" => {
+                delete(&pool).await?;
+            }
+            _ => anyhow::bail!("Wrong argument specified"),
+        },
+        None => anyhow::bail!("NO ARGUMENT SPECIFIED"),
+    }
+
+    Ok(())
+}
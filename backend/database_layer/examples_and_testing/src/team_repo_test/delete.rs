@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game::{GameRepo, PgGameRepo},
+        repo::Repo,
+        team::{PgTeamRepo, TeamRepo},
+    },
+};
+
+use crate::fixtures::{create_game_with_teams, create_match, unique_run};
+
+/// A team referenced by a match is kept, an unused one is deleted along with its games
+pub async fn delete(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let games = PgGameRepo::new(pool);
+    let teams = PgTeamRepo::new(pool);
+
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Deleted team", unique_run(), &["one", "two", "spare"])
+            .await?;
+    let (one, two, spare) = (team_ids[0], team_ids[1], team_ids[2]);
+
+    create_match(pool, game_id, one, two).await?;
+    assert!(teams.delete(one).await.is_err());
+    assert!(teams.get(one).await.is_ok());
+    println!("team with a match kept");
+
+    let deleted = teams.delete(spare).await?;
+    assert_eq!(deleted.id, spare);
+    assert!(teams.get(spare).await.is_err());
+    assert!(!games
+        .get_teams_playing(game_id)
+        .await?
+        .iter()
+        .any(|team| team.id == spare));
+    println!("unused team deleted");
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        player::{PgPlayerRepo, PlayerRepo},
+        repo::Repo,
+        team::{PgTeamRepo, TeamRepo},
+    },
+    db_models::{player::CreatePlayer, roster_membership::CreateRosterMembership},
+    error::DomainError,
+};
+
+use crate::fixtures::{create_game_with_teams, create_match, unique_run};
+
+/// Only a team without matches and without players of the game on its roster
+/// is removed from the game
+pub async fn remove_from_game(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let teams = PgTeamRepo::new(pool);
+    let players = PgPlayerRepo::new(pool);
+
+    let (game_id, team_ids) = create_game_with_teams(
+        pool,
+        "Removed",
+        unique_run(),
+        &["one", "two", "rostered", "spare"],
+    )
+    .await?;
+    let (one, two, rostered, spare) = (team_ids[0], team_ids[1], team_ids[2], team_ids[3]);
+
+    create_match(pool, game_id, one, two).await?;
+    let err = teams.remove_from_game(one, game_id).await.unwrap_err();
+    assert!(matches!(DomainError::of(&err), DomainError::Conflict(_)));
+    println!("team with a match kept in the game");
+
+    let player_id = players
+        .create(CreatePlayer::new(
+            game_id,
+            "player",
+            "Rostered Player",
+            "SK",
+            "",
+        ))
+        .await?;
+    players
+        .join_team(CreateRosterMembership::new(
+            rostered,
+            player_id,
+            "captain",
+            Utc::now() - Duration::days(1),
+        ))
+        .await?;
+    let err = teams.remove_from_game(rostered, game_id).await.unwrap_err();
+    assert!(matches!(DomainError::of(&err), DomainError::Conflict(_)));
+    println!("team with players of the game on its roster kept in the game");
+
+    teams.remove_from_game(spare, game_id).await?;
+    assert!(teams.games_played(spare).await?.is_empty());
+    println!("spare team removed from the game");
+
+    Ok(())
+}
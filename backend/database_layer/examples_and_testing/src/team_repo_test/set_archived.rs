@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+        team::{PgTeamRepo, TeamRepo},
+    },
+};
+
+use crate::fixtures::{create_game_with_teams, create_match, unique_run};
+
+/// An archived team is left out of the listing and new matches, its matches stay
+pub async fn set_archived(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let teams = PgTeamRepo::new(pool);
+    let matches = PgMatchRepo::new(pool);
+
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Archived team", unique_run(), &["one", "two"]).await?;
+    let (one, two) = (team_ids[0], team_ids[1]);
+    let match_id = create_match(pool, game_id, one, two).await?;
+
+    teams.set_archived(one, true).await?;
+    assert!(teams.get(one).await?.archived);
+    assert!(!teams
+        .get_all(false)
+        .await?
+        .iter()
+        .any(|team| team.id == one));
+    assert!(teams.get_all(true).await?.iter().any(|team| team.id == one));
+    assert!(create_match(pool, game_id, one, two).await.is_err());
+    // the match of the archived team stays
+    assert_eq!(matches.get(match_id).await?.team_one_id, one);
+    println!("archived team left out of the listing and new matches");
+
+    teams.set_archived(one, false).await?;
+    create_match(pool, game_id, one, two).await?;
+    println!("team unarchived");
+
+    Ok(())
+}
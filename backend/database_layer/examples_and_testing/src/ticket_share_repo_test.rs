@@ -0,0 +1,76 @@
+mod copy_to_user;
+mod get_selections;
+mod share;
+use copy_to_user::copy_to_user;
+use get_selections::get_selections;
+use share::share;
+
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+    },
+    db_models::{bet::CreateBet, bet_market::BetMarket, game_match_event::GameMatchEventType},
+};
+
+use crate::fixtures::{self, create_game_with_teams, create_match, open_ticket, MAX_SELECTIONS};
+
+/// run ticket sharing testing options
+pub async fn run(argument: Option<&str>) -> anyhow::Result<()> {
+    let pool = fixtures::pool().await?;
+    match argument {
+        Some(arg) => match arg {
+            "share" => {
+                share(&pool).await?;
+            }
+            "selections" => {
+                get_selections(&pool).await?;
+            }
+            "copy" => {
+                copy_to_user(&pool).await?;
+            }
+            _ => anyhow::bail!("Wrong argument specified"),
+        },
+        None => anyhow::bail!("NO ARGUMENT SPECIFIED"),
+    }
+
+    Ok(())
+}
+
+/// Place the bets of the user on two live matches
+///
+/// Returns
+/// ---
+/// - ID of the ticket and the pairs of the matches and the teams bet on
+async fn place_two_bets(
+    pool: &Arc<PgPool>,
+    user_id: i32,
+    run: i64,
+) -> anyhow::Result<(i32, Vec<(i32, i32)>)> {
+    let matches = PgMatchRepo::new(pool);
+    let tickets = PgBetAndTicketRepo::new(pool);
+
+    let (game_id, team_ids) = create_game_with_teams(pool, "Sharing", run, &["one", "two"]).await?;
+    let ticket = open_ticket(pool, user_id).await?;
+    let mut selections = Vec::new();
+    for team_id in team_ids.iter() {
+        // bets can only be placed on live matches
+        let match_id = create_match(pool, game_id, team_ids[0], team_ids[1]).await?;
+        matches
+            .create_event(match_id, GameMatchEventType::Live)
+            .await?;
+        tickets
+            .place_a_bet(
+                ticket.id,
+                CreateBet::new(match_id, ticket.id, *team_id, BetMarket::MatchWinner),
+                MAX_SELECTIONS,
+            )
+            .await?;
+        selections.push((match_id, *team_id));
+    }
+    Ok((ticket.id, selections))
+}
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+        ticket_share::{PgTicketShareRepo, TicketShareRepo},
+    },
+    db_models::{game_match_event::GameMatchEventType, ticket_share_code::SharedTicket},
+};
+
+use super::place_two_bets;
+use crate::fixtures::{create_user, open_ticket, unique_run, MAX_SELECTIONS};
+
+/// The selections of a shared ticket are copied to the open ticket of another user
+/// as long as they can still be placed
+pub async fn copy_to_user(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let matches = PgMatchRepo::new(pool);
+    let tickets = PgBetAndTicketRepo::new(pool);
+    let shares = PgTicketShareRepo::new(pool);
+
+    let run = unique_run();
+    let owner = create_user(pool, "Owner", run).await?;
+    let copier = create_user(pool, "Copier", run).await?;
+    let (ticket_id, placed) = place_two_bets(pool, owner, run).await?;
+    let ((kept, _), (ended, ended_winner)) = (placed[0], placed[1]);
+    let code = shares.share(owner, SharedTicket::Open(ticket_id)).await?;
+
+    matches
+        .create_event(ended, GameMatchEventType::Ended(ended_winner))
+        .await?;
+    let copied = shares.copy_to_user(&code, copier, MAX_SELECTIONS).await?;
+    assert_eq!(copied.ticket_id, open_ticket(pool, copier).await?.id);
+    assert_eq!(
+        copied
+            .copied
+            .iter()
+            .map(|(bet, _)| bet.game_match_id)
+            .collect::<Vec<_>>(),
+        [kept]
+    );
+    assert_eq!(
+        copied
+            .rejected
+            .iter()
+            .map(|(selection, _)| selection.game_match_id)
+            .collect::<Vec<_>>(),
+        [ended]
+    );
+    let copied = shares.copy_to_user(&code, copier, MAX_SELECTIONS).await?;
+    assert!(copied.copied.is_empty());
+    assert_eq!(copied.rejected.len(), 2);
+    assert_eq!(tickets.get_bets(copied.ticket_id).await?.len(), 1);
+    println!("selections copied once, the ended match rejected");
+
+    let limited = create_user(pool, "Limited", run).await?;
+    let copied = shares.copy_to_user(&code, limited, 0).await?;
+    assert!(copied.copied.is_empty());
+    assert!(copied.rejected[0].1.contains("at most 0 bets"));
+    assert!(tickets.get_bets(copied.ticket_id).await?.is_empty());
+    println!("selections over the ticket limit rejected");
+
+    Ok(())
+}
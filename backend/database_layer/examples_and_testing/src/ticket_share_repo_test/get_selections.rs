@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        repo::Repo,
+        ticket_share::{PgTicketShareRepo, TicketShareRepo},
+    },
+    db_models::ticket_share_code::SharedTicket,
+};
+
+use super::place_two_bets;
+use crate::fixtures::{create_user, unique_run};
+
+/// The selections of a shared ticket are looked up by its code in any case
+pub async fn get_selections(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let shares = PgTicketShareRepo::new(pool);
+
+    let run = unique_run();
+    let owner = create_user(pool, "Owner", run).await?;
+    let (ticket_id, placed) = place_two_bets(pool, owner, run).await?;
+
+    let code = shares.share(owner, SharedTicket::Open(ticket_id)).await?;
+    let selections = shares.get_selections(&code.to_lowercase()).await?;
+    assert_eq!(
+        selections
+            .iter()
+            .map(|selection| (selection.game_match_id, selection.team_id))
+            .collect::<Vec<_>>(),
+        placed
+    );
+    assert!(shares.get_selections("UNKNOWN").await.is_err());
+    println!("selections of the shared ticket found");
+
+    Ok(())
+}
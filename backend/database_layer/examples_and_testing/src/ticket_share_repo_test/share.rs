@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        repo::Repo,
+        ticket_share::{PgTicketShareRepo, TicketShareRepo},
+        user::{PgUserRepo, UserRepo},
+    },
+    db_models::ticket_share_code::SharedTicket,
+};
+
+use super::place_two_bets;
+use crate::fixtures::{create_user, unique_run};
+
+/// A ticket is shared under a single code, only by its owner,
+/// and the code follows the ticket when it gets submitted
+pub async fn share(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let users = PgUserRepo::new(pool);
+    let tickets = PgBetAndTicketRepo::new(pool);
+    let shares = PgTicketShareRepo::new(pool);
+
+    let run = unique_run();
+    let owner = create_user(pool, "Owner", run).await?;
+    let stranger = create_user(pool, "Stranger", run).await?;
+    let (ticket_id, _) = place_two_bets(pool, owner, run).await?;
+
+    let code = shares.share(owner, SharedTicket::Open(ticket_id)).await?;
+    assert_eq!(
+        shares.share(owner, SharedTicket::Open(ticket_id)).await?,
+        code
+    );
+    assert!(shares
+        .share(stranger, SharedTicket::Open(ticket_id))
+        .await
+        .is_err());
+    println!("ticket shared under a single code, only by its owner");
+
+    users.add_balance(owner, 100.0).await?;
+    let submitted_id = tickets.submit_ticket(ticket_id, 10.0).await?;
+    assert_eq!(
+        shares
+            .share(owner, SharedTicket::Submitted(submitted_id))
+            .await?,
+        code
+    );
+    assert_eq!(shares.get_selections(&code).await?.len(), 2);
+    println!("code moved over to the submitted ticket");
+
+    Ok(())
+}
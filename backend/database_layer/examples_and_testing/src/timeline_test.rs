@@ -0,0 +1,110 @@
+use chrono::{Duration, Utc};
+use dotenv::dotenv;
+use std::env;
+use std::sync::Arc;
+
+use database_layer::{
+    connection::*,
+    db_access::{
+        game::{GameRepo, PgGameRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        match_timeline::{PgTimelineRepo, TimelineRepo},
+        repo::Repo,
+        team::{PgTeamRepo, TeamRepo},
+    },
+    db_models::{
+        game::CreateGame,
+        game_match::CreateGameMatch,
+        match_timeline_event::{Score, TimelineEventPayload},
+        team::CreateTeam,
+    },
+};
+
+/// Record a few in-play events of a new match and check its timeline and current score
+pub async fn run() -> anyhow::Result<()> {
+    // for development purposes only, using dotenv to retrieve the connection string
+    dotenv().ok();
+    let database_url = env::var("DATABASE_URL")?;
+    let database_connection_pool = Arc::new(db_connect_create_pool(&database_url).await?);
+    let pg_game = PgGameRepo::new(&database_connection_pool);
+    let pg_team = PgTeamRepo::new(&database_connection_pool);
+    let pg_match = PgMatchRepo::new(&database_connection_pool);
+    let pg_timeline = PgTimelineRepo::new(&database_connection_pool);
+
+    // unique names, so the test can be run repeatedly
+    let run = Utc::now().timestamp_millis();
+    let game_id = pg_game
+        .create(CreateGame::new(&format!("Timeline game {}", run), "", ""))
+        .await?;
+    let team_one_id = pg_team
+        .create(CreateTeam::new(&format!("Timeline one {}", run), "", ""))
+        .await?;
+    let team_two_id = pg_team
+        .create(CreateTeam::new(&format!("Timeline two {}", run), "", ""))
+        .await?;
+    for team_id in [team_one_id, team_two_id] {
+        pg_team.add_to_game(team_id, game_id).await?;
+    }
+    let match_id = pg_match
+        .create(CreateGameMatch::new(
+            game_id,
+            team_one_id,
+            team_two_id,
+            "1.8",
+            "2.0",
+            Utc::now() + Duration::hours(1),
+            "timeline test",
+        ))
+        .await?;
+
+    let recorded = vec![
+        TimelineEventPayload::RoundResult {
+            map_number: 1,
+            round_number: 1,
+            winner_id: team_one_id,
+        },
+        TimelineEventPayload::Kill {
+            team_id: team_one_id,
+            player: String::from("player one"),
+            victim: String::from("player two"),
+        },
+        TimelineEventPayload::ScoreChanged(Score {
+            team_one: 1,
+            team_two: 0,
+        }),
+        TimelineEventPayload::ScoreChanged(Score {
+            team_one: 1,
+            team_two: 1,
+        }),
+    ];
+    for payload in recorded.iter() {
+        let event = pg_timeline.append(match_id, payload).await?;
+        println!("Recorded {:?}", event);
+    }
+
+    let timeline = pg_timeline.get_timeline(match_id).await?;
+    let payloads = timeline
+        .iter()
+        .map(|event| event.extract_payload())
+        .collect::<anyhow::Result<Vec<TimelineEventPayload>>>()?;
+    anyhow::ensure!(
+        payloads == recorded,
+        "unexpected timeline of the match {}: {:?}",
+        match_id,
+        payloads
+    );
+
+    let scores = pg_timeline.get_scores(&[match_id]).await?;
+    anyhow::ensure!(
+        scores.get(&match_id)
+            == Some(&Score {
+                team_one: 1,
+                team_two: 1
+            }),
+        "unexpected score of the match {}: {:?}",
+        match_id,
+        scores
+    );
+    println!("Current score {:?}", scores[&match_id]);
+    Ok(())
+}
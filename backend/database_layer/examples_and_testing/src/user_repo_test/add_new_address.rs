@@ -47,7 +47,7 @@ pub async fn new_address() -> anyhow::Result<()> {
 
     let results: Vec<&str> = results.iter().map(|user_input| user_input.trim()).collect();
 
-    let area = if results[4] == "" {
+    let area = if results[4].is_empty() {
         None
     } else {
         Some(results[4])
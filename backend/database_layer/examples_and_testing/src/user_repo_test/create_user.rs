@@ -49,7 +49,7 @@ pub async fn create_user() -> anyhow::Result<()> {
 
     let results: Vec<&str> = results.iter().map(|user_input| user_input.trim()).collect();
 
-    let area = if results[8] == "" {
+    let area = if results[8].is_empty() {
         None
     } else {
         Some(results[8])
@@ -47,7 +47,13 @@ pub async fn edit_user() -> anyhow::Result<()> {
     let results: Vec<Option<&str>> = results
         .iter()
         .map(|user_input| user_input.trim())
-        .map(|string| if string == "" { None } else { Some(string) })
+        .map(|string| {
+            if string.is_empty() {
+                None
+            } else {
+                Some(string)
+            }
+        })
         .collect();
 
     let user = pg_user.get(results[0].unwrap_or("0").parse()?).await?;
@@ -0,0 +1 @@
+DROP TABLE "ticket_share_code";
\ No newline at end of file
@@ -0,0 +1,11 @@
+-- Share code of a ticket, either still open or already submitted
+-- (when an open ticket gets submitted, its code is moved over to the submitted ticket)
+CREATE TABLE "ticket_share_code" (
+    id SERIAL PRIMARY KEY,
+    code TEXT NOT NULL UNIQUE,
+    ticket_id INTEGER REFERENCES "ticket",
+    submitted_ticket_id INTEGER REFERENCES "submitted_ticket",
+    created_at TEXT NOT NULL,
+    -- the code points to exactly one ticket
+    CHECK ((ticket_id IS NULL) <> (submitted_ticket_id IS NULL))
+);
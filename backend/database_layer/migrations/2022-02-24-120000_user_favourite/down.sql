@@ -0,0 +1 @@
+DROP TABLE "user_favourite";
\ No newline at end of file
@@ -0,0 +1,15 @@
+-- Teams, games and matches followed by the user
+-- (exactly one of the references is set)
+CREATE TABLE "user_favourite" (
+    id SERIAL PRIMARY KEY,
+    "user_id" INTEGER REFERENCES "user" NOT NULL,
+    team_id INTEGER REFERENCES "team",
+    game_id INTEGER REFERENCES "game",
+    game_match_id INTEGER REFERENCES "game_match",
+    created_at TEXT NOT NULL,
+    CHECK (num_nonnulls(team_id, game_id, game_match_id) = 1),
+    -- the same entity cannot be followed twice
+    UNIQUE ("user_id", team_id),
+    UNIQUE ("user_id", game_id),
+    UNIQUE ("user_id", game_match_id)
+);
@@ -0,0 +1,11 @@
+-- Sessions of logged in users, the token authenticates the websocket connections
+CREATE TABLE "user_session" (
+    id SERIAL PRIMARY KEY,
+    "user_id" INTEGER REFERENCES "user" NOT NULL,
+    token TEXT NOT NULL UNIQUE,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+-- the expired sessions are looked up when they get deleted
+CREATE INDEX user_session_expires_at ON "user_session" (expires_at);
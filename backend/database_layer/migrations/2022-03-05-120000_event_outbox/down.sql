@@ -0,0 +1,3 @@
+DROP TRIGGER event_outbox_notify ON "event_outbox";
+DROP FUNCTION notify_event_outbox();
+DROP TABLE "event_outbox";
\ No newline at end of file
@@ -0,0 +1,19 @@
+-- Domain events waiting to be delivered by every backend instance,
+-- the payload is the event serialized into JSON
+CREATE TABLE "event_outbox" (
+    id SERIAL PRIMARY KEY,
+    payload TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+-- Wake up the listening instances once the event is committed
+CREATE FUNCTION notify_event_outbox() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify('event_outbox', NEW.id::TEXT);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER event_outbox_notify
+    AFTER INSERT ON "event_outbox"
+    FOR EACH ROW EXECUTE PROCEDURE notify_event_outbox();
@@ -0,0 +1 @@
+DROP TABLE "match_timeline_event";
\ No newline at end of file
@@ -0,0 +1,11 @@
+-- In-play events of a match (score changes, map and round results, kills, objectives),
+-- the payload is the typed event serialized into JSON
+CREATE TABLE "match_timeline_event" (
+    id SERIAL PRIMARY KEY,
+    game_match_id INTEGER REFERENCES "game_match" NOT NULL,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX match_timeline_event_game_match_id ON "match_timeline_event" (game_match_id);
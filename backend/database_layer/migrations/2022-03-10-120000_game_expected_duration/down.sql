@@ -0,0 +1 @@
+ALTER TABLE "game" DROP COLUMN expected_duration_minutes;
\ No newline at end of file
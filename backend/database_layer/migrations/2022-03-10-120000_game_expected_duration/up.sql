@@ -0,0 +1,2 @@
+-- How long a match of the game usually takes, live matches running longer are reported to the admins
+ALTER TABLE "game" ADD COLUMN expected_duration_minutes INTEGER NOT NULL DEFAULT 120;
@@ -0,0 +1 @@
+DROP TABLE "external_reference";
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- IDs the imported games, teams and matches have in the files they were imported from,
+-- re-importing a record with a known external ID updates it instead of creating a duplicate
+CREATE TABLE "external_reference" (
+    id SERIAL PRIMARY KEY,
+    entity TEXT NOT NULL,
+    external_id TEXT NOT NULL,
+    internal_id INTEGER NOT NULL,
+    UNIQUE (entity, external_id)
+);
@@ -0,0 +1,5 @@
+DROP TABLE "outright_bet";
+DROP TABLE "outright_selection";
+DROP TABLE "stage_match";
+DROP TABLE "stage";
+DROP TABLE "tournament";
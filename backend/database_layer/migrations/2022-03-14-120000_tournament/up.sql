@@ -0,0 +1,52 @@
+-- Tournaments (a major, a league season) grouping the matches of a game into stages
+CREATE TABLE "tournament" (
+    id SERIAL PRIMARY KEY,
+    game_id INTEGER REFERENCES "game" NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL,
+    starts_at TEXT NOT NULL,
+    ends_at TEXT NOT NULL,
+    -- set once the tournament has ended
+    winner_id INTEGER REFERENCES "team",
+    ended_at TEXT
+);
+
+CREATE TABLE "stage" (
+    id SERIAL PRIMARY KEY,
+    tournament_id INTEGER REFERENCES "tournament" NOT NULL,
+    name TEXT NOT NULL,
+    stage_type TEXT NOT NULL,
+    -- order of the stage within the tournament
+    position INTEGER NOT NULL
+);
+
+-- a match belongs to at most one stage
+CREATE TABLE "stage_match" (
+    id SERIAL PRIMARY KEY,
+    stage_id INTEGER REFERENCES "stage" NOT NULL,
+    game_match_id INTEGER REFERENCES "game_match" NOT NULL UNIQUE
+);
+
+-- teams which can be bet on to win the tournament
+CREATE TABLE "outright_selection" (
+    id SERIAL PRIMARY KEY,
+    tournament_id INTEGER REFERENCES "tournament" NOT NULL,
+    team_id INTEGER REFERENCES "team" NOT NULL,
+    ratio TEXT NOT NULL,
+    UNIQUE (tournament_id, team_id)
+);
+
+CREATE TABLE "outright_bet" (
+    id SERIAL PRIMARY KEY,
+    tournament_id INTEGER REFERENCES "tournament" NOT NULL,
+    team_id INTEGER REFERENCES "team" NOT NULL,
+    user_id INTEGER REFERENCES "user" NOT NULL,
+    bet_ratio TEXT NOT NULL,
+    price_paid TEXT NOT NULL,
+    placed_at TEXT NOT NULL,
+    won BOOLEAN
+);
+
+CREATE INDEX stage_tournament_id ON "stage" (tournament_id);
+CREATE INDEX outright_bet_tournament_id ON "outright_bet" (tournament_id);
+CREATE INDEX outright_bet_user_id ON "outright_bet" (user_id);
@@ -0,0 +1,6 @@
+DROP TABLE "match_map";
+ALTER TABLE "submitted_bet" DROP COLUMN market_value;
+ALTER TABLE "submitted_bet" DROP COLUMN market;
+ALTER TABLE "bet" DROP COLUMN market_value;
+ALTER TABLE "bet" DROP COLUMN market;
+ALTER TABLE "game_match" DROP COLUMN series_format;
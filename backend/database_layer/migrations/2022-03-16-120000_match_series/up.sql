@@ -0,0 +1,23 @@
+-- Number of maps played in the match: Bo1, Bo3 or Bo5
+ALTER TABLE "game_match" ADD COLUMN series_format TEXT NOT NULL DEFAULT 'Bo1';
+
+-- What the bet is on: MatchWinner, MapWinner (value is the map number)
+-- or SeriesScore (value is the score of the team bet on, e.g. 2:1)
+ALTER TABLE "bet" ADD COLUMN market TEXT NOT NULL DEFAULT 'MatchWinner';
+ALTER TABLE "bet" ADD COLUMN market_value TEXT;
+ALTER TABLE "submitted_bet" ADD COLUMN market TEXT NOT NULL DEFAULT 'MatchWinner';
+ALTER TABLE "submitted_bet" ADD COLUMN market_value TEXT;
+
+-- finished maps of a match
+CREATE TABLE "match_map" (
+    id SERIAL PRIMARY KEY,
+    game_match_id INTEGER REFERENCES "game_match" NOT NULL,
+    map_number INTEGER NOT NULL,
+    map_name TEXT NOT NULL,
+    winner_id INTEGER REFERENCES "team" NOT NULL,
+    -- rounds won by each team on the map
+    team_one_score INTEGER NOT NULL,
+    team_two_score INTEGER NOT NULL,
+    finished_at TEXT NOT NULL,
+    UNIQUE (game_match_id, map_number)
+);
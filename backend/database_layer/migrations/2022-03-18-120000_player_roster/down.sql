@@ -0,0 +1,2 @@
+DROP TABLE "roster_membership";
+DROP TABLE "player";
@@ -0,0 +1,25 @@
+-- Players of a game, their teams are tracked by the roster memberships
+CREATE TABLE "player" (
+    id SERIAL PRIMARY KEY,
+    game_id INTEGER REFERENCES "game" NOT NULL,
+    nickname TEXT NOT NULL,
+    real_name TEXT NOT NULL,
+    country TEXT NOT NULL,
+    photo TEXT NOT NULL
+);
+
+-- a player has been in the roster of the team since `joined_at`, until `left_at` if set
+CREATE TABLE "roster_membership" (
+    id SERIAL PRIMARY KEY,
+    team_id INTEGER REFERENCES "team" NOT NULL,
+    player_id INTEGER REFERENCES "player" NOT NULL,
+    -- e.g. "captain", "AWPer", "coach"
+    role TEXT NOT NULL,
+    joined_at TEXT NOT NULL,
+    left_at TEXT
+);
+
+CREATE INDEX player_game_id ON "player" (game_id);
+CREATE INDEX roster_membership_team_id ON "roster_membership" (team_id);
+-- a player is in at most one roster at a time
+CREATE UNIQUE INDEX roster_membership_current ON "roster_membership" (player_id) WHERE left_at IS NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE "team" DROP COLUMN archived;
+ALTER TABLE "game" DROP COLUMN archived;
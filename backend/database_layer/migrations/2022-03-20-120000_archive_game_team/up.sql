@@ -0,0 +1,4 @@
+-- Archived games and teams are kept for the history of their matches and bets,
+-- but they are left out of the listings and cannot get new matches
+ALTER TABLE "game" ADD COLUMN archived BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE "team" ADD COLUMN archived BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,4 @@
+ALTER TABLE "user" DROP COLUMN photo_asset_id;
+ALTER TABLE "team" DROP COLUMN logo_asset_id;
+ALTER TABLE "game" DROP COLUMN logo_asset_id;
+DROP TABLE "media_asset";
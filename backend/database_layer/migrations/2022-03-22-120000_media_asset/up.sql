@@ -0,0 +1,19 @@
+-- Uploaded images, stored on the local disk under their content hash
+CREATE TABLE "media_asset" (
+    id SERIAL PRIMARY KEY,
+    -- hex SHA-256 of the original file, the same image is stored only once
+    content_hash TEXT NOT NULL UNIQUE,
+    content_type TEXT NOT NULL,
+    -- names of the original and the thumbnail in the media directory
+    file_name TEXT NOT NULL,
+    thumbnail_name TEXT NOT NULL,
+    byte_size INTEGER NOT NULL,
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+-- the logo or photo url points to the asset, when one is set
+ALTER TABLE "game" ADD COLUMN logo_asset_id INTEGER REFERENCES "media_asset";
+ALTER TABLE "team" ADD COLUMN logo_asset_id INTEGER REFERENCES "media_asset";
+ALTER TABLE "user" ADD COLUMN photo_asset_id INTEGER REFERENCES "media_asset";
@@ -0,0 +1,62 @@
+use tokio::task::JoinHandle;
+use tokio_postgres::{Client, NoTls};
+
+/// Key of the lock held by the instance running the match scheduler
+pub const SCHEDULER_LOCK: i64 = 1;
+
+/// Postgres advisory lock held by a single instance for as long as its connection is open.
+/// Diesel connections are shared through the pool, so the lock uses a dedicated
+/// connection of its own. When the instance dies, the lock is released by the database
+/// and another instance may take it over.
+pub struct AdvisoryLock {
+    client: Client,
+    connection_task: JoinHandle<()>,
+}
+
+impl AdvisoryLock {
+    /// Try to take the lock, without waiting for the instance holding it
+    ///
+    /// Params
+    /// ---
+    /// - database_url: Connection string
+    /// - key: key of the lock
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(Some(lock)) if the lock has been taken
+    /// - Ok(None) if another instance holds the lock
+    /// - Err(_) if the connection fails
+    pub async fn try_acquire(database_url: &str, key: i64) -> anyhow::Result<Option<AdvisoryLock>> {
+        let (client, connection) = tokio_postgres::connect(database_url, NoTls).await?;
+        let connection_task = tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                println!("advisory lock connection failed: {}", err);
+            }
+        });
+
+        let acquired: bool = client
+            .query_one("SELECT pg_try_advisory_lock($1)", &[&key])
+            .await?
+            .get(0);
+        if !acquired {
+            connection_task.abort();
+            return Ok(None);
+        }
+        Ok(Some(AdvisoryLock {
+            client,
+            connection_task,
+        }))
+    }
+
+    /// Check whether the lock is still held, it is lost together with its connection
+    pub fn is_held(&self) -> bool {
+        !self.client.is_closed()
+    }
+}
+
+impl Drop for AdvisoryLock {
+    /// Closing the connection releases the lock
+    fn drop(&mut self) {
+        self.connection_task.abort();
+    }
+}
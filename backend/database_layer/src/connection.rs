@@ -1,9 +1,11 @@
 use diesel::{
-    pg::PgConnection,
     r2d2::{ConnectionManager, Pool, PooledConnection},
     Connection,
 };
 
+/// Connection the repos make their changes on within a transaction of the caller
+pub use diesel::pg::PgConnection;
+
 pub type PgPool = Pool<ConnectionManager<PgConnection>>;
 pub type PgPooledConnection = PooledConnection<ConnectionManager<PgConnection>>;
 
@@ -12,15 +14,16 @@ pub type PgPooledConnection = PooledConnection<ConnectionManager<PgConnection>>;
 /// Params
 /// ---
 /// - database_url: Connection string
+/// - pool_size: largest number of open connections
 ///
 /// Returns
 /// ---
 /// - Ok(connection_pool) if the pool has been created successfully
 /// - Err(_) otherwise
-fn initialize_pool(database_url: &str) -> anyhow::Result<PgPool> {
+fn initialize_pool(database_url: &str, pool_size: u32) -> anyhow::Result<PgPool> {
     let manager = ConnectionManager::<PgConnection>::new(database_url);
 
-    Ok(Pool::builder().build(manager)?)
+    Ok(Pool::builder().max_size(pool_size).build(manager)?)
 }
 
 /// Establish a pooled connection to the database.
@@ -33,8 +36,25 @@ fn initialize_pool(database_url: &str) -> anyhow::Result<PgPool> {
 /// - Ok(PgPool) = after successfull database connection and pool creation
 /// - Err(_) = after an error occurred
 pub async fn db_connect_create_pool(database_url: &str) -> anyhow::Result<PgPool> {
+    db_connect_create_pool_with_size(database_url, 10).await
+}
+
+/// Establish a pooled connection to the database, with the given size of the pool.
+///
+/// Params
+/// ---
+/// - database_url: connection slice, used by diesel to connect to the database
+/// - pool_size: largest number of open connections
+///
+/// Returns
+/// - Ok(PgPool) = after successfull database connection and pool creation
+/// - Err(_) = after an error occurred
+pub async fn db_connect_create_pool_with_size(
+    database_url: &str,
+    pool_size: u32,
+) -> anyhow::Result<PgPool> {
     PgConnection::establish(database_url)?;
     println!("Database connection has been successful.");
 
-    initialize_pool(database_url)
+    initialize_pool(database_url, pool_size)
 }
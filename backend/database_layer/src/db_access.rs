@@ -1,8 +1,19 @@
 pub mod bet_and_ticket;
+pub mod event_outbox;
+pub mod external_reference;
+pub mod favourite;
 pub mod game;
 pub mod game_match;
+pub mod match_map;
+pub mod match_timeline;
+pub mod media_asset;
+pub mod outright;
+pub mod player;
 pub mod repo;
+pub mod session;
 pub mod submitted_bet_and_ticket;
 pub mod team;
+pub mod ticket_share;
+pub mod tournament;
 pub mod user;
 // pub mod user_address;
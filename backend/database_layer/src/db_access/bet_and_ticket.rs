@@ -3,25 +3,28 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::connection::{PgPool, PgPooledConnection};
-use crate::diesel::{delete, insert_into, prelude::*, QueryDsl, RunQueryDsl};
+use crate::diesel::{delete, insert_into, prelude::*, update, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
 use crate::type_storing::time_handling::TimeHandling;
 
 // type and structure imports
 use crate::{
-    db_access::{
-        repo::Repo,
-        user::{PgUserRepo, UserRepo},
-    },
+    db_access::{repo::Repo, user::PgUserRepo},
     db_models::{
         bet::{Bet, CreateBet},
+        bet_market::BetMarket,
         game_match::GameMatch,
         game_match_event::{GameMatchEvent, GameMatchEventType},
+        match_map::MatchMap,
         ticket::{CreateTicket, ObtainedTicket, Ticket},
     },
 };
 
 // schema imports
-use crate::schema::{bet, game_match, game_match_event, submitted_bet, submitted_ticket, ticket};
+use crate::schema::{
+    bet, game_match, game_match_event, match_map, submitted_bet, submitted_ticket, ticket,
+    ticket_share_code,
+};
 
 /// Structure containing a reference to a database connection pool
 /// and methods to access the database
@@ -116,12 +119,18 @@ pub trait BetAndTicketRepo {
     /// ---
     /// - `desired_ticket_id`: ID of the ticket we wish to place the bet on
     /// - `new_bet`: bet we wish to place
+    /// - `max_selections`: maximum number of bets in a ticket
     ///
     /// Returns
     /// ---
     /// - `Ok(bet)` when the bet has been successful
-    /// - `Err(_)` if an error has occurred
-    async fn place_a_bet(&self, desired_ticket_id: i32, new_bet: CreateBet) -> anyhow::Result<Bet>;
+    /// - `Err(_)` if the ticket is full or an error has occurred
+    async fn place_a_bet(
+        &self,
+        desired_ticket_id: i32,
+        new_bet: CreateBet,
+        max_selections: usize,
+    ) -> anyhow::Result<Bet>;
 
     /// Discard a specific bet.
     /// The validity date of the ticket will be re-calculated
@@ -152,6 +161,30 @@ pub trait BetAndTicketRepo {
     /// - `Ok(id)` with ID of the newly submitted ticket
     /// - `Err(_)` if an error occurrs
     async fn submit_ticket(&self, desired_ticket_id: i32, paid_price: f64) -> anyhow::Result<i32>;
+
+    /// Get IDs of the users holding a bet on the match in their open ticket
+    ///
+    /// Params
+    /// ---
+    /// - `desired_match_id`: ID of the match
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(Vec<user_id>)` with IDs of the ticket holders (without duplicates)
+    /// - `Err(_)` if an error occurrs
+    async fn get_open_ticket_holders(&self, desired_match_id: i32) -> anyhow::Result<Vec<i32>>;
+
+    /// Discard the bets on the match from all open tickets, the submitted bets are kept
+    ///
+    /// Params
+    /// ---
+    /// - `desired_match_id`: ID of the match
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(Vec<user_id>)` with IDs of the users whose bets have been discarded (without duplicates)
+    /// - `Err(_)` if an error occurrs
+    async fn discard_bets_on_match(&self, desired_match_id: i32) -> anyhow::Result<Vec<i32>>;
 }
 
 #[async_trait]
@@ -220,7 +253,9 @@ impl BetAndTicketRepo for PgBetAndTicketRepo {
             1 => Ok(ObtainedTicket::StillValid(potential_open_ticket[0].clone())),
             // when used correctly, this will never happen
             _ => {
-                anyhow::bail!("More than one ticket found open! Please, contact site administrator")
+                anyhow::bail!(DomainError::internal(
+                    "More than one ticket found open! Please, contact site administrator"
+                ))
             }
         }
     }
@@ -239,44 +274,21 @@ impl BetAndTicketRepo for PgBetAndTicketRepo {
 
     /// Place a bet on a team in a match
     /// Ticket validity date will be re-calculated
-    async fn place_a_bet(&self, desired_ticket_id: i32, new_bet: CreateBet) -> anyhow::Result<Bet> {
-        let connection = self.get_connection().await?;
-
-        // check if the game is played right now
-        let is_game_played: GameMatchEvent = game_match_event::table
-            .filter(game_match_event::game_match_id.eq(new_bet.game_match_id))
-            .order(game_match_event::created_at.desc())
-            .first(&connection)?;
-
-        let event = is_game_played.extract_event()?;
-
-        // if the game is not live or overtime, the placing of the bet cannot happen
-        match event {
-            GameMatchEventType::Live | GameMatchEventType::Overtime => {}
-            _ => anyhow::bail!("The game is not currently played!"),
-        };
-
-        // retrieve the ticket
-        let desired_ticket: Ticket = ticket::table.find(desired_ticket_id).first(&connection)?;
-
-        // does the same person already have a bet on the match in the ticket?
-        let already_has_bet: usize = bet::table
-            .filter(
-                bet::game_match_id
-                    .eq(new_bet.game_match_id)
-                    .and(bet::ticket_id.eq(desired_ticket_id)),
+    async fn place_a_bet(
+        &self,
+        desired_ticket_id: i32,
+        new_bet: CreateBet,
+        max_selections: usize,
+    ) -> anyhow::Result<Bet> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            PgBetAndTicketRepo::place_a_bet_in(
+                &connection,
+                desired_ticket_id,
+                new_bet,
+                max_selections,
             )
-            .execute(&connection)?;
-
-        if already_has_bet != 0 {
-            anyhow::bail!("Cannot put more bets on the same match!");
-        }
-
-        let query_result: Bet = insert_into(bet::table)
-            .values(new_bet)
-            .get_result(&connection)?;
-
-        Ok(query_result)
+        })
     }
 
     /// Discard a specific bet.
@@ -298,9 +310,13 @@ impl BetAndTicketRepo for PgBetAndTicketRepo {
             .execute(&connection)?;
 
         if is_bet_in_ticket == 0 {
-            anyhow::bail!("The bet does not belong to the ticket!")
+            anyhow::bail!(DomainError::not_found(
+                "The bet does not belong to the ticket!"
+            ))
         } else if is_bet_in_ticket != 1 {
-            anyhow::bail!("Inconsistent data in the database. The bet is present twice!");
+            anyhow::bail!(DomainError::internal(
+                "Inconsistent data in the database. The bet is present twice!"
+            ));
         }
 
         // remove the bet from the ticket
@@ -312,7 +328,116 @@ impl BetAndTicketRepo for PgBetAndTicketRepo {
     /// Submit a ticket -> the ticket then gets submitted, 'paid' and shows up in the ticket history
     async fn submit_ticket(&self, desired_ticket_id: i32, paid_price: f64) -> anyhow::Result<i32> {
         let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            PgBetAndTicketRepo::submit_ticket_in(&connection, desired_ticket_id, paid_price)
+        })
+    }
 
+    /// Get IDs of the users holding a bet on the match in their open ticket
+    async fn get_open_ticket_holders(&self, desired_match_id: i32) -> anyhow::Result<Vec<i32>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgBetAndTicketRepo::get_open_ticket_holders_in(&connection, desired_match_id)
+    }
+
+    /// Discard the bets on the match from all open tickets
+    async fn discard_bets_on_match(&self, desired_match_id: i32) -> anyhow::Result<Vec<i32>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            PgBetAndTicketRepo::discard_bets_on_match_in(&connection, desired_match_id)
+        })
+    }
+}
+
+/// The bets placed and the tickets submitted within the transaction of the caller,
+/// see `OutboxRepo::transaction`
+impl PgBetAndTicketRepo {
+    /// Place a bet on a team in a match
+    /// Ticket validity date will be re-calculated, see `BetAndTicketRepo::place_a_bet`
+    pub fn place_a_bet_in(
+        connection: &PgConnection,
+        desired_ticket_id: i32,
+        new_bet: CreateBet,
+        max_selections: usize,
+    ) -> anyhow::Result<Bet> {
+        // the ticket is locked, so the bets placed at the same time are counted one after another
+        let _: Ticket = ticket::table
+            .find(desired_ticket_id)
+            .for_update()
+            .first(connection)?;
+        let placed_bets: i64 = bet::table
+            .filter(bet::ticket_id.eq(desired_ticket_id))
+            .count()
+            .get_result(connection)?;
+        if placed_bets as usize >= max_selections {
+            anyhow::bail!(DomainError::validation(format!(
+                "A ticket can contain at most {} bets",
+                max_selections
+            )));
+        }
+
+        // check if the game is played right now
+        let is_game_played: GameMatchEvent = game_match_event::table
+            .filter(game_match_event::game_match_id.eq(new_bet.game_match_id))
+            .order(game_match_event::created_at.desc())
+            .first(connection)?;
+
+        let event = is_game_played.extract_event()?;
+
+        // if the game is not live or overtime, the placing of the bet cannot happen
+        match event {
+            GameMatchEventType::Live | GameMatchEventType::Overtime => {}
+            _ => anyhow::bail!(DomainError::betting_closed(
+                "The game is not currently played!"
+            )),
+        };
+
+        // the market has to exist for the match and its map or score must not be decided yet
+        let game_match: GameMatch = game_match::table
+            .find(new_bet.game_match_id)
+            .get_result(connection)?;
+        if game_match.ratio_of(new_bet.team_id).is_none() {
+            anyhow::bail!(DomainError::validation(
+                "The team does not play in the match!"
+            ));
+        }
+        let maps: Vec<MatchMap> = match_map::table
+            .filter(match_map::game_match_id.eq(new_bet.game_match_id))
+            .get_results(connection)?;
+        BetMarket::from_columns(&new_bet.market, new_bet.market_value.as_deref())?.validate(
+            &game_match,
+            new_bet.team_id,
+            &maps,
+        )?;
+
+        // does the same person already have a bet on the match in the ticket?
+        let already_has_bet: usize = bet::table
+            .filter(
+                bet::game_match_id
+                    .eq(new_bet.game_match_id)
+                    .and(bet::ticket_id.eq(desired_ticket_id)),
+            )
+            .execute(connection)?;
+
+        if already_has_bet != 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot put more bets on the same match!"
+            ));
+        }
+
+        let query_result: Bet = insert_into(bet::table)
+            .values(new_bet)
+            .get_result(connection)?;
+
+        Ok(query_result)
+    }
+
+    /// Submit a ticket -> the ticket then gets submitted, 'paid' and shows up in the ticket history,
+    /// see `BetAndTicketRepo::submit_ticket`
+    pub fn submit_ticket_in(
+        connection: &PgConnection,
+        desired_ticket_id: i32,
+        paid_price: f64,
+    ) -> anyhow::Result<i32> {
         // obtain current ticket, along with the bets
         let tickets_bets_games_and_latest_event: Vec<(Ticket, Bet, GameMatch, GameMatchEvent)> =
             ticket::table
@@ -328,11 +453,11 @@ impl BetAndTicketRepo for PgBetAndTicketRepo {
                     game_match::all_columns,
                     game_match_event::all_columns,
                 ))
-                .get_results(&connection)?;
+                .get_results(connection)?;
 
         // the ticket is empty
         if tickets_bets_games_and_latest_event.is_empty() {
-            anyhow::bail!("Cannot submit an empty ticket!");
+            anyhow::bail!(DomainError::validation("Cannot submit an empty ticket!"));
         }
 
         // find if there are matches that are not live
@@ -348,7 +473,7 @@ impl BetAndTicketRepo for PgBetAndTicketRepo {
 
         // not all matches are live!
         if are_some_not_live.is_some() {
-            anyhow::bail!("Cannot submit ticket with bets on matches that have ended or have not been played yet!");
+            anyhow::bail!(DomainError::betting_closed("Cannot submit ticket with bets on matches that have ended or have not been played yet!"));
         }
 
         // obtain the ticket
@@ -359,39 +484,80 @@ impl BetAndTicketRepo for PgBetAndTicketRepo {
             .collect();
 
         // check user balance first
-        let user_repo = PgUserRepo::new(&self.pool);
-        let balance: f64 = user_repo.get_balance(ticket.user_id).await?.parse()?;
+        let balance: f64 = PgUserRepo::get_balance_in(connection, ticket.user_id)?.parse()?;
 
         if balance < paid_price {
-            anyhow::bail!("You do not have enough balance to do that!")
+            anyhow::bail!(DomainError::InsufficientFunds {
+                balance,
+                required: paid_price
+            })
         } else if paid_price < 0.0 {
-            anyhow::bail!("Cannot pay with negative amount of currency")
+            anyhow::bail!(DomainError::validation(
+                "Cannot pay with negative amount of currency"
+            ))
         }
 
         // create the submit ticket now and create the submit bets now
         let submitted_ticket_id: i32 = insert_into(submitted_ticket::table)
             .values(ticket.submit(paid_price, &bets_and_matches)?)
             .returning(submitted_ticket::id)
-            .get_result(&connection)?;
+            .get_result(connection)?;
 
         let submitted_bets = Bet::submit_bets(submitted_ticket_id, &bets_and_matches)?;
 
         // add bets to the submitted ticket
         let _ = insert_into(submitted_bet::table)
             .values(submitted_bets)
-            .execute(&connection)?;
+            .execute(connection)?;
 
         // get ids of the bets that need to be deleted
         let original_bets_id: Vec<i32> = bets_and_matches.iter().map(|(bet, _)| bet.id).collect();
 
         // delete bets that are bound to the ticket
-        let _ = delete(bet::table.filter(bet::id.eq_any(original_bets_id))).execute(&connection)?;
+        let _ = delete(bet::table.filter(bet::id.eq_any(original_bets_id))).execute(connection)?;
+
+        // the share code of the ticket keeps pointing to the same bets after the submission
+        let _ = update(ticket_share_code::table.filter(ticket_share_code::ticket_id.eq(ticket.id)))
+            .set((
+                ticket_share_code::ticket_id.eq(None::<i32>),
+                ticket_share_code::submitted_ticket_id.eq(submitted_ticket_id),
+            ))
+            .execute(connection)?;
 
         // delete ticket
-        let _ = delete(ticket::table.filter(ticket::id.eq(ticket.id))).execute(&connection)?;
+        let _ = delete(ticket::table.filter(ticket::id.eq(ticket.id))).execute(connection)?;
 
-        user_repo.spend_balance(ticket.user_id, paid_price).await?;
+        PgUserRepo::spend_balance_in(connection, ticket.user_id, paid_price)?;
 
         Ok(submitted_ticket_id)
     }
+
+    /// Get IDs of the users holding a bet on the match in their open ticket,
+    /// see `BetAndTicketRepo::get_open_ticket_holders`
+    pub fn get_open_ticket_holders_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+    ) -> anyhow::Result<Vec<i32>> {
+        let query_result: Vec<i32> = bet::table
+            .inner_join(ticket::table)
+            .filter(bet::game_match_id.eq(desired_match_id))
+            .select(ticket::user_id)
+            .distinct()
+            .get_results(connection)?;
+
+        Ok(query_result)
+    }
+
+    /// Discard the bets on the match from all open tickets,
+    /// see `BetAndTicketRepo::discard_bets_on_match`
+    pub fn discard_bets_on_match_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+    ) -> anyhow::Result<Vec<i32>> {
+        let holders = PgBetAndTicketRepo::get_open_ticket_holders_in(connection, desired_match_id)?;
+        let _ = delete(bet::table.filter(bet::game_match_id.eq(desired_match_id)))
+            .execute(connection)?;
+
+        Ok(holders)
+    }
 }
@@ -0,0 +1,245 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{delete, insert_into, prelude::*, QueryDsl, RunQueryDsl};
+
+// type and structure imports
+use super::repo::Repo;
+use crate::db_models::event_outbox::{CreateOutboxEvent, OutboxEvent};
+
+// schema imports
+use crate::schema::event_outbox;
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with OutboxEvent records
+pub struct PgOutboxRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgOutboxRepo {
+    /// Create a new Outbox repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new Outbox repo
+    fn new(pool: &Arc<PgPool>) -> PgOutboxRepo {
+        PgOutboxRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+#[async_trait]
+pub trait OutboxRepo {
+    /// Make the changes and store the events describing them in a single transaction,
+    /// so the events are delivered if and only if the changes are committed
+    ///
+    /// Params
+    /// ---
+    /// - `changes`: makes the changes on the given connection and returns
+    ///   the result along with the serialized events
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(result)` with the result of the changes, once committed with the events
+    /// - `Err(_)` if the changes failed or an error occurred, nothing is committed then
+    async fn transaction<T, F>(&self, changes: F) -> anyhow::Result<T>
+    where
+        T: Send,
+        F: FnOnce(&PgConnection) -> anyhow::Result<(T, Vec<String>)> + Send;
+
+    /// Store the event, the listening instances get notified once it is committed
+    ///
+    /// Params
+    /// ---
+    /// - `payload`: the serialized event
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(id)` with the ID of the stored event
+    /// - `Err(_)` if an error occurred
+    async fn append(&self, payload: &str) -> anyhow::Result<i32>;
+
+    /// Get the ID of the latest stored event
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(id)` with the ID of the latest event, 0 if there are no events
+    /// - `Err(_)` if an error occurred
+    async fn get_last_id(&self) -> anyhow::Result<i32>;
+
+    /// Get the ID of the latest event stored before the desired time
+    ///
+    /// Params
+    /// ---
+    /// - `created_before`: timestamp in the format used for storing
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(id)` with the ID of the latest event stored before the time, 0 if there is none
+    /// - `Err(_)` if an error occurred
+    async fn get_last_id_before(&self, created_before: &str) -> anyhow::Result<i32>;
+
+    /// Get the IDs of the events stored after the desired one, ordered
+    ///
+    /// Params
+    /// ---
+    /// - `after_id`: ID of the last event which is not wanted
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(ids)` with the IDs of the events following the desired one
+    /// - `Err(_)` if an error occurred
+    async fn get_ids_after(&self, after_id: i32) -> anyhow::Result<Vec<i32>>;
+
+    /// Get the events stored after the desired one, ordered by their IDs
+    ///
+    /// Params
+    /// ---
+    /// - `after_id`: ID of the last event already read
+    /// - `limit`: maximum number of returned events
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(events)` with the events following the desired one
+    /// - `Err(_)` if an error occurred
+    async fn get_after(&self, after_id: i32, limit: i64) -> anyhow::Result<Vec<OutboxEvent>>;
+
+    /// Get the desired events which have been committed, ordered by their IDs
+    ///
+    /// Params
+    /// ---
+    /// - `desired_ids`: IDs of the events
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(events)` with the events found, the ones not committed yet are left out
+    /// - `Err(_)` if an error occurred
+    async fn get_by_ids(&self, desired_ids: &[i32]) -> anyhow::Result<Vec<OutboxEvent>>;
+
+    /// Delete the events stored before the desired time
+    ///
+    /// Params
+    /// ---
+    /// - `created_before`: timestamp in the format used for storing
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(count)` with the number of deleted events
+    /// - `Err(_)` if an error occurred
+    async fn delete_created_before(&self, created_before: &str) -> anyhow::Result<usize>;
+}
+
+#[async_trait]
+impl OutboxRepo for PgOutboxRepo {
+    /// Make the changes and store the events describing them in a single transaction
+    async fn transaction<T, F>(&self, changes: F) -> anyhow::Result<T>
+    where
+        T: Send,
+        F: FnOnce(&PgConnection) -> anyhow::Result<(T, Vec<String>)> + Send,
+    {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            let (result, payloads) = changes(&connection)?;
+            for payload in payloads.iter() {
+                PgOutboxRepo::append_in(&connection, payload)?;
+            }
+            Ok(result)
+        })
+    }
+
+    /// Store the event, the listening instances get notified once it is committed
+    async fn append(&self, payload: &str) -> anyhow::Result<i32> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgOutboxRepo::append_in(&connection, payload)
+    }
+
+    /// Get the ID of the latest stored event
+    async fn get_last_id(&self) -> anyhow::Result<i32> {
+        let last_id: Option<i32> = event_outbox::table
+            .select(diesel::dsl::max(event_outbox::id))
+            .first(&self.get_connection().await?)?;
+
+        Ok(last_id.unwrap_or(0))
+    }
+
+    /// Get the ID of the latest event stored before the desired time
+    async fn get_last_id_before(&self, created_before: &str) -> anyhow::Result<i32> {
+        let last_id: Option<i32> = event_outbox::table
+            .filter(event_outbox::created_at.lt(created_before))
+            .select(diesel::dsl::max(event_outbox::id))
+            .first(&self.get_connection().await?)?;
+
+        Ok(last_id.unwrap_or(0))
+    }
+
+    /// Get the IDs of the events stored after the desired one, ordered
+    async fn get_ids_after(&self, after_id: i32) -> anyhow::Result<Vec<i32>> {
+        let ids: Vec<i32> = event_outbox::table
+            .filter(event_outbox::id.gt(after_id))
+            .select(event_outbox::id)
+            .order(event_outbox::id.asc())
+            .get_results(&self.get_connection().await?)?;
+
+        Ok(ids)
+    }
+
+    /// Get the events stored after the desired one, ordered by their IDs
+    async fn get_after(&self, after_id: i32, limit: i64) -> anyhow::Result<Vec<OutboxEvent>> {
+        let events: Vec<OutboxEvent> = event_outbox::table
+            .filter(event_outbox::id.gt(after_id))
+            .order(event_outbox::id.asc())
+            .limit(limit)
+            .get_results(&self.get_connection().await?)?;
+
+        Ok(events)
+    }
+
+    /// Get the desired events which have been committed, ordered by their IDs
+    async fn get_by_ids(&self, desired_ids: &[i32]) -> anyhow::Result<Vec<OutboxEvent>> {
+        let events: Vec<OutboxEvent> = event_outbox::table
+            .filter(event_outbox::id.eq_any(desired_ids))
+            .order(event_outbox::id.asc())
+            .get_results(&self.get_connection().await?)?;
+
+        Ok(events)
+    }
+
+    /// Delete the events stored before the desired time
+    async fn delete_created_before(&self, created_before: &str) -> anyhow::Result<usize> {
+        let count = delete(event_outbox::table.filter(event_outbox::created_at.lt(created_before)))
+            .execute(&self.get_connection().await?)?;
+
+        Ok(count)
+    }
+}
+
+impl PgOutboxRepo {
+    /// Store the event within the transaction of the caller, see `OutboxRepo::append`
+    pub fn append_in(connection: &PgConnection, payload: &str) -> anyhow::Result<i32> {
+        let id: i32 = insert_into(event_outbox::table)
+            .values(CreateOutboxEvent::new(payload))
+            .returning(event_outbox::id)
+            .get_result(connection)?;
+
+        Ok(id)
+    }
+}
@@ -0,0 +1,137 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{insert_into, prelude::*, QueryDsl, RunQueryDsl};
+
+// type and structure imports
+use super::repo::Repo;
+use crate::db_models::external_reference::{CreateExternalReference, ExternalEntity};
+
+// schema imports
+use crate::schema::external_reference;
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with ExternalReference records
+pub struct PgExternalReferenceRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgExternalReferenceRepo {
+    /// Create a new ExternalReference repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new ExternalReference repo
+    fn new(pool: &Arc<PgPool>) -> PgExternalReferenceRepo {
+        PgExternalReferenceRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+#[async_trait]
+pub trait ExternalReferenceRepo {
+    /// Find the record known under the external ID
+    ///
+    /// Params
+    /// ---
+    /// - `entity`: kind of the record
+    /// - `external_id`: ID of the record in the outside source
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(Some(id))` with the ID of the record in our database
+    /// - `Ok(None)` if the external ID is not known
+    /// - `Err(_)` if an error occurred
+    async fn find(&self, entity: ExternalEntity, external_id: &str) -> anyhow::Result<Option<i32>>;
+
+    /// Remember the external ID of the record, replacing the previous record with the same external ID
+    ///
+    /// Params
+    /// ---
+    /// - `entity`: kind of the record
+    /// - `external_id`: ID of the record in the outside source
+    /// - `internal_id`: ID of the record in our database
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the reference has been stored
+    /// - `Err(_)` if an error occurred
+    async fn link(
+        &self,
+        entity: ExternalEntity,
+        external_id: &str,
+        internal_id: i32,
+    ) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+impl ExternalReferenceRepo for PgExternalReferenceRepo {
+    /// Find the record known under the external ID
+    async fn find(&self, entity: ExternalEntity, external_id: &str) -> anyhow::Result<Option<i32>> {
+        let internal_id: Option<i32> = external_reference::table
+            .filter(
+                external_reference::entity
+                    .eq(entity.as_str())
+                    .and(external_reference::external_id.eq(external_id)),
+            )
+            .select(external_reference::internal_id)
+            .get_result(&self.get_connection().await?)
+            .optional()?;
+
+        Ok(internal_id)
+    }
+
+    /// Remember the external ID of the record, replacing the previous record with the same external ID
+    async fn link(
+        &self,
+        entity: ExternalEntity,
+        external_id: &str,
+        internal_id: i32,
+    ) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgExternalReferenceRepo::link_in(&connection, entity, external_id, internal_id)
+    }
+}
+
+impl PgExternalReferenceRepo {
+    /// Remember the external ID of the record, replacing the previous record with the same external ID,
+    /// within the transaction of the caller, see `ExternalReferenceRepo::link`
+    pub fn link_in(
+        connection: &PgConnection,
+        entity: ExternalEntity,
+        external_id: &str,
+        internal_id: i32,
+    ) -> anyhow::Result<()> {
+        let _ = insert_into(external_reference::table)
+            .values(CreateExternalReference::new(
+                entity,
+                external_id,
+                internal_id,
+            ))
+            .on_conflict((external_reference::entity, external_reference::external_id))
+            .do_update()
+            .set(external_reference::internal_id.eq(internal_id))
+            .execute(connection)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{delete, insert_into, prelude::*, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
+
+// type and structure imports
+use super::repo::Repo;
+use crate::db_models::{
+    game_match::GameMatch,
+    user_favourite::{CreateUserFavourite, Favourite, UserFavourite},
+};
+
+// schema imports
+use crate::schema::{game_match, user_favourite};
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with UserFavourite records
+pub struct PgFavouriteRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgFavouriteRepo {
+    /// Create a new Favourite repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new Favourite repo
+    fn new(pool: &Arc<PgPool>) -> PgFavouriteRepo {
+        PgFavouriteRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+impl PgFavouriteRepo {
+    /// Filter of the `user_favourite` records pointing to the followed entity
+    fn favourite_filter(
+        desired_user_id: i32,
+        favourite: Favourite,
+    ) -> user_favourite::BoxedQuery<'static, diesel::pg::Pg> {
+        let query = user_favourite::table
+            .filter(user_favourite::user_id.eq(desired_user_id))
+            .into_boxed();
+
+        match favourite {
+            Favourite::Team(id) => query.filter(user_favourite::team_id.eq(id)),
+            Favourite::Game(id) => query.filter(user_favourite::game_id.eq(id)),
+            Favourite::Match(id) => query.filter(user_favourite::game_match_id.eq(id)),
+        }
+    }
+}
+
+#[async_trait]
+pub trait FavouriteRepo {
+    /// Follow a team, a game or a match
+    ///
+    /// Params
+    /// ---
+    /// - `desired_user_id`: ID of the user who wants to follow the entity
+    /// - `favourite`: the entity to follow
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(id)` with the ID of the new favourite record
+    /// - `Err(_)` if the user already follows the entity or an error occurred
+    async fn add(&self, desired_user_id: i32, favourite: Favourite) -> anyhow::Result<i32>;
+
+    /// Stop following a team, a game or a match
+    ///
+    /// Params
+    /// ---
+    /// - `desired_user_id`: ID of the user who wants to stop following the entity
+    /// - `favourite`: the followed entity
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the entity is no longer followed
+    /// - `Err(_)` if the user does not follow the entity or an error occurred
+    async fn remove(&self, desired_user_id: i32, favourite: Favourite) -> anyhow::Result<()>;
+
+    /// Get everything the user follows
+    ///
+    /// Params
+    /// ---
+    /// - `desired_user_id`: ID of the desired user
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(Vec<Favourite>)` with all followed teams, games and matches
+    /// - `Err(_)` if an error occurred
+    async fn get_all(&self, desired_user_id: i32) -> anyhow::Result<Vec<Favourite>>;
+
+    /// Get IDs of the users following the match,
+    /// either directly, or through one of its teams or its game
+    ///
+    /// Params
+    /// ---
+    /// - `desired_match_id`: ID of the desired match
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(Vec<user_id>)` with IDs of all followers (without duplicates)
+    /// - `Err(_)` if an error occurred
+    async fn get_match_followers(&self, desired_match_id: i32) -> anyhow::Result<Vec<i32>>;
+}
+
+#[async_trait]
+impl FavouriteRepo for PgFavouriteRepo {
+    /// Follow a team, a game or a match
+    async fn add(&self, desired_user_id: i32, favourite: Favourite) -> anyhow::Result<i32> {
+        // nothing gets inserted if the user already follows the entity
+        let query_result: Option<i32> = insert_into(user_favourite::table)
+            .values(CreateUserFavourite::new(desired_user_id, favourite))
+            .on_conflict_do_nothing()
+            .returning(user_favourite::id)
+            .get_result(&self.get_connection().await?)
+            .optional()?;
+
+        match query_result {
+            Some(id) => Ok(id),
+            None => anyhow::bail!(DomainError::conflict("The user already follows this!")),
+        }
+    }
+
+    /// Stop following a team, a game or a match
+    async fn remove(&self, desired_user_id: i32, favourite: Favourite) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+
+        let favourite_ids: Vec<i32> = Self::favourite_filter(desired_user_id, favourite)
+            .select(user_favourite::id)
+            .get_results(&connection)?;
+
+        if favourite_ids.is_empty() {
+            anyhow::bail!(DomainError::not_found("The user does not follow this!"));
+        }
+
+        let _ = delete(user_favourite::table.filter(user_favourite::id.eq_any(favourite_ids)))
+            .execute(&connection)?;
+
+        Ok(())
+    }
+
+    /// Get everything the user follows
+    async fn get_all(&self, desired_user_id: i32) -> anyhow::Result<Vec<Favourite>> {
+        let query_result: Vec<UserFavourite> = user_favourite::table
+            .filter(user_favourite::user_id.eq(desired_user_id))
+            .order(user_favourite::created_at.desc())
+            .get_results(&self.get_connection().await?)?;
+
+        query_result
+            .iter()
+            .map(|favourite| favourite.extract_favourite())
+            .collect()
+    }
+
+    /// Get IDs of the users following the match
+    async fn get_match_followers(&self, desired_match_id: i32) -> anyhow::Result<Vec<i32>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+
+        let game_match: GameMatch = game_match::table
+            .find(desired_match_id)
+            .get_result(&connection)?;
+
+        let query_result: Vec<i32> = user_favourite::table
+            .filter(user_favourite::game_match_id.eq(game_match.id))
+            .or_filter(user_favourite::game_id.eq(game_match.game_id))
+            .or_filter(
+                user_favourite::team_id
+                    .eq_any(vec![game_match.team_one_id, game_match.team_two_id]),
+            )
+            .select(user_favourite::user_id)
+            .distinct()
+            .get_results(&connection)?;
+
+        Ok(query_result)
+    }
+}
@@ -2,15 +2,21 @@ use async_trait::async_trait;
 use std::sync::Arc;
 
 use crate::connection::{PgPool, PgPooledConnection};
-use crate::diesel::{insert_into, prelude::*, update, QueryDsl, RunQueryDsl};
+use crate::diesel::{delete, insert_into, prelude::*, update, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
 
 // type and structure imports
 use super::repo::Repo;
-use crate::db_models::game::{CreateGame, Game};
-use crate::result_types::{GameInfo, TeamInfo};
+use crate::db_models::{
+    external_reference::ExternalEntity,
+    game::{CreateGame, Game},
+};
+use crate::result_types::TeamInfo;
 
 // schema imports
-use crate::schema::{game, team, team_plays_game};
+use crate::schema::{
+    external_reference, game, game_match, player, team, team_plays_game, tournament, user_favourite,
+};
 
 pub struct PgGameRepo {
     pub pool: Arc<PgPool>,
@@ -72,13 +78,61 @@ pub trait GameRepo {
     /// - Err(_) if an error occurred
     async fn edit(&self, desired_game_id: i32, edited_game: CreateGame) -> anyhow::Result<()>;
 
-    /// Get all game names, id's and image urls
+    /// Get all games, ordered by their name
+    ///
+    /// Params
+    /// ---
+    /// - include_archived: list the archived games as well
     ///
     /// Returns
     /// ---
-    /// - Ok(Vec<(id, name, logo)>) on successful database connection
+    /// - Ok(games) on successful database connection
     /// - Err(_) otherwise
-    async fn get_all(&self) -> anyhow::Result<Vec<GameInfo>>;
+    async fn get_all(&self, include_archived: bool) -> anyhow::Result<Vec<Game>>;
+
+    /// Archive a game or bring it back from the archive
+    ///
+    /// Params
+    /// ---
+    /// - desired_game_id: ID of the game
+    /// - archived: whether the game should be archived
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(()) if the game has been found and updated
+    /// - Err(_) if an error occurred
+    async fn set_archived(&self, desired_game_id: i32, archived: bool) -> anyhow::Result<()>;
+
+    /// Point the logo of the game to an uploaded image, or drop the reference to it
+    ///
+    /// Params
+    /// ---
+    /// - desired_game_id: ID of the game
+    /// - logo_asset_id: ID of the uploaded image, None if the logo is not an uploaded one
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(()) if the game has been found and updated
+    /// - Err(_) if the image does not exist or an error occurred
+    async fn set_logo_asset(
+        &self,
+        desired_game_id: i32,
+        logo_asset_id: Option<i32>,
+    ) -> anyhow::Result<()>;
+
+    /// Delete a game along with the registrations of its teams and the favourites of the users.
+    /// Only possible if no matches, tournaments or players belong to the game,
+    /// such games can be archived instead.
+    ///
+    /// Params
+    /// ---
+    /// - desired_game_id: ID of the game
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(game) with the deleted game
+    /// - Err(_) if the game is still referenced or an error occurred
+    async fn delete(&self, desired_game_id: i32) -> anyhow::Result<Game>;
 
     /// Get all teams that are playing a specific game
     ///
@@ -109,21 +163,14 @@ pub trait GameRepo {
 impl GameRepo for PgGameRepo {
     /// Create a new Game record in the database
     async fn create(&self, new_game: CreateGame) -> anyhow::Result<i32> {
-        let id: i32 = insert_into(game::table)
-            .values(new_game)
-            .returning(game::id)
-            .get_result(&self.get_connection().await?)?;
-
-        Ok(id)
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgGameRepo::create_in(&connection, new_game)
     }
 
     /// Edit a certain game specified by id
     async fn edit(&self, desired_game_id: i32, edited_game: CreateGame) -> anyhow::Result<()> {
-        let _ = update(game::table.find(desired_game_id))
-            .set(edited_game)
-            .execute(&self.get_connection().await?)?;
-
-        Ok(())
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgGameRepo::edit_in(&connection, desired_game_id, edited_game)
     }
 
     /// Get details about one specific game
@@ -135,16 +182,94 @@ impl GameRepo for PgGameRepo {
         Ok(query_result)
     }
 
-    /// Get all game names and image urls
-    async fn get_all(&self) -> anyhow::Result<Vec<GameInfo>> {
-        let query_result: Vec<GameInfo> = game::table
-            .order(game::name.asc())
-            .select((game::id, game::name, game::logo))
-            .get_results(&self.get_connection().await?)?;
+    /// Get all games, ordered by their name
+    async fn get_all(&self, include_archived: bool) -> anyhow::Result<Vec<Game>> {
+        let query_result: Vec<Game> = match include_archived {
+            true => game::table
+                .order(game::name.asc())
+                .get_results(&self.get_connection().await?)?,
+            false => game::table
+                .filter(game::archived.eq(false))
+                .order(game::name.asc())
+                .get_results(&self.get_connection().await?)?,
+        };
 
         Ok(query_result)
     }
 
+    /// Archive a game or bring it back from the archive
+    async fn set_archived(&self, desired_game_id: i32, archived: bool) -> anyhow::Result<()> {
+        let updated: usize = update(game::table.find(desired_game_id))
+            .set(game::archived.eq(archived))
+            .execute(&self.get_connection().await?)?;
+
+        if updated == 0 {
+            anyhow::bail!(DomainError::not_found("The game does not exist"));
+        }
+        Ok(())
+    }
+
+    /// Point the logo of the game to an uploaded image, or drop the reference to it
+    async fn set_logo_asset(
+        &self,
+        desired_game_id: i32,
+        logo_asset_id: Option<i32>,
+    ) -> anyhow::Result<()> {
+        let updated: usize = update(game::table.find(desired_game_id))
+            .set(game::logo_asset_id.eq(logo_asset_id))
+            .execute(&self.get_connection().await?)?;
+
+        if updated == 0 {
+            anyhow::bail!(DomainError::not_found("The game does not exist"));
+        }
+        Ok(())
+    }
+
+    /// Delete a game which is not referenced by any matches, tournaments or players
+    async fn delete(&self, desired_game_id: i32) -> anyhow::Result<Game> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        let to_be_removed: Game = game::table.find(desired_game_id).get_result(&connection)?;
+
+        let matches: usize = game_match::table
+            .filter(game_match::game_id.eq(desired_game_id))
+            .execute(&connection)?;
+        if matches > 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot delete a game with matches, archive it instead"
+            ));
+        }
+        let tournaments: usize = tournament::table
+            .filter(tournament::game_id.eq(desired_game_id))
+            .execute(&connection)?;
+        if tournaments > 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot delete a game with tournaments, archive it instead"
+            ));
+        }
+        let players: usize = player::table
+            .filter(player::game_id.eq(desired_game_id))
+            .execute(&connection)?;
+        if players > 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot delete a game with players, archive it instead"
+            ));
+        }
+
+        let _ = delete(team_plays_game::table.filter(team_plays_game::game_id.eq(desired_game_id)))
+            .execute(&connection)?;
+        let _ = delete(user_favourite::table.filter(user_favourite::game_id.eq(desired_game_id)))
+            .execute(&connection)?;
+        let _ = delete(
+            external_reference::table
+                .filter(external_reference::entity.eq(ExternalEntity::Game.as_str()))
+                .filter(external_reference::internal_id.eq(desired_game_id)),
+        )
+        .execute(&connection)?;
+        let _ = delete(game::table.find(desired_game_id)).execute(&connection)?;
+
+        Ok(to_be_removed)
+    }
+
     /// Get all teams that are playing a specific game
     async fn get_teams_playing(&self, desired_game_id: i32) -> anyhow::Result<Vec<TeamInfo>> {
         let query_result: Vec<TeamInfo> = game::table
@@ -157,3 +282,28 @@ impl GameRepo for PgGameRepo {
         Ok(query_result)
     }
 }
+
+impl PgGameRepo {
+    /// Create a new Game record within the transaction of the caller, see `GameRepo::create`
+    pub fn create_in(connection: &PgConnection, new_game: CreateGame) -> anyhow::Result<i32> {
+        let id: i32 = insert_into(game::table)
+            .values(new_game)
+            .returning(game::id)
+            .get_result(connection)?;
+
+        Ok(id)
+    }
+
+    /// Edit a certain game within the transaction of the caller, see `GameRepo::edit`
+    pub fn edit_in(
+        connection: &PgConnection,
+        desired_game_id: i32,
+        edited_game: CreateGame,
+    ) -> anyhow::Result<()> {
+        let _ = update(game::table.find(desired_game_id))
+            .set(edited_game)
+            .execute(connection)?;
+
+        Ok(())
+    }
+}
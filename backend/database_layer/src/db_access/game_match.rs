@@ -3,22 +3,27 @@ use std::sync::Arc;
 
 use crate::connection::{PgPool, PgPooledConnection};
 use crate::diesel::{delete, insert_into, prelude::*, update, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
 use crate::type_storing::time_handling::TimeHandling;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 
 // type and structure imports
 use super::repo::Repo;
 use crate::db_models::{
-    game_match::{CreateGameMatch, GameMatch},
+    bet_market::BetOutcome,
+    game_match::{CreateGameMatch, GameMatch, GameMatchUpdate},
     game_match_event::{
         CreateGameMatchEvent, GameMatchEvent, GameMatchEventFilter, GameMatchEventType,
     },
+    match_map::{series_score, MatchMap},
     submitted_bet::SubmittedBet,
+    submitted_ticket::SubmittedTicket,
 };
 
 // schema imports
 use crate::schema::{
-    bet, game, game_match, game_match_event, submitted_bet, team, team_plays_game,
+    bet, game, game_match, game_match_event, match_map, match_timeline_event, stage_match,
+    submitted_bet, submitted_ticket, team, team_plays_game, user_favourite,
 };
 
 /// Structure containing a reference to a database connection pool
@@ -145,6 +150,62 @@ pub trait MatchRepo {
     /// - Err(_) if an error has occurred
     async fn update_status(&self, desired_match_id: i32, new_status: &str) -> anyhow::Result<()>;
 
+    /// Update the editable properties of a match which has not started yet
+    ///
+    /// Params
+    /// ---
+    /// - desired_match_id: ID of the match we wish to update
+    /// - match_update: new ratios, start and display string of the match
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(()) if the update was successful
+    /// - Err(_) if the match is no longer upcoming or an error has occurred
+    async fn update(
+        &self,
+        desired_match_id: i32,
+        match_update: GameMatchUpdate,
+    ) -> anyhow::Result<()>;
+
+    /// Replace the teams of a match which has not started yet,
+    /// both teams have to play the game of the match and none of them can be archived
+    ///
+    /// Params
+    /// ---
+    /// - desired_match_id: ID of the match we wish to update
+    /// - team_one_id: ID of the new first team
+    /// - team_two_id: ID of the new second team
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(()) if the teams have been replaced
+    /// - Err(_) if the match is no longer upcoming, the teams cannot play it or an error has occurred
+    async fn change_teams(
+        &self,
+        desired_match_id: i32,
+        team_one_id: i32,
+        team_two_id: i32,
+    ) -> anyhow::Result<()>;
+
+    /// Move a match to another start. A match which has already started is played again
+    /// from the start, its phases, maps and timeline are dropped and its undecided bets voided
+    ///
+    /// Params
+    /// ---
+    /// - desired_match_id: ID of the match we wish to move
+    /// - supposed_start_at: the new start of the match
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(user_ids) with IDs of the users whose submitted bets have been voided (without duplicates)
+    /// - Err(_) if the match has ended or been cancelled, the start is in the past
+    ///   or an error has occurred
+    async fn reschedule(
+        &self,
+        desired_match_id: i32,
+        supposed_start_at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<i32>>;
+
     /// Obtain matches' game ratios.
     /// Useful for recalculating the new ratio values.
     ///
@@ -207,17 +268,218 @@ pub trait MatchRepo {
     /// - Err(_) if there was an internal consistency error / connection error etc
     async fn newest_event(&self, desired_match_id: i32) -> anyhow::Result<GameMatchEvent>;
 
-    /// Evaluate all submitted bets for this match
-    /// Set the `won` field of the `submitted_bet` table to either false or true.
+    /// Get the ended matches of a team together with their `Ended` events
+    ///
+    /// Params
+    /// ---
+    /// - desired_team_id: ID of the team which played the matches
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(results) ordered from the most recently ended match
+    /// - Err(_) if an error has occurred
+    async fn get_team_results(
+        &self,
+        desired_team_id: i32,
+    ) -> anyhow::Result<Vec<(GameMatch, GameMatchEvent)>>;
+
+    /// Evaluate the submitted bets for this match which have been decided by its result or its finished maps
+    /// Set the `won` field of the `submitted_bet` table to either false or true,
+    /// the bets on the maps which have not been played count with the ratio of 1.
     async fn evaluate_bets(&self, desired_match_id: i32) -> anyhow::Result<()>;
 }
 
 #[async_trait]
 impl MatchRepo for PgMatchRepo {
     /// Create a new game match structure (and set the latest event of the match to upcoming)
-    /// Additionally, it checks that both teams are playing the chosen game
+    /// Additionally, it checks that both teams are playing the chosen game and none of them is archived
     async fn create(&self, new_match: CreateGameMatch) -> anyhow::Result<i32> {
         let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| PgMatchRepo::create_in(&connection, new_match))
+    }
+
+    /// Delete a game match
+    /// Only possible if there are no bets tied to the match yet.
+    /// Deleting the match does not work, if the match is about to start in 2 seconds
+    async fn delete(&self, desired_match_id: i32) -> anyhow::Result<GameMatch> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| PgMatchRepo::delete_in(&connection, desired_match_id))
+    }
+
+    /// Get a desired match by its ID
+    async fn get(&self, desired_match_id: i32) -> anyhow::Result<GameMatch> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgMatchRepo::get_in(&connection, desired_match_id)
+    }
+
+    /// Get a desired match (together with display information) by its ID
+    async fn get_show_info(
+        &self,
+        desired_match_id: i32,
+    ) -> anyhow::Result<(GameMatch, GameMatchEvent)> {
+        let query_result: (GameMatch, GameMatchEvent) = game_match::table
+            .filter(game_match::id.eq(desired_match_id))
+            .inner_join(game_match_event::table)
+            .order((game_match::id, game_match_event::created_at.desc()))
+            .distinct_on(game_match::id)
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(query_result)
+    }
+
+    /// Get all matches, optionally we can filter by the time period (upcoming),
+    /// filter by the team and filter by the game
+    async fn get_all_show_info(
+        &self,
+        filter_by_time_period: Option<GameMatchEventFilter>,
+        filter_by_game: Option<i32>,
+    ) -> anyhow::Result<Vec<(GameMatch, GameMatchEvent)>> {
+        let basic_query: Vec<(GameMatch, GameMatchEvent)> = game_match::table
+            .inner_join(game_match_event::table)
+            .order((game_match::id, game_match_event::created_at.desc()))
+            .distinct_on(game_match::id)
+            .get_results(&self.get_connection().await?)?;
+
+        // filter by method parameters
+        let query_result: Vec<(GameMatch, GameMatchEvent)> =
+            match (filter_by_time_period, filter_by_game) {
+                (Some(period), Some(game)) => basic_query
+                    .into_iter()
+                    .filter(|(game_match, game_event)| {
+                        game_match.game_id == game && game_event.event_type == period.to_string()
+                    })
+                    .collect(),
+                (Some(period), None) => basic_query
+                    .into_iter()
+                    .filter(|(_, game_event)| game_event.event_type == period.to_string())
+                    .collect(),
+
+                (None, Some(game)) => basic_query
+                    .into_iter()
+                    .filter(|(game_match, _)| game_match.game_id == game)
+                    .collect(),
+                _ => basic_query,
+            };
+
+        Ok(query_result)
+    }
+
+    /// Update match display string -> status
+    async fn update_status(&self, desired_match_id: i32, new_status: &str) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgMatchRepo::update_status_in(&connection, desired_match_id, new_status)
+    }
+
+    /// Update the editable properties of a match which has not started yet
+    async fn update(
+        &self,
+        desired_match_id: i32,
+        match_update: GameMatchUpdate,
+    ) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection
+            .transaction(|| PgMatchRepo::update_in(&connection, desired_match_id, match_update))
+    }
+
+    /// Replace the teams of a match which has not started yet
+    async fn change_teams(
+        &self,
+        desired_match_id: i32,
+        team_one_id: i32,
+        team_two_id: i32,
+    ) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            PgMatchRepo::change_teams_in(&connection, desired_match_id, team_one_id, team_two_id)
+        })
+    }
+
+    /// Move a match to another start, playing it again if it has already started
+    async fn reschedule(
+        &self,
+        desired_match_id: i32,
+        supposed_start_at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<i32>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            PgMatchRepo::reschedule_in(&connection, desired_match_id, supposed_start_at)
+        })
+    }
+
+    /// Obtain matches' game ratios.
+    /// Useful for recalculating the new ratio values.
+    async fn get_ratios(&self, desired_match_id: i32) -> anyhow::Result<(f64, f64)> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgMatchRepo::get_ratios_in(&connection, desired_match_id)
+    }
+
+    /// Set matches' game ratios. Useful after placing a bet
+    async fn set_ratios(
+        &self,
+        desired_match_id: i32,
+        first: f64,
+        second: f64,
+    ) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgMatchRepo::set_ratios_in(&connection, desired_match_id, first, second)
+    }
+
+    /// Create an event for the match
+    /// Fails if such event already exists
+    async fn create_event(
+        &self,
+        desired_match_id: i32,
+        desired_event_type: GameMatchEventType,
+    ) -> anyhow::Result<i32> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            PgMatchRepo::create_event_in(&connection, desired_match_id, desired_event_type)
+        })
+    }
+
+    /// Get newest event of the match
+    async fn newest_event(&self, desired_match_id: i32) -> anyhow::Result<GameMatchEvent> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgMatchRepo::newest_event_in(&connection, desired_match_id)
+    }
+
+    /// Get the ended matches of a team together with their `Ended` events
+    async fn get_team_results(
+        &self,
+        desired_team_id: i32,
+    ) -> anyhow::Result<Vec<(GameMatch, GameMatchEvent)>> {
+        let mut query_result: Vec<(GameMatch, GameMatchEvent)> = game_match::table
+            .inner_join(game_match_event::table)
+            .filter(
+                game_match::team_one_id
+                    .eq(desired_team_id)
+                    .or(game_match::team_two_id.eq(desired_team_id)),
+            )
+            .filter(game_match_event::event_type.eq(GameMatchEventFilter::Ended.to_string()))
+            .get_results(&self.get_connection().await?)?;
+
+        // the timestamps are stored as text, so they are compared once parsed
+        query_result.sort_by_cached_key(|(game_match, ended)| {
+            (
+                TimeHandling::load_timestamp(&ended.created_at).ok(),
+                game_match.id,
+            )
+        });
+        query_result.reverse();
+        Ok(query_result)
+    }
+
+    async fn evaluate_bets(&self, desired_match_id: i32) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| PgMatchRepo::evaluate_bets_in(&connection, desired_match_id))
+    }
+}
+
+/// The changes of the matches made within the transaction of the caller,
+/// so they can be committed together with other changes, see `OutboxRepo::transaction`
+impl PgMatchRepo {
+    /// Create a new game match, see `MatchRepo::create`
+    pub fn create_in(connection: &PgConnection, new_match: CreateGameMatch) -> anyhow::Result<i32> {
         // Check if both teams are playing the game
         let both_teams_play_the_game: usize = team_plays_game::table
             .filter(
@@ -230,179 +492,273 @@ impl MatchRepo for PgMatchRepo {
                     .eq(new_match.team_two_id)
                     .and(team_plays_game::game_id.eq(new_match.game_id)),
             )
-            .execute(&connection)?;
+            .execute(connection)?;
 
         // possible results
         match both_teams_play_the_game {
-            0 => anyhow::bail!("None of the teams selected is playing this game"),
-            1 => anyhow::bail!("One of the teams selected is not playing this game"),
+            0 => anyhow::bail!(DomainError::validation("None of the teams selected is playing this game")),
+            1 => anyhow::bail!(DomainError::validation("One of the teams selected is not playing this game")),
             2 => {}
-            _ => anyhow::bail!("Internal error!!! More occurrences of the same team playing the game multiple times!"),
+            _ => anyhow::bail!(DomainError::internal("Internal error!!! More occurrences of the same team playing the game multiple times!")),
         }
 
-        let team_one_name: String = team::table
+        let (team_one_name, team_one_archived): (String, bool) = team::table
             .find(new_match.team_one_id)
-            .select(team::name)
-            .get_result(&connection)?;
+            .select((team::name, team::archived))
+            .get_result(connection)?;
 
-        let team_two_name: String = team::table
+        let (team_two_name, team_two_archived): (String, bool) = team::table
             .find(new_match.team_two_id)
-            .select(team::name)
-            .get_result(&connection)?;
+            .select((team::name, team::archived))
+            .get_result(connection)?;
 
-        let game_name: String = game::table
+        let (game_name, game_archived): (String, bool) = game::table
             .find(new_match.game_id)
-            .select(game::name)
-            .get_result(&connection)?;
+            .select((game::name, game::archived))
+            .get_result(connection)?;
+
+        if team_one_archived || team_two_archived || game_archived {
+            anyhow::bail!(DomainError::conflict(
+                "Archived games and teams cannot get new matches"
+            ));
+        }
 
         // create the game match
         let query_result: i32 = insert_into(game_match::table)
             .values(new_match.store(&game_name, &team_one_name, &team_two_name))
             .returning(game_match::id)
-            .get_result(&connection)?;
+            .get_result(connection)?;
         // create an upcoming event for the new match
-        self.create_event(query_result, GameMatchEventType::Upcoming)
-            .await?;
+        PgMatchRepo::create_event_in(connection, query_result, GameMatchEventType::Upcoming)?;
         Ok(query_result)
     }
 
-    /// Delete a game match
-    /// Only possible if there are no bets tied to the match yet.
-    /// Deleting the match does not work, if the match is about to start in 2 seconds
-    async fn delete(&self, desired_match_id: i32) -> anyhow::Result<GameMatch> {
-        let connection: PgPooledConnection = self.get_connection().await?;
-
+    /// Delete a game match, see `MatchRepo::delete`
+    pub fn delete_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+    ) -> anyhow::Result<GameMatch> {
         let any_bets: usize = submitted_bet::table
             .filter(submitted_bet::game_match_id.eq(desired_match_id))
-            .execute(&connection)?;
+            .execute(connection)?;
 
         // there are bets submitted on the match
         if any_bets > 0 {
-            anyhow::bail!("Cannot delete a game match, if there are submitted bets placed on it");
+            anyhow::bail!(DomainError::conflict(
+                "Cannot delete a game match, if there are submitted bets placed on it"
+            ));
         }
 
         let to_be_removed: GameMatch = game_match::table
             .find(desired_match_id)
-            .get_result(&connection)?;
+            .get_result(connection)?;
 
         // cannot delete an already starting
         if TimeHandling::load_timestamp(&to_be_removed.supposed_start_at)?
             < (Utc::now() + Duration::seconds(2))
         {
-            anyhow::bail!("Cannot delete a game match, if it is about to start!");
+            anyhow::bail!(DomainError::conflict(
+                "Cannot delete a game match, if it is about to start!"
+            ));
         }
 
         // remove all events first
         let _ = delete(
             game_match_event::table.filter(game_match_event::game_match_id.eq(desired_match_id)),
         )
-        .execute(&connection)?;
+        .execute(connection)?;
+        let _ = delete(
+            match_timeline_event::table
+                .filter(match_timeline_event::game_match_id.eq(desired_match_id)),
+        )
+        .execute(connection)?;
+        let _ = delete(stage_match::table.filter(stage_match::game_match_id.eq(desired_match_id)))
+            .execute(connection)?;
+        let _ = delete(match_map::table.filter(match_map::game_match_id.eq(desired_match_id)))
+            .execute(connection)?;
+        let _ = delete(
+            user_favourite::table.filter(user_favourite::game_match_id.eq(desired_match_id)),
+        )
+        .execute(connection)?;
 
         // remove all unsubmitted bets second
         let _ = delete(bet::table.filter(bet::game_match_id.eq(desired_match_id)))
-            .execute(&connection)?;
+            .execute(connection)?;
 
         // remove the match
-        let _ = delete(game_match::table.find(desired_match_id)).execute(&connection)?;
+        let _ = delete(game_match::table.find(desired_match_id)).execute(connection)?;
 
         Ok(to_be_removed)
     }
 
-    /// Get a desired match by its ID
-    async fn get(&self, desired_match_id: i32) -> anyhow::Result<GameMatch> {
+    /// Get a desired match by its ID, see `MatchRepo::get`
+    pub fn get_in(connection: &PgConnection, desired_match_id: i32) -> anyhow::Result<GameMatch> {
         let query_result: GameMatch = game_match::table
             .find(desired_match_id)
-            .get_result(&self.get_connection().await?)?;
+            .get_result(connection)?;
 
         Ok(query_result)
     }
 
-    /// Get a desired match (together with display information) by its ID
-    async fn get_show_info(
-        &self,
+    /// Update match display string, see `MatchRepo::update_status`
+    pub fn update_status_in(
+        connection: &PgConnection,
         desired_match_id: i32,
-    ) -> anyhow::Result<(GameMatch, GameMatchEvent)> {
-        let query_result: (GameMatch, GameMatchEvent) = game_match::table
-            .filter(game_match::id.eq(desired_match_id))
-            .inner_join(game_match_event::table)
-            .order((game_match::id, game_match_event::created_at.desc()))
-            .distinct_on(game_match::id)
-            .get_result(&self.get_connection().await?)?;
-
-        Ok(query_result)
-    }
-
-    /// Get all matches, optionally we can filter by the time period (upcoming),
-    /// filter by the team and filter by the game
-    async fn get_all_show_info(
-        &self,
-        filter_by_time_period: Option<GameMatchEventFilter>,
-        filter_by_game: Option<i32>,
-    ) -> anyhow::Result<Vec<(GameMatch, GameMatchEvent)>> {
-        let basic_query: Vec<(GameMatch, GameMatchEvent)> = game_match::table
-            .inner_join(game_match_event::table)
-            .order((game_match::id, game_match_event::created_at.desc()))
-            .distinct_on(game_match::id)
-            .get_results(&self.get_connection().await?)?;
-
-        // filter by method parameters
-        let query_result: Vec<(GameMatch, GameMatchEvent)> =
-            match (filter_by_time_period, filter_by_game) {
-                (Some(period), Some(game)) => basic_query
-                    .into_iter()
-                    .filter(|(game_match, game_event)| {
-                        game_match.game_id == game && game_event.event_type == period.to_string()
-                    })
-                    .collect(),
-                (Some(period), None) => basic_query
-                    .into_iter()
-                    .filter(|(_, game_event)| game_event.event_type == period.to_string())
-                    .collect(),
+        new_status: &str,
+    ) -> anyhow::Result<()> {
+        let number_of_effected_rows: usize =
+            update(game_match::table.filter(game_match::id.eq(desired_match_id)))
+                .set(game_match::state.eq(new_status))
+                .execute(connection)?;
 
-                (None, Some(game)) => basic_query
-                    .into_iter()
-                    .filter(|(game_match, _)| game_match.game_id == game)
-                    .collect(),
-                _ => basic_query,
-            };
+        match number_of_effected_rows {
+            0 => anyhow::bail!(DomainError::not_found("No match was updated")),
+            1 => {}
+            _ => anyhow::bail!(DomainError::internal(
+                "Internal error -> multiple matches have been updated"
+            )),
+        }
 
-        Ok(query_result)
+        Ok(())
     }
 
-    /// Update match display string -> status
-    async fn update_status(&self, desired_match_id: i32, new_status: &str) -> anyhow::Result<()> {
+    /// Update the editable properties of a match which has not started yet,
+    /// see `MatchRepo::update`
+    pub fn update_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+        match_update: GameMatchUpdate,
+    ) -> anyhow::Result<()> {
+        if !PgMatchRepo::newest_event_in(connection, desired_match_id)?
+            .extract_event()?
+            .cmp_type(&GameMatchEventType::Upcoming)
+        {
+            anyhow::bail!(DomainError::conflict(
+                "Only the matches which have not started yet can be updated"
+            ));
+        }
+
         let number_of_effected_rows: usize =
             update(game_match::table.filter(game_match::id.eq(desired_match_id)))
-                .set(game_match::state.eq(new_status))
-                .execute(&self.get_connection().await?)?;
+                .set(match_update)
+                .execute(connection)?;
 
         match number_of_effected_rows {
-            0 => anyhow::bail!("No match was updated"),
+            0 => anyhow::bail!(DomainError::not_found("No match was updated")),
             1 => {}
-            _ => anyhow::bail!("Internal error -> multiple matches have been updated"),
+            _ => anyhow::bail!(DomainError::internal(
+                "Internal error -> multiple matches have been updated"
+            )),
         }
 
         Ok(())
     }
 
-    /// Obtain matches' game ratios.
-    /// Useful for recalculating the new ratio values.
-    async fn get_ratios(&self, desired_match_id: i32) -> anyhow::Result<(f64, f64)> {
+    /// Replace the teams of a match which has not started yet, see `MatchRepo::change_teams`
+    pub fn change_teams_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+        team_one_id: i32,
+        team_two_id: i32,
+    ) -> anyhow::Result<()> {
+        if team_one_id == team_two_id {
+            anyhow::bail!(DomainError::validation("A team cannot play against itself"));
+        }
+        if !PgMatchRepo::newest_event_in(connection, desired_match_id)?
+            .extract_event()?
+            .cmp_type(&GameMatchEventType::Upcoming)
+        {
+            anyhow::bail!(DomainError::conflict(
+                "Only the matches which have not started yet can be updated"
+            ));
+        }
+
+        let game_match: GameMatch = game_match::table
+            .find(desired_match_id)
+            .get_result(connection)?;
+
+        let mut names = Vec::new();
+        for team_id in [team_one_id, team_two_id] {
+            let plays_the_game: usize = team_plays_game::table
+                .filter(team_plays_game::team_id.eq(team_id))
+                .filter(team_plays_game::game_id.eq(game_match.game_id))
+                .execute(connection)?;
+            if plays_the_game == 0 {
+                anyhow::bail!(DomainError::validation(
+                    "One of the teams selected is not playing this game"
+                ));
+            }
+            let (name, archived): (String, bool) = team::table
+                .find(team_id)
+                .select((team::name, team::archived))
+                .get_result(connection)?;
+            if archived {
+                anyhow::bail!(DomainError::conflict(
+                    "Archived teams cannot get new matches"
+                ));
+            }
+            names.push(name);
+        }
+
+        let _ = update(game_match::table.find(desired_match_id))
+            .set((
+                game_match::team_one_id.eq(team_one_id),
+                game_match::team_one_name.eq(&names[0]),
+                game_match::team_two_id.eq(team_two_id),
+                game_match::team_two_name.eq(&names[1]),
+            ))
+            .execute(connection)?;
+
+        Ok(())
+    }
+
+    /// Move a match to another start, playing it again if it has already started,
+    /// see `MatchRepo::reschedule`
+    pub fn reschedule_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+        supposed_start_at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<i32>> {
+        let game_match = PgMatchRepo::get_in(connection, desired_match_id)?;
+        let match_update = game_match.to_update(None, None, Some(supposed_start_at), None)?;
+
+        let voided_user_ids =
+            match PgMatchRepo::newest_event_in(connection, desired_match_id)?.extract_event()? {
+                GameMatchEventType::Upcoming => Vec::new(),
+                GameMatchEventType::Live | GameMatchEventType::Overtime => {
+                    restart_match(connection, desired_match_id)?
+                }
+                GameMatchEventType::Cancelled | GameMatchEventType::Ended(_) => {
+                    anyhow::bail!(DomainError::conflict(
+                        "A match which has ended or been cancelled cannot be rescheduled"
+                    ))
+                }
+            };
+
+        PgMatchRepo::update_in(connection, desired_match_id, match_update)?;
+        Ok(voided_user_ids)
+    }
+
+    /// Obtain matches' game ratios, see `MatchRepo::get_ratios`
+    pub fn get_ratios_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+    ) -> anyhow::Result<(f64, f64)> {
         let (first_ratio_retrieved, second_ratio_retrieved): (String, String) = game_match::table
             .find(desired_match_id)
             .select((game_match::team_one_ratio, game_match::team_two_ratio))
-            .get_result(&self.get_connection().await?)?;
+            .get_result(connection)?;
 
         // return the retrieved values
         match (first_ratio_retrieved.parse::<f64>(), second_ratio_retrieved.parse::<f64>()) {
             (Ok(first), Ok(second)) => Ok((first, second)),
-            _ => anyhow::bail!("Could not retrieve game ratios. There has been a problem with input types while creating or manipulating with the bet ratios. Please, contact your site administrator."),
+            _ => anyhow::bail!(DomainError::internal("Could not retrieve game ratios. There has been a problem with input types while creating or manipulating with the bet ratios. Please, contact your site administrator.")),
         }
     }
 
-    /// Set matches' game ratios. Useful after placing a bet
-    async fn set_ratios(
-        &self,
+    /// Set matches' game ratios, see `MatchRepo::set_ratios`
+    pub fn set_ratios_in(
+        connection: &PgConnection,
         desired_match_id: i32,
         first: f64,
         second: f64,
@@ -412,21 +768,17 @@ impl MatchRepo for PgMatchRepo {
                 game_match::team_one_ratio.eq(first.to_string()),
                 game_match::team_two_ratio.eq(second.to_string()),
             ))
-            .execute(&self.get_connection().await?)?;
+            .execute(connection)?;
 
         Ok(())
     }
 
-    /// Create an event for the match
-    /// Fails if such event already exists
-    async fn create_event(
-        &self,
+    /// Create an event for the match, see `MatchRepo::create_event`
+    pub fn create_event_in(
+        connection: &PgConnection,
         desired_match_id: i32,
         desired_event_type: GameMatchEventType,
     ) -> anyhow::Result<i32> {
-        // obtain connection
-        let connection: PgPooledConnection = self.get_connection().await?;
-
         // find if an event already exists
         let exists: usize = game_match_event::table
             .filter(
@@ -434,25 +786,47 @@ impl MatchRepo for PgMatchRepo {
                     .eq(desired_match_id)
                     .and(game_match_event::event_type.eq(desired_event_type.to_string())),
             )
-            .execute(&connection)?;
+            .execute(connection)?;
 
         // what to do with an already existing event?
         match exists {
             0 => {}
-            1 => anyhow::bail!("The event you wish to create already exists!"),
-            _ => anyhow::bail!("Internal error! More than 1 events of the same type exist"),
+            1 => anyhow::bail!(DomainError::conflict(
+                "The event you wish to create already exists!"
+            )),
+            _ => anyhow::bail!(DomainError::internal(
+                "Internal error! More than 1 events of the same type exist"
+            )),
         }
 
         // check if the team belongs to the match (when setting the winner of the match)
         if let GameMatchEventType::Ended(id) = desired_event_type {
             let game_match: GameMatch = game_match::table
                 .find(desired_match_id)
-                .get_result(&connection)?;
+                .get_result(connection)?;
 
             if !(game_match.team_one_id == id || game_match.team_two_id == id) {
-                anyhow::bail!(
+                anyhow::bail!(DomainError::validation(
                     "The team you wish to select as the winner does not belong to this match"
-                );
+                ));
+            }
+
+            // once the maps are recorded, the winner has to have won the series on them
+            let maps: Vec<MatchMap> = match_map::table
+                .filter(match_map::game_match_id.eq(desired_match_id))
+                .get_results(connection)?;
+            if !maps.is_empty() {
+                let (team_one, team_two) = series_score(&game_match, &maps);
+                let winner_maps = if id == game_match.team_one_id {
+                    team_one
+                } else {
+                    team_two
+                };
+                if winner_maps != game_match.extract_series_format()?.maps_to_win() {
+                    anyhow::bail!(DomainError::validation(
+                        "The winner has not won the match on the recorded maps"
+                    ));
+                }
             }
         }
 
@@ -463,91 +837,162 @@ impl MatchRepo for PgMatchRepo {
                 desired_event_type.clone(),
             ))
             .returning(game_match_event::id)
-            .get_result(&connection)?;
+            .get_result(connection)?;
 
         // if the match has ended, evaluate its bets
         if let GameMatchEventType::Ended(_) = desired_event_type {
-            self.evaluate_bets(desired_match_id).await?
+            PgMatchRepo::evaluate_bets_in(connection, desired_match_id)?
         }
 
         Ok(query_result)
     }
 
-    /// Get newest event of the match
-    async fn newest_event(&self, desired_match_id: i32) -> anyhow::Result<GameMatchEvent> {
+    /// Get newest event of the match, see `MatchRepo::newest_event`
+    pub fn newest_event_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+    ) -> anyhow::Result<GameMatchEvent> {
         let query_result = game_match_event::table
             .filter(game_match_event::game_match_id.eq(desired_match_id))
             .order(game_match_event::created_at.desc())
-            .first(&self.get_connection().await?)?;
+            .first(connection)?;
 
         Ok(query_result)
     }
 
-    async fn evaluate_bets(&self, desired_match_id: i32) -> anyhow::Result<()> {
-        let connection: PgPooledConnection = self.get_connection().await?;
-
-        // retrieve all unresolved bets and the ending event of the game they are tied to
-        // let query_result: Vec<(SubmittedBet, GameMatchEvent)> = submitted_bet::table
-        //     .filter(submitted_bet::won.is_null())
-        //     .inner_join(game_match::table.inner_join(game_match_event::table))
-        //     .filter(game_match_event::event_type.eq(GameMatchEventFilter::Ended.to_string()))
-        //     .select((submitted_bet::all_columns, game_match_event::all_columns))
-        //     .get_results(&connection)?;
+    /// Evaluate the submitted bets decided by the match, see `MatchRepo::evaluate_bets`
+    pub fn evaluate_bets_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+    ) -> anyhow::Result<()> {
+        let game_match: GameMatch = game_match::table
+            .find(desired_match_id)
+            .get_result(connection)?;
+        let winner_id =
+            match PgMatchRepo::newest_event_in(connection, desired_match_id)?.extract_event()? {
+                GameMatchEventType::Ended(winner_id) => Some(winner_id),
+                _ => None,
+            };
+        let maps: Vec<MatchMap> = match_map::table
+            .filter(match_map::game_match_id.eq(desired_match_id))
+            .get_results(connection)?;
 
-        // retrieve all unresolved bets and the ending event of the game they are tied to
-        let query_result: Vec<(SubmittedBet, GameMatchEvent)> = (game_match::table
-            .filter(game_match::id.eq(desired_match_id))
-            .inner_join(game_match_event::table))
-        .inner_join(submitted_bet::table)
-        .filter(
-            game_match_event::event_type
-                .eq(GameMatchEventFilter::Ended.to_string())
-                .and(submitted_bet::won.is_null()),
-        )
-        .select((submitted_bet::all_columns, game_match_event::all_columns))
-        .get_results(&connection)?;
-
-        // obtain those matches that have winners
-        let obtain_match_winners: Vec<(SubmittedBet, i32)> = query_result
-            .into_iter()
-            .map(|(bet, event)| {
-                (
-                    bet,
-                    event // obtain those matches that have set the winner and are able to be parsed as id's
-                        .event_value
-                        .unwrap_or_else(|| String::from("0"))
-                        .parse::<i32>()
-                        .ok(), // convert the results to options
-                )
-            }) // unconvertable results are mapped to 0 (but that will not happen thanks to guarantees of our interface)
-            .map(|(bet, winner_id)| (bet, winner_id.unwrap_or(0)))
-            .filter(|(_, winner_id)| *winner_id != 0)
-            .collect();
-
-        // filter those which are won -> get IDs of bets that have won
-        let won_bets: Vec<i32> = obtain_match_winners
-            .iter()
-            .filter(|(bet, winner_id)| bet.team_id == *winner_id)
-            .map(|(bet, _)| bet.id)
-            .collect();
-
-        // filter those which are lost -> get IDs of bets that have lost
-        let lost_bets: Vec<i32> = obtain_match_winners
-            .iter()
-            .filter(|(bet, winner_id)| bet.team_id != *winner_id)
-            .map(|(bet, _)| bet.id)
-            .collect();
+        // retrieve all unresolved bets on the match
+        let unresolved_bets: Vec<SubmittedBet> = submitted_bet::table
+            .filter(
+                submitted_bet::game_match_id
+                    .eq(desired_match_id)
+                    .and(submitted_bet::won.is_null()),
+            )
+            .get_results(connection)?;
+
+        // decide the bets whose match or map has finished
+        let mut won_bets: Vec<i32> = Vec::new();
+        let mut lost_bets: Vec<i32> = Vec::new();
+        for bet in unresolved_bets {
+            match bet
+                .extract_market()?
+                .outcome(&game_match, bet.team_id, winner_id, &maps)
+            {
+                Some(BetOutcome::Won) => won_bets.push(bet.id),
+                Some(BetOutcome::Lost) => lost_bets.push(bet.id),
+                Some(BetOutcome::Void) => void_bet(connection, &bet)?,
+                None => {}
+            }
+        }
 
         // set all won games to won
         let _ = update(submitted_bet::table.filter(submitted_bet::id.eq_any(won_bets)))
             .set(submitted_bet::won.eq(true))
-            .execute(&connection)?;
+            .execute(connection)?;
 
         // set all lost games to lost
         let _ = update(submitted_bet::table.filter(submitted_bet::id.eq_any(lost_bets)))
             .set(submitted_bet::won.eq(false))
-            .execute(&connection)?;
+            .execute(connection)?;
 
         Ok(())
     }
 }
+
+/// Return a started match to its upcoming phase, dropping its maps and timeline,
+/// the bets which have not been decided yet are voided, as the match is played again
+///
+/// Returns
+/// ---
+/// - Ok(user_ids) with IDs of the users whose bets have been voided (without duplicates)
+/// - Err(_) if an error occurred
+fn restart_match(connection: &PgConnection, desired_match_id: i32) -> anyhow::Result<Vec<i32>> {
+    let undecided_bets: Vec<SubmittedBet> = submitted_bet::table
+        .filter(submitted_bet::game_match_id.eq(desired_match_id))
+        .filter(submitted_bet::won.is_null())
+        .get_results(connection)?;
+    let mut user_ids: Vec<i32> = submitted_ticket::table
+        .filter(
+            submitted_ticket::id.eq_any(
+                undecided_bets
+                    .iter()
+                    .map(|bet| bet.submitted_ticket_id)
+                    .collect::<Vec<i32>>(),
+            ),
+        )
+        .select(submitted_ticket::user_id)
+        .get_results(connection)?;
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    for bet in undecided_bets.iter() {
+        void_bet(connection, bet)?;
+    }
+
+    let _ = delete(
+        game_match_event::table
+            .filter(game_match_event::game_match_id.eq(desired_match_id))
+            .filter(game_match_event::event_type.eq_any(vec![
+                GameMatchEventType::Live.to_string(),
+                GameMatchEventType::Overtime.to_string(),
+            ])),
+    )
+    .execute(connection)?;
+    let _ = delete(match_map::table.filter(match_map::game_match_id.eq(desired_match_id)))
+        .execute(connection)?;
+    let _ = delete(
+        match_timeline_event::table
+            .filter(match_timeline_event::game_match_id.eq(desired_match_id)),
+    )
+    .execute(connection)?;
+
+    Ok(user_ids)
+}
+
+/// Count the bet on a map which has not been played with the ratio of 1,
+/// the ratio and the winnable price of its ticket are lowered accordingly
+fn void_bet(connection: &PgConnection, bet: &SubmittedBet) -> anyhow::Result<()> {
+    let voided: usize = update(
+        submitted_bet::table
+            .find(bet.id)
+            .filter(submitted_bet::won.is_null()),
+    )
+    .set((
+        submitted_bet::won.eq(true),
+        submitted_bet::bet_ratio.eq("1"),
+    ))
+    .execute(connection)?;
+
+    // the bet has been decided in the meantime
+    if voided == 0 {
+        return Ok(());
+    }
+
+    let ticket: SubmittedTicket = submitted_ticket::table
+        .find(bet.submitted_ticket_id)
+        .get_result(connection)?;
+    let total_ratio = ticket.total_ratio.parse::<f64>()? / bet.bet_ratio.parse::<f64>()?;
+    let winnable_price = total_ratio * ticket.price_paid.parse::<f64>()?;
+    let _ = update(submitted_ticket::table.find(ticket.id))
+        .set((
+            submitted_ticket::total_ratio.eq(total_ratio.to_string()),
+            submitted_ticket::winnable_price.eq(winnable_price.to_string()),
+        ))
+        .execute(connection)?;
+    Ok(())
+}
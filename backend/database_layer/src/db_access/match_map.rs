@@ -0,0 +1,191 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{insert_into, prelude::*, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
+
+// type and structure imports
+use super::{game_match::PgMatchRepo, repo::Repo};
+use crate::db_models::{
+    game_match::GameMatch,
+    game_match_event::GameMatchEventType,
+    match_map::{series_score, CreateMatchMap, MatchMap},
+};
+
+// schema imports
+use crate::schema::{game_match, match_map};
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with MatchMap records
+pub struct PgMatchMapRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgMatchMapRepo {
+    /// Create a new MatchMap repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new MatchMap repo
+    fn new(pool: &Arc<PgPool>) -> PgMatchMapRepo {
+        PgMatchMapRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+#[async_trait]
+pub trait MatchMapRepo {
+    /// Store the result of a finished map of a live match,
+    /// the bets decided by the map are evaluated right away
+    ///
+    /// Params
+    /// ---
+    /// - `new_map`: the finished map
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(map)` with the stored map
+    /// - `Err(_)` if the match is not live, the map is not the next one to be played,
+    ///   the match has already been decided, the winner does not play the match or an error occurred
+    async fn finish(&self, new_map: CreateMatchMap) -> anyhow::Result<MatchMap>;
+
+    /// Get the finished maps of the match
+    ///
+    /// Params
+    /// ---
+    /// - `desired_match_id`: ID of the match
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(maps)` ordered by their number
+    /// - `Err(_)` if an error occurred
+    async fn get_maps(&self, desired_match_id: i32) -> anyhow::Result<Vec<MatchMap>>;
+
+    /// Get the finished maps of multiple matches at once
+    ///
+    /// Params
+    /// ---
+    /// - `desired_match_ids`: IDs of the matches
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(maps)` by the match ID, ordered by their number, the matches without finished maps are left out
+    /// - `Err(_)` if an error occurred
+    async fn get_maps_of(
+        &self,
+        desired_match_ids: &[i32],
+    ) -> anyhow::Result<HashMap<i32, Vec<MatchMap>>>;
+}
+
+#[async_trait]
+impl MatchMapRepo for PgMatchMapRepo {
+    /// Store the result of a finished map of a live match
+    async fn finish(&self, new_map: CreateMatchMap) -> anyhow::Result<MatchMap> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| PgMatchMapRepo::finish_in(&connection, new_map))
+    }
+
+    /// Get the finished maps of the match
+    async fn get_maps(&self, desired_match_id: i32) -> anyhow::Result<Vec<MatchMap>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgMatchMapRepo::get_maps_in(&connection, desired_match_id)
+    }
+
+    /// Get the finished maps of multiple matches at once
+    async fn get_maps_of(
+        &self,
+        desired_match_ids: &[i32],
+    ) -> anyhow::Result<HashMap<i32, Vec<MatchMap>>> {
+        let query_result: Vec<MatchMap> = match_map::table
+            .filter(match_map::game_match_id.eq_any(desired_match_ids))
+            .order((match_map::game_match_id, match_map::map_number))
+            .get_results(&self.get_connection().await?)?;
+
+        let mut maps: HashMap<i32, Vec<MatchMap>> = HashMap::new();
+        for map in query_result {
+            maps.entry(map.game_match_id).or_default().push(map);
+        }
+        Ok(maps)
+    }
+}
+
+/// The changes of the maps made within the transaction of the caller,
+/// see `OutboxRepo::transaction`
+impl PgMatchMapRepo {
+    /// Store the result of a finished map of a live match, see `MatchMapRepo::finish`
+    pub fn finish_in(
+        connection: &PgConnection,
+        new_map: CreateMatchMap,
+    ) -> anyhow::Result<MatchMap> {
+        match PgMatchRepo::newest_event_in(connection, new_map.game_match_id)?.extract_event()? {
+            GameMatchEventType::Live | GameMatchEventType::Overtime => {}
+            _ => anyhow::bail!(DomainError::conflict(
+                "Maps can be finished only in live matches!"
+            )),
+        }
+
+        let game_match: GameMatch = game_match::table
+            .find(new_map.game_match_id)
+            .get_result(connection)?;
+        if new_map.winner_id != game_match.team_one_id
+            && new_map.winner_id != game_match.team_two_id
+        {
+            anyhow::bail!(DomainError::validation(
+                "The winner of the map does not play the match!"
+            ));
+        }
+
+        let maps = PgMatchMapRepo::get_maps_in(connection, new_map.game_match_id)?;
+        let series_format = game_match.extract_series_format()?;
+        let (team_one, team_two) = series_score(&game_match, &maps);
+        if team_one == series_format.maps_to_win() || team_two == series_format.maps_to_win() {
+            anyhow::bail!(DomainError::conflict("The match has already been decided!"));
+        }
+        if new_map.map_number != maps.len() as i32 + 1 {
+            anyhow::bail!(DomainError::validation(format!(
+                "Map number {} is to be played next!",
+                maps.len() + 1
+            )));
+        }
+
+        let finished: MatchMap = insert_into(match_map::table)
+            .values(new_map)
+            .get_result(connection)?;
+
+        PgMatchRepo::evaluate_bets_in(connection, finished.game_match_id)?;
+        Ok(finished)
+    }
+
+    /// Get the finished maps of the match, see `MatchMapRepo::get_maps`
+    pub fn get_maps_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+    ) -> anyhow::Result<Vec<MatchMap>> {
+        let query_result: Vec<MatchMap> = match_map::table
+            .filter(match_map::game_match_id.eq(desired_match_id))
+            .order(match_map::map_number)
+            .get_results(connection)?;
+
+        Ok(query_result)
+    }
+}
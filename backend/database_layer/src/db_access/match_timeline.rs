@@ -0,0 +1,178 @@
+use async_trait::async_trait;
+use std::{collections::HashMap, sync::Arc};
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{insert_into, prelude::*, QueryDsl, RunQueryDsl};
+
+// type and structure imports
+use super::repo::Repo;
+use crate::db_models::match_timeline_event::{
+    CreateMatchTimelineEvent, MatchTimelineEvent, Score, TimelineEventPayload,
+};
+
+// schema imports
+use crate::schema::match_timeline_event;
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with MatchTimelineEvent records
+pub struct PgTimelineRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgTimelineRepo {
+    /// Create a new Timeline repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new Timeline repo
+    fn new(pool: &Arc<PgPool>) -> PgTimelineRepo {
+        PgTimelineRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+#[async_trait]
+pub trait TimelineRepo {
+    /// Store an in-play event of the match
+    ///
+    /// Params
+    /// ---
+    /// - `desired_match_id`: ID of the match the event happened in
+    /// - `payload`: the event
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(event)` with the stored event
+    /// - `Err(_)` if an error occurred
+    async fn append(
+        &self,
+        desired_match_id: i32,
+        payload: &TimelineEventPayload,
+    ) -> anyhow::Result<MatchTimelineEvent>;
+
+    /// Get a single event
+    ///
+    /// Params
+    /// ---
+    /// - `desired_event_id`: ID of the event
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(event)` if the event exists
+    /// - `Err(_)` if an error occurred
+    async fn get(&self, desired_event_id: i32) -> anyhow::Result<MatchTimelineEvent>;
+
+    /// Get all events of the match, ordered from the oldest
+    ///
+    /// Params
+    /// ---
+    /// - `desired_match_id`: ID of the match
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(events)` with the timeline of the match
+    /// - `Err(_)` if an error occurred
+    async fn get_timeline(&self, desired_match_id: i32) -> anyhow::Result<Vec<MatchTimelineEvent>>;
+
+    /// Get the current scores of the matches, given by their latest score changes
+    ///
+    /// Params
+    /// ---
+    /// - `desired_match_ids`: IDs of the matches
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(scores)` keyed by the match ID, matches without any score change are left out
+    /// - `Err(_)` if an error occurred
+    async fn get_scores(&self, desired_match_ids: &[i32]) -> anyhow::Result<HashMap<i32, Score>>;
+}
+
+#[async_trait]
+impl TimelineRepo for PgTimelineRepo {
+    /// Store an in-play event of the match
+    async fn append(
+        &self,
+        desired_match_id: i32,
+        payload: &TimelineEventPayload,
+    ) -> anyhow::Result<MatchTimelineEvent> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgTimelineRepo::append_in(&connection, desired_match_id, payload)
+    }
+
+    /// Get a single event
+    async fn get(&self, desired_event_id: i32) -> anyhow::Result<MatchTimelineEvent> {
+        let event: MatchTimelineEvent = match_timeline_event::table
+            .find(desired_event_id)
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(event)
+    }
+
+    /// Get all events of the match, ordered from the oldest
+    async fn get_timeline(&self, desired_match_id: i32) -> anyhow::Result<Vec<MatchTimelineEvent>> {
+        let events: Vec<MatchTimelineEvent> = match_timeline_event::table
+            .filter(match_timeline_event::game_match_id.eq(desired_match_id))
+            .order(match_timeline_event::id.asc())
+            .get_results(&self.get_connection().await?)?;
+
+        Ok(events)
+    }
+
+    /// Get the current scores of the matches, given by their latest score changes
+    async fn get_scores(&self, desired_match_ids: &[i32]) -> anyhow::Result<HashMap<i32, Score>> {
+        let latest: Vec<MatchTimelineEvent> = match_timeline_event::table
+            .filter(
+                match_timeline_event::game_match_id
+                    .eq_any(desired_match_ids)
+                    .and(match_timeline_event::event_type.eq("ScoreChanged")),
+            )
+            .distinct_on(match_timeline_event::game_match_id)
+            .order((
+                match_timeline_event::game_match_id,
+                match_timeline_event::id.desc(),
+            ))
+            .get_results(&self.get_connection().await?)?;
+
+        let mut scores = HashMap::new();
+        for event in latest.iter() {
+            if let TimelineEventPayload::ScoreChanged(score) = event.extract_payload()? {
+                scores.insert(event.game_match_id, score);
+            }
+        }
+        Ok(scores)
+    }
+}
+
+impl PgTimelineRepo {
+    /// Store an in-play event of the match within the transaction of the caller,
+    /// see `TimelineRepo::append`
+    pub fn append_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+        payload: &TimelineEventPayload,
+    ) -> anyhow::Result<MatchTimelineEvent> {
+        let event: MatchTimelineEvent = insert_into(match_timeline_event::table)
+            .values(CreateMatchTimelineEvent::new(desired_match_id, payload)?)
+            .get_result(connection)?;
+
+        Ok(event)
+    }
+}
@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{insert_into, prelude::*, QueryDsl, RunQueryDsl};
+
+// type and structure imports
+use super::repo::Repo;
+use crate::db_models::media_asset::{CreateMediaAsset, MediaAsset};
+
+// schema imports
+use crate::schema::media_asset;
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with MediaAsset records
+pub struct PgMediaAssetRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgMediaAssetRepo {
+    /// Create a new MediaAsset repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new MediaAsset repo
+    fn new(pool: &Arc<PgPool>) -> PgMediaAssetRepo {
+        PgMediaAssetRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+#[async_trait]
+pub trait MediaAssetRepo {
+    /// Store the record of an uploaded image, the same content is stored only once
+    ///
+    /// Params
+    /// ---
+    /// - `new_asset`: the stored image
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(asset)` with the new asset, or the one stored before with the same content hash
+    /// - `Err(_)` if an error occurred
+    async fn create(&self, new_asset: CreateMediaAsset) -> anyhow::Result<MediaAsset>;
+
+    /// Get a desired asset by its ID
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(asset)` if the asset exists
+    /// - `Err(_)` if the asset does not exist or an error occurred
+    async fn get(&self, desired_asset_id: i32) -> anyhow::Result<MediaAsset>;
+}
+
+#[async_trait]
+impl MediaAssetRepo for PgMediaAssetRepo {
+    /// Store the record of an uploaded image, unless its content has been stored before
+    async fn create(&self, new_asset: CreateMediaAsset) -> anyhow::Result<MediaAsset> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+
+        // concurrent uploads of the same image end up with a single record
+        let _ = insert_into(media_asset::table)
+            .values(&new_asset)
+            .on_conflict(media_asset::content_hash)
+            .do_nothing()
+            .execute(&connection)?;
+
+        let query_result: MediaAsset = media_asset::table
+            .filter(media_asset::content_hash.eq(&new_asset.content_hash))
+            .first(&connection)?;
+
+        Ok(query_result)
+    }
+
+    /// Get a desired asset by its ID
+    async fn get(&self, desired_asset_id: i32) -> anyhow::Result<MediaAsset> {
+        let query_result: MediaAsset = media_asset::table
+            .find(desired_asset_id)
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(query_result)
+    }
+}
@@ -0,0 +1,363 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{delete, insert_into, prelude::*, update, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
+
+// type and structure imports
+use super::{repo::Repo, user::PgUserRepo};
+use crate::db_models::{
+    outright_bet::{CreateOutrightBet, OutrightBet},
+    outright_selection::{CreateOutrightSelection, OutrightSelection},
+    tournament::Tournament,
+};
+
+// schema imports
+use crate::schema::{outright_bet, outright_selection, tournament};
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with OutrightSelection and OutrightBet records
+pub struct PgOutrightRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgOutrightRepo {
+    /// Create a new Outright repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new Outright repo
+    fn new(pool: &Arc<PgPool>) -> PgOutrightRepo {
+        PgOutrightRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+#[async_trait]
+pub trait OutrightRepo {
+    /// Offer the team to be bet on to win the tournament, or change its ratio if it is already offered.
+    /// The bets placed before keep their ratios.
+    ///
+    /// Params
+    /// ---
+    /// - `new_selection`: the team and its ratio
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the selection has been stored
+    /// - `Err(_)` if the tournament has already ended or an error occurred
+    async fn set_selection(&self, new_selection: CreateOutrightSelection) -> anyhow::Result<()>;
+
+    /// Stop offering the team to be bet on, the bets placed before are kept
+    ///
+    /// Params
+    /// ---
+    /// - `desired_tournament_id`: ID of the tournament
+    /// - `desired_team_id`: ID of the team
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the selection has been removed
+    /// - `Err(_)` if the team is not offered or an error occurred
+    async fn remove_selection(
+        &self,
+        desired_tournament_id: i32,
+        desired_team_id: i32,
+    ) -> anyhow::Result<()>;
+
+    /// Get the teams which can be bet on to win the tournament
+    ///
+    /// Params
+    /// ---
+    /// - `desired_tournament_id`: ID of the tournament
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(selections)` ordered from the favourite
+    /// - `Err(_)` if an error occurred
+    async fn get_selections(
+        &self,
+        desired_tournament_id: i32,
+    ) -> anyhow::Result<Vec<OutrightSelection>>;
+
+    /// Bet on the team to win the tournament, paying the stake from the balance of the user
+    ///
+    /// Params
+    /// ---
+    /// - `desired_user_id`: ID of the user placing the bet
+    /// - `desired_tournament_id`: ID of the tournament
+    /// - `desired_team_id`: ID of the team bet on
+    /// - `paid_price`: the stake
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(bet)` with the placed bet
+    /// - `Err(_)` if the tournament has ended, the team is not offered,
+    ///   the user cannot afford the stake or an error occurred
+    async fn place_bet(
+        &self,
+        desired_user_id: i32,
+        desired_tournament_id: i32,
+        desired_team_id: i32,
+        paid_price: f64,
+    ) -> anyhow::Result<OutrightBet>;
+
+    /// Get all outright bets of the user, the newest first
+    ///
+    /// Params
+    /// ---
+    /// - `desired_user_id`: ID of the user
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(bets)` with the bets
+    /// - `Err(_)` if an error occurred
+    async fn get_bets(&self, desired_user_id: i32) -> anyhow::Result<Vec<OutrightBet>>;
+
+    /// Settle the bets on an ended tournament, paying out the winning ones
+    ///
+    /// Params
+    /// ---
+    /// - `desired_tournament_id`: ID of the tournament
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(bets)` with the bets settled by this call
+    /// - `Err(_)` if the tournament has not ended yet or an error occurred
+    async fn settle(&self, desired_tournament_id: i32) -> anyhow::Result<Vec<OutrightBet>>;
+}
+
+#[async_trait]
+impl OutrightRepo for PgOutrightRepo {
+    /// Offer the team to be bet on to win the tournament, or change its ratio
+    async fn set_selection(&self, new_selection: CreateOutrightSelection) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+
+        let current: Tournament = tournament::table
+            .find(new_selection.tournament_id)
+            .get_result(&connection)?;
+        if current.has_ended() {
+            anyhow::bail!(DomainError::betting_closed(
+                "The tournament has already ended"
+            ));
+        }
+
+        let ratio = new_selection.ratio.clone();
+        let _ = insert_into(outright_selection::table)
+            .values(new_selection)
+            .on_conflict((
+                outright_selection::tournament_id,
+                outright_selection::team_id,
+            ))
+            .do_update()
+            .set(outright_selection::ratio.eq(ratio))
+            .execute(&connection)?;
+
+        Ok(())
+    }
+
+    /// Stop offering the team to be bet on
+    async fn remove_selection(
+        &self,
+        desired_tournament_id: i32,
+        desired_team_id: i32,
+    ) -> anyhow::Result<()> {
+        let number_of_rows: usize = delete(
+            outright_selection::table.filter(
+                outright_selection::tournament_id
+                    .eq(desired_tournament_id)
+                    .and(outright_selection::team_id.eq(desired_team_id)),
+            ),
+        )
+        .execute(&self.get_connection().await?)?;
+
+        if number_of_rows == 0 {
+            anyhow::bail!(DomainError::validation(
+                "The team is not offered in the tournament"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the teams which can be bet on to win the tournament
+    async fn get_selections(
+        &self,
+        desired_tournament_id: i32,
+    ) -> anyhow::Result<Vec<OutrightSelection>> {
+        let mut query_result: Vec<OutrightSelection> = outright_selection::table
+            .filter(outright_selection::tournament_id.eq(desired_tournament_id))
+            .get_results(&self.get_connection().await?)?;
+
+        // the ratios are stored as text
+        query_result.sort_by(|one, two| {
+            let one = one.ratio.parse::<f64>().unwrap_or(f64::MAX);
+            let two = two.ratio.parse::<f64>().unwrap_or(f64::MAX);
+            one.partial_cmp(&two).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Ok(query_result)
+    }
+
+    /// Bet on the team to win the tournament
+    async fn place_bet(
+        &self,
+        desired_user_id: i32,
+        desired_tournament_id: i32,
+        desired_team_id: i32,
+        paid_price: f64,
+    ) -> anyhow::Result<OutrightBet> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            PgOutrightRepo::place_bet_in(
+                &connection,
+                desired_user_id,
+                desired_tournament_id,
+                desired_team_id,
+                paid_price,
+            )
+        })
+    }
+
+    /// Get all outright bets of the user, the newest first
+    async fn get_bets(&self, desired_user_id: i32) -> anyhow::Result<Vec<OutrightBet>> {
+        let query_result: Vec<OutrightBet> = outright_bet::table
+            .filter(outright_bet::user_id.eq(desired_user_id))
+            .order(outright_bet::id.desc())
+            .get_results(&self.get_connection().await?)?;
+
+        Ok(query_result)
+    }
+
+    /// Settle the bets on an ended tournament, paying out the winning ones
+    async fn settle(&self, desired_tournament_id: i32) -> anyhow::Result<Vec<OutrightBet>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| PgOutrightRepo::settle_in(&connection, desired_tournament_id))
+    }
+}
+
+/// The outright bets placed and settled within the transaction of the caller,
+/// see `OutboxRepo::transaction`
+impl PgOutrightRepo {
+    /// Bet on the team to win the tournament, see `OutrightRepo::place_bet`
+    pub fn place_bet_in(
+        connection: &PgConnection,
+        desired_user_id: i32,
+        desired_tournament_id: i32,
+        desired_team_id: i32,
+        paid_price: f64,
+    ) -> anyhow::Result<OutrightBet> {
+        let current: Tournament = tournament::table
+            .find(desired_tournament_id)
+            .get_result(connection)?;
+        if current.has_ended() {
+            anyhow::bail!(DomainError::betting_closed(
+                "The tournament has already ended"
+            ));
+        }
+
+        let selection: Option<OutrightSelection> = outright_selection::table
+            .filter(
+                outright_selection::tournament_id
+                    .eq(desired_tournament_id)
+                    .and(outright_selection::team_id.eq(desired_team_id)),
+            )
+            .get_result(connection)
+            .optional()?;
+        let selection = match selection {
+            Some(selection) => selection,
+            None => anyhow::bail!(DomainError::validation(
+                "The team cannot be bet on to win the tournament"
+            )),
+        };
+
+        // check user balance first, locked until the bet is paid for
+        let balance: f64 = PgUserRepo::lock_balance_in(connection, desired_user_id)?.parse()?;
+        if paid_price <= 0.0 {
+            anyhow::bail!(DomainError::validation(
+                "Cannot pay with negative amount of currency"
+            ))
+        } else if balance < paid_price {
+            anyhow::bail!(DomainError::InsufficientFunds {
+                balance,
+                required: paid_price
+            })
+        }
+
+        let placed: OutrightBet = insert_into(outright_bet::table)
+            .values(CreateOutrightBet::new(
+                desired_user_id,
+                &selection,
+                paid_price,
+            ))
+            .get_result(connection)?;
+        PgUserRepo::spend_balance_in(connection, desired_user_id, paid_price)?;
+
+        Ok(placed)
+    }
+
+    /// Settle the bets on an ended tournament, paying out the winning ones,
+    /// see `OutrightRepo::settle`
+    pub fn settle_in(
+        connection: &PgConnection,
+        desired_tournament_id: i32,
+    ) -> anyhow::Result<Vec<OutrightBet>> {
+        let current: Tournament = tournament::table
+            .find(desired_tournament_id)
+            .get_result(connection)?;
+        let winner_id = match current.winner_id {
+            Some(winner_id) if current.has_ended() => winner_id,
+            _ => anyhow::bail!(DomainError::conflict("The tournament has not ended yet")),
+        };
+
+        let unsettled: Vec<OutrightBet> = outright_bet::table
+            .filter(
+                outright_bet::tournament_id
+                    .eq(desired_tournament_id)
+                    .and(outright_bet::won.is_null()),
+            )
+            .get_results(connection)?;
+
+        let mut settled = Vec::new();
+        for bet in unsettled {
+            // a bet settled in the meantime by another instance is skipped, so it is never paid twice
+            let settled_bet: Option<OutrightBet> = update(
+                outright_bet::table
+                    .find(bet.id)
+                    .filter(outright_bet::won.is_null()),
+            )
+            .set(outright_bet::won.eq(bet.team_id == winner_id))
+            .get_result(connection)
+            .optional()?;
+
+            if let Some(settled_bet) = settled_bet {
+                if settled_bet.won == Some(true) {
+                    PgUserRepo::add_balance_in(
+                        connection,
+                        settled_bet.user_id,
+                        settled_bet.winnable_price()?,
+                    )?;
+                }
+                settled.push(settled_bet);
+            }
+        }
+        Ok(settled)
+    }
+}
@@ -0,0 +1,438 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{delete, insert_into, prelude::*, update, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
+use crate::type_storing::time_handling::TimeHandling;
+
+// type and structure imports
+use super::repo::Repo;
+use crate::db_models::{
+    player::{CreatePlayer, Player},
+    roster_membership::{CreateRosterMembership, RosterMembership},
+};
+
+// schema imports
+use crate::schema::{player, roster_membership, team_plays_game};
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with Player and RosterMembership records
+pub struct PgPlayerRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgPlayerRepo {
+    /// Create a new Player repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new Player repo
+    fn new(pool: &Arc<PgPool>) -> PgPlayerRepo {
+        PgPlayerRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+impl PgPlayerRepo {
+    /// Get the roster the player is in right now
+    ///
+    /// Params
+    /// ---
+    /// - `desired_player_id`: ID of the player
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(Some(membership))` if the player has not left the team yet
+    /// - `Ok(None)` if the player is not in any roster
+    /// - `Err(_)` if an error occurred
+    async fn current_membership(
+        &self,
+        desired_player_id: i32,
+    ) -> anyhow::Result<Option<RosterMembership>> {
+        let query_result: Option<RosterMembership> = roster_membership::table
+            .filter(
+                roster_membership::player_id
+                    .eq(desired_player_id)
+                    .and(roster_membership::left_at.is_null()),
+            )
+            .first(&self.get_connection().await?)
+            .optional()?;
+
+        Ok(query_result)
+    }
+}
+
+#[async_trait]
+pub trait PlayerRepo {
+    /// Create a new player
+    ///
+    /// Params
+    /// ---
+    /// - `new_player`: a write structure for creating a player
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(id)` if the player has been created
+    /// - `Err(_)` if an error occurred
+    async fn create(&self, new_player: CreatePlayer) -> anyhow::Result<i32>;
+
+    /// Edit the player information, the game can be changed only while the player is in no roster
+    ///
+    /// Params
+    /// ---
+    /// - `desired_player_id`: ID of the player
+    /// - `edited_player`: new information of the player
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the player has been edited
+    /// - `Err(_)` if an error occurred
+    async fn edit(&self, desired_player_id: i32, edited_player: CreatePlayer)
+        -> anyhow::Result<()>;
+
+    /// Delete the player along with their roster history
+    ///
+    /// Params
+    /// ---
+    /// - `desired_player_id`: ID of the player
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the player has been deleted
+    /// - `Err(_)` if an error occurred
+    async fn delete(&self, desired_player_id: i32) -> anyhow::Result<()>;
+
+    /// Get a single player
+    ///
+    /// Params
+    /// ---
+    /// - `desired_player_id`: ID of the player
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(player)` if the player exists
+    /// - `Err(_)` if an error occurred
+    async fn get(&self, desired_player_id: i32) -> anyhow::Result<Player>;
+
+    /// Get all players, ordered by their nickname
+    ///
+    /// Params
+    /// ---
+    /// - `desired_game_id`: only the players of this game, if set
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(players)` with the players
+    /// - `Err(_)` if an error occurred
+    async fn get_all(&self, desired_game_id: Option<i32>) -> anyhow::Result<Vec<Player>>;
+
+    /// Add the player into the roster of the team,
+    /// the team has to play the game of the player and the player cannot be in another roster
+    ///
+    /// Params
+    /// ---
+    /// - `new_membership`: the player, the team and the role of the player
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(membership)` with the new membership
+    /// - `Err(_)` if the player cannot join the team or an error occurred
+    async fn join_team(
+        &self,
+        new_membership: CreateRosterMembership,
+    ) -> anyhow::Result<RosterMembership>;
+
+    /// Remove the player from the roster they are in
+    ///
+    /// Params
+    /// ---
+    /// - `desired_player_id`: ID of the player
+    /// - `left_at`: when the player has left the team
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(membership)` with the ended membership
+    /// - `Err(_)` if the player is in no roster, would leave before joining or an error occurred
+    async fn leave_team(
+        &self,
+        desired_player_id: i32,
+        left_at: DateTime<Utc>,
+    ) -> anyhow::Result<RosterMembership>;
+
+    /// Get the roster of the team at the time
+    ///
+    /// Params
+    /// ---
+    /// - `desired_team_id`: ID of the team
+    /// - `at`: the time of the roster, e.g. the start of a match
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(members)` with the players in the roster at the time, ordered by their joining
+    /// - `Err(_)` if an error occurred
+    async fn get_roster(
+        &self,
+        desired_team_id: i32,
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<(RosterMembership, Player)>>;
+
+    /// Get all players who have ever been in the roster of the team
+    ///
+    /// Params
+    /// ---
+    /// - `desired_team_id`: ID of the team
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(members)` with all memberships of the team, ordered by their joining
+    /// - `Err(_)` if an error occurred
+    async fn get_team_history(
+        &self,
+        desired_team_id: i32,
+    ) -> anyhow::Result<Vec<(RosterMembership, Player)>>;
+
+    /// Get all teams the player has been in
+    ///
+    /// Params
+    /// ---
+    /// - `desired_player_id`: ID of the player
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(memberships)` with all memberships of the player, ordered by their joining
+    /// - `Err(_)` if an error occurred
+    async fn get_player_history(
+        &self,
+        desired_player_id: i32,
+    ) -> anyhow::Result<Vec<RosterMembership>>;
+}
+
+#[async_trait]
+impl PlayerRepo for PgPlayerRepo {
+    /// Create a new player
+    async fn create(&self, new_player: CreatePlayer) -> anyhow::Result<i32> {
+        let id: i32 = insert_into(player::table)
+            .values(new_player)
+            .returning(player::id)
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(id)
+    }
+
+    /// Edit the player information
+    async fn edit(
+        &self,
+        desired_player_id: i32,
+        edited_player: CreatePlayer,
+    ) -> anyhow::Result<()> {
+        let current = self.get(desired_player_id).await?;
+        if current.game_id != edited_player.game_id
+            && self.current_membership(desired_player_id).await?.is_some()
+        {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot change the game of a player who is in a roster"
+            ));
+        }
+
+        let _ = update(player::table.find(desired_player_id))
+            .set(edited_player)
+            .execute(&self.get_connection().await?)?;
+
+        Ok(())
+    }
+
+    /// Delete the player along with their roster history
+    async fn delete(&self, desired_player_id: i32) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+
+        let _ = delete(
+            roster_membership::table.filter(roster_membership::player_id.eq(desired_player_id)),
+        )
+        .execute(&connection)?;
+        let deleted: usize = delete(player::table.find(desired_player_id)).execute(&connection)?;
+        if deleted == 0 {
+            anyhow::bail!(DomainError::not_found("The player does not exist!"));
+        }
+
+        Ok(())
+    }
+
+    /// Get a single player
+    async fn get(&self, desired_player_id: i32) -> anyhow::Result<Player> {
+        let query_result: Player = player::table
+            .find(desired_player_id)
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(query_result)
+    }
+
+    /// Get all players, ordered by their nickname
+    async fn get_all(&self, desired_game_id: Option<i32>) -> anyhow::Result<Vec<Player>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        let query = player::table.order((player::nickname.asc(), player::id.asc()));
+
+        let query_result: Vec<Player> = match desired_game_id {
+            Some(desired_game_id) => query
+                .filter(player::game_id.eq(desired_game_id))
+                .get_results(&connection)?,
+            None => query.get_results(&connection)?,
+        };
+        Ok(query_result)
+    }
+
+    /// Add the player into the roster of the team
+    async fn join_team(
+        &self,
+        new_membership: CreateRosterMembership,
+    ) -> anyhow::Result<RosterMembership> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        let joining = self.get(new_membership.player_id).await?;
+
+        let plays_game: usize = team_plays_game::table
+            .filter(
+                team_plays_game::team_id
+                    .eq(new_membership.team_id)
+                    .and(team_plays_game::game_id.eq(joining.game_id)),
+            )
+            .execute(&connection)?;
+        if plays_game == 0 {
+            anyhow::bail!(DomainError::validation(
+                "The team does not play the game of the player!"
+            ));
+        }
+
+        if self.current_membership(joining.id).await?.is_some() {
+            anyhow::bail!(DomainError::conflict(
+                "The player has to leave their current team first!"
+            ));
+        }
+
+        // the memberships of a player cannot overlap
+        let joined_at = TimeHandling::load_timestamp(&new_membership.joined_at)?;
+        for membership in self.get_player_history(joining.id).await? {
+            if let Some(left_at) = membership.left_at {
+                if TimeHandling::load_timestamp(&left_at)? > joined_at {
+                    anyhow::bail!(DomainError::validation(
+                        "The player cannot join a team before leaving the previous one!"
+                    ));
+                }
+            }
+        }
+
+        let query_result: RosterMembership = insert_into(roster_membership::table)
+            .values(new_membership)
+            .get_result(&connection)?;
+
+        Ok(query_result)
+    }
+
+    /// Remove the player from the roster they are in
+    async fn leave_team(
+        &self,
+        desired_player_id: i32,
+        left_at: DateTime<Utc>,
+    ) -> anyhow::Result<RosterMembership> {
+        let membership = match self.current_membership(desired_player_id).await? {
+            Some(membership) => membership,
+            None => anyhow::bail!(DomainError::conflict("The player is not in any roster!")),
+        };
+        if TimeHandling::load_timestamp(&membership.joined_at)? > left_at {
+            anyhow::bail!(DomainError::validation(
+                "The player cannot leave the team before joining it!"
+            ));
+        }
+
+        let query_result: RosterMembership = update(roster_membership::table.find(membership.id))
+            .set(roster_membership::left_at.eq(left_at.to_string()))
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(query_result)
+    }
+
+    /// Get the roster of the team at the time
+    async fn get_roster(
+        &self,
+        desired_team_id: i32,
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<(RosterMembership, Player)>> {
+        let mut roster = Vec::new();
+        for (membership, member) in self.get_team_history(desired_team_id).await? {
+            if membership.is_member_at(at)? {
+                roster.push((membership, member));
+            }
+        }
+
+        Ok(roster)
+    }
+
+    /// Get all players who have ever been in the roster of the team
+    async fn get_team_history(
+        &self,
+        desired_team_id: i32,
+    ) -> anyhow::Result<Vec<(RosterMembership, Player)>> {
+        let query_result: Vec<(RosterMembership, Player)> = roster_membership::table
+            .filter(roster_membership::team_id.eq(desired_team_id))
+            .inner_join(player::table)
+            .get_results(&self.get_connection().await?)?;
+
+        // the times are stored as text, they have to be compared once loaded
+        let mut keyed = Vec::with_capacity(query_result.len());
+        for (membership, member) in query_result {
+            keyed.push((
+                TimeHandling::load_timestamp(&membership.joined_at)?,
+                membership,
+                member,
+            ));
+        }
+        keyed.sort_by_key(|(joined_at, membership, ..)| (*joined_at, membership.id));
+
+        Ok(keyed
+            .into_iter()
+            .map(|(_, membership, member)| (membership, member))
+            .collect())
+    }
+
+    /// Get all teams the player has been in
+    async fn get_player_history(
+        &self,
+        desired_player_id: i32,
+    ) -> anyhow::Result<Vec<RosterMembership>> {
+        let query_result: Vec<RosterMembership> = roster_membership::table
+            .filter(roster_membership::player_id.eq(desired_player_id))
+            .get_results(&self.get_connection().await?)?;
+
+        let mut keyed = Vec::with_capacity(query_result.len());
+        for membership in query_result {
+            keyed.push((
+                TimeHandling::load_timestamp(&membership.joined_at)?,
+                membership,
+            ));
+        }
+        keyed.sort_by_key(|(joined_at, membership)| (*joined_at, membership.id));
+
+        Ok(keyed
+            .into_iter()
+            .map(|(_, membership)| membership)
+            .collect())
+    }
+}
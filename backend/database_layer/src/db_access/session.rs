@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{delete, insert_into, prelude::*, QueryDsl, RunQueryDsl};
+
+// type and structure imports
+use super::repo::Repo;
+use crate::db_models::user_session::{CreateUserSession, UserSession};
+use crate::type_storing::time_handling::TimeHandling;
+
+// schema imports
+use crate::schema::user_session;
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with UserSession records
+pub struct PgSessionRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgSessionRepo {
+    /// Create a new Session repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new Session repo
+    fn new(pool: &Arc<PgPool>) -> PgSessionRepo {
+        PgSessionRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+#[async_trait]
+pub trait SessionRepo {
+    /// Start a new session of the user
+    ///
+    /// Params
+    /// ---
+    /// - `desired_user_id`: ID of the user who logged in
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(token)` with the token of the new session
+    /// - `Err(_)` if an error occurred
+    async fn create(&self, desired_user_id: i32) -> anyhow::Result<String>;
+
+    /// Find the user the session belongs to
+    ///
+    /// Params
+    /// ---
+    /// - `desired_token`: token of the session
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(Some(user_id))` if the session exists and has not expired
+    /// - `Ok(None)` if the session does not exist or has expired
+    /// - `Err(_)` if an error occurred
+    async fn get_user_id(&self, desired_token: &str) -> anyhow::Result<Option<i32>>;
+
+    /// End the session
+    ///
+    /// Params
+    /// ---
+    /// - `desired_token`: token of the session
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the session no longer exists
+    /// - `Err(_)` if an error occurred
+    async fn delete(&self, desired_token: &str) -> anyhow::Result<()>;
+
+    /// Delete the sessions which have expired, so they do not pile up
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(count)` with the number of deleted sessions
+    /// - `Err(_)` if an error occurred
+    async fn delete_expired(&self) -> anyhow::Result<usize>;
+}
+
+#[async_trait]
+impl SessionRepo for PgSessionRepo {
+    /// Start a new session of the user
+    async fn create(&self, desired_user_id: i32) -> anyhow::Result<String> {
+        let token: String = insert_into(user_session::table)
+            .values(CreateUserSession::new(desired_user_id))
+            .returning(user_session::token)
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(token)
+    }
+
+    /// Find the user the session belongs to
+    async fn get_user_id(&self, desired_token: &str) -> anyhow::Result<Option<i32>> {
+        let session: Option<UserSession> = user_session::table
+            .filter(user_session::token.eq(desired_token))
+            .first(&self.get_connection().await?)
+            .optional()?;
+
+        match session {
+            Some(session) if session.is_valid()? => Ok(Some(session.user_id)),
+            _ => Ok(None),
+        }
+    }
+
+    /// End the session
+    async fn delete(&self, desired_token: &str) -> anyhow::Result<()> {
+        let _ = delete(user_session::table.filter(user_session::token.eq(desired_token)))
+            .execute(&self.get_connection().await?)?;
+
+        Ok(())
+    }
+
+    /// Delete the sessions which have expired
+    async fn delete_expired(&self) -> anyhow::Result<usize> {
+        let count =
+            delete(user_session::table.filter(user_session::expires_at.lt(TimeHandling::store())))
+                .execute(&self.get_connection().await?)?;
+
+        Ok(count)
+    }
+}
@@ -4,13 +4,11 @@ use std::sync::Arc;
 
 use crate::connection::{PgPool, PgPooledConnection};
 use crate::diesel::{prelude::*, update, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
 
 // type and structure imports
 use crate::{
-    db_access::{
-        repo::Repo,
-        user::{PgUserRepo, UserRepo},
-    },
+    db_access::{repo::Repo, user::PgUserRepo},
     db_models::{submitted_bet::SubmittedBet, submitted_ticket::SubmittedTicket},
 };
 
@@ -22,7 +20,7 @@ pub struct PgSubmittedBetAndTicketRepo {
 }
 
 impl PgSubmittedBetAndTicketRepo {
-    /// Evaluate all bets that have not yet been evaluated -> showing user whether they won the bet or not
+    /// Evaluate all bets that have not yet been evaluated, within the transaction of the caller -> showing user whether they won the bet or not
     ///
     /// Params
     /// ---
@@ -30,12 +28,12 @@ impl PgSubmittedBetAndTicketRepo {
     ///
     /// Returns
     /// ---
-    /// - `Ok(())` after this method has ran successfully
+    /// - `Ok(Vec<SubmittedTicket>)` with the tickets settled by this call
     /// - `Err(_)` otherwise
-    async fn evaluate_submitted_tickets(&self, desired_user_id: i32) -> anyhow::Result<()> {
-        let connection: PgPooledConnection = self.get_connection().await?;
-        let pg_user = PgUserRepo::new(&self.pool);
-
+    fn evaluate_submitted_tickets(
+        connection: &PgConnection,
+        desired_user_id: i32,
+    ) -> anyhow::Result<Vec<SubmittedTicket>> {
         let tickets_to_reevaluate: Vec<(SubmittedTicket, SubmittedBet)> = submitted_ticket::table
             .filter(
                 submitted_ticket::user_id
@@ -43,25 +41,22 @@ impl PgSubmittedBetAndTicketRepo {
                     .and(submitted_ticket::won.is_null()),
             )
             .inner_join(submitted_bet::table)
-            .get_results(&connection)?;
+            .get_results(connection)?;
 
         let mut bind_match_and_bets: HashMap<SubmittedTicket, Vec<SubmittedBet>> = HashMap::new();
 
         // bind the bets to the ticket
         for (ticket, bet) in tickets_to_reevaluate {
-            let bets_vector = bind_match_and_bets.entry(ticket).or_insert_with(Vec::new);
+            let bets_vector = bind_match_and_bets.entry(ticket).or_default();
             bets_vector.push(bet);
         }
 
         let mut lost_matches: Vec<i32> = Vec::new();
-        let mut won_price: f64 = 0.0;
         let mut won_matches: Vec<i32> = Vec::new();
 
         // look through the bets and set lost and won matches accordingly
         for (ticket, bets) in bind_match_and_bets.iter() {
             let win_status: Vec<Option<bool>> = bets.iter().map(|bet| bet.won).collect();
-            let total_ratio = ticket.total_ratio.parse::<f64>().ok();
-            let price_paid = ticket.price_paid.parse::<f64>().ok();
 
             // if any bet.won is false, the match is lost
             if win_status.contains(&Some(false)) {
@@ -69,32 +64,105 @@ impl PgSubmittedBetAndTicketRepo {
             // this means there was no loss, also if all matches are over, this means the bet is won
             } else if !win_status.contains(&None) {
                 won_matches.push(ticket.id);
-
-                match (total_ratio, price_paid) {
-                    (Some(ratio), Some(price)) => won_price += ratio * price,
-                    _ => {
-                        anyhow::bail!("There has been an internal error while adding the won price")
-                    }
-                }
             }
         }
 
-        // set lost matches
-        let _ = update(submitted_ticket::table.filter(submitted_ticket::id.eq_any(lost_matches)))
-            .set(submitted_ticket::won.eq(false))
-            .execute(&connection)?;
+        // the tickets settled in the meantime by another transaction are skipped,
+        // so only the tickets changed here are returned and paid out, never twice
+        let mut settled_tickets: Vec<SubmittedTicket> = update(
+            submitted_ticket::table.filter(
+                submitted_ticket::id
+                    .eq_any(lost_matches)
+                    .and(submitted_ticket::won.is_null()),
+            ),
+        )
+        .set(submitted_ticket::won.eq(false))
+        .get_results(connection)?;
+
+        let won_tickets: Vec<SubmittedTicket> = update(
+            submitted_ticket::table.filter(
+                submitted_ticket::id
+                    .eq_any(won_matches)
+                    .and(submitted_ticket::won.is_null()),
+            ),
+        )
+        .set(submitted_ticket::won.eq(true))
+        .get_results(connection)?;
 
-        // set won matches
-        let _ = update(submitted_ticket::table.filter(submitted_ticket::id.eq_any(won_matches)))
-            .set(submitted_ticket::won.eq(true))
-            .execute(&connection)?;
+        let mut won_price: f64 = 0.0;
+        for ticket in won_tickets.iter() {
+            let total_ratio = ticket.total_ratio.parse::<f64>().ok();
+            let price_paid = ticket.price_paid.parse::<f64>().ok();
+            match (total_ratio, price_paid) {
+                (Some(ratio), Some(price)) => won_price += ratio * price,
+                _ => {
+                    anyhow::bail!(DomainError::internal(
+                        "There has been an internal error while adding the won price"
+                    ))
+                }
+            }
+        }
 
         // add balance to the user
         if won_price > 0.0 {
-            pg_user.add_balance(desired_user_id, won_price).await?;
+            PgUserRepo::add_balance_in(connection, desired_user_id, won_price)?;
         }
 
-        Ok(())
+        settled_tickets.extend(won_tickets);
+        Ok(settled_tickets)
+    }
+
+    /// Retrieve all bets bound to the ticket, see `SubmittedBetAndTicketRepo::get_bets`
+    pub fn get_bets_in(
+        connection: &PgConnection,
+        desired_ticket_id: i32,
+    ) -> anyhow::Result<Vec<SubmittedBet>> {
+        let query_result: Vec<SubmittedBet> = submitted_bet::table
+            .filter(submitted_bet::submitted_ticket_id.eq(desired_ticket_id))
+            .get_results(connection)?;
+
+        Ok(query_result)
+    }
+
+    /// Retrieve a single submitted ticket, see `SubmittedBetAndTicketRepo::get_ticket`
+    pub fn get_ticket_in(
+        connection: &PgConnection,
+        desired_ticket_id: i32,
+    ) -> anyhow::Result<SubmittedTicket> {
+        let query_result: SubmittedTicket = submitted_ticket::table
+            .find(desired_ticket_id)
+            .get_result(connection)?;
+
+        Ok(query_result)
+    }
+
+    /// Retrieve IDs of the users with not yet settled tickets containing a bet on the match,
+    /// see `SubmittedBetAndTicketRepo::get_unsettled_users`
+    pub fn get_unsettled_users_in(
+        connection: &PgConnection,
+        desired_match_id: i32,
+    ) -> anyhow::Result<Vec<i32>> {
+        let query_result: Vec<i32> = submitted_ticket::table
+            .inner_join(submitted_bet::table)
+            .filter(
+                submitted_bet::game_match_id
+                    .eq(desired_match_id)
+                    .and(submitted_ticket::won.is_null()),
+            )
+            .select(submitted_ticket::user_id)
+            .distinct()
+            .get_results(connection)?;
+
+        Ok(query_result)
+    }
+
+    /// Settle all user's tickets whose bets have all been evaluated,
+    /// see `SubmittedBetAndTicketRepo::settle`
+    pub fn settle_in(
+        connection: &PgConnection,
+        desired_user_id: i32,
+    ) -> anyhow::Result<Vec<SubmittedTicket>> {
+        PgSubmittedBetAndTicketRepo::evaluate_submitted_tickets(connection, desired_user_id)
     }
 }
 
@@ -155,6 +223,43 @@ pub trait SubmittedBetAndTicketRepo {
     /// - `Ok(Vec<SubmittedBets>)` if the ticket exists and we retrieved its bets
     /// - `Err(_)` if any errors have occurred during this operation
     async fn get_bets(&self, desired_ticket_id: i32) -> anyhow::Result<Vec<SubmittedBet>>;
+
+    /// Retrieve a single submitted ticket
+    ///
+    /// Params
+    /// ---
+    /// - desired_ticket_id: ID of the desired ticket
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(SubmittedTicket)` if the ticket exists
+    /// - `Err(_)` if any errors have occurred during this operation
+    async fn get_ticket(&self, desired_ticket_id: i32) -> anyhow::Result<SubmittedTicket>;
+
+    /// Retrieve IDs of the users with not yet settled tickets containing a bet on the match
+    ///
+    /// Params
+    /// ---
+    /// - desired_match_id: ID of the desired match
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(Vec<user_id>)` with IDs of the users (without duplicates)
+    /// - `Err(_)` if any errors have occurred during this operation
+    async fn get_unsettled_users(&self, desired_match_id: i32) -> anyhow::Result<Vec<i32>>;
+
+    /// Settle all user's tickets whose bets have all been evaluated,
+    /// the prize of the won tickets is added to the user's balance
+    ///
+    /// Params
+    /// ---
+    /// - desired_user_id: ID of the user we wish to settle the tickets of
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(Vec<SubmittedTicket>)` with the tickets settled by this call
+    /// - `Err(_)` if any errors have occurred during this operation
+    async fn settle(&self, desired_user_id: i32) -> anyhow::Result<Vec<SubmittedTicket>>;
 }
 
 #[async_trait]
@@ -164,34 +269,54 @@ impl SubmittedBetAndTicketRepo for PgSubmittedBetAndTicketRepo {
         &self,
         desired_user_id: i32,
     ) -> anyhow::Result<Vec<(SubmittedTicket, Vec<SubmittedBet>)>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+
         // firstly evaluate all submitted tickets
-        self.evaluate_submitted_tickets(desired_user_id).await?;
+        connection.transaction(|| {
+            PgSubmittedBetAndTicketRepo::evaluate_submitted_tickets(&connection, desired_user_id)
+        })?;
 
         // perform join, only one call for the database needed
         let query_result: Vec<(SubmittedTicket, SubmittedBet)> = submitted_ticket::table
             .filter(submitted_ticket::user_id.eq(desired_user_id))
             .inner_join(submitted_bet::table)
             .order(submitted_ticket::submitted_at.desc())
-            .get_results(&self.get_connection().await?)?;
+            .get_results(&connection)?;
 
         let mut dedup_output: HashMap<SubmittedTicket, Vec<SubmittedBet>> = HashMap::new();
 
         // deduplicating the list
         for (ticket, bet) in query_result {
-            let bets_vector = dedup_output.entry(ticket).or_insert_with(Vec::new);
+            let bets_vector = dedup_output.entry(ticket).or_default();
             bets_vector.push(bet);
         }
 
         // output as a vector
-        Ok(Vec::from_iter(dedup_output.into_iter()))
+        Ok(Vec::from_iter(dedup_output))
     }
 
     /// Retrieve all bets that are bound to a certain ticket
     async fn get_bets(&self, desired_ticket_id: i32) -> anyhow::Result<Vec<SubmittedBet>> {
-        let query_result: Vec<SubmittedBet> = submitted_bet::table
-            .filter(submitted_bet::submitted_ticket_id.eq(desired_ticket_id))
-            .get_results(&self.get_connection().await?)?;
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgSubmittedBetAndTicketRepo::get_bets_in(&connection, desired_ticket_id)
+    }
 
-        Ok(query_result)
+    /// Retrieve a single submitted ticket
+    async fn get_ticket(&self, desired_ticket_id: i32) -> anyhow::Result<SubmittedTicket> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgSubmittedBetAndTicketRepo::get_ticket_in(&connection, desired_ticket_id)
+    }
+
+    /// Retrieve IDs of the users with not yet settled tickets containing a bet on the match
+    async fn get_unsettled_users(&self, desired_match_id: i32) -> anyhow::Result<Vec<i32>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgSubmittedBetAndTicketRepo::get_unsettled_users_in(&connection, desired_match_id)
+    }
+
+    /// Settle all user's tickets whose bets have all been evaluated
+    async fn settle(&self, desired_user_id: i32) -> anyhow::Result<Vec<SubmittedTicket>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection
+            .transaction(|| PgSubmittedBetAndTicketRepo::settle_in(&connection, desired_user_id))
     }
 }
@@ -3,18 +3,24 @@ use std::sync::Arc;
 
 use crate::connection::{PgPool, PgPooledConnection};
 use crate::diesel::{delete, insert_into, prelude::*, update, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
 use std::cmp::Ordering;
 
 // type and structure imports
 use super::repo::Repo;
 use crate::db_models::{
+    external_reference::ExternalEntity,
+    game::Game,
     team::{CreateTeam, Team},
     team_plays_game::CreateTeamPlaysGame,
 };
-use crate::result_types::{GameInfo, TeamInfo};
+use crate::result_types::GameInfo;
 
 // schema imports
-use crate::schema::{game, team, team_plays_game};
+use crate::schema::{
+    bet, external_reference, game, game_match, outright_bet, outright_selection, player,
+    roster_membership, submitted_bet, team, team_plays_game, tournament, user_favourite,
+};
 
 /// Structure containing a reference to a database connection pool
 /// and methods to access the database
@@ -57,6 +63,7 @@ impl PgTeamRepo {
     ///
     /// Params
     /// ---
+    /// - connection: connection to the database
     /// - desired_team_id: ID of the desired team
     /// - desired_game_id: ID of the game we want to check the team belonging to
     ///
@@ -64,14 +71,74 @@ impl PgTeamRepo {
     /// ---
     /// - Ok(number) - number of records (if done correctly only getting a 1 or 0)
     /// - Err(_) if an error occurred
-    async fn in_game(&self, desired_team_id: i32, desired_game_id: i32) -> anyhow::Result<usize> {
+    fn in_game(
+        connection: &PgConnection,
+        desired_team_id: i32,
+        desired_game_id: i32,
+    ) -> anyhow::Result<usize> {
         let number_of_rows: usize = team_plays_game::table
             .filter(team_plays_game::team_id.eq(desired_team_id))
             .filter(team_plays_game::game_id.eq(desired_game_id))
-            .execute(&self.get_connection().await?)?;
+            .execute(connection)?;
 
         Ok(number_of_rows)
     }
+
+    /// Create a new team within the transaction of the caller, see `TeamRepo::create`
+    pub fn create_in(connection: &PgConnection, new_team: CreateTeam) -> anyhow::Result<i32> {
+        let query_result: i32 = insert_into(team::table)
+            .values(new_team)
+            .returning(team::id)
+            .get_result(connection)?;
+
+        Ok(query_result)
+    }
+
+    /// Edit the team information within the transaction of the caller, see `TeamRepo::edit`
+    pub fn edit_in(
+        connection: &PgConnection,
+        desired_team_id: i32,
+        edited_team: CreateTeam,
+    ) -> anyhow::Result<()> {
+        let _ = update(team::table.find(desired_team_id))
+            .set(edited_team)
+            .execute(connection)?;
+
+        Ok(())
+    }
+
+    /// Add a team into a list of teams that play the game within the transaction of the caller,
+    /// see `TeamRepo::add_to_game`
+    pub fn add_to_game_in(
+        connection: &PgConnection,
+        desired_team_id: i32,
+        desired_game_id: i32,
+    ) -> anyhow::Result<()> {
+        // check whether the team is already playing the game, if it is, return an error
+        let in_game = PgTeamRepo::in_game(connection, desired_team_id, desired_game_id)?;
+
+        match in_game.cmp(&1_usize) {
+            Ordering::Less => {},
+            Ordering::Equal => anyhow::bail!(DomainError::conflict("The team already plays the game!")),
+            Ordering::Greater => anyhow::bail!(DomainError::internal("The team already plays a game multiple times! This is a bug, please contact the site administrator.")),
+        };
+
+        let team: Team = team::table.find(desired_team_id).get_result(connection)?;
+        let game: Game = game::table.find(desired_game_id).get_result(connection)?;
+        if team.archived || game.archived {
+            anyhow::bail!(DomainError::conflict(
+                "An archived team cannot be added to a game, nor a team to an archived game"
+            ));
+        }
+
+        // add the team to the game
+        let _ = insert_into(team_plays_game::table)
+            .values(CreateTeamPlaysGame::new(desired_game_id, desired_team_id))
+            .execute(connection)?;
+
+        // all went well
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -112,15 +179,61 @@ pub trait TeamRepo {
     /// - Err(_) if an error occurred
     async fn get(&self, desired_team_id: i32) -> anyhow::Result<Team>;
 
-    /// Get all teams
+    /// Get all teams, ordered by their name
     ///
-    /// Returns
+    /// Params
     /// ---
-    /// TeamInfo is a type alias for a tuple containing (team_id, team_name, team_logo_url)
+    /// - include_archived: list the archived teams as well
     ///
-    /// - Ok(Vec<TeamInfo>) with all teams on the site
+    /// Returns
+    /// ---
+    /// - Ok(teams) with all teams on the site
     /// - Err(_) if an error has occurred
-    async fn get_all(&self) -> anyhow::Result<Vec<TeamInfo>>;
+    async fn get_all(&self, include_archived: bool) -> anyhow::Result<Vec<Team>>;
+
+    /// Archive a team or bring it back from the archive
+    ///
+    /// Params
+    /// ---
+    /// - desired_team_id: ID of the team
+    /// - archived: whether the team should be archived
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(()) if the team has been found and updated
+    /// - Err(_) if an error occurred
+    async fn set_archived(&self, desired_team_id: i32, archived: bool) -> anyhow::Result<()>;
+
+    /// Point the logo of the team to an uploaded image, or drop the reference to it
+    ///
+    /// Params
+    /// ---
+    /// - desired_team_id: ID of the team
+    /// - logo_asset_id: ID of the uploaded image, None if the logo is not an uploaded one
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(()) if the team has been found and updated
+    /// - Err(_) if the image does not exist or an error occurred
+    async fn set_logo_asset(
+        &self,
+        desired_team_id: i32,
+        logo_asset_id: Option<i32>,
+    ) -> anyhow::Result<()>;
+
+    /// Delete a team along with its game registrations and the favourites of the users.
+    /// Only possible if the team has no matches, bets, tournaments or roster history,
+    /// such teams can be archived instead.
+    ///
+    /// Params
+    /// ---
+    /// - desired_team_id: ID of the team
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(team) with the deleted team
+    /// - Err(_) if the team is still referenced or an error occurred
+    async fn delete(&self, desired_team_id: i32) -> anyhow::Result<Team>;
 
     /// Get a list of games which a certain team plays
     ///
@@ -137,7 +250,7 @@ pub trait TeamRepo {
     async fn games_played(&self, desired_team_id: i32) -> anyhow::Result<Vec<GameInfo>>;
 
     /// Add a team into a list of teams that play the game
-    /// It is only possible, if the team is not already in the
+    /// It is only possible, if the team is not already in the list and neither of them is archived
     ///
     /// Params
     /// ---
@@ -160,7 +273,8 @@ pub trait TeamRepo {
     /// Returns
     /// ---
     /// - Ok(()) if the deletion has been successful
-    /// - Err(_) if an error occurred while deleting
+    /// - Err(_) if the team has matches in the game or players of the game on its roster,
+    ///   or an error occurred while deleting
     async fn remove_from_game(
         &self,
         desired_team_id: i32,
@@ -172,21 +286,14 @@ pub trait TeamRepo {
 impl TeamRepo for PgTeamRepo {
     /// Create a new team
     async fn create(&self, new_team: CreateTeam) -> anyhow::Result<i32> {
-        let query_result: i32 = insert_into(team::table)
-            .values(new_team)
-            .returning(team::id)
-            .get_result(&self.get_connection().await?)?;
-
-        Ok(query_result)
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgTeamRepo::create_in(&connection, new_team)
     }
 
     /// Edit the team information
     async fn edit(&self, desired_team_id: i32, edited_team: CreateTeam) -> anyhow::Result<()> {
-        let _ = update(team::table.find(desired_team_id))
-            .set(edited_team)
-            .execute(&self.get_connection().await?)?;
-
-        Ok(())
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgTeamRepo::edit_in(&connection, desired_team_id, edited_team)
     }
 
     /// Get a specific desired team record
@@ -198,18 +305,115 @@ impl TeamRepo for PgTeamRepo {
         Ok(query_result)
     }
 
-    /// Get all teams
-    async fn get_all(&self) -> anyhow::Result<Vec<TeamInfo>> {
-        let selection = (team::id, team::name, team::logo);
-
-        let query_result: Vec<TeamInfo> = team::table
-            .order(team::name.asc())
-            .select(selection)
-            .get_results(&self.get_connection().await?)?;
+    /// Get all teams, ordered by their name
+    async fn get_all(&self, include_archived: bool) -> anyhow::Result<Vec<Team>> {
+        let query_result: Vec<Team> = match include_archived {
+            true => team::table
+                .order(team::name.asc())
+                .get_results(&self.get_connection().await?)?,
+            false => team::table
+                .filter(team::archived.eq(false))
+                .order(team::name.asc())
+                .get_results(&self.get_connection().await?)?,
+        };
 
         Ok(query_result)
     }
 
+    /// Archive a team or bring it back from the archive
+    async fn set_archived(&self, desired_team_id: i32, archived: bool) -> anyhow::Result<()> {
+        let updated: usize = update(team::table.find(desired_team_id))
+            .set(team::archived.eq(archived))
+            .execute(&self.get_connection().await?)?;
+
+        if updated == 0 {
+            anyhow::bail!(DomainError::not_found("The team does not exist"));
+        }
+        Ok(())
+    }
+
+    /// Point the logo of the team to an uploaded image, or drop the reference to it
+    async fn set_logo_asset(
+        &self,
+        desired_team_id: i32,
+        logo_asset_id: Option<i32>,
+    ) -> anyhow::Result<()> {
+        let updated: usize = update(team::table.find(desired_team_id))
+            .set(team::logo_asset_id.eq(logo_asset_id))
+            .execute(&self.get_connection().await?)?;
+
+        if updated == 0 {
+            anyhow::bail!(DomainError::not_found("The team does not exist"));
+        }
+        Ok(())
+    }
+
+    /// Delete a team which is not referenced by any matches, bets, tournaments or rosters
+    async fn delete(&self, desired_team_id: i32) -> anyhow::Result<Team> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        let to_be_removed: Team = team::table.find(desired_team_id).get_result(&connection)?;
+
+        let matches: usize = game_match::table
+            .filter(
+                game_match::team_one_id
+                    .eq(desired_team_id)
+                    .or(game_match::team_two_id.eq(desired_team_id)),
+            )
+            .execute(&connection)?;
+        if matches > 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot delete a team with matches, archive it instead"
+            ));
+        }
+        let bets: usize = bet::table
+            .filter(bet::team_id.eq(desired_team_id))
+            .execute(&connection)?
+            + submitted_bet::table
+                .filter(submitted_bet::team_id.eq(desired_team_id))
+                .execute(&connection)?
+            + outright_bet::table
+                .filter(outright_bet::team_id.eq(desired_team_id))
+                .execute(&connection)?;
+        if bets > 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot delete a team with bets placed on it, archive it instead"
+            ));
+        }
+        let tournaments: usize = outright_selection::table
+            .filter(outright_selection::team_id.eq(desired_team_id))
+            .execute(&connection)?
+            + tournament::table
+                .filter(tournament::winner_id.eq(desired_team_id))
+                .execute(&connection)?;
+        if tournaments > 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot delete a team taking part in tournaments, archive it instead"
+            ));
+        }
+        let memberships: usize = roster_membership::table
+            .filter(roster_membership::team_id.eq(desired_team_id))
+            .execute(&connection)?;
+        if memberships > 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot delete a team with a roster history, archive it instead"
+            ));
+        }
+
+        let _ = delete(team_plays_game::table.filter(team_plays_game::team_id.eq(desired_team_id)))
+            .execute(&connection)?;
+        let _ = delete(user_favourite::table.filter(user_favourite::team_id.eq(desired_team_id)))
+            .execute(&connection)?;
+        let _ = delete(
+            external_reference::table
+                .filter(external_reference::entity.eq(ExternalEntity::Team.as_str()))
+                .filter(external_reference::internal_id.eq(desired_team_id)),
+        )
+        .execute(&connection)?;
+        let _ = delete(team::table.find(desired_team_id)).execute(&connection)?;
+
+        Ok(to_be_removed)
+    }
+
     /// Get a list of games which a certain team plays
     async fn games_played(&self, desired_team_id: i32) -> anyhow::Result<Vec<GameInfo>> {
         let query_result: Vec<GameInfo> = team::table
@@ -224,22 +428,8 @@ impl TeamRepo for PgTeamRepo {
 
     /// Add a team into a list of teams that play the game
     async fn add_to_game(&self, desired_team_id: i32, desired_game_id: i32) -> anyhow::Result<()> {
-        // check whether the team is already playing the game, if it is, return an error
-        let in_game = self.in_game(desired_team_id, desired_game_id).await?;
-
-        match in_game.cmp(&1_usize) {
-            Ordering::Less => {},
-            Ordering::Equal => anyhow::bail!("The team already plays the game!"),
-            Ordering::Greater => anyhow::bail!("The team already plays a game multiple times! This is a bug, please contact the site administrator."),
-        };
-
-        // add the team to the game
-        let _ = insert_into(team_plays_game::table)
-            .values(CreateTeamPlaysGame::new(desired_game_id, desired_team_id))
-            .execute(&self.get_connection().await?)?;
-
-        // all went well
-        Ok(())
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgTeamRepo::add_to_game_in(&connection, desired_team_id, desired_game_id)
     }
 
     /// Remove a team from playing a certain game
@@ -249,11 +439,38 @@ impl TeamRepo for PgTeamRepo {
         desired_game_id: i32,
     ) -> anyhow::Result<()> {
         // check whether the team is playing the game, if it isnt, return an error
-        let in_game = self.in_game(desired_team_id, desired_game_id).await?;
+        let connection: PgPooledConnection = self.get_connection().await?;
+        let in_game = PgTeamRepo::in_game(&connection, desired_team_id, desired_game_id)?;
         if in_game == 0 {
-            anyhow::bail!("The team does not play the game!");
+            anyhow::bail!(DomainError::not_found("The team does not play the game!"));
         } else if in_game > 1 {
-            anyhow::bail!("Internal error, the team is registered to play the game more than once. Contact the site administrator, this is a bug.");
+            anyhow::bail!(DomainError::internal("Internal error, the team is registered to play the game more than once. Contact the site administrator, this is a bug."));
+        }
+
+        // the matches and the rosters of the team in the game refer to the team playing it
+        let matches: usize = game_match::table
+            .filter(game_match::game_id.eq(desired_game_id))
+            .filter(
+                game_match::team_one_id
+                    .eq(desired_team_id)
+                    .or(game_match::team_two_id.eq(desired_team_id)),
+            )
+            .execute(&connection)?;
+        if matches > 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot remove a team with matches in the game"
+            ));
+        }
+        let rostered: usize = roster_membership::table
+            .inner_join(player::table)
+            .filter(roster_membership::team_id.eq(desired_team_id))
+            .filter(roster_membership::left_at.is_null())
+            .filter(player::game_id.eq(desired_game_id))
+            .execute(&connection)?;
+        if rostered > 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot remove a team with players of the game on its roster"
+            ));
         }
 
         // remove the team from the game
@@ -264,7 +481,7 @@ impl TeamRepo for PgTeamRepo {
                     .and(team_plays_game::game_id.eq(desired_game_id)),
             ),
         )
-        .execute(&self.get_connection().await?)?;
+        .execute(&connection)?;
 
         // all went well
         Ok(())
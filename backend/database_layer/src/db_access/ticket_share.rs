@@ -0,0 +1,374 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{insert_into, prelude::*, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+
+// type and structure imports
+use crate::{
+    db_access::{
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        repo::Repo,
+    },
+    db_models::{
+        bet::{Bet, CreateBet},
+        game_match::GameMatch,
+        submitted_bet::SubmittedBet,
+        ticket::ObtainedTicket,
+        ticket_share_code::{
+            CopiedTicket, CreateTicketShareCode, SharedSelection, SharedTicket, TicketShareCode,
+        },
+    },
+};
+
+// schema imports
+use crate::schema::{bet, game_match, submitted_bet, submitted_ticket, ticket, ticket_share_code};
+
+/// How many times a share code gets generated before giving up,
+/// when the generated codes are already taken
+const SHARE_CODE_ATTEMPTS: usize = 5;
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with TicketShareCode records
+pub struct PgTicketShareRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgTicketShareRepo {
+    /// Create a new Ticket share repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new Ticket share repo
+    fn new(pool: &Arc<PgPool>) -> PgTicketShareRepo {
+        PgTicketShareRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+#[async_trait]
+pub trait TicketShareRepo {
+    /// Get the share code of a ticket, the code gets generated when the ticket
+    /// has not been shared yet. The ticket has to belong to the user.
+    ///
+    /// Params
+    /// ---
+    /// - `desired_user_id`: ID of the user who owns the ticket
+    /// - `shared_ticket`: the open or submitted ticket we wish to share
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(code)` with the share code of the ticket
+    /// - `Err(_)` if the ticket does not exist or does not belong to the user
+    async fn share(
+        &self,
+        desired_user_id: i32,
+        shared_ticket: SharedTicket,
+    ) -> anyhow::Result<String>;
+
+    /// Resolve the share code to the selections of the shared ticket
+    /// Open tickets are shown with the current ratios, submitted tickets
+    /// with the ratios the bets were submitted with.
+    ///
+    /// Params
+    /// ---
+    /// - `desired_code`: the share code
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(Vec<SharedSelection>)` with the selections of the shared ticket
+    /// - `Err(_)` if the code does not exist or an error occurred
+    async fn get_selections(&self, desired_code: &str) -> anyhow::Result<Vec<SharedSelection>>;
+
+    /// Copy the selections of the shared ticket into the open ticket of the user.
+    /// Every selection is validated again, so only the ones which can still
+    /// be placed get copied. The rest is returned together with the reason.
+    ///
+    /// Params
+    /// ---
+    /// - `desired_code`: the share code
+    /// - `desired_user_id`: ID of the user we wish to copy the selections for
+    /// - `max_selections`: maximum number of bets in a ticket
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(CopiedTicket)` with copied and rejected selections
+    /// - `Err(_)` if the code does not exist or an error occurred
+    async fn copy_to_user(
+        &self,
+        desired_code: &str,
+        desired_user_id: i32,
+        max_selections: usize,
+    ) -> anyhow::Result<CopiedTicket>;
+}
+
+#[async_trait]
+impl TicketShareRepo for PgTicketShareRepo {
+    /// Get the share code of a ticket, generate it if needed
+    async fn share(
+        &self,
+        desired_user_id: i32,
+        shared_ticket: SharedTicket,
+    ) -> anyhow::Result<String> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+
+        // check the owner of the ticket and look for an already existing code
+        let existing_code: Option<String> = match shared_ticket {
+            SharedTicket::Open(id) => {
+                let owner: i32 = ticket::table
+                    .find(id)
+                    .select(ticket::user_id)
+                    .get_result(&connection)?;
+                if owner != desired_user_id {
+                    anyhow::bail!(DomainError::validation(
+                        "Cannot share a ticket of another user!"
+                    ));
+                }
+
+                ticket_share_code::table
+                    .filter(ticket_share_code::ticket_id.eq(id))
+                    .select(ticket_share_code::code)
+                    .first(&connection)
+                    .optional()?
+            }
+            SharedTicket::Submitted(id) => {
+                let owner: i32 = submitted_ticket::table
+                    .find(id)
+                    .select(submitted_ticket::user_id)
+                    .get_result(&connection)?;
+                if owner != desired_user_id {
+                    anyhow::bail!(DomainError::validation(
+                        "Cannot share a ticket of another user!"
+                    ));
+                }
+
+                ticket_share_code::table
+                    .filter(ticket_share_code::submitted_ticket_id.eq(id))
+                    .select(ticket_share_code::code)
+                    .first(&connection)
+                    .optional()?
+            }
+        };
+
+        if let Some(code) = existing_code {
+            return Ok(code);
+        }
+
+        // the generated code may already be taken, another one is generated then
+        for _ in 0..SHARE_CODE_ATTEMPTS {
+            let inserted: Result<String, DieselError> = insert_into(ticket_share_code::table)
+                .values(CreateTicketShareCode::new(shared_ticket))
+                .returning(ticket_share_code::code)
+                .get_result(&connection);
+
+            match inserted {
+                Ok(code) => return Ok(code),
+                Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        anyhow::bail!(DomainError::internal(
+            "Could not generate an unused share code!"
+        ))
+    }
+
+    /// Resolve the share code to the selections of the shared ticket
+    async fn get_selections(&self, desired_code: &str) -> anyhow::Result<Vec<SharedSelection>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgTicketShareRepo::get_selections_in(&connection, desired_code)
+    }
+
+    /// Copy the selections of the shared ticket into the open ticket of the user
+    async fn copy_to_user(
+        &self,
+        desired_code: &str,
+        desired_user_id: i32,
+        max_selections: usize,
+    ) -> anyhow::Result<CopiedTicket> {
+        let open_ticket = match PgBetAndTicketRepo::new(&self.pool)
+            .get_user_current_ticket(desired_user_id)
+            .await?
+        {
+            ObtainedTicket::StillValid(ticket)
+            | ObtainedTicket::NoTicketFound(ticket)
+            | ObtainedTicket::NewAfterInvalid(ticket) => ticket,
+        };
+
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            PgTicketShareRepo::copy_to_ticket_in(
+                &connection,
+                desired_code,
+                open_ticket.id,
+                max_selections,
+            )
+        })
+    }
+}
+
+impl PgTicketShareRepo {
+    /// Resolve the share code to the selections of the shared ticket,
+    /// using the given connection
+    ///
+    /// Params
+    /// ---
+    /// - `connection`: connection to the database
+    /// - `desired_code`: the share code, case insensitive
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(selections)` in the order they were placed
+    /// - `Err(_)` if the code does not exist or an error occurred
+    pub fn get_selections_in(
+        connection: &PgConnection,
+        desired_code: &str,
+    ) -> anyhow::Result<Vec<SharedSelection>> {
+        let share_code: TicketShareCode = ticket_share_code::table
+            .filter(ticket_share_code::code.eq(desired_code.trim().to_uppercase()))
+            .first(connection)
+            .optional()?
+            .ok_or_else(|| DomainError::not_found("The share code does not exist!"))?;
+
+        let selections: Vec<SharedSelection> = match share_code.shared_ticket()? {
+            SharedTicket::Open(id) => {
+                let bets_and_matches: Vec<(Bet, GameMatch)> = bet::table
+                    .filter(bet::ticket_id.eq(id))
+                    .inner_join(game_match::table)
+                    .order(bet::created_at.asc())
+                    .get_results(connection)?;
+
+                bets_and_matches
+                    .iter()
+                    .map(|(bet, game_match)| {
+                        Ok(SharedSelection {
+                            game_match_id: bet.game_match_id,
+                            team_id: bet.team_id,
+                            ratio: bet
+                                .ratio(game_match)
+                                .map(|ratio| ratio.to_string())
+                                .unwrap_or_default(),
+                            market: bet.extract_market()?,
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<SharedSelection>>>()?
+            }
+            SharedTicket::Submitted(id) => {
+                let submitted_bets: Vec<SubmittedBet> = submitted_bet::table
+                    .filter(submitted_bet::submitted_ticket_id.eq(id))
+                    .order(submitted_bet::placed_at.asc())
+                    .get_results(connection)?;
+
+                submitted_bets
+                    .into_iter()
+                    .map(|bet| {
+                        Ok(SharedSelection {
+                            game_match_id: bet.game_match_id,
+                            team_id: bet.team_id,
+                            market: bet.extract_market()?,
+                            ratio: bet.bet_ratio,
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<SharedSelection>>>()?
+            }
+        };
+
+        Ok(selections)
+    }
+
+    /// Copy the selections of the shared ticket into the given open ticket,
+    /// within the transaction of the connection. Every selection is placed in its own
+    /// savepoint, so a rejected selection leaves the copied ones in place.
+    ///
+    /// Params
+    /// ---
+    /// - `connection`: connection of the transaction copying the selections
+    /// - `desired_code`: the share code
+    /// - `open_ticket_id`: ID of the open ticket we wish to copy the selections into
+    /// - `max_selections`: maximum number of bets in a ticket
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(CopiedTicket)` with copied and rejected selections
+    /// - `Err(_)` if the code does not exist or an error occurred
+    pub fn copy_to_ticket_in(
+        connection: &PgConnection,
+        desired_code: &str,
+        open_ticket_id: i32,
+        max_selections: usize,
+    ) -> anyhow::Result<CopiedTicket> {
+        let selections = PgTicketShareRepo::get_selections_in(connection, desired_code)?;
+
+        let mut copied_ticket = CopiedTicket {
+            ticket_id: open_ticket_id,
+            copied: Vec::new(),
+            rejected: Vec::new(),
+        };
+
+        for selection in selections {
+            // validate the selection against the current state of the match
+            let game_match: GameMatch = game_match::table
+                .find(selection.game_match_id)
+                .get_result(connection)?;
+
+            let current_ratio = match selection.market.ratio(&game_match, selection.team_id) {
+                Ok(ratio) => ratio.to_string(),
+                Err(_) => {
+                    copied_ticket.rejected.push((
+                        selection,
+                        String::from("The team does not play in the match anymore"),
+                    ));
+                    continue;
+                }
+            };
+
+            // placing the bet checks the ticket limit, whether the match is played
+            // and whether it is not already on the ticket
+            let placed = connection.transaction(|| {
+                PgBetAndTicketRepo::place_a_bet_in(
+                    connection,
+                    open_ticket_id,
+                    CreateBet::new(
+                        selection.game_match_id,
+                        open_ticket_id,
+                        selection.team_id,
+                        selection.market,
+                    ),
+                    max_selections,
+                )
+            });
+            match placed {
+                Ok(bet) => copied_ticket.copied.push((
+                    bet,
+                    SharedSelection {
+                        ratio: current_ratio,
+                        ..selection
+                    },
+                )),
+                Err(err) => copied_ticket.rejected.push((selection, err.to_string())),
+            }
+        }
+
+        Ok(copied_ticket)
+    }
+}
@@ -0,0 +1,569 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::connection::{PgPool, PgPooledConnection};
+use crate::diesel::{delete, insert_into, prelude::*, update, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
+use crate::type_storing::time_handling::TimeHandling;
+
+// type and structure imports
+use super::repo::Repo;
+use crate::db_models::{
+    game_match::GameMatch,
+    game_match_event::GameMatchEvent,
+    stage::{CreateStage, Stage},
+    stage_match::CreateStageMatch,
+    tournament::{CreateTournament, Tournament},
+};
+
+// schema imports
+use crate::schema::{
+    game_match, game_match_event, outright_bet, outright_selection, stage, stage_match,
+    team_plays_game, tournament,
+};
+
+/// Structure containing a reference to a database connection pool
+/// and methods to access the database
+/// to work with Tournament, Stage and StageMatch records
+pub struct PgTournamentRepo {
+    pub pool: Arc<PgPool>,
+}
+
+#[async_trait]
+impl Repo for PgTournamentRepo {
+    /// Create a new Tournament repo with a reference to an initialized pool.
+    ///
+    /// Params
+    /// ---
+    /// - `pool`: A reference to an already initialized database connection pool,
+    ///         used for connecting to the database
+    ///
+    /// Returns
+    /// ---
+    /// - new Tournament repo
+    fn new(pool: &Arc<PgPool>) -> PgTournamentRepo {
+        PgTournamentRepo {
+            pool: Arc::clone(pool),
+        }
+    }
+
+    /// Get a connection from the pool
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(pooled_connection)` if no error occurs
+    /// - `Err(_)` if the wait for another connection is too long
+    async fn get_connection(&self) -> anyhow::Result<PgPooledConnection> {
+        Ok(self.pool.get()?)
+    }
+}
+
+#[async_trait]
+pub trait TournamentRepo {
+    /// Create a new tournament
+    ///
+    /// Params
+    /// ---
+    /// - `new_tournament`: a write structure for creating a tournament
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(id)` if the tournament has been created
+    /// - `Err(_)` if an error occurred
+    async fn create(&self, new_tournament: CreateTournament) -> anyhow::Result<i32>;
+
+    /// Edit the tournament information, the game can be changed only while no match is assigned
+    ///
+    /// Params
+    /// ---
+    /// - `desired_tournament_id`: ID of the tournament
+    /// - `edited_tournament`: new information of the tournament
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the tournament has been edited
+    /// - `Err(_)` if an error occurred
+    async fn edit(
+        &self,
+        desired_tournament_id: i32,
+        edited_tournament: CreateTournament,
+    ) -> anyhow::Result<()>;
+
+    /// Delete the tournament along with its stages and outright selections,
+    /// the matches themselves are kept.
+    /// Only possible if no outright bet has been placed on the tournament.
+    ///
+    /// Params
+    /// ---
+    /// - `desired_tournament_id`: ID of the tournament
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the tournament has been deleted
+    /// - `Err(_)` if an error occurred
+    async fn delete(&self, desired_tournament_id: i32) -> anyhow::Result<()>;
+
+    /// Get a single tournament
+    ///
+    /// Params
+    /// ---
+    /// - `desired_tournament_id`: ID of the tournament
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(tournament)` if the tournament exists
+    /// - `Err(_)` if an error occurred
+    async fn get(&self, desired_tournament_id: i32) -> anyhow::Result<Tournament>;
+
+    /// Get all tournaments, ordered by their start
+    ///
+    /// Params
+    /// ---
+    /// - `desired_game_id`: only the tournaments of this game, if set
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(tournaments)` with the tournaments
+    /// - `Err(_)` if an error occurred
+    async fn get_all(&self, desired_game_id: Option<i32>) -> anyhow::Result<Vec<Tournament>>;
+
+    /// End the tournament with a winner
+    ///
+    /// Params
+    /// ---
+    /// - `desired_tournament_id`: ID of the tournament
+    /// - `winner_id`: ID of the team which has won the tournament, it has to play its game
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(tournament)` with the ended tournament
+    /// - `Err(_)` if the tournament has already ended or an error occurred
+    async fn end(&self, desired_tournament_id: i32, winner_id: i32) -> anyhow::Result<Tournament>;
+
+    /// Create a new stage of a tournament
+    ///
+    /// Params
+    /// ---
+    /// - `new_stage`: a write structure for creating a stage
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(id)` if the stage has been created
+    /// - `Err(_)` if an error occurred
+    async fn create_stage(&self, new_stage: CreateStage) -> anyhow::Result<i32>;
+
+    /// Edit the stage, it cannot be moved to another tournament
+    ///
+    /// Params
+    /// ---
+    /// - `desired_stage_id`: ID of the stage
+    /// - `edited_stage`: new information of the stage
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the stage has been edited
+    /// - `Err(_)` if an error occurred
+    async fn edit_stage(
+        &self,
+        desired_stage_id: i32,
+        edited_stage: CreateStage,
+    ) -> anyhow::Result<()>;
+
+    /// Delete the stage, its matches are kept but no longer belong to the tournament
+    ///
+    /// Params
+    /// ---
+    /// - `desired_stage_id`: ID of the stage
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the stage has been deleted
+    /// - `Err(_)` if an error occurred
+    async fn delete_stage(&self, desired_stage_id: i32) -> anyhow::Result<()>;
+
+    /// Get a single stage
+    ///
+    /// Params
+    /// ---
+    /// - `desired_stage_id`: ID of the stage
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(stage)` if the stage exists
+    /// - `Err(_)` if an error occurred
+    async fn get_stage(&self, desired_stage_id: i32) -> anyhow::Result<Stage>;
+
+    /// Get the stages of a tournament in their order
+    ///
+    /// Params
+    /// ---
+    /// - `desired_tournament_id`: ID of the tournament
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(stages)` with the stages
+    /// - `Err(_)` if an error occurred
+    async fn get_stages(&self, desired_tournament_id: i32) -> anyhow::Result<Vec<Stage>>;
+
+    /// Assign the match to the stage, the match has to be of the game of the tournament
+    /// and cannot belong to another stage
+    ///
+    /// Params
+    /// ---
+    /// - `desired_stage_id`: ID of the stage
+    /// - `desired_match_id`: ID of the match
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the match has been assigned
+    /// - `Err(_)` if an error occurred
+    async fn add_match(&self, desired_stage_id: i32, desired_match_id: i32) -> anyhow::Result<()>;
+
+    /// Remove the match from the stage
+    ///
+    /// Params
+    /// ---
+    /// - `desired_stage_id`: ID of the stage
+    /// - `desired_match_id`: ID of the match
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(())` if the match has been removed
+    /// - `Err(_)` if the match is not in the stage or an error occurred
+    async fn remove_match(
+        &self,
+        desired_stage_id: i32,
+        desired_match_id: i32,
+    ) -> anyhow::Result<()>;
+
+    /// Get the matches of the tournament, ordered by the stages
+    ///
+    /// Params
+    /// ---
+    /// - `desired_tournament_id`: ID of the tournament
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(stage_and_match_ids)` with pairs of a stage ID and a match ID
+    /// - `Err(_)` if an error occurred
+    async fn get_matches(&self, desired_tournament_id: i32) -> anyhow::Result<Vec<(i32, i32)>>;
+
+    /// Get the matches of the stage along with their newest events, used for computing the standings
+    ///
+    /// Params
+    /// ---
+    /// - `desired_stage_id`: ID of the stage
+    ///
+    /// Returns
+    /// ---
+    /// - `Ok(matches_and_events)` with the matches and their newest events
+    /// - `Err(_)` if an error occurred
+    async fn get_stage_results(
+        &self,
+        desired_stage_id: i32,
+    ) -> anyhow::Result<Vec<(GameMatch, GameMatchEvent)>>;
+}
+
+#[async_trait]
+impl TournamentRepo for PgTournamentRepo {
+    /// Create a new tournament
+    async fn create(&self, new_tournament: CreateTournament) -> anyhow::Result<i32> {
+        let id: i32 = insert_into(tournament::table)
+            .values(new_tournament)
+            .returning(tournament::id)
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(id)
+    }
+
+    /// Edit the tournament information, the game can be changed only while no match is assigned
+    async fn edit(
+        &self,
+        desired_tournament_id: i32,
+        edited_tournament: CreateTournament,
+    ) -> anyhow::Result<()> {
+        let current = self.get(desired_tournament_id).await?;
+        if current.game_id != edited_tournament.game_id
+            && !self.get_matches(desired_tournament_id).await?.is_empty()
+        {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot change the game of a tournament which already has matches"
+            ));
+        }
+
+        let _ = update(tournament::table.find(desired_tournament_id))
+            .set(edited_tournament)
+            .execute(&self.get_connection().await?)?;
+
+        Ok(())
+    }
+
+    /// Delete the tournament along with its stages and outright selections
+    async fn delete(&self, desired_tournament_id: i32) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+
+        let any_bets: usize = outright_bet::table
+            .filter(outright_bet::tournament_id.eq(desired_tournament_id))
+            .execute(&connection)?;
+        if any_bets > 0 {
+            anyhow::bail!(DomainError::conflict(
+                "Cannot delete a tournament, if there are outright bets placed on it"
+            ));
+        }
+
+        let stage_ids: Vec<i32> = stage::table
+            .filter(stage::tournament_id.eq(desired_tournament_id))
+            .select(stage::id)
+            .get_results(&connection)?;
+        let _ = delete(stage_match::table.filter(stage_match::stage_id.eq_any(&stage_ids)))
+            .execute(&connection)?;
+        let _ = delete(stage::table.filter(stage::id.eq_any(&stage_ids))).execute(&connection)?;
+        let _ = delete(
+            outright_selection::table
+                .filter(outright_selection::tournament_id.eq(desired_tournament_id)),
+        )
+        .execute(&connection)?;
+        let _ = delete(tournament::table.find(desired_tournament_id)).execute(&connection)?;
+
+        Ok(())
+    }
+
+    /// Get a single tournament
+    async fn get(&self, desired_tournament_id: i32) -> anyhow::Result<Tournament> {
+        let query_result: Tournament = tournament::table
+            .find(desired_tournament_id)
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(query_result)
+    }
+
+    /// Get all tournaments, ordered by their start
+    async fn get_all(&self, desired_game_id: Option<i32>) -> anyhow::Result<Vec<Tournament>> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        let query = tournament::table.order((tournament::starts_at.asc(), tournament::id.asc()));
+
+        let query_result: Vec<Tournament> = match desired_game_id {
+            Some(desired_game_id) => query
+                .filter(tournament::game_id.eq(desired_game_id))
+                .get_results(&connection)?,
+            None => query.get_results(&connection)?,
+        };
+        Ok(query_result)
+    }
+
+    /// End the tournament with a winner
+    async fn end(&self, desired_tournament_id: i32, winner_id: i32) -> anyhow::Result<Tournament> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgTournamentRepo::end_in(&connection, desired_tournament_id, winner_id)
+    }
+
+    /// Create a new stage of a tournament
+    async fn create_stage(&self, new_stage: CreateStage) -> anyhow::Result<i32> {
+        let id: i32 = insert_into(stage::table)
+            .values(new_stage)
+            .returning(stage::id)
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(id)
+    }
+
+    /// Edit the stage, it cannot be moved to another tournament
+    async fn edit_stage(
+        &self,
+        desired_stage_id: i32,
+        edited_stage: CreateStage,
+    ) -> anyhow::Result<()> {
+        if self.get_stage(desired_stage_id).await?.tournament_id != edited_stage.tournament_id {
+            anyhow::bail!(DomainError::validation(
+                "Cannot move a stage to another tournament"
+            ));
+        }
+
+        let _ = update(stage::table.find(desired_stage_id))
+            .set(edited_stage)
+            .execute(&self.get_connection().await?)?;
+
+        Ok(())
+    }
+
+    /// Delete the stage, its matches are kept but no longer belong to the tournament
+    async fn delete_stage(&self, desired_stage_id: i32) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+
+        let _ = delete(stage_match::table.filter(stage_match::stage_id.eq(desired_stage_id)))
+            .execute(&connection)?;
+        let number_of_rows: usize =
+            delete(stage::table.find(desired_stage_id)).execute(&connection)?;
+        if number_of_rows == 0 {
+            anyhow::bail!(DomainError::not_found("The stage does not exist"));
+        }
+
+        Ok(())
+    }
+
+    /// Get a single stage
+    async fn get_stage(&self, desired_stage_id: i32) -> anyhow::Result<Stage> {
+        let query_result: Stage = stage::table
+            .find(desired_stage_id)
+            .get_result(&self.get_connection().await?)?;
+
+        Ok(query_result)
+    }
+
+    /// Get the stages of a tournament in their order
+    async fn get_stages(&self, desired_tournament_id: i32) -> anyhow::Result<Vec<Stage>> {
+        let query_result: Vec<Stage> = stage::table
+            .filter(stage::tournament_id.eq(desired_tournament_id))
+            .order((stage::position.asc(), stage::id.asc()))
+            .get_results(&self.get_connection().await?)?;
+
+        Ok(query_result)
+    }
+
+    /// Assign the match to the stage
+    async fn add_match(&self, desired_stage_id: i32, desired_match_id: i32) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+
+        let tournament_game_id: i32 = stage::table
+            .inner_join(tournament::table)
+            .filter(stage::id.eq(desired_stage_id))
+            .select(tournament::game_id)
+            .get_result(&connection)?;
+        let match_game_id: i32 = game_match::table
+            .find(desired_match_id)
+            .select(game_match::game_id)
+            .get_result(&connection)?;
+        if match_game_id != tournament_game_id {
+            anyhow::bail!(DomainError::validation(
+                "The match is not of the game of the tournament"
+            ));
+        }
+
+        let current_stage: Option<i32> = stage_match::table
+            .filter(stage_match::game_match_id.eq(desired_match_id))
+            .select(stage_match::stage_id)
+            .get_result(&connection)
+            .optional()?;
+        match current_stage {
+            Some(stage_id) if stage_id == desired_stage_id => {
+                anyhow::bail!(DomainError::conflict(
+                    "The match already belongs to the stage"
+                ))
+            }
+            Some(_) => anyhow::bail!(DomainError::conflict(
+                "The match already belongs to another stage"
+            )),
+            None => {}
+        }
+
+        let _ = insert_into(stage_match::table)
+            .values(CreateStageMatch::new(desired_stage_id, desired_match_id))
+            .execute(&connection)?;
+
+        Ok(())
+    }
+
+    /// Remove the match from the stage
+    async fn remove_match(
+        &self,
+        desired_stage_id: i32,
+        desired_match_id: i32,
+    ) -> anyhow::Result<()> {
+        let number_of_rows: usize = delete(
+            stage_match::table.filter(
+                stage_match::stage_id
+                    .eq(desired_stage_id)
+                    .and(stage_match::game_match_id.eq(desired_match_id)),
+            ),
+        )
+        .execute(&self.get_connection().await?)?;
+
+        if number_of_rows == 0 {
+            anyhow::bail!(DomainError::not_found(
+                "The match does not belong to the stage"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the matches of the tournament, ordered by the stages
+    async fn get_matches(&self, desired_tournament_id: i32) -> anyhow::Result<Vec<(i32, i32)>> {
+        let query_result: Vec<(i32, i32)> = stage_match::table
+            .inner_join(stage::table)
+            .filter(stage::tournament_id.eq(desired_tournament_id))
+            .order((
+                stage::position.asc(),
+                stage::id.asc(),
+                stage_match::game_match_id.asc(),
+            ))
+            .select((stage_match::stage_id, stage_match::game_match_id))
+            .get_results(&self.get_connection().await?)?;
+
+        Ok(query_result)
+    }
+
+    /// Get the matches of the stage along with their newest events
+    async fn get_stage_results(
+        &self,
+        desired_stage_id: i32,
+    ) -> anyhow::Result<Vec<(GameMatch, GameMatchEvent)>> {
+        let match_ids: Vec<i32> = stage_match::table
+            .filter(stage_match::stage_id.eq(desired_stage_id))
+            .select(stage_match::game_match_id)
+            .get_results(&self.get_connection().await?)?;
+
+        let query_result: Vec<(GameMatch, GameMatchEvent)> = game_match::table
+            .inner_join(game_match_event::table)
+            .filter(game_match::id.eq_any(match_ids))
+            .order((game_match::id, game_match_event::created_at.desc()))
+            .distinct_on(game_match::id)
+            .get_results(&self.get_connection().await?)?;
+
+        Ok(query_result)
+    }
+}
+
+impl PgTournamentRepo {
+    /// End the tournament with a winner within the transaction of the caller,
+    /// see `TournamentRepo::end`
+    pub fn end_in(
+        connection: &PgConnection,
+        desired_tournament_id: i32,
+        winner_id: i32,
+    ) -> anyhow::Result<Tournament> {
+        let current: Tournament = tournament::table
+            .find(desired_tournament_id)
+            .get_result(connection)?;
+
+        let plays_the_game: usize = team_plays_game::table
+            .filter(
+                team_plays_game::team_id
+                    .eq(winner_id)
+                    .and(team_plays_game::game_id.eq(current.game_id)),
+            )
+            .execute(connection)?;
+        if plays_the_game == 0 {
+            anyhow::bail!(DomainError::validation(
+                "The winner does not play the game of the tournament"
+            ));
+        }
+
+        // only the first end of the tournament succeeds
+        let ended: Option<Tournament> = update(
+            tournament::table
+                .find(desired_tournament_id)
+                .filter(tournament::ended_at.is_null()),
+        )
+        .set((
+            tournament::winner_id.eq(winner_id),
+            tournament::ended_at.eq(TimeHandling::store()),
+        ))
+        .get_result(connection)
+        .optional()?;
+
+        match ended {
+            Some(ended) => Ok(ended),
+            None => anyhow::bail!(DomainError::conflict("The tournament has already ended")),
+        }
+    }
+}
@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use std::sync::Arc;
 
 use crate::diesel::{insert_into, prelude::*, update, QueryDsl, RunQueryDsl};
+use crate::error::DomainError;
 
 use crate::connection::{PgPool, PgPooledConnection};
 
@@ -93,6 +94,23 @@ pub trait UserRepo {
     /// - `Err(_)` if an error occurrs
     async fn edit(&self, desired_user_id: i32, edited_record: CreateUser) -> anyhow::Result<()>;
 
+    /// Point the photo of the user to an uploaded image, or drop the reference to it
+    ///
+    /// Params
+    /// ---
+    /// - desired_user_id: ID of the user
+    /// - photo_asset_id: ID of the uploaded image, None if the photo is not an uploaded one
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(()) if the user has been found and updated
+    /// - Err(_) if the image does not exist or an error occurred
+    async fn set_photo_asset(
+        &self,
+        desired_user_id: i32,
+        photo_asset_id: Option<i32>,
+    ) -> anyhow::Result<()>;
+
     /// Add a new address for the user
     ///
     /// Params
@@ -198,7 +216,7 @@ impl UserRepo for PgUserRepo {
             .execute(&connection)?;
 
         if already_exists != 0 {
-            anyhow::bail!("The user already exists!");
+            anyhow::bail!(DomainError::conflict("The user already exists!"));
         }
 
         let new_user_id: i32 = insert_into(user::table)
@@ -228,15 +246,13 @@ impl UserRepo for PgUserRepo {
 
                 // check if the address is in use
                 if found_record.email == edited_record.email && found_record.id != desired_user_id {
-                    anyhow::bail!(
-                        "Cannot change the email address to an email address that is already in use!"
-                    );
+                    anyhow::bail!(DomainError::conflict("Cannot change the email address to an email address that is already in use!"));
                 }
             }
             _ => {
-                anyhow::bail!(
+                anyhow::bail!(DomainError::internal(
                     "Internal error. Multiple accounts with the same email have been found"
-                )
+                ))
             }
         }
 
@@ -247,6 +263,22 @@ impl UserRepo for PgUserRepo {
         Ok(())
     }
 
+    /// Point the photo of the user to an uploaded image, or drop the reference to it
+    async fn set_photo_asset(
+        &self,
+        desired_user_id: i32,
+        photo_asset_id: Option<i32>,
+    ) -> anyhow::Result<()> {
+        let updated: usize = update(user::table.find(desired_user_id))
+            .set(user::photo_asset_id.eq(photo_asset_id))
+            .execute(&self.get_connection().await?)?;
+
+        if updated == 0 {
+            anyhow::bail!(DomainError::not_found("The user does not exist"));
+        }
+        Ok(())
+    }
+
     /// Get user's current address
     async fn get_current_address(&self, desired_user_id: i32) -> anyhow::Result<UserAddress> {
         let query_result: UserAddress = user_address::table
@@ -276,56 +308,113 @@ impl UserRepo for PgUserRepo {
 
     /// Get user's balance
     async fn get_balance(&self, desired_user_id: i32) -> anyhow::Result<String> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        PgUserRepo::get_balance_in(&connection, desired_user_id)
+    }
+
+    /// Add balance to the user's account
+    /// Fails if the balance specified is negative
+    async fn add_balance(&self, desired_user_id: i32, desired_amount: f64) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            PgUserRepo::add_balance_in(&connection, desired_user_id, desired_amount)
+        })
+    }
+
+    /// Withdraw the user's balance
+    /// Fails if the balance specified is higher than the current balance
+    async fn spend_balance(
+        &self,
+        desired_user_id: i32,
+        desired_spending: f64,
+    ) -> anyhow::Result<()> {
+        let connection: PgPooledConnection = self.get_connection().await?;
+        connection.transaction(|| {
+            PgUserRepo::spend_balance_in(&connection, desired_user_id, desired_spending)
+        })
+    }
+}
+
+/// The changes of the balances made within the transaction of the caller,
+/// see `OutboxRepo::transaction`
+impl PgUserRepo {
+    /// Get user's balance, see `UserRepo::get_balance`
+    pub fn get_balance_in(
+        connection: &PgConnection,
+        desired_user_id: i32,
+    ) -> anyhow::Result<String> {
         let query_result: String = user::table
             .find(desired_user_id)
             .select(user::balance)
-            .get_result(&self.get_connection().await?)?;
+            .get_result(connection)?;
 
         Ok(query_result)
     }
 
-    /// Add balance to the user's account
-    /// Fails if the balance specified is negative
-    async fn add_balance(&self, desired_user_id: i32, desired_amount: f64) -> anyhow::Result<()> {
+    /// Get user's balance and lock the user until the transaction of the caller ends,
+    /// so concurrent spendings wait for each other instead of overdrawing the balance
+    pub fn lock_balance_in(
+        connection: &PgConnection,
+        desired_user_id: i32,
+    ) -> anyhow::Result<String> {
+        let query_result: String = user::table
+            .find(desired_user_id)
+            .select(user::balance)
+            .for_update()
+            .get_result(connection)?;
+
+        Ok(query_result)
+    }
+
+    /// Add balance to the user's account, see `UserRepo::add_balance`
+    pub fn add_balance_in(
+        connection: &PgConnection,
+        desired_user_id: i32,
+        desired_amount: f64,
+    ) -> anyhow::Result<()> {
         if desired_amount <= 0.0 {
-            anyhow::bail!("Cannot 'add' a negative balance!")
+            anyhow::bail!(DomainError::validation("Cannot 'add' a negative balance!"))
         }
 
         // retrieve balance
-        let user_balance: String = self.get_balance(desired_user_id).await?;
+        let user_balance: String = PgUserRepo::get_balance_in(connection, desired_user_id)?;
         let converted_balance: f64 = user_balance.parse::<f64>()?;
 
         // update the balance
         let _ = update(user::table.find(desired_user_id))
             .set(user::balance.eq((converted_balance + desired_amount).to_string()))
-            .execute(&self.get_connection().await?)?;
+            .execute(connection)?;
 
         Ok(())
     }
 
-    /// Withdraw the user's balance
-    /// Fails if the balance specified is higher than the current balance
-    async fn spend_balance(
-        &self,
+    /// Withdraw the user's balance, see `UserRepo::spend_balance`
+    pub fn spend_balance_in(
+        connection: &PgConnection,
         desired_user_id: i32,
         desired_spending: f64,
     ) -> anyhow::Result<()> {
         if desired_spending <= 0.0 {
-            anyhow::bail!("Cannot 'withdraw' a negative balance!")
+            anyhow::bail!(DomainError::validation(
+                "Cannot 'withdraw' a negative balance!"
+            ))
         }
 
         // retrieve balance
-        let user_balance: String = self.get_balance(desired_user_id).await?;
+        let user_balance: String = PgUserRepo::get_balance_in(connection, desired_user_id)?;
         let converted_balance: f64 = user_balance.parse::<f64>()?;
 
         if converted_balance < desired_spending {
-            anyhow::bail!("Cannot withdraw more money that user has.")
+            anyhow::bail!(DomainError::InsufficientFunds {
+                balance: converted_balance,
+                required: desired_spending
+            })
         }
 
         // update the balance
         let _ = update(user::table.find(desired_user_id))
             .set(user::balance.eq((converted_balance - desired_spending).to_string()))
-            .execute(&self.get_connection().await?)?;
+            .execute(connection)?;
 
         Ok(())
     }
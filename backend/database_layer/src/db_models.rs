@@ -1,11 +1,27 @@
 pub mod bet;
+pub mod bet_market;
+pub mod event_outbox;
+pub mod external_reference;
 pub mod game;
 pub mod game_match;
 pub mod game_match_event;
+pub mod match_map;
+pub mod match_timeline_event;
+pub mod media_asset;
+pub mod outright_bet;
+pub mod outright_selection;
+pub mod player;
+pub mod roster_membership;
+pub mod stage;
+pub mod stage_match;
 pub mod submitted_bet;
 pub mod submitted_ticket;
 pub mod team;
 pub mod team_plays_game;
 pub mod ticket;
+pub mod ticket_share_code;
+pub mod tournament;
 pub mod user;
 pub mod user_address;
+pub mod user_favourite;
+pub mod user_session;
@@ -1,5 +1,6 @@
 use crate::db_models::{
-    game_match::GameMatch, submitted_bet::CreateSubmittedBet, team::Team, ticket::Ticket,
+    bet_market::BetMarket, game_match::GameMatch, submitted_bet::CreateSubmittedBet, team::Team,
+    ticket::Ticket,
 };
 use crate::schema::bet;
 use crate::type_storing::time_handling::TimeHandling;
@@ -17,6 +18,8 @@ pub struct Bet {
     pub ticket_id: i32,
     pub team_id: i32,
     pub created_at: String,
+    pub market: String,
+    pub market_value: Option<String>,
 }
 
 /// Write structure, used for inserting
@@ -28,9 +31,35 @@ pub struct CreateBet {
     pub ticket_id: i32,
     pub team_id: i32,
     pub created_at: String,
+    pub market: String,
+    pub market_value: Option<String>,
 }
 
 impl Bet {
+    /// Get the typed market of the bet
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(market) if the stored market is known
+    /// - Err(_) otherwise
+    pub fn extract_market(&self) -> anyhow::Result<BetMarket> {
+        BetMarket::from_columns(&self.market, self.market_value.as_deref())
+    }
+
+    /// Get the current ratio of the bet from the ratios of its match
+    ///
+    /// Params
+    /// ---
+    /// - game_match: the match the bet is on
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(ratio) of the bet
+    /// - Err(_) if the market or the ratios of the match could not be read
+    pub fn ratio(&self, game_match: &GameMatch) -> anyhow::Result<f64> {
+        self.extract_market()?.ratio(game_match, self.team_id)
+    }
+
     pub fn submit_bets(
         desired_submitted_ticket_id: i32,
         bets_and_tickets: &[(Bet, GameMatch)],
@@ -39,20 +68,16 @@ impl Bet {
         let submission_date = TimeHandling::store();
 
         for (bet, game_match) in bets_and_tickets {
-            let bet_ratio = if bet.team_id == game_match.team_one_id {
-                &game_match.team_one_ratio
-            } else {
-                &game_match.team_two_ratio
-            };
-
             submitted_bets.push(CreateSubmittedBet {
                 game_match_id: bet.game_match_id,
                 submitted_ticket_id: desired_submitted_ticket_id,
                 team_id: bet.team_id,
-                bet_ratio: bet_ratio.parse::<f64>()?.to_string(),
+                bet_ratio: bet.ratio(game_match)?.to_string(),
                 placed_at: bet.created_at.clone(),
                 submitted_at: submission_date.clone(),
                 won: None,
+                market: bet.market.clone(),
+                market_value: bet.market_value.clone(),
             })
         }
 
@@ -67,16 +92,20 @@ impl CreateBet {
     /// ---
     /// - game_match_id: ID of the match we place the bet on
     /// - ticket_id: ID of the ticket this bet is put into
+    /// - team_id: ID of the team we bet on
+    /// - market: what we bet the team does in the match
     ///
     /// Returns
     /// ---
     /// - new `bet` insert structure
-    pub fn new(game_match_id: i32, ticket_id: i32, team_id: i32) -> CreateBet {
+    pub fn new(game_match_id: i32, ticket_id: i32, team_id: i32, market: BetMarket) -> CreateBet {
         CreateBet {
             game_match_id,
             ticket_id,
             team_id,
             created_at: TimeHandling::store(),
+            market: market.market(),
+            market_value: market.market_value(),
         }
     }
 }
@@ -0,0 +1,265 @@
+use crate::db_models::{
+    game_match::GameMatch,
+    match_map::{series_score, MatchMap},
+};
+use crate::error::DomainError;
+
+/// What a bet on a team is on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BetMarket {
+    /// the team wins the match
+    MatchWinner,
+    /// the team wins the map with the number
+    MapWinner { map_number: i32 },
+    /// the team wins the match with the score, in maps won and lost
+    SeriesScore { won: i32, lost: i32 },
+}
+
+/// Result of a bet, known once the match or the map it is on has finished
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BetOutcome {
+    Won,
+    Lost,
+    /// the map has not been played, the bet counts with the ratio of 1
+    Void,
+}
+
+/// Highest precision of the computed ratios
+const RATIO_PRECISION: f64 = 100.0;
+
+/// Lowest ratio offered for a market
+const MIN_RATIO: f64 = 1.01;
+
+/// Binomial coefficient, small enough numbers only (the maps of a match)
+fn choose(n: i32, k: i32) -> f64 {
+    (0..k).fold(1.0, |acc, i| acc * f64::from(n - i) / f64::from(i + 1))
+}
+
+/// Probability of winning a series to `maps_to_win` maps with `lost` maps lost,
+/// when every map is won with the probability `map_probability`
+fn score_probability(map_probability: f64, maps_to_win: i32, lost: i32) -> f64 {
+    choose(maps_to_win - 1 + lost, lost)
+        * map_probability.powi(maps_to_win)
+        * (1.0 - map_probability).powi(lost)
+}
+
+/// Find the probability of winning a single map, which gives the probability of winning the series
+fn map_probability(series_probability: f64, maps_to_win: i32) -> f64 {
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..50 {
+        let middle = (low + high) / 2.0;
+        let series: f64 = (0..maps_to_win)
+            .map(|lost| score_probability(middle, maps_to_win, lost))
+            .sum();
+        if series < series_probability {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.0
+}
+
+impl BetMarket {
+    /// Read the market stored in the `market` and `market_value` columns
+    ///
+    /// Params
+    /// ---
+    /// - market: name of the market
+    /// - market_value: map number of the map winner market, score of the series score market
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(market) if the stored market is known
+    /// - Err(_) otherwise
+    pub fn from_columns(market: &str, market_value: Option<&str>) -> anyhow::Result<BetMarket> {
+        match (market, market_value) {
+            ("MatchWinner", _) => Ok(BetMarket::MatchWinner),
+            ("MapWinner", Some(value)) => Ok(BetMarket::MapWinner {
+                map_number: value.parse()?,
+            }),
+            ("SeriesScore", Some(value)) => match value.split_once(':') {
+                Some((won, lost)) => Ok(BetMarket::SeriesScore {
+                    won: won.parse()?,
+                    lost: lost.parse()?,
+                }),
+                None => anyhow::bail!(DomainError::internal(
+                    "Could not read the series score of the bet"
+                )),
+            },
+            _ => anyhow::bail!(DomainError::validation("Unsupported bet market")),
+        }
+    }
+
+    /// Name of the market, stored in the `market` column
+    pub fn market(&self) -> String {
+        match self {
+            BetMarket::MatchWinner => String::from("MatchWinner"),
+            BetMarket::MapWinner { .. } => String::from("MapWinner"),
+            BetMarket::SeriesScore { .. } => String::from("SeriesScore"),
+        }
+    }
+
+    /// Value of the market, stored in the `market_value` column
+    pub fn market_value(&self) -> Option<String> {
+        match self {
+            BetMarket::MatchWinner => None,
+            BetMarket::MapWinner { map_number } => Some(map_number.to_string()),
+            BetMarket::SeriesScore { won, lost } => Some(format!("{}:{}", won, lost)),
+        }
+    }
+
+    /// Check whether the market can still be bet on
+    ///
+    /// Params
+    /// ---
+    /// - game_match: the match the bet is on
+    /// - team_id: ID of the team the bet is on
+    /// - maps: maps of the match finished so far
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(()) if the market exists for the match and has not been decided yet
+    /// - Err(_) otherwise
+    pub fn validate(
+        &self,
+        game_match: &GameMatch,
+        team_id: i32,
+        maps: &[MatchMap],
+    ) -> anyhow::Result<()> {
+        let series_format = game_match.extract_series_format()?;
+        match *self {
+            BetMarket::MatchWinner => {}
+            BetMarket::MapWinner { map_number } => {
+                if map_number < 1 || map_number > series_format.maps() {
+                    anyhow::bail!(DomainError::validation(
+                        "The map is not played in the match!"
+                    ));
+                }
+                if maps.iter().any(|map| map.map_number == map_number) {
+                    anyhow::bail!(DomainError::betting_closed(
+                        "The map has already been played!"
+                    ));
+                }
+            }
+            BetMarket::SeriesScore { won, lost } => {
+                if won != series_format.maps_to_win() || lost < 0 || lost >= won {
+                    anyhow::bail!(DomainError::validation(
+                        "The match cannot end with this score!"
+                    ));
+                }
+                let (team_one, team_two) = series_score(game_match, maps);
+                let (team_maps, other_maps) = if team_id == game_match.team_one_id {
+                    (team_one, team_two)
+                } else {
+                    (team_two, team_one)
+                };
+                if team_maps > won || other_maps > lost {
+                    anyhow::bail!(DomainError::betting_closed(
+                        "The match cannot end with this score anymore!"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Compute the ratio of the market from the ratios of the match,
+    /// keeping the margin of the match ratios
+    ///
+    /// Params
+    /// ---
+    /// - game_match: the match the bet is on
+    /// - team_id: ID of the team the bet is on
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(ratio) of the market
+    /// - Err(_) if the team does not play the match or the ratios could not be read
+    pub fn ratio(&self, game_match: &GameMatch, team_id: i32) -> anyhow::Result<f64> {
+        let (team_ratio, other_ratio) = if team_id == game_match.team_one_id {
+            (&game_match.team_one_ratio, &game_match.team_two_ratio)
+        } else if team_id == game_match.team_two_id {
+            (&game_match.team_two_ratio, &game_match.team_one_ratio)
+        } else {
+            anyhow::bail!(DomainError::validation(
+                "The team does not play in the match!"
+            ));
+        };
+        let team_ratio: f64 = team_ratio.parse()?;
+        if let BetMarket::MatchWinner = self {
+            return Ok(team_ratio);
+        }
+
+        let other_ratio: f64 = other_ratio.parse()?;
+        let margin = 1.0 / team_ratio + 1.0 / other_ratio;
+        let maps_to_win = game_match.extract_series_format()?.maps_to_win();
+        let map_probability = map_probability(1.0 / team_ratio / margin, maps_to_win);
+        let probability = match *self {
+            BetMarket::SeriesScore { won, lost } => score_probability(map_probability, won, lost),
+            _ => map_probability,
+        };
+
+        let ratio = (RATIO_PRECISION / (probability * margin)).round() / RATIO_PRECISION;
+        Ok(if ratio.is_finite() {
+            ratio.max(MIN_RATIO)
+        } else {
+            MIN_RATIO
+        })
+    }
+
+    /// Decide the bet from the results of the match
+    ///
+    /// Params
+    /// ---
+    /// - game_match: the match the bet is on
+    /// - team_id: ID of the team the bet is on
+    /// - winner_id: ID of the winner of the match, if the match has ended
+    /// - maps: maps of the match finished so far
+    ///
+    /// Returns
+    /// ---
+    /// - Some(outcome) if the bet can be decided
+    /// - None if its match or map has not finished yet
+    pub fn outcome(
+        &self,
+        game_match: &GameMatch,
+        team_id: i32,
+        winner_id: Option<i32>,
+        maps: &[MatchMap],
+    ) -> Option<BetOutcome> {
+        let decide = |won: bool| {
+            if won {
+                BetOutcome::Won
+            } else {
+                BetOutcome::Lost
+            }
+        };
+
+        match *self {
+            BetMarket::MatchWinner => winner_id.map(|winner_id| decide(winner_id == team_id)),
+            BetMarket::MapWinner { map_number } => {
+                match maps.iter().find(|map| map.map_number == map_number) {
+                    Some(map) => Some(decide(map.winner_id == team_id)),
+                    None => winner_id.map(|_| BetOutcome::Void),
+                }
+            }
+            BetMarket::SeriesScore { won, lost } => {
+                let winner_id = winner_id?;
+                if maps.is_empty() {
+                    // the maps of the match have not been recorded
+                    return Some(BetOutcome::Void);
+                }
+                let (team_one, team_two) = series_score(game_match, maps);
+                let (team_maps, other_maps) = if team_id == game_match.team_one_id {
+                    (team_one, team_two)
+                } else {
+                    (team_two, team_one)
+                };
+                Some(decide(
+                    winner_id == team_id && team_maps == won && other_maps == lost,
+                ))
+            }
+        }
+    }
+}
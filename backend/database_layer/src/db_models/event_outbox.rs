@@ -0,0 +1,39 @@
+use crate::schema::event_outbox;
+use crate::type_storing::time_handling::TimeHandling;
+
+/// Read structure, used for data mapping of
+/// `event_outbox` record from the database
+#[derive(Identifiable, Queryable, PartialEq, Clone, Debug)]
+#[table_name = "event_outbox"]
+pub struct OutboxEvent {
+    pub id: i32,
+    pub payload: String,
+    pub created_at: String,
+}
+
+/// Write structure, used for inserting
+/// `event_outbox` records into the database
+#[derive(Insertable)]
+#[table_name = "event_outbox"]
+pub struct CreateOutboxEvent {
+    pub payload: String,
+    pub created_at: String,
+}
+
+impl CreateOutboxEvent {
+    /// Create a new `event_outbox` insert structure
+    ///
+    /// Params
+    /// ---
+    /// - payload: the serialized event
+    ///
+    /// Returns
+    /// ---
+    /// - new `event_outbox` insert structure
+    pub fn new(payload: &str) -> CreateOutboxEvent {
+        CreateOutboxEvent {
+            payload: String::from(payload),
+            created_at: TimeHandling::store(),
+        }
+    }
+}
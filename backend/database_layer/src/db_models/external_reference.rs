@@ -0,0 +1,62 @@
+use crate::schema::external_reference;
+
+/// Kinds of the records which can be referenced by an external ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExternalEntity {
+    Game,
+    Team,
+    Match,
+}
+
+impl ExternalEntity {
+    /// Name of the entity, as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExternalEntity::Game => "game",
+            ExternalEntity::Team => "team",
+            ExternalEntity::Match => "game_match",
+        }
+    }
+}
+
+/// Read structure, used for data mapping of
+/// `external_reference` record from the database
+#[derive(Identifiable, Queryable, PartialEq, Clone, Debug)]
+#[table_name = "external_reference"]
+pub struct ExternalReference {
+    pub id: i32,
+    pub entity: String,
+    pub external_id: String,
+    pub internal_id: i32,
+}
+
+/// Write structure, used for inserting
+/// `external_reference` records into the database
+#[derive(Insertable)]
+#[table_name = "external_reference"]
+pub struct CreateExternalReference {
+    pub entity: String,
+    pub external_id: String,
+    pub internal_id: i32,
+}
+
+impl CreateExternalReference {
+    /// Create a new `external_reference` insert structure
+    ///
+    /// Params
+    /// ---
+    /// - entity: kind of the referenced record
+    /// - external_id: ID of the record in the outside source
+    /// - internal_id: ID of the record in our database
+    ///
+    /// Returns
+    /// ---
+    /// - new `external_reference` insert structure
+    pub fn new(entity: ExternalEntity, external_id: &str, internal_id: i32) -> Self {
+        Self {
+            entity: String::from(entity.as_str()),
+            external_id: String::from(external_id),
+            internal_id,
+        }
+    }
+}
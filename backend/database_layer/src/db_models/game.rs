@@ -8,6 +8,11 @@ pub struct Game {
     pub name: String,
     pub description: String,
     pub logo_url: String,
+    pub expected_duration_minutes: i32,
+    /// archived games are left out of the listings and cannot get new matches
+    pub archived: bool,
+    /// the uploaded logo, `logo_url` points to it when set
+    pub logo_asset_id: Option<i32>,
 }
 
 /// Write structure, used for inserting
@@ -18,6 +23,8 @@ pub struct CreateGame {
     pub name: String,
     pub description: String,
     pub logo: String,
+    /// the default duration is used when not set
+    pub expected_duration_minutes: Option<i32>,
 }
 
 impl CreateGame {
@@ -37,6 +44,7 @@ impl CreateGame {
             name: String::from(name),
             description: String::from(description),
             logo: String::from(logo),
+            expected_duration_minutes: None,
         }
     }
 }
@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
+use std::fmt::Display;
 
 use crate::db_models::game::Game;
+use crate::error::DomainError;
 use crate::schema::game_match;
 
 /// Read structure, used for data mapping of
@@ -22,6 +24,7 @@ pub struct GameMatch {
     pub team_two_name: String,
     pub supposed_start_at: String,
     pub state: String,
+    pub series_format: String,
 }
 
 /// Write structure, used for inserting
@@ -40,6 +43,54 @@ pub struct CreateGameMatch {
     pub team_two_name: String,
     pub supposed_start_at: String,
     pub state: String,
+    /// best of one when not set otherwise
+    pub series_format: String,
+}
+
+/// Number of maps played in a match, the team winning the majority of them wins the match
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeriesFormat {
+    Bo1,
+    Bo3,
+    Bo5,
+}
+
+impl SeriesFormat {
+    pub fn from_input(input: &str) -> anyhow::Result<SeriesFormat> {
+        match input {
+            "Bo1" => Ok(SeriesFormat::Bo1),
+            "Bo3" => Ok(SeriesFormat::Bo3),
+            "Bo5" => Ok(SeriesFormat::Bo5),
+            _ => anyhow::bail!(DomainError::validation("Unsupported series format")),
+        }
+    }
+
+    /// Most maps which can be played in the match
+    pub fn maps(&self) -> i32 {
+        match self {
+            SeriesFormat::Bo1 => 1,
+            SeriesFormat::Bo3 => 3,
+            SeriesFormat::Bo5 => 5,
+        }
+    }
+
+    /// Maps a team has to win to win the match
+    pub fn maps_to_win(&self) -> i32 {
+        self.maps() / 2 + 1
+    }
+}
+
+impl Display for SeriesFormat {
+    /// Implement the display trait for converting the enum and writing the result to the database
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let self_string = match self {
+            SeriesFormat::Bo1 => "Bo1",
+            SeriesFormat::Bo3 => "Bo3",
+            SeriesFormat::Bo5 => "Bo5",
+        };
+
+        write!(f, "{}", self_string)
+    }
 }
 
 impl GameMatch {
@@ -50,7 +101,7 @@ impl GameMatch {
     /// - update_ratio_one: option to change the first ratio
     /// - update_ratio_two: option to change the second ratio,
     /// - update_supposed_start_at: option to change the supposed start of the match
-    ///                             (note -> the query will fail if the supposed start is in the past, or the match has already started)
+    ///   (note -> the query will fail if the supposed start is in the past, or the match has already started)
     /// - update_state: option to update the state of the match
     ///
     /// Returns
@@ -60,16 +111,56 @@ impl GameMatch {
         &self,
         update_ratio_one: Option<&str>,
         update_ratio_two: Option<&str>,
+        update_supposed_start_at: Option<DateTime<Utc>>,
         update_state: Option<&str>,
     ) -> anyhow::Result<GameMatchUpdate> {
+        if let Some(supposed_start_at) = update_supposed_start_at {
+            if supposed_start_at < Utc::now() {
+                anyhow::bail!(DomainError::validation(
+                    "The match cannot be moved to the past"
+                ));
+            }
+        }
         Ok(GameMatchUpdate {
             team_one_ratio: update_ratio_one
                 .map_or_else(|| self.team_one_ratio.clone(), String::from),
             team_two_ratio: update_ratio_two
                 .map_or_else(|| self.team_two_ratio.clone(), String::from),
+            supposed_start_at: update_supposed_start_at
+                .map_or_else(|| self.supposed_start_at.clone(), |start| start.to_string()),
             state: update_state.map_or_else(|| self.state.clone(), String::from),
         })
     }
+
+    /// Get the current ratio of a team playing in the match
+    ///
+    /// Params
+    /// ---
+    /// - team_id: ID of the team we wish to get the ratio of
+    ///
+    /// Returns
+    /// ---
+    /// - Some(ratio) if the team plays in the match
+    /// - None otherwise
+    pub fn ratio_of(&self, team_id: i32) -> Option<String> {
+        if team_id == self.team_one_id {
+            Some(self.team_one_ratio.clone())
+        } else if team_id == self.team_two_id {
+            Some(self.team_two_ratio.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Get the typed series format of the match
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(series_format) if the stored format is known
+    /// - Err(_) otherwise
+    pub fn extract_series_format(&self) -> anyhow::Result<SeriesFormat> {
+        SeriesFormat::from_input(&self.series_format)
+    }
 }
 
 impl CreateGameMatch {
@@ -108,6 +199,7 @@ impl CreateGameMatch {
             team_two_name: String::from(""),
             supposed_start_at: supposed_start_at.to_string(),
             state: String::from(state),
+            series_format: SeriesFormat::Bo1.to_string(),
         }
     }
 
@@ -129,13 +221,17 @@ impl CreateGameMatch {
             team_two_name: String::from(team_two_name),
             supposed_start_at: self.supposed_start_at.clone(),
             state: self.state.clone(),
+            series_format: self.series_format.clone(),
         }
     }
 }
 
 /// Structure which allows us to edit editable game match properties
+#[derive(AsChangeset)]
+#[table_name = "game_match"]
 pub struct GameMatchUpdate {
     pub team_one_ratio: String,
     pub team_two_ratio: String,
+    pub supposed_start_at: String,
     pub state: String,
 }
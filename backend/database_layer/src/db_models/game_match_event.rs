@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use crate::db_models::game_match::GameMatch;
+use crate::error::DomainError;
 use crate::schema::game_match_event;
 use crate::type_storing::time_handling::TimeHandling;
 
@@ -76,7 +77,7 @@ impl GameMatchEventFilter {
             "Cancelled" => Ok(GameMatchEventFilter::Cancelled),
             "Overtime" => Ok(GameMatchEventFilter::Overtime),
             "Ended" => Ok(GameMatchEventFilter::Ended),
-            _ => anyhow::bail!("Cannot convert to a event filter"),
+            _ => anyhow::bail!(DomainError::validation("Cannot convert to a event filter")),
         }
     }
 }
@@ -0,0 +1,90 @@
+use crate::db_models::game_match::GameMatch;
+use crate::schema::match_map;
+use crate::type_storing::time_handling::TimeHandling;
+
+/// Read structure, used for data mapping of
+/// `match_map` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone, Debug)]
+#[belongs_to(GameMatch)]
+#[table_name = "match_map"]
+pub struct MatchMap {
+    pub id: i32,
+    pub game_match_id: i32,
+    pub map_number: i32,
+    pub map_name: String,
+    pub winner_id: i32,
+    pub team_one_score: i32,
+    pub team_two_score: i32,
+    pub finished_at: String,
+}
+
+/// Write structure, used for inserting
+/// `match_map` records into the database
+#[derive(Insertable)]
+#[table_name = "match_map"]
+pub struct CreateMatchMap {
+    pub game_match_id: i32,
+    pub map_number: i32,
+    pub map_name: String,
+    pub winner_id: i32,
+    pub team_one_score: i32,
+    pub team_two_score: i32,
+    pub finished_at: String,
+}
+
+impl CreateMatchMap {
+    /// Create a new `match_map` insert structure for a finished map
+    ///
+    /// Params
+    /// ---
+    /// - game_match_id: ID of the match the map was played in
+    /// - map_number: order of the map within the match, starting from 1
+    /// - map_name: name of the map
+    /// - winner_id: ID of the team which won the map
+    /// - team_one_score: rounds won by the first team of the match
+    /// - team_two_score: rounds won by the second team of the match
+    ///
+    /// Returns
+    /// ---
+    /// - new `match_map` insert structure
+    pub fn new(
+        game_match_id: i32,
+        map_number: i32,
+        map_name: &str,
+        winner_id: i32,
+        team_one_score: i32,
+        team_two_score: i32,
+    ) -> CreateMatchMap {
+        CreateMatchMap {
+            game_match_id,
+            map_number,
+            map_name: String::from(map_name),
+            winner_id,
+            team_one_score,
+            team_two_score,
+            finished_at: TimeHandling::store(),
+        }
+    }
+}
+
+/// Count the maps won by each team of the match
+///
+/// Params
+/// ---
+/// - game_match: the match the maps were played in
+/// - maps: finished maps of the match
+///
+/// Returns
+/// ---
+/// - (maps won by the first team, maps won by the second team)
+pub fn series_score(game_match: &GameMatch, maps: &[MatchMap]) -> (i32, i32) {
+    let team_one = maps
+        .iter()
+        .filter(|map| map.winner_id == game_match.team_one_id)
+        .count() as i32;
+    let team_two = maps
+        .iter()
+        .filter(|map| map.winner_id == game_match.team_two_id)
+        .count() as i32;
+    (team_one, team_two)
+}
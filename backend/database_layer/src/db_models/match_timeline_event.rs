@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db_models::game_match::GameMatch;
+use crate::schema::match_timeline_event;
+use crate::type_storing::time_handling::TimeHandling;
+
+/// Read structure, used for data mapping of
+/// `match_timeline_event` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone, Debug)]
+#[belongs_to(GameMatch)]
+#[table_name = "match_timeline_event"]
+pub struct MatchTimelineEvent {
+    pub id: i32,
+    pub game_match_id: i32,
+    pub event_type: String,
+    pub payload: String,
+    pub created_at: String,
+}
+
+/// Write structure, used for inserting
+/// `match_timeline_event` records into the database
+#[derive(Insertable)]
+#[table_name = "match_timeline_event"]
+pub struct CreateMatchTimelineEvent {
+    pub game_match_id: i32,
+    pub event_type: String,
+    pub payload: String,
+    pub created_at: String,
+}
+
+/// Score of a match, in maps won for the games played in series,
+/// in goals or points otherwise
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub team_one: i32,
+    pub team_two: i32,
+}
+
+/// Structure capturing the possible in-play events of a match,
+/// kills and objectives are recorded only for the games which have them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TimelineEventPayload {
+    ScoreChanged(Score),
+    MapResult {
+        map_number: i32,
+        map_name: String,
+        winner_id: i32,
+        /// rounds won by each team on the map
+        rounds: Score,
+    },
+    RoundResult {
+        map_number: i32,
+        round_number: i32,
+        winner_id: i32,
+    },
+    Kill {
+        team_id: i32,
+        player: String,
+        victim: String,
+    },
+    Objective {
+        team_id: i32,
+        objective: String,
+    },
+}
+
+impl TimelineEventPayload {
+    /// Name of the event type, stored alongside the payload for filtering
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            TimelineEventPayload::ScoreChanged(_) => "ScoreChanged",
+            TimelineEventPayload::MapResult { .. } => "MapResult",
+            TimelineEventPayload::RoundResult { .. } => "RoundResult",
+            TimelineEventPayload::Kill { .. } => "Kill",
+            TimelineEventPayload::Objective { .. } => "Objective",
+        }
+    }
+
+    /// IDs of the teams the event refers to
+    pub fn team_ids(&self) -> Vec<i32> {
+        match self {
+            TimelineEventPayload::ScoreChanged(_) => Vec::new(),
+            TimelineEventPayload::MapResult { winner_id, .. }
+            | TimelineEventPayload::RoundResult { winner_id, .. } => vec![*winner_id],
+            TimelineEventPayload::Kill { team_id, .. }
+            | TimelineEventPayload::Objective { team_id, .. } => vec![*team_id],
+        }
+    }
+}
+
+impl MatchTimelineEvent {
+    /// Convert the stored payload into the typed event
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(payload) - `TimelineEventPayload` if the payload has been stored and retrieved successfully
+    /// - Err(_) - otherwise
+    pub fn extract_payload(&self) -> anyhow::Result<TimelineEventPayload> {
+        Ok(serde_json::from_str(&self.payload)?)
+    }
+}
+
+impl CreateMatchTimelineEvent {
+    /// Create a new `match_timeline_event` insert structure
+    ///
+    /// Params
+    /// ---
+    /// - game_match_id: id of the match the event happened in
+    /// - payload: the event we want to store
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(new `match_timeline_event` insert structure)
+    /// - Err(_) if the payload could not be serialized
+    pub fn new(
+        game_match_id: i32,
+        payload: &TimelineEventPayload,
+    ) -> anyhow::Result<CreateMatchTimelineEvent> {
+        Ok(CreateMatchTimelineEvent {
+            game_match_id,
+            event_type: String::from(payload.event_type()),
+            payload: serde_json::to_string(payload)?,
+            created_at: TimeHandling::store(),
+        })
+    }
+}
@@ -0,0 +1,73 @@
+use crate::schema::media_asset;
+use crate::type_storing::time_handling::TimeHandling;
+
+/// Read structure, used for data mapping of
+/// `media_asset` record from the database
+#[derive(Identifiable, Queryable, PartialEq, Clone, Debug)]
+#[table_name = "media_asset"]
+pub struct MediaAsset {
+    pub id: i32,
+    /// hex SHA-256 of the original file
+    pub content_hash: String,
+    pub content_type: String,
+    /// name of the original file in the media directory
+    pub file_name: String,
+    /// name of the thumbnail in the media directory
+    pub thumbnail_name: String,
+    pub byte_size: i32,
+    pub width: i32,
+    pub height: i32,
+    pub created_at: String,
+}
+
+/// Write structure, used for inserting
+/// `media_asset` records into the database
+#[derive(Insertable)]
+#[table_name = "media_asset"]
+pub struct CreateMediaAsset {
+    pub content_hash: String,
+    pub content_type: String,
+    pub file_name: String,
+    pub thumbnail_name: String,
+    pub byte_size: i32,
+    pub width: i32,
+    pub height: i32,
+    pub created_at: String,
+}
+
+impl CreateMediaAsset {
+    /// Create a new `media_asset` insert structure,
+    /// the files are named after the content hash
+    ///
+    /// Params
+    /// ---
+    /// - content_hash: hex SHA-256 of the original file
+    /// - content_type: MIME type of the original file
+    /// - extension: extension of the original file, without the dot
+    /// - byte_size: size of the original file
+    /// - width: width of the image in pixels
+    /// - height: height of the image in pixels
+    ///
+    /// Returns
+    /// ---
+    /// - new `media_asset` insert structure
+    pub fn new(
+        content_hash: &str,
+        content_type: &str,
+        extension: &str,
+        byte_size: i32,
+        width: i32,
+        height: i32,
+    ) -> CreateMediaAsset {
+        CreateMediaAsset {
+            content_hash: String::from(content_hash),
+            content_type: String::from(content_type),
+            file_name: format!("{}.{}", content_hash, extension),
+            thumbnail_name: format!("{}_thumb.png", content_hash),
+            byte_size,
+            width,
+            height,
+            created_at: TimeHandling::store(),
+        }
+    }
+}
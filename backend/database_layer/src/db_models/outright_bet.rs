@@ -0,0 +1,69 @@
+use crate::db_models::{outright_selection::OutrightSelection, tournament::Tournament, user::User};
+use crate::schema::outright_bet;
+use crate::type_storing::time_handling::TimeHandling;
+
+/// Read structure, used for data mapping of
+/// `outright_bet` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone, Debug)]
+#[belongs_to(Tournament)]
+#[belongs_to(User)]
+#[table_name = "outright_bet"]
+pub struct OutrightBet {
+    pub id: i32,
+    pub tournament_id: i32,
+    pub team_id: i32,
+    pub user_id: i32,
+    pub bet_ratio: String,
+    pub price_paid: String,
+    pub placed_at: String,
+    pub won: Option<bool>,
+}
+
+/// Write structure, used for inserting
+/// `outright_bet` records into the database
+#[derive(Insertable)]
+#[table_name = "outright_bet"]
+pub struct CreateOutrightBet {
+    pub tournament_id: i32,
+    pub team_id: i32,
+    pub user_id: i32,
+    pub bet_ratio: String,
+    pub price_paid: String,
+    pub placed_at: String,
+}
+
+impl OutrightBet {
+    /// Get the amount paid out for the bet if it wins
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(amount) with the price paid multiplied by the ratio
+    /// - Err(_) if the stored values are not numbers
+    pub fn winnable_price(&self) -> anyhow::Result<f64> {
+        Ok(self.price_paid.parse::<f64>()? * self.bet_ratio.parse::<f64>()?)
+    }
+}
+
+impl CreateOutrightBet {
+    /// Create a new `outright_bet` insert structure, the bet takes the current ratio of the selection
+    ///
+    /// Params
+    /// ---
+    /// - user_id: ID of the user placing the bet
+    /// - selection: the team bet on to win the tournament
+    /// - price_paid: the stake
+    ///
+    /// Returns
+    /// ---
+    /// - new `outright_bet` insert structure
+    pub fn new(user_id: i32, selection: &OutrightSelection, price_paid: f64) -> Self {
+        Self {
+            tournament_id: selection.tournament_id,
+            team_id: selection.team_id,
+            user_id,
+            bet_ratio: selection.ratio.clone(),
+            price_paid: price_paid.to_string(),
+            placed_at: TimeHandling::store(),
+        }
+    }
+}
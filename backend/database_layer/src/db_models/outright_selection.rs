@@ -0,0 +1,50 @@
+use crate::db_models::tournament::Tournament;
+use crate::error::DomainError;
+use crate::schema::outright_selection;
+
+/// Read structure, used for data mapping of
+/// `outright_selection` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone, Debug)]
+#[belongs_to(Tournament)]
+#[table_name = "outright_selection"]
+pub struct OutrightSelection {
+    pub id: i32,
+    pub tournament_id: i32,
+    pub team_id: i32,
+    pub ratio: String,
+}
+
+/// Write structure, used for inserting
+/// `outright_selection` records into the database
+#[derive(Insertable)]
+#[table_name = "outright_selection"]
+pub struct CreateOutrightSelection {
+    pub tournament_id: i32,
+    pub team_id: i32,
+    pub ratio: String,
+}
+
+impl CreateOutrightSelection {
+    /// Create a new `outright_selection` insert structure
+    ///
+    /// Params
+    /// ---
+    /// - tournament_id: ID of the tournament
+    /// - team_id: ID of the team which can be bet on to win the tournament
+    /// - ratio: bet ratio of the team
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(new `outright_selection` insert structure)
+    /// - Err(_) if the ratio is lower than 1
+    pub fn new(tournament_id: i32, team_id: i32, ratio: f64) -> anyhow::Result<Self> {
+        if !ratio.is_finite() || ratio < 1.0 {
+            anyhow::bail!(DomainError::validation("The ratio has to be at least 1"));
+        }
+        Ok(Self {
+            tournament_id,
+            team_id,
+            ratio: ratio.to_string(),
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use crate::db_models::game::Game;
+use crate::schema::player;
+
+/// Read structure, used for data mapping of
+/// `player` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone, Debug)]
+#[belongs_to(Game)]
+#[table_name = "player"]
+pub struct Player {
+    pub id: i32,
+    pub game_id: i32,
+    pub nickname: String,
+    pub real_name: String,
+    pub country: String,
+    pub photo: String,
+}
+
+/// Write structure, used for inserting and editing
+/// `player` records in the database
+#[derive(Insertable, AsChangeset)]
+#[table_name = "player"]
+pub struct CreatePlayer {
+    pub game_id: i32,
+    pub nickname: String,
+    pub real_name: String,
+    pub country: String,
+    pub photo: String,
+}
+
+impl CreatePlayer {
+    /// Create a new `player` insert structure
+    ///
+    /// Params
+    /// ---
+    /// - game_id: ID of the game the player plays
+    /// - nickname: the name the player is known by
+    /// - real_name: full name of the player
+    /// - country: country the player represents
+    /// - photo: path to the photo of the player
+    ///
+    /// Returns
+    /// ---
+    /// - new `player` insert structure
+    pub fn new(
+        game_id: i32,
+        nickname: &str,
+        real_name: &str,
+        country: &str,
+        photo: &str,
+    ) -> CreatePlayer {
+        CreatePlayer {
+            game_id,
+            nickname: String::from(nickname),
+            real_name: String::from(real_name),
+            country: String::from(country),
+            photo: String::from(photo),
+        }
+    }
+}
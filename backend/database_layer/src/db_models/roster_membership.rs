@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+
+use crate::db_models::{player::Player, team::Team};
+use crate::schema::roster_membership;
+use crate::type_storing::time_handling::TimeHandling;
+
+/// Read structure, used for data mapping of
+/// `roster_membership` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone, Debug)]
+#[belongs_to(Player)]
+#[belongs_to(Team)]
+#[table_name = "roster_membership"]
+pub struct RosterMembership {
+    pub id: i32,
+    pub team_id: i32,
+    pub player_id: i32,
+    pub role: String,
+    pub joined_at: String,
+    pub left_at: Option<String>,
+}
+
+/// Write structure, used for inserting
+/// `roster_membership` records into the database
+#[derive(Insertable)]
+#[table_name = "roster_membership"]
+pub struct CreateRosterMembership {
+    pub team_id: i32,
+    pub player_id: i32,
+    pub role: String,
+    pub joined_at: String,
+}
+
+impl RosterMembership {
+    /// Check whether the player was in the roster at the time
+    ///
+    /// Params
+    /// ---
+    /// - at: the time in question
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(true) if the player had joined the team and not left it yet at the time
+    /// - Ok(false) otherwise
+    /// - Err(_) if the stored times could not be read
+    pub fn is_member_at(&self, at: DateTime<Utc>) -> anyhow::Result<bool> {
+        if TimeHandling::load_timestamp(&self.joined_at)? > at {
+            return Ok(false);
+        }
+        match &self.left_at {
+            Some(left_at) => Ok(TimeHandling::load_timestamp(left_at)? > at),
+            None => Ok(true),
+        }
+    }
+}
+
+impl CreateRosterMembership {
+    /// Create a new `roster_membership` insert structure
+    ///
+    /// Params
+    /// ---
+    /// - team_id: ID of the team the player joins
+    /// - player_id: ID of the player
+    /// - role: role of the player in the team
+    /// - joined_at: when the player has joined the team
+    ///
+    /// Returns
+    /// ---
+    /// - new `roster_membership` insert structure
+    pub fn new(
+        team_id: i32,
+        player_id: i32,
+        role: &str,
+        joined_at: DateTime<Utc>,
+    ) -> CreateRosterMembership {
+        CreateRosterMembership {
+            team_id,
+            player_id,
+            role: String::from(role),
+            joined_at: joined_at.to_string(),
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::fmt::Display;
+
+use crate::db_models::tournament::Tournament;
+use crate::error::DomainError;
+use crate::schema::stage;
+
+/// Read structure, used for data mapping of
+/// `stage` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone, Debug)]
+#[belongs_to(Tournament)]
+#[table_name = "stage"]
+pub struct Stage {
+    pub id: i32,
+    pub tournament_id: i32,
+    pub name: String,
+    pub stage_type: String,
+    pub position: i32,
+}
+
+/// Write structure, used for inserting and editing
+/// `stage` records in the database
+#[derive(Insertable, AsChangeset)]
+#[table_name = "stage"]
+pub struct CreateStage {
+    pub tournament_id: i32,
+    pub name: String,
+    pub stage_type: String,
+    pub position: i32,
+}
+
+/// Format of a stage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StageType {
+    /// every team of a group plays the others, the teams are ranked by the standings
+    Group,
+    /// the losers of the matches are knocked out
+    Playoffs,
+}
+
+impl StageType {
+    pub fn from_input(input: &str) -> anyhow::Result<StageType> {
+        match input {
+            "Group" => Ok(StageType::Group),
+            "Playoffs" => Ok(StageType::Playoffs),
+            _ => anyhow::bail!(DomainError::validation("Unsupported stage type")),
+        }
+    }
+}
+
+impl Display for StageType {
+    /// Implement the display trait for converting the enum and writing the result to the database
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let self_string = match self {
+            StageType::Group => "Group",
+            StageType::Playoffs => "Playoffs",
+        };
+
+        write!(f, "{}", self_string)
+    }
+}
+
+impl Stage {
+    /// Get the typed format of the stage
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(stage_type) if the stored type is known
+    /// - Err(_) otherwise
+    pub fn extract_type(&self) -> anyhow::Result<StageType> {
+        StageType::from_input(&self.stage_type)
+    }
+}
+
+impl CreateStage {
+    /// Create a new `stage` insert structure
+    ///
+    /// Params
+    /// ---
+    /// - tournament_id: ID of the tournament the stage belongs to
+    /// - name: name of the stage, e.g. Group A
+    /// - stage_type: format of the stage
+    /// - position: order of the stage within the tournament
+    ///
+    /// Returns
+    /// ---
+    /// - new `stage` insert structure
+    pub fn new(tournament_id: i32, name: &str, stage_type: StageType, position: i32) -> Self {
+        Self {
+            tournament_id,
+            name: String::from(name),
+            stage_type: stage_type.to_string(),
+            position,
+        }
+    }
+}
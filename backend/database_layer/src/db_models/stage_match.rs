@@ -0,0 +1,42 @@
+use crate::db_models::{game_match::GameMatch, stage::Stage};
+use crate::schema::stage_match;
+
+/// Read structure, used for data mapping of
+/// `stage_match` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq)]
+#[belongs_to(Stage)]
+#[belongs_to(GameMatch)]
+#[table_name = "stage_match"]
+pub struct StageMatch {
+    pub id: i32,
+    pub stage_id: i32,
+    pub game_match_id: i32,
+}
+
+/// Write structure, used for inserting
+/// `stage_match` records into the database
+#[derive(Insertable)]
+#[table_name = "stage_match"]
+pub struct CreateStageMatch {
+    pub stage_id: i32,
+    pub game_match_id: i32,
+}
+
+impl CreateStageMatch {
+    /// Create a new `stage_match` insert structure
+    ///
+    /// Params
+    /// ---
+    /// - stage_id: ID of the stage the match is played in
+    /// - game_match_id: ID of the match
+    ///
+    /// Returns
+    /// ---
+    /// - new `stage_match` insert structure
+    pub fn new(stage_id: i32, game_match_id: i32) -> Self {
+        Self {
+            stage_id,
+            game_match_id,
+        }
+    }
+}
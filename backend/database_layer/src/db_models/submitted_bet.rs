@@ -1,4 +1,6 @@
-use crate::db_models::{game_match::GameMatch, submitted_ticket::SubmittedTicket, team::Team};
+use crate::db_models::{
+    bet_market::BetMarket, game_match::GameMatch, submitted_ticket::SubmittedTicket, team::Team,
+};
 use crate::schema::submitted_bet;
 use crate::type_storing::time_handling::TimeHandling;
 
@@ -18,6 +20,8 @@ pub struct SubmittedBet {
     pub placed_at: String,
     pub submitted_at: String,
     pub won: Option<bool>,
+    pub market: String,
+    pub market_value: Option<String>,
 }
 
 /// Write structure, used for inserting
@@ -32,6 +36,8 @@ pub struct CreateSubmittedBet {
     pub placed_at: String,
     pub submitted_at: String,
     pub won: Option<bool>,
+    pub market: String,
+    pub market_value: Option<String>,
 }
 
 impl CreateSubmittedBet {
@@ -61,6 +67,20 @@ impl CreateSubmittedBet {
             placed_at: String::from(placed_at),
             submitted_at: TimeHandling::store(),
             won: None,
+            market: BetMarket::MatchWinner.market(),
+            market_value: None,
         }
     }
 }
+
+impl SubmittedBet {
+    /// Get the typed market of the bet
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(market) if the stored market is known
+    /// - Err(_) otherwise
+    pub fn extract_market(&self) -> anyhow::Result<BetMarket> {
+        BetMarket::from_columns(&self.market, self.market_value.as_deref())
+    }
+}
@@ -8,6 +8,10 @@ pub struct Team {
     pub name: String,
     pub description: String,
     pub logo: String,
+    /// archived teams are left out of the listings and cannot get new matches
+    pub archived: bool,
+    /// the uploaded logo, `logo` points to it when set
+    pub logo_asset_id: Option<i32>,
 }
 
 /// Write structure, used for inserting
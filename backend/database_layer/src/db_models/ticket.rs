@@ -1,6 +1,7 @@
 use crate::db_models::{
     bet::Bet, game_match::GameMatch, submitted_ticket::CreateSubmittedTicket, user::User,
 };
+use crate::error::DomainError;
 use crate::{schema::ticket, type_storing::time_handling::TimeHandling};
 
 /// encapuslates an obtained ticket
@@ -37,24 +38,18 @@ impl Ticket {
         bets_and_matches: &[(Bet, GameMatch)],
     ) -> anyhow::Result<CreateSubmittedTicket> {
         if bets_and_matches.is_empty() {
-            anyhow::bail!("Cannot submit an empty ticket!")
+            anyhow::bail!(DomainError::validation("Cannot submit an empty ticket!"))
         }
 
         let total_ratio = bets_and_matches
             .iter()
-            .map(|(bet, game_match)| {
-                if bet.team_id == game_match.team_one_id {
-                    game_match.team_one_ratio.clone()
-                } else {
-                    game_match.team_two_ratio.clone()
-                }
-            })
-            .map(|ratio| ratio.parse::<f64>().ok())
-            .flatten()
+            .filter_map(|(bet, game_match)| bet.ratio(game_match).ok())
             .reduce(|element_one, element_two| element_one * element_two);
 
         if total_ratio.is_none() {
-            anyhow::bail!("Could not compute the ratio for the final bet!")
+            anyhow::bail!(DomainError::internal(
+                "Could not compute the ratio for the final bet!"
+            ))
         }
 
         // ratio will always get obtained via unwrap
@@ -0,0 +1,165 @@
+use crate::db_models::{
+    bet::Bet, bet_market::BetMarket, submitted_ticket::SubmittedTicket, ticket::Ticket,
+};
+use crate::error::DomainError;
+use crate::schema::ticket_share_code;
+use crate::type_storing::time_handling::TimeHandling;
+use uuid::Uuid;
+
+/// Length of the generated share code
+const SHARE_CODE_LENGTH: usize = 8;
+
+/// Read structure, used for data mapping of
+/// `ticket_share_code` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone)]
+#[belongs_to(Ticket)]
+#[belongs_to(SubmittedTicket)]
+#[table_name = "ticket_share_code"]
+pub struct TicketShareCode {
+    pub id: i32,
+    pub code: String,
+    pub ticket_id: Option<i32>,
+    pub submitted_ticket_id: Option<i32>,
+    pub created_at: String,
+}
+
+/// Write structure, used for inserting
+/// `ticket_share_code` records into the database
+#[derive(Insertable)]
+#[table_name = "ticket_share_code"]
+pub struct CreateTicketShareCode {
+    pub code: String,
+    pub ticket_id: Option<i32>,
+    pub submitted_ticket_id: Option<i32>,
+    pub created_at: String,
+}
+
+/// Ticket the share code is pointing to
+#[derive(Debug, Clone, Copy)]
+pub enum SharedTicket {
+    Open(i32),
+    Submitted(i32),
+}
+
+/// A single selection (a team picked in a match) of a shared ticket
+#[derive(Clone)]
+pub struct SharedSelection {
+    pub game_match_id: i32,
+    pub team_id: i32,
+    pub ratio: String,
+    /// what the team has been picked for
+    pub market: BetMarket,
+}
+
+/// Result of copying a shared ticket into the open ticket of a user
+pub struct CopiedTicket {
+    /// ID of the open ticket the selections were copied into
+    pub ticket_id: i32,
+    /// placed bets together with the selection (containing the current ratio)
+    pub copied: Vec<(Bet, SharedSelection)>,
+    /// selections which could not be placed, together with the reason
+    pub rejected: Vec<(SharedSelection, String)>,
+}
+
+impl CreateTicketShareCode {
+    /// Create a new `ticket_share_code` insert structure with a freshly generated code
+    ///
+    /// Params
+    /// ---
+    /// - shared_ticket: the open or submitted ticket we wish to share
+    ///
+    /// Returns
+    /// ---
+    /// - new `ticket_share_code` insert structure
+    pub fn new(shared_ticket: SharedTicket) -> CreateTicketShareCode {
+        let (ticket_id, submitted_ticket_id) = match shared_ticket {
+            SharedTicket::Open(id) => (Some(id), None),
+            SharedTicket::Submitted(id) => (None, Some(id)),
+        };
+
+        CreateTicketShareCode {
+            code: Uuid::new_v4().to_simple().to_string()[..SHARE_CODE_LENGTH].to_uppercase(),
+            ticket_id,
+            submitted_ticket_id,
+            created_at: TimeHandling::store(),
+        }
+    }
+}
+
+impl TicketShareCode {
+    /// Find out which ticket the share code is pointing to
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(shared_ticket) if the code points to exactly one ticket
+    /// - Err(_) if the record is inconsistent
+    pub fn shared_ticket(&self) -> anyhow::Result<SharedTicket> {
+        match (self.ticket_id, self.submitted_ticket_id) {
+            (Some(id), None) => Ok(SharedTicket::Open(id)),
+            (None, Some(id)) => Ok(SharedTicket::Submitted(id)),
+            _ => anyhow::bail!(DomainError::internal(
+                "The share code does not point to a single ticket"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored_code(ticket_id: Option<i32>, submitted_ticket_id: Option<i32>) -> TicketShareCode {
+        TicketShareCode {
+            id: 1,
+            code: String::from("ABCDEF12"),
+            ticket_id,
+            submitted_ticket_id,
+            created_at: TimeHandling::store(),
+        }
+    }
+
+    #[test]
+    fn generated_code_is_short_uppercase_hex() {
+        let code = CreateTicketShareCode::new(SharedTicket::Open(1)).code;
+        assert_eq!(code.len(), SHARE_CODE_LENGTH);
+        assert!(code
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('A'..='F').contains(&c)));
+    }
+
+    #[test]
+    fn generated_codes_differ() {
+        let first = CreateTicketShareCode::new(SharedTicket::Open(1)).code;
+        let second = CreateTicketShareCode::new(SharedTicket::Open(1)).code;
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn generated_code_points_to_the_shared_ticket() {
+        let open = CreateTicketShareCode::new(SharedTicket::Open(3));
+        assert_eq!((open.ticket_id, open.submitted_ticket_id), (Some(3), None));
+        let submitted = CreateTicketShareCode::new(SharedTicket::Submitted(4));
+        assert_eq!(
+            (submitted.ticket_id, submitted.submitted_ticket_id),
+            (None, Some(4))
+        );
+    }
+
+    #[test]
+    fn stored_code_points_to_a_single_ticket() {
+        assert!(matches!(
+            stored_code(Some(3), None).shared_ticket(),
+            Ok(SharedTicket::Open(3))
+        ));
+        assert!(matches!(
+            stored_code(None, Some(4)).shared_ticket(),
+            Ok(SharedTicket::Submitted(4))
+        ));
+        for (ticket_id, submitted_ticket_id) in [(Some(3), Some(4)), (None, None)] {
+            let err = stored_code(ticket_id, submitted_ticket_id)
+                .shared_ticket()
+                .unwrap_err();
+            assert!(matches!(DomainError::of(&err), DomainError::Internal(_)));
+        }
+    }
+}
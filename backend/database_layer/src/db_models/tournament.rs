@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+
+use crate::db_models::game::Game;
+use crate::error::DomainError;
+use crate::schema::tournament;
+
+/// Read structure, used for data mapping of
+/// `tournament` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone, Debug)]
+#[belongs_to(Game)]
+#[table_name = "tournament"]
+pub struct Tournament {
+    pub id: i32,
+    pub game_id: i32,
+    pub name: String,
+    pub description: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub winner_id: Option<i32>,
+    pub ended_at: Option<String>,
+}
+
+/// Write structure, used for inserting and editing
+/// `tournament` records in the database
+#[derive(Insertable, AsChangeset)]
+#[table_name = "tournament"]
+pub struct CreateTournament {
+    pub game_id: i32,
+    pub name: String,
+    pub description: String,
+    pub starts_at: String,
+    pub ends_at: String,
+}
+
+impl Tournament {
+    /// Check whether the tournament has ended and its winner is known
+    pub fn has_ended(&self) -> bool {
+        self.ended_at.is_some()
+    }
+}
+
+impl CreateTournament {
+    /// Create a new `tournament` insert structure
+    ///
+    /// Params
+    /// ---
+    /// - game_id: ID of the game the tournament is played in
+    /// - name: name of the tournament
+    /// - description: description of the tournament
+    /// - starts_at: when the tournament starts
+    /// - ends_at: when the tournament is supposed to end
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(new `tournament` insert structure)
+    /// - Err(_) if the tournament would end before it starts
+    pub fn new(
+        game_id: i32,
+        name: &str,
+        description: &str,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> anyhow::Result<CreateTournament> {
+        if ends_at < starts_at {
+            anyhow::bail!(DomainError::validation(
+                "The tournament cannot end before it starts"
+            ));
+        }
+        Ok(CreateTournament {
+            game_id,
+            name: String::from(name),
+            description: String::from(description),
+            starts_at: starts_at.to_string(),
+            ends_at: ends_at.to_string(),
+        })
+    }
+}
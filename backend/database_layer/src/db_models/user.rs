@@ -16,6 +16,8 @@ pub struct User {
     pub created_at: String,
     pub balance: String,
     pub photo: Option<String>,
+    /// the uploaded photo, `photo` points to it when set
+    pub photo_asset_id: Option<i32>,
 }
 
 /// Write structure, used for inserting
@@ -0,0 +1,106 @@
+use crate::db_models::{game_match::GameMatch, user::User};
+use crate::error::DomainError;
+use crate::schema::user_favourite;
+use crate::type_storing::time_handling::TimeHandling;
+
+/// Read structure, used for data mapping of
+/// `user_favourite` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone)]
+#[belongs_to(User)]
+#[belongs_to(GameMatch)]
+#[table_name = "user_favourite"]
+pub struct UserFavourite {
+    pub id: i32,
+    pub user_id: i32,
+    pub team_id: Option<i32>,
+    pub game_id: Option<i32>,
+    pub game_match_id: Option<i32>,
+    pub created_at: String,
+}
+
+/// Write structure, used for inserting
+/// `user_favourite` records into the database
+#[derive(Insertable)]
+#[table_name = "user_favourite"]
+pub struct CreateUserFavourite {
+    pub user_id: i32,
+    pub team_id: Option<i32>,
+    pub game_id: Option<i32>,
+    pub game_match_id: Option<i32>,
+    pub created_at: String,
+}
+
+/// Structure capturing what the user can follow
+#[derive(PartialEq, Clone, Copy)]
+pub enum Favourite {
+    Team(i32),
+    Game(i32),
+    Match(i32),
+}
+
+impl Favourite {
+    /// Check whether the followed entity covers the match,
+    /// i.e. it is the match itself, one of its teams or its game
+    ///
+    /// Params
+    /// ---
+    /// - game_match: the match we want to check
+    ///
+    /// Returns
+    /// ---
+    /// - true if the match is covered by the favourite
+    pub fn covers(&self, game_match: &GameMatch) -> bool {
+        match *self {
+            Favourite::Team(id) => game_match.team_one_id == id || game_match.team_two_id == id,
+            Favourite::Game(id) => game_match.game_id == id,
+            Favourite::Match(id) => game_match.id == id,
+        }
+    }
+}
+
+impl UserFavourite {
+    /// Convert the record into the followed entity
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(favourite) if exactly one of the references is set
+    /// - Err(_) otherwise
+    pub fn extract_favourite(&self) -> anyhow::Result<Favourite> {
+        match (self.team_id, self.game_id, self.game_match_id) {
+            (Some(id), None, None) => Ok(Favourite::Team(id)),
+            (None, Some(id), None) => Ok(Favourite::Game(id)),
+            (None, None, Some(id)) => Ok(Favourite::Match(id)),
+            _ => anyhow::bail!(DomainError::internal(
+                "The favourite record does not point to a single entity"
+            )),
+        }
+    }
+}
+
+impl CreateUserFavourite {
+    /// Create a new `user_favourite` insert structure
+    ///
+    /// Params
+    /// ---
+    /// - user_id: ID of the user who follows the entity
+    /// - favourite: the team, game or match the user follows
+    ///
+    /// Returns
+    /// ---
+    /// - new `user_favourite` insert structure
+    pub fn new(user_id: i32, favourite: Favourite) -> CreateUserFavourite {
+        let (team_id, game_id, game_match_id) = match favourite {
+            Favourite::Team(id) => (Some(id), None, None),
+            Favourite::Game(id) => (None, Some(id), None),
+            Favourite::Match(id) => (None, None, Some(id)),
+        };
+
+        CreateUserFavourite {
+            user_id,
+            team_id,
+            game_id,
+            game_match_id,
+            created_at: TimeHandling::store(),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use crate::db_models::user::User;
+use crate::schema::user_session;
+use crate::type_storing::time_handling::TimeHandling;
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+/// Number of days the session stays valid
+const SESSION_VALIDITY_DAYS: i64 = 30;
+
+/// Read structure, used for data mapping of
+/// `user_session` record from the database
+#[derive(Identifiable, Associations, Queryable, PartialEq, Clone)]
+#[belongs_to(User)]
+#[table_name = "user_session"]
+pub struct UserSession {
+    pub id: i32,
+    pub user_id: i32,
+    pub token: String,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+/// Write structure, used for inserting
+/// `user_session` records into the database
+#[derive(Insertable)]
+#[table_name = "user_session"]
+pub struct CreateUserSession {
+    pub user_id: i32,
+    pub token: String,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+impl CreateUserSession {
+    /// Create a new `user_session` insert structure with a freshly generated token
+    ///
+    /// Params
+    /// ---
+    /// - user_id: ID of the user who logged in
+    ///
+    /// Returns
+    /// ---
+    /// - new `user_session` insert structure
+    pub fn new(user_id: i32) -> CreateUserSession {
+        CreateUserSession {
+            user_id,
+            token: format!(
+                "{}{}",
+                Uuid::new_v4().to_simple(),
+                Uuid::new_v4().to_simple()
+            ),
+            created_at: TimeHandling::store(),
+            expires_at: (Utc::now() + Duration::days(SESSION_VALIDITY_DAYS)).to_string(),
+        }
+    }
+}
+
+impl UserSession {
+    /// Check whether the session has not expired yet
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(true) if the session is still valid
+    /// - Err(_) if the expiration could not be parsed
+    pub fn is_valid(&self) -> anyhow::Result<bool> {
+        Ok(TimeHandling::load_timestamp(&self.expires_at)? > Utc::now())
+    }
+}
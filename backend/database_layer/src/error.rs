@@ -0,0 +1,108 @@
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use std::fmt;
+
+/// Failure of an operation on the records, telling the clients what went wrong.
+/// The repositories return it inside `anyhow::Error`, `DomainError::of` gets it back.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DomainError {
+    /// the record does not exist
+    NotFound(String),
+    /// the request is malformed or breaks a rule of the domain
+    Validation(String),
+    /// the request clashes with the current state of the records,
+    /// e.g. a duplicate, records depending on the deleted one or a match in another phase
+    Conflict(String),
+    /// the user cannot afford the stake
+    InsufficientFunds { balance: f64, required: f64 },
+    /// the match or the tournament cannot be bet on
+    BettingClosed(String),
+    /// the credentials are wrong
+    Unauthenticated(String),
+    /// a failure the client cannot do anything about, e.g. an unreachable database
+    Internal(String),
+}
+
+impl DomainError {
+    pub fn not_found(message: impl Into<String>) -> DomainError {
+        DomainError::NotFound(message.into())
+    }
+
+    pub fn validation(message: impl Into<String>) -> DomainError {
+        DomainError::Validation(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> DomainError {
+        DomainError::Conflict(message.into())
+    }
+
+    pub fn betting_closed(message: impl Into<String>) -> DomainError {
+        DomainError::BettingClosed(message.into())
+    }
+
+    pub fn unauthenticated(message: impl Into<String>) -> DomainError {
+        DomainError::Unauthenticated(message.into())
+    }
+
+    pub fn internal(message: impl Into<String>) -> DomainError {
+        DomainError::Internal(message.into())
+    }
+
+    /// Find out what kind of failure the error is
+    ///
+    /// Params
+    /// ---
+    /// - err: error returned by a repository
+    ///
+    /// Returns
+    /// ---
+    /// - the domain error the repository returned
+    /// - NotFound or Conflict for the errors of the database reporting a missing record,
+    ///   a duplicate or a reference to a missing record
+    /// - Internal with the description of the error otherwise
+    pub fn of(err: &anyhow::Error) -> DomainError {
+        if let Some(domain_error) = err.downcast_ref::<DomainError>() {
+            return domain_error.clone();
+        }
+        match err.downcast_ref::<DieselError>() {
+            Some(DieselError::NotFound) => DomainError::not_found("The record does not exist"),
+            Some(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                DomainError::conflict("The record already exists")
+            }
+            Some(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+                DomainError::conflict(
+                    "The record is referred to by other records, or refers to a missing one",
+                )
+            }
+            _ => DomainError::internal(err.to_string()),
+        }
+    }
+
+    /// Values the clients need to describe the failure in their own words
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            DomainError::InsufficientFunds { balance, required } => vec![
+                ("balance", format!("{:.2}", balance)),
+                ("required", format!("{:.2}", required)),
+            ],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainError::NotFound(message)
+            | DomainError::Validation(message)
+            | DomainError::Conflict(message)
+            | DomainError::BettingClosed(message)
+            | DomainError::Unauthenticated(message)
+            | DomainError::Internal(message) => write!(f, "{}", message),
+            DomainError::InsufficientFunds { .. } => {
+                write!(f, "You do not have enough balance to do that!")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DomainError {}
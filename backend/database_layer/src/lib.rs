@@ -1,12 +1,17 @@
 #![allow(dead_code, unused_variables, unused_imports)]
+// the derives of diesel 1.4 implement the traits within constants
+#![allow(non_local_definitions)]
 // #![warn(clippy::all, clippy::pedantic, clippy::nursery, clippy::cargo)]
 
 #[macro_use]
 extern crate diesel;
 
+pub mod advisory_lock;
 pub mod connection;
 pub mod db_access;
 pub mod db_models;
+pub mod error;
+pub mod listener;
 pub mod result_types;
 mod schema;
 pub mod type_storing;
@@ -0,0 +1,61 @@
+use futures::{stream, StreamExt};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio_postgres::{AsyncMessage, NoTls};
+
+/// Channel notified by the database whenever an event is stored in the outbox
+pub const OUTBOX_CHANNEL: &str = "event_outbox";
+
+/// Listen to the notifications of the channel, waking up the receiver
+/// for every notification. Diesel connections cannot receive notifications,
+/// so the listener uses a dedicated connection of its own.
+///
+/// Params
+/// ---
+/// - database_url: Connection string
+/// - channel: name of the channel to listen to
+/// - wake_up: sender waking up the reader of the notified changes
+///
+/// Returns
+/// ---
+/// - Ok(()) when the receiver of the wake ups is gone
+/// - Err(_) when the connection fails, the caller may listen again
+pub async fn listen(
+    database_url: &str,
+    channel: &str,
+    wake_up: mpsc::Sender<()>,
+) -> anyhow::Result<()> {
+    let (client, mut connection) = tokio_postgres::connect(database_url, NoTls).await?;
+
+    // the connection has to be polled to receive the notifications
+    let (notification_sender, mut notifications) = mpsc::unbounded_channel();
+    let mut messages = stream::poll_fn(move |cx| connection.poll_message(cx));
+    let connection_task = tokio::spawn(async move {
+        while let Some(message) = messages.next().await {
+            match message {
+                Ok(AsyncMessage::Notification(_)) => {
+                    if notification_sender.send(()).is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(err) => return Err(anyhow::Error::from(err)),
+            }
+        }
+        Ok(())
+    });
+
+    client
+        .batch_execute(&format!("LISTEN \"{}\"", channel))
+        .await?;
+    // changes made before listening would go unnoticed otherwise
+    let _ = wake_up.try_send(());
+
+    while notifications.recv().await.is_some() {
+        // a pending wake up covers this notification as well
+        if let Err(TrySendError::Closed(_)) = wake_up.try_send(()) {
+            return Ok(());
+        }
+    }
+
+    connection_task.await?
+}
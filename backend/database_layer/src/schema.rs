@@ -5,6 +5,25 @@ table! {
         ticket_id -> Int4,
         team_id -> Int4,
         created_at -> Text,
+        market -> Text,
+        market_value -> Nullable<Text>,
+    }
+}
+
+table! {
+    event_outbox (id) {
+        id -> Int4,
+        payload -> Text,
+        created_at -> Text,
+    }
+}
+
+table! {
+    external_reference (id) {
+        id -> Int4,
+        entity -> Text,
+        external_id -> Text,
+        internal_id -> Int4,
     }
 }
 
@@ -14,6 +33,9 @@ table! {
         name -> Text,
         description -> Text,
         logo -> Text,
+        expected_duration_minutes -> Int4,
+        archived -> Bool,
+        logo_asset_id -> Nullable<Int4>,
     }
 }
 
@@ -30,6 +52,7 @@ table! {
         team_two_name -> Text,
         supposed_start_at -> Text,
         state -> Text,
+        series_format -> Text,
     }
 }
 
@@ -43,6 +66,105 @@ table! {
     }
 }
 
+table! {
+    match_map (id) {
+        id -> Int4,
+        game_match_id -> Int4,
+        map_number -> Int4,
+        map_name -> Text,
+        winner_id -> Int4,
+        team_one_score -> Int4,
+        team_two_score -> Int4,
+        finished_at -> Text,
+    }
+}
+
+table! {
+    match_timeline_event (id) {
+        id -> Int4,
+        game_match_id -> Int4,
+        event_type -> Text,
+        payload -> Text,
+        created_at -> Text,
+    }
+}
+
+table! {
+    media_asset (id) {
+        id -> Int4,
+        content_hash -> Text,
+        content_type -> Text,
+        file_name -> Text,
+        thumbnail_name -> Text,
+        byte_size -> Int4,
+        width -> Int4,
+        height -> Int4,
+        created_at -> Text,
+    }
+}
+
+table! {
+    outright_bet (id) {
+        id -> Int4,
+        tournament_id -> Int4,
+        team_id -> Int4,
+        user_id -> Int4,
+        bet_ratio -> Text,
+        price_paid -> Text,
+        placed_at -> Text,
+        won -> Nullable<Bool>,
+    }
+}
+
+table! {
+    outright_selection (id) {
+        id -> Int4,
+        tournament_id -> Int4,
+        team_id -> Int4,
+        ratio -> Text,
+    }
+}
+
+table! {
+    player (id) {
+        id -> Int4,
+        game_id -> Int4,
+        nickname -> Text,
+        real_name -> Text,
+        country -> Text,
+        photo -> Text,
+    }
+}
+
+table! {
+    roster_membership (id) {
+        id -> Int4,
+        team_id -> Int4,
+        player_id -> Int4,
+        role -> Text,
+        joined_at -> Text,
+        left_at -> Nullable<Text>,
+    }
+}
+
+table! {
+    stage (id) {
+        id -> Int4,
+        tournament_id -> Int4,
+        name -> Text,
+        stage_type -> Text,
+        position -> Int4,
+    }
+}
+
+table! {
+    stage_match (id) {
+        id -> Int4,
+        stage_id -> Int4,
+        game_match_id -> Int4,
+    }
+}
+
 table! {
     submitted_bet (id) {
         id -> Int4,
@@ -53,6 +175,8 @@ table! {
         placed_at -> Text,
         submitted_at -> Text,
         won -> Nullable<Bool>,
+        market -> Text,
+        market_value -> Nullable<Text>,
     }
 }
 
@@ -74,6 +198,8 @@ table! {
         name -> Text,
         description -> Text,
         logo -> Text,
+        archived -> Bool,
+        logo_asset_id -> Nullable<Int4>,
     }
 }
 
@@ -93,6 +219,29 @@ table! {
     }
 }
 
+table! {
+    ticket_share_code (id) {
+        id -> Int4,
+        code -> Text,
+        ticket_id -> Nullable<Int4>,
+        submitted_ticket_id -> Nullable<Int4>,
+        created_at -> Text,
+    }
+}
+
+table! {
+    tournament (id) {
+        id -> Int4,
+        game_id -> Int4,
+        name -> Text,
+        description -> Text,
+        starts_at -> Text,
+        ends_at -> Text,
+        winner_id -> Nullable<Int4>,
+        ended_at -> Nullable<Text>,
+    }
+}
+
 table! {
     user (id) {
         id -> Int4,
@@ -106,6 +255,7 @@ table! {
         created_at -> Text,
         balance -> Text,
         photo -> Nullable<Text>,
+        photo_asset_id -> Nullable<Int4>,
     }
 }
 
@@ -123,11 +273,46 @@ table! {
     }
 }
 
+table! {
+    user_favourite (id) {
+        id -> Int4,
+        user_id -> Int4,
+        team_id -> Nullable<Int4>,
+        game_id -> Nullable<Int4>,
+        game_match_id -> Nullable<Int4>,
+        created_at -> Text,
+    }
+}
+
+table! {
+    user_session (id) {
+        id -> Int4,
+        user_id -> Int4,
+        token -> Text,
+        created_at -> Text,
+        expires_at -> Text,
+    }
+}
+
 joinable!(bet -> game_match (game_match_id));
 joinable!(bet -> team (team_id));
 joinable!(bet -> ticket (ticket_id));
 joinable!(game_match -> game (game_id));
 joinable!(game_match_event -> game_match (game_match_id));
+joinable!(match_map -> game_match (game_match_id));
+joinable!(match_map -> team (winner_id));
+joinable!(match_timeline_event -> game_match (game_match_id));
+joinable!(outright_bet -> team (team_id));
+joinable!(outright_bet -> tournament (tournament_id));
+joinable!(outright_bet -> user (user_id));
+joinable!(outright_selection -> team (team_id));
+joinable!(outright_selection -> tournament (tournament_id));
+joinable!(player -> game (game_id));
+joinable!(roster_membership -> player (player_id));
+joinable!(roster_membership -> team (team_id));
+joinable!(stage -> tournament (tournament_id));
+joinable!(stage_match -> game_match (game_match_id));
+joinable!(stage_match -> stage (stage_id));
 joinable!(submitted_bet -> game_match (game_match_id));
 joinable!(submitted_bet -> submitted_ticket (submitted_ticket_id));
 joinable!(submitted_bet -> team (team_id));
@@ -135,18 +320,41 @@ joinable!(submitted_ticket -> user (user_id));
 joinable!(team_plays_game -> game (game_id));
 joinable!(team_plays_game -> team (team_id));
 joinable!(ticket -> user (user_id));
+joinable!(ticket_share_code -> submitted_ticket (submitted_ticket_id));
+joinable!(ticket_share_code -> ticket (ticket_id));
+joinable!(tournament -> game (game_id));
 joinable!(user_address -> user (user_id));
+joinable!(user_favourite -> game (game_id));
+joinable!(user_favourite -> game_match (game_match_id));
+joinable!(user_favourite -> team (team_id));
+joinable!(user_favourite -> user (user_id));
+joinable!(user_session -> user (user_id));
 
 allow_tables_to_appear_in_same_query!(
     bet,
+    event_outbox,
+    external_reference,
     game,
     game_match,
     game_match_event,
+    match_map,
+    match_timeline_event,
+    media_asset,
+    outright_bet,
+    outright_selection,
+    player,
+    roster_membership,
+    stage,
+    stage_match,
     submitted_bet,
     submitted_ticket,
     team,
     team_plays_game,
     ticket,
+    ticket_share_code,
+    tournament,
     user,
     user_address,
+    user_favourite,
+    user_session,
 );
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+/// What happened to a match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MatchChange {
+    Created,
+    /// the free text state of the match has been changed
+    StateChanged,
+    /// the match has become upcoming, live or ended
+    PhaseChanged(MatchPhase),
+    /// the ratios have moved after a ticket has been submitted
+    RatiosChanged,
+    /// the teams, the ratios, the scheduled start or the state of an upcoming match have been edited
+    Updated,
+    /// the upcoming match has been moved to another start,
+    /// its bets have been discarded from the open tickets of the users
+    Rescheduled {
+        /// the users who held a bet on the match in their open ticket, or follow it
+        affected_user_ids: Vec<i32>,
+    },
+    /// the match has been deleted, it cannot be loaded anymore
+    Deleted {
+        game_id: i32,
+        /// the users who held a bet on the match in their open ticket, or followed it
+        affected_user_ids: Vec<i32>,
+    },
+    /// a map of the live match has finished, deciding the bets on it
+    MapFinished {
+        map_number: i32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MatchPhase {
+    Upcoming,
+    Live,
+    Ended { winner_id: i32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchChanged {
+    pub match_id: i32,
+    pub change: MatchChange,
+}
+
+/// Why a match needs the attention of the admins
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MatchAlertKind {
+    /// the match has not gone live at its scheduled start
+    Overdue,
+    /// the live match has run past the expected duration of its game
+    RunningLong,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchAlert {
+    pub match_id: i32,
+    pub kind: MatchAlertKind,
+}
+
+/// An in-play event (score change, map or round result, ...) has been added to the timeline of the match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEventRecorded {
+    pub event_id: i32,
+    pub match_id: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BetPlaced {
+    pub bet_id: i32,
+    pub ticket_id: i32,
+    pub match_id: i32,
+    pub team_id: i32,
+}
+
+/// A bet of a submitted ticket, with the ratio it was submitted with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubmittedSelection {
+    pub match_id: i32,
+    pub team_id: i32,
+    pub ratio: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TicketSubmitted {
+    pub ticket_id: i32,
+    pub user_id: i32,
+    pub price_paid: String,
+    pub selections: Vec<SubmittedSelection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TicketSettled {
+    pub ticket_id: i32,
+    pub user_id: i32,
+    pub won: bool,
+    pub price_paid: String,
+    pub total_ratio: String,
+}
+
+/// The winner of the tournament has been set, its outright bets can be settled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TournamentEnded {
+    pub tournament_id: i32,
+    pub winner_id: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutrightSettled {
+    pub bet_id: i32,
+    pub tournament_id: i32,
+    pub user_id: i32,
+    pub won: bool,
+    pub price_paid: String,
+    pub bet_ratio: String,
+}
+
+/// Events of the betting domain, published by the request handlers
+/// and consumed by the subscribers of the bus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DomainEvent {
+    MatchChanged(MatchChanged),
+    TimelineEventRecorded(TimelineEventRecorded),
+    /// the scheduler has found a match needing the attention of the admins
+    MatchAlert(MatchAlert),
+    BetPlaced(BetPlaced),
+    TicketSubmitted(TicketSubmitted),
+    TicketSettled(TicketSettled),
+    TournamentEnded(TournamentEnded),
+    OutrightSettled(OutrightSettled),
+    /// the balance of the user has been changed by a submitted or settled ticket or an outright bet
+    BalanceChanged {
+        user_id: i32,
+    },
+}
+
+impl DomainEvent {
+    /// Serialize the event for storing it outside of the process
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserialize an event stored by `to_json`
+    pub fn from_json(json: &str) -> anyhow::Result<DomainEvent> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
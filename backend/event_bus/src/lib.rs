@@ -0,0 +1,141 @@
+use std::sync::Arc;
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
+
+pub mod events;
+
+pub use async_trait::async_trait;
+pub use events::DomainEvent;
+
+/// Number of events a subscriber may fall behind before it starts missing them
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// A domain event delivered by the bus
+#[derive(Debug, Clone)]
+pub struct Published {
+    /// ID of the event in the outbox, shared by all instances,
+    /// 0 for the events published before they have been stored
+    pub position: u64,
+    pub event: DomainEvent,
+}
+
+/// Consumer of the domain events, every subscriber runs in its own task
+/// and receives all events in the order they were published
+#[async_trait]
+pub trait Subscriber: Send + Sync + 'static {
+    async fn handle(&self, event: &DomainEvent) -> anyhow::Result<()>;
+
+    /// Handle the event along with its position in the outbox,
+    /// for the subscribers which need to know it
+    async fn handle_at(&self, position: u64, event: &DomainEvent) -> anyhow::Result<()> {
+        let _ = position;
+        self.handle(event).await
+    }
+}
+
+/// A shared subscriber, for subscribers also used outside of the bus
+#[async_trait]
+impl<S: Subscriber> Subscriber for Arc<S> {
+    async fn handle(&self, event: &DomainEvent) -> anyhow::Result<()> {
+        S::handle(self, event).await
+    }
+
+    async fn handle_at(&self, position: u64, event: &DomainEvent) -> anyhow::Result<()> {
+        S::handle_at(self, position, event).await
+    }
+}
+
+/// Subscribers handling every event one after the other in a single task,
+/// so everything the first one does for an event is done before the second one starts
+#[async_trait]
+impl<A: Subscriber, B: Subscriber> Subscriber for (A, B) {
+    async fn handle(&self, event: &DomainEvent) -> anyhow::Result<()> {
+        self.handle_at(0, event).await
+    }
+
+    async fn handle_at(&self, position: u64, event: &DomainEvent) -> anyhow::Result<()> {
+        let first = self.0.handle_at(position, event).await;
+        self.1.handle_at(position, event).await?;
+        first
+    }
+}
+
+/// Delivers the published domain events to all subscribers.
+/// Publishers do not wait for the subscribers, so a slow subscriber
+/// delays only itself.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Published>,
+}
+
+impl EventBus {
+    /// Create a bus keeping at most `capacity` events for the slowest subscriber
+    pub fn new(capacity: usize) -> EventBus {
+        let (sender, _) = broadcast::channel(capacity);
+        EventBus { sender }
+    }
+
+    /// Publish the event to all current subscribers,
+    /// the event is lost if there are none
+    pub fn publish(&self, event: DomainEvent) {
+        self.publish_at(0, event)
+    }
+
+    /// Publish the event read from the outbox to all current subscribers,
+    /// the event is lost if there are none
+    ///
+    /// Params
+    /// ---
+    /// - position: ID of the event in the outbox
+    /// - event: the published event
+    pub fn publish_at(&self, position: u64, event: DomainEvent) {
+        let _ = self.sender.send(Published { position, event });
+    }
+
+    /// Receive all events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<Published> {
+        self.sender.subscribe()
+    }
+
+    /// Run the subscriber in a new task, until the bus is dropped.
+    /// Errors of the subscriber are logged, they do not stop it.
+    ///
+    /// Params
+    /// ---
+    /// - name: name of the subscriber used in the logs
+    /// - subscriber: consumer of the events
+    ///
+    /// Returns
+    /// ---
+    /// - handle of the task running the subscriber
+    pub fn attach<S: Subscriber>(&self, name: &'static str, subscriber: S) -> JoinHandle<()> {
+        // subscribe before spawning, so no event published after attaching is missed
+        let mut receiver = self.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(published) => {
+                        if let Err(err) = subscriber
+                            .handle_at(published.position, &published.event)
+                            .await
+                        {
+                            println!("{} failed to handle {:?}: {}", name, published.event, err);
+                        }
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        println!("{} fell behind, missed {} events", name, missed);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus::new(DEFAULT_CAPACITY)
+    }
+}
@@ -5,5 +5,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     tonic_build::compile_protos("../../proto/game.proto")?;
     tonic_build::compile_protos("../../proto/user.proto")?;
     tonic_build::compile_protos("../../proto/team.proto")?;
+    tonic_build::compile_protos("../../proto/tournament.proto")?;
+    tonic_build::compile_protos("../../proto/envelope.proto")?;
+    tonic_build::compile_protos("../../proto/fixture_import.proto")?;
+    tonic_build::compile_protos("../../proto/media.proto")?;
+    tonic_build::compile_protos("../../proto/error.proto")?;
     Ok(())
 }
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use tonic::Code;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game::{GameRepo, PgGameRepo},
+        repo::Repo,
+        team::{PgTeamRepo, TeamRepo},
+        user::{PgUserRepo, UserRepo},
+    },
+    error::DomainError,
+};
+use grpc_layer::status::{error_status, invalid_argument};
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// ID of the administrator account, whose balance is checked
+const USER_ID: i32 = 0;
+
+/// The failures of the repositories are told apart and turned into the matching status codes,
+/// the internal ones without their description
+pub async fn run(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let users = PgUserRepo::new(pool);
+    let games = PgGameRepo::new(pool);
+    let teams = PgTeamRepo::new(pool);
+
+    let err = users.get(-1).await.err().expect("the user does not exist");
+    assert!(matches!(DomainError::of(&err), DomainError::NotFound(_)));
+    assert_eq!(error_status(err).code(), Code::NotFound);
+
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Error", unique_run(), &["team"]).await?;
+    let team_id = team_ids[0];
+    let err = teams.add_to_game(team_id, game_id).await.unwrap_err();
+    assert!(matches!(DomainError::of(&err), DomainError::Conflict(_)));
+    assert_eq!(error_status(err).code(), Code::FailedPrecondition);
+    println!("missing records and conflicting changes told apart");
+
+    let balance: f64 = users.get_balance(USER_ID).await?.parse()?;
+    let err = users
+        .spend_balance(USER_ID, balance + 100.0)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        DomainError::of(&err).params(),
+        vec![
+            ("balance", format!("{:.2}", balance)),
+            ("required", format!("{:.2}", balance + 100.0)),
+        ]
+    );
+    let status = error_status(err);
+    assert_eq!(status.code(), Code::FailedPrecondition);
+    assert!(!status.details().is_empty());
+    let err = users.spend_balance(USER_ID, -1.0).await.unwrap_err();
+    assert_eq!(error_status(err).code(), Code::InvalidArgument);
+    println!("missing balance reported along with the amounts");
+
+    let status = invalid_argument("The ID is not a number");
+    assert_eq!(status.code(), Code::InvalidArgument);
+    let status = error_status(anyhow::anyhow!("connection refused"));
+    assert_eq!(status.code(), Code::Internal);
+    assert_eq!(status.message(), "Internal server error");
+    println!("internal failures hidden from the clients");
+
+    teams.remove_from_game(team_id, game_id).await?;
+    teams.delete(team_id).await?;
+    games.delete(game_id).await?;
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use chrono::{Duration, SecondsFormat, Utc};
+use std::{env, fs, sync::Arc};
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        external_reference::{ExternalReferenceRepo, PgExternalReferenceRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        match_timeline::{PgTimelineRepo, TimelineRepo},
+        repo::Repo,
+        submitted_bet_and_ticket::{PgSubmittedBetAndTicketRepo, SubmittedBetAndTicketRepo},
+        user::{PgUserRepo, UserRepo},
+    },
+    db_models::{
+        bet::CreateBet, bet_market::BetMarket, external_reference::ExternalEntity,
+        game_match_event::GameMatchEventType, match_timeline_event::Score, ticket::ObtainedTicket,
+    },
+};
+use event_bus::{
+    events::{MatchChange, MatchPhase},
+    DomainEvent, EventBus,
+};
+use grpc_layer::{
+    config::BettingLimits,
+    importer::{FixtureImporter, ImportFormat},
+    match_feed::{FeedIngester, FileReplayFeed},
+};
+
+use crate::fixtures::unique_run;
+
+/// ID of the administrator account, used as the bettor
+const USER_ID: i32 = 0;
+
+/// Replay the recorded messages at once, failing on any broken message
+async fn replay(ingester: &FeedIngester, recorded: String) -> anyhow::Result<()> {
+    let path = env::temp_dir().join(format!("feed-test-{}.jsonl", unique_run()));
+    fs::write(&path, recorded)?;
+    let report = ingester
+        .run(&mut FileReplayFeed::open(&path.to_string_lossy(), false)?)
+        .await;
+    fs::remove_file(&path)?;
+    anyhow::ensure!(report.errors.is_empty(), "{}", report);
+    Ok(())
+}
+
+/// A recorded feed announces a match, opens it, changes its odds and score and ends it,
+/// the broken messages are reported and replaying the feed again changes nothing.
+/// A ticket on a match ended by the feed is settled along with the end of the match.
+pub async fn run(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let references = PgExternalReferenceRepo::new(pool);
+    let matches = PgMatchRepo::new(pool);
+    let timeline = PgTimelineRepo::new(pool);
+
+    let run = unique_run();
+    let catalog = format!(
+        r#"{{
+            "games": [{{ "external_id": "feed-game-{run}", "name": "Feed game {run}" }}],
+            "teams": [
+                {{ "external_id": "feed-one-{run}", "name": "Feed one {run}", "games": ["feed-game-{run}"] }},
+                {{ "external_id": "feed-two-{run}", "name": "Feed two {run}", "games": ["feed-game-{run}"] }}
+            ]
+        }}"#,
+        run = run
+    );
+    let imported = FixtureImporter::new(pool, EventBus::default())
+        .import_content(&catalog, ImportFormat::Json, false)
+        .await?;
+    assert!(imported.errors.is_empty());
+
+    let start = (Utc::now() + Duration::days(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    let recorded = format!(
+        r#"{{"type": "fixture", "external_id": "feed-match-{run}", "game": "feed-game-{run}", "team_one": "feed-one-{run}", "team_two": "feed-two-{run}", "team_one_ratio": 1.8, "team_two_ratio": 2.0, "supposed_start_at": "{start}"}}
+{{"type": "odds", "match_id": "feed-match-{run}", "team_one_ratio": 1.7, "team_two_ratio": 2.2}}
+{{"type": "live", "match_id": "feed-match-{run}", "delay_ms": 20}}
+
+{{"type": "score", "match_id": "feed-match-{run}", "team_one": 1, "team_two": 0}}
+{{"type": "halftime", "match_id": "feed-match-{run}"}}
+{{"type": "odds", "match_id": "missing-{run}", "team_one_ratio": 1.5, "team_two_ratio": 2.5}}
+{{"type": "score", "match_id": "feed-match-{run}", "team_one": 1, "team_two": 1, "delay_ms": 20}}
+{{"type": "ended", "match_id": "feed-match-{run}", "winner": "feed-two-{run}"}}
+"#,
+        run = run,
+        start = start
+    );
+    let path = env::temp_dir().join(format!("feed-test-{}.jsonl", run));
+    fs::write(&path, recorded)?;
+
+    let bus = EventBus::default();
+    let mut published = bus.subscribe();
+    let ingester = FeedIngester::new(pool, bus);
+    let report = ingester
+        .run(&mut FileReplayFeed::open(&path.to_string_lossy(), true)?)
+        .await;
+    println!("{}", report);
+    assert_eq!((report.applied, report.unchanged), (6, 0));
+    assert_eq!(report.errors.len(), 2);
+    assert!(report.errors[0].starts_with("line 6: "));
+    assert!(report.errors[1].starts_with(&format!("line 7 (missing-{}): ", run)));
+
+    let match_id = references
+        .find(ExternalEntity::Match, &format!("feed-match-{}", run))
+        .await?
+        .unwrap();
+    let winner_id = references
+        .find(ExternalEntity::Team, &format!("feed-two-{}", run))
+        .await?
+        .unwrap();
+    assert_eq!(matches.get_ratios(match_id).await?, (1.7, 2.2));
+    assert_eq!(
+        timeline.get_scores(&[match_id]).await?.get(&match_id),
+        Some(&Score {
+            team_one: 1,
+            team_two: 1
+        })
+    );
+    assert!(
+        matches.newest_event(match_id).await?.extract_event()?
+            == GameMatchEventType::Ended(winner_id)
+    );
+    println!("match announced, opened, scored and ended by the feed");
+
+    let mut changes = Vec::new();
+    while let Ok(event) = published.try_recv() {
+        changes.push(event.event);
+    }
+    assert_eq!(changes.len(), 6);
+    assert!(matches!(
+        &changes[..],
+        [
+            DomainEvent::MatchChanged(created),
+            DomainEvent::MatchChanged(odds),
+            DomainEvent::MatchChanged(live),
+            DomainEvent::TimelineEventRecorded(_),
+            DomainEvent::TimelineEventRecorded(_),
+            DomainEvent::MatchChanged(ended),
+        ] if created.change == MatchChange::Created
+            && odds.change == MatchChange::RatiosChanged
+            && live.change == MatchChange::PhaseChanged(MatchPhase::Live)
+            && ended.change == MatchChange::PhaseChanged(MatchPhase::Ended { winner_id })
+    ));
+    println!("changes published like the admins' edits");
+
+    let replayed = ingester
+        .run(&mut FileReplayFeed::open(&path.to_string_lossy(), false)?)
+        .await;
+    assert_eq!((replayed.applied, replayed.unchanged), (0, 6));
+    assert_eq!(replayed.errors.len(), 2);
+    assert!(published.try_recv().is_err());
+    println!("replaying the feed again changed nothing");
+    fs::remove_file(&path)?;
+
+    replay(
+        &ingester,
+        format!(
+        r#"{{"type": "fixture", "external_id": "feed-settled-{run}", "game": "feed-game-{run}", "team_one": "feed-one-{run}", "team_two": "feed-two-{run}", "team_one_ratio": 2.0, "team_two_ratio": 1.8, "supposed_start_at": "{start}"}}
+{{"type": "live", "match_id": "feed-settled-{run}"}}
+"#,
+            run = run,
+            start = start
+        ),
+    )
+    .await?;
+    let settled_match_id = references
+        .find(ExternalEntity::Match, &format!("feed-settled-{}", run))
+        .await?
+        .unwrap();
+    let one_id = references
+        .find(ExternalEntity::Team, &format!("feed-one-{}", run))
+        .await?
+        .unwrap();
+
+    let tickets = PgBetAndTicketRepo::new(pool);
+    let submitted = PgSubmittedBetAndTicketRepo::new(pool);
+    let users = PgUserRepo::new(pool);
+    let ticket = match tickets.get_user_current_ticket(USER_ID).await? {
+        ObtainedTicket::NoTicketFound(ticket)
+        | ObtainedTicket::NewAfterInvalid(ticket)
+        | ObtainedTicket::StillValid(ticket) => ticket,
+    };
+    for bet in tickets.get_bets(ticket.id).await? {
+        tickets.discard_a_bet(ticket.id, bet.id).await?;
+    }
+    tickets
+        .place_a_bet(
+            ticket.id,
+            CreateBet::new(settled_match_id, ticket.id, one_id, BetMarket::MatchWinner),
+            BettingLimits::default().max_selections,
+        )
+        .await?;
+    users.add_balance(USER_ID, 10.0).await?;
+    let submitted_id = tickets.submit_ticket(ticket.id, 10.0).await?;
+    let balance_before = users.get_balance(USER_ID).await?.parse::<f64>()?;
+    while published.try_recv().is_ok() {}
+
+    replay(
+        &ingester,
+        format!(
+            r#"{{"type": "ended", "match_id": "feed-settled-{run}", "winner": "feed-one-{run}"}}
+"#,
+            run = run
+        ),
+    )
+    .await?;
+    assert_eq!(submitted.get_ticket(submitted_id).await?.won, Some(true));
+    assert_eq!(
+        users.get_balance(USER_ID).await?.parse::<f64>()?,
+        balance_before + 20.0
+    );
+    let mut settled = Vec::new();
+    while let Ok(event) = published.try_recv() {
+        settled.push(event.event);
+    }
+    assert!(settled.iter().any(|event| matches!(
+        event,
+        DomainEvent::TicketSettled(ticket) if ticket.ticket_id == submitted_id && ticket.won
+    )));
+    println!("ticket settled in the transaction ending the match");
+    Ok(())
+}
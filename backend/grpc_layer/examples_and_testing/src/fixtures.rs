@@ -0,0 +1,50 @@
+use chrono::Utc;
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game::{GameRepo, PgGameRepo},
+        repo::Repo,
+        team::{PgTeamRepo, TeamRepo},
+    },
+    db_models::{game::CreateGame, team::CreateTeam},
+};
+
+/// Suffix of the names and external IDs created by a scenario,
+/// unique so the scenarios can be run repeatedly against the same database
+pub fn unique_run() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+/// Create the game `<name> game <run>` along with the teams `<name> <team> <run>` playing it
+///
+/// Returns
+/// ---
+/// - ID of the game and the IDs of the teams, in the order of their names
+pub async fn create_game_with_teams(
+    pool: &Arc<PgPool>,
+    name: &str,
+    run: i64,
+    team_names: &[&str],
+) -> anyhow::Result<(i32, Vec<i32>)> {
+    let games = PgGameRepo::new(pool);
+    let teams = PgTeamRepo::new(pool);
+
+    let game_id = games
+        .create(CreateGame::new(&format!("{} game {}", name, run), "", ""))
+        .await?;
+    let mut team_ids = Vec::new();
+    for team_name in team_names {
+        let team_id = teams
+            .create(CreateTeam::new(
+                &format!("{} {} {}", name, team_name, run),
+                "",
+                "",
+            ))
+            .await?;
+        teams.add_to_game(team_id, game_id).await?;
+        team_ids.push(team_id);
+    }
+    Ok((game_id, team_ids))
+}
@@ -0,0 +1,137 @@
+use chrono::{Duration, SecondsFormat, Utc};
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        external_reference::{ExternalReferenceRepo, PgExternalReferenceRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+    },
+    db_models::external_reference::ExternalEntity,
+};
+use event_bus::EventBus;
+use grpc_layer::importer::{FixtureImporter, ImportCounts, ImportFormat};
+
+use crate::fixtures::unique_run;
+
+fn counts(created: u32, updated: u32, unchanged: u32) -> ImportCounts {
+    ImportCounts {
+        created,
+        updated,
+        unchanged,
+    }
+}
+
+/// Games, teams and fixtures get created by the import, updated by importing them again,
+/// the broken rows are reported and a dry run changes nothing
+pub async fn run(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let importer = FixtureImporter::new(pool, EventBus::default());
+    let references = PgExternalReferenceRepo::new(pool);
+    let matches = PgMatchRepo::new(pool);
+
+    let run = unique_run();
+    let start = (Utc::now() + Duration::days(1)).to_rfc3339_opts(SecondsFormat::Secs, true);
+    let json = format!(
+        r#"{{
+            "games": [
+                {{ "external_id": "game-{run}", "name": "Import game {run}", "expected_duration_minutes": 90 }}
+            ],
+            "teams": [
+                {{ "external_id": "one-{run}", "name": "Import one {run}", "games": ["game-{run}"] }},
+                {{ "external_id": "two-{run}", "name": "Import two {run}", "games": ["game-{run}"] }},
+                {{ "external_id": "bench-{run}", "name": "Import bench {run}" }},
+                {{ "external_id": "lost-{run}", "name": "Import lost {run}", "games": ["missing-{run}"] }}
+            ],
+            "fixtures": [
+                {{ "external_id": "match-{run}", "game": "game-{run}", "team_one": "one-{run}", "team_two": "two-{run}",
+                   "team_one_ratio": 1.5, "team_two_ratio": 2.5, "supposed_start_at": "{start}" }},
+                {{ "external_id": "bench-match-{run}", "game": "game-{run}", "team_one": "one-{run}", "team_two": "bench-{run}",
+                   "team_one_ratio": 1.5, "team_two_ratio": 2.5, "supposed_start_at": "{start}" }},
+                {{ "external_id": "broken-{run}", "game": "game-{run}", "team_one": "one-{run}" }}
+            ]
+        }}"#,
+        run = run,
+        start = start
+    );
+
+    let dry_run = importer
+        .import_content(&json, ImportFormat::Json, true)
+        .await?;
+    println!("{}", dry_run);
+    assert_eq!(dry_run.games, counts(1, 0, 0));
+    assert_eq!(dry_run.teams, counts(3, 0, 0));
+    assert_eq!(dry_run.fixtures, counts(1, 0, 0));
+    assert_eq!(dry_run.links_created, 2);
+    let failed: Vec<&str> = dry_run
+        .errors
+        .iter()
+        .map(|error| error.row.as_str())
+        .collect();
+    assert_eq!(failed, vec!["fixtures[2]", "teams[3]", "fixtures[1]"]);
+    assert_eq!(
+        references
+            .find(ExternalEntity::Game, &format!("game-{}", run))
+            .await?,
+        None
+    );
+    println!("dry run changed nothing");
+
+    let imported = importer
+        .import_content(&json, ImportFormat::Json, false)
+        .await?;
+    assert_eq!(imported.games, dry_run.games);
+    assert_eq!(imported.teams, dry_run.teams);
+    assert_eq!(imported.fixtures, dry_run.fixtures);
+    assert_eq!(imported.links_created, 2);
+    assert_eq!(imported.errors, dry_run.errors);
+    assert_eq!(imported.created_matches.len(), 1);
+    let match_id = imported.created_matches[0];
+    assert_eq!(
+        references
+            .find(ExternalEntity::Match, &format!("match-{}", run))
+            .await?,
+        Some(match_id)
+    );
+    println!("file imported");
+
+    let again = importer
+        .import_content(&json, ImportFormat::Json, false)
+        .await?;
+    assert_eq!(again.games, counts(0, 0, 1));
+    assert_eq!(again.teams, counts(0, 0, 3));
+    assert_eq!(again.fixtures, counts(0, 0, 1));
+    assert_eq!(again.links_created, 0);
+    println!("importing again changed nothing");
+
+    let csv = format!(
+        "record,external_id,name,games,game,team_one,team_two,team_one_ratio,team_two_ratio,supposed_start_at,state\n\
+         team,bench-{run},Import bench {run},game-{run},,,,,,,\n\
+         fixture,match-{run},,,game-{run},one-{run},two-{run},1.8,2.1,{start},Final\n\
+         fixture,bench-match-{run},,,game-{run},bench-{run},two-{run},1.2,4,{start},\n\
+         player,someone-{run},,,,,,,,,\n",
+        run = run,
+        start = start
+    );
+    let updated = importer
+        .import_content(&csv, ImportFormat::Csv, false)
+        .await?;
+    println!("{}", updated);
+    assert_eq!(updated.teams, counts(0, 0, 1));
+    assert_eq!(updated.links_created, 1);
+    assert_eq!(updated.fixtures, counts(1, 1, 0));
+    assert_eq!(updated.updated_matches, vec![match_id]);
+    assert_eq!(updated.errors.len(), 1);
+    assert_eq!(updated.errors[0].row, "line 5");
+    let game_match = matches.get(match_id).await?;
+    assert_eq!(
+        (
+            game_match.team_one_ratio.as_str(),
+            game_match.state.as_str()
+        ),
+        ("1.8", "Final")
+    );
+    println!("CSV file updated the match and created another one");
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use chrono::{Duration, Utc};
+use prost::Message;
+use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex};
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+    },
+    db_models::game_match::CreateGameMatch,
+};
+use event_bus::{
+    events::{SubmittedSelection, TicketSubmitted},
+    DomainEvent, Subscriber,
+};
+use grpc_layer::{
+    config::LatestBetsConfig,
+    envelope::{envelope::Payload, Envelope, LatestBets, StakeBucket},
+    subscribers::latest_bets::LatestBetsFeed,
+};
+use ws_layer::{Client, ClientRegistry, Clients, Topic, WsSettings};
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// Resync message of the scenario, the client never resumes
+fn resync(_sequence: u64) -> ws_layer::Msg {
+    ws_layer::Msg::text("resync")
+}
+
+/// Register a websocket client subscribed to the latest bets, returning the receiving end of its queue
+async fn add_client(clients: &Clients) -> mpsc::Receiver<ws_layer::Msg> {
+    let (sender, receiver) = mpsc::channel(WsSettings::default().queue_capacity);
+    clients.lock().await.clients.insert(
+        String::from("latest bets"),
+        Client {
+            client_id: String::from("latest bets"),
+            user_id: None,
+            topics: vec![Topic::LatestBets].into_iter().collect(),
+            sender: Some(sender),
+            dropped_messages: 0,
+        },
+    );
+    receiver
+}
+
+/// Take the batch of the latest bets sent to the client
+fn received_batch(client: &mut mpsc::Receiver<ws_layer::Msg>) -> anyhow::Result<LatestBets> {
+    let message = client.try_recv()?;
+    match Envelope::decode(message.as_bytes())?.payload {
+        Some(Payload::LatestBets(batch)) => Ok(batch),
+        other => anyhow::bail!("Expected the latest bets, got {:?}", other),
+    }
+}
+
+/// Event of a ticket submitted with the selections of (match, team, ratio)
+fn submitted(ticket_id: i32, price_paid: &str, selections: &[(i32, i32, &str)]) -> DomainEvent {
+    DomainEvent::TicketSubmitted(TicketSubmitted {
+        ticket_id,
+        user_id: 0,
+        price_paid: String::from(price_paid),
+        selections: selections
+            .iter()
+            .map(|(match_id, team_id, ratio)| SubmittedSelection {
+                match_id: *match_id,
+                team_id: *team_id,
+                ratio: String::from(*ratio),
+            })
+            .collect(),
+    })
+}
+
+/// Tickets submitted within a single interval are sent to the websocket clients as one batch,
+/// the selections of the same team in the same match as a single entry,
+/// the stakes bucketed by the configuration
+pub async fn run(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let matches = PgMatchRepo::new(pool);
+
+    let run = unique_run();
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Latest bets", run, &["one", "two"]).await?;
+    let (one, two) = (team_ids[0], team_ids[1]);
+    let mut match_ids = Vec::new();
+    for _ in 0..2 {
+        let match_id = matches
+            .create(CreateGameMatch::new(
+                game_id,
+                one,
+                two,
+                "1.8",
+                "2.0",
+                Utc::now() + Duration::hours(1),
+                "latest bets test",
+            ))
+            .await?;
+        match_ids.push(match_id);
+    }
+    let (first, second) = (match_ids[0], match_ids[1]);
+
+    let clients: Clients = Arc::new(Mutex::new(ClientRegistry::new(
+        WsSettings::default(),
+        resync,
+    )));
+    let mut client = add_client(&clients).await;
+    let settings = LatestBetsConfig {
+        small_stake: 20.0,
+        medium_stake: 100.0,
+        large_stake: 400.0,
+        max_batch_size: 2,
+    };
+    let feed = LatestBetsFeed::new(pool, clients.clone(), settings);
+
+    feed.handle(&submitted(
+        1,
+        "5",
+        &[(first, one, "1.8"), (second, two, "2.0")],
+    ))
+    .await?;
+    feed.handle(&submitted(2, "60", &[(first, one, "1.71")]))
+        .await?;
+    feed.handle(&submitted(3, "500", &[(first, two, "2.1")]))
+        .await?;
+    feed.flush().await;
+
+    let batch = received_batch(&mut client)?;
+    let entries: Vec<(i32, i32, &str, Option<StakeBucket>, u32)> = batch
+        .bets
+        .iter()
+        .map(|bet| {
+            (
+                bet.match_id,
+                bet.team_id,
+                bet.ratio.as_str(),
+                StakeBucket::from_i32(bet.stake),
+                bet.count,
+            )
+        })
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            (first, two, "2.1", Some(StakeBucket::HighRoller), 1),
+            (first, one, "1.71", Some(StakeBucket::Medium), 2),
+        ]
+    );
+    // the selection bet on least recently does not fit into the batch
+    assert_eq!(batch.omitted, 1);
+    assert_eq!(
+        batch.bets[1].team_name,
+        format!("Latest bets one {}", run),
+        "the names of the teams are filled in"
+    );
+    println!("selections of the same team in a match sent as one, the stakes bucketed");
+
+    // nothing new, nothing sent
+    feed.flush().await;
+    assert!(client.try_recv().is_err());
+    feed.handle(&submitted(4, "25", &[(second, two, "2.0")]))
+        .await?;
+    feed.flush().await;
+    let batch = received_batch(&mut client)?;
+    assert_eq!(batch.bets.len(), 1);
+    assert_eq!((batch.bets[0].count, batch.omitted), (1, 0));
+    println!("every batch aggregates the tickets submitted since the previous one");
+    Ok(())
+}
@@ -1,12 +1,58 @@
+use database_layer::connection::db_connect_create_pool;
 use dotenv::dotenv;
-use grpc_layer;
-use std::env;
+use grpc_layer::config::Config;
+use std::{env, sync::Arc};
+
+mod error_test;
+mod feed_test;
+mod fixtures;
+mod import_test;
+mod latest_bets_test;
+mod match_edit_test;
+mod media_test;
+mod outbox_test;
+mod scheduler_test;
+mod series_test;
+mod stats_test;
+mod tournament_test;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv().ok();
     let database_url = env::var("DATABASE_URL")?;
-    let server_address = env::var("SERVER_ADDRESS")?;
-    grpc_layer::run_grpc_server(&server_address, &database_url).await?;
+
+    // `--<scenario>` runs the scenario instead of the server,
+    // the repositories on their own are checked by `database_layer/examples_and_testing`
+    let scenario = env::args().find_map(|arg| arg.strip_prefix("--").map(String::from));
+    if let Some(scenario) = scenario {
+        let pool = Arc::new(db_connect_create_pool(&database_url).await?);
+        return match scenario.as_str() {
+            // the match scheduler
+            "scheduler" => scheduler_test::run(&pool).await,
+            // editing, rescheduling and deleting the matches
+            "matches" => match_edit_test::run(&pool).await,
+            // the uploaded images
+            "media" => media_test::run(&pool).await,
+            // the fixture import
+            "import" => import_test::run(&pool).await,
+            // the tournaments and their outright bets
+            "tournament" => tournament_test::run(&pool).await,
+            // the best-of series and their map bets
+            "series" => series_test::run(&pool).await,
+            // the replayed match-data feed
+            "feed" => feed_test::run(&pool).await,
+            // the team statistics and their cache
+            "stats" => stats_test::run(&pool).await,
+            // the typed errors and their status codes
+            "errors" => error_test::run(&pool).await,
+            // the aggregated feed of the latest bets
+            "latest-bets" => latest_bets_test::run(&pool).await,
+            // the events committed out of order
+            "outbox" => outbox_test::run(&pool, &database_url).await,
+            _ => anyhow::bail!("Unknown scenario --{}", scenario),
+        };
+    }
+
+    grpc_layer::run_grpc_server(Config::load()?).await?;
     Ok(())
 }
@@ -0,0 +1,201 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        favourite::{FavouriteRepo, PgFavouriteRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+        submitted_bet_and_ticket::{PgSubmittedBetAndTicketRepo, SubmittedBetAndTicketRepo},
+        team::{PgTeamRepo, TeamRepo},
+        user::{PgUserRepo, UserRepo},
+    },
+    db_models::{
+        bet::CreateBet, bet_market::BetMarket, game_match::CreateGameMatch,
+        game_match_event::GameMatchEventType, team::CreateTeam, ticket::ObtainedTicket,
+        user_favourite::Favourite,
+    },
+};
+use grpc_layer::config::BettingLimits;
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// ID of the administrator account, used as the bettor and the follower
+const USER_ID: i32 = 0;
+
+/// Place a bet on the team into the open ticket of the user, clearing the bets left in it
+///
+/// Returns
+/// ---
+/// - Ok(id) of the open ticket
+/// - Err(_) if the bet could not be placed
+async fn bet_on(tickets: &PgBetAndTicketRepo, match_id: i32, team_id: i32) -> anyhow::Result<i32> {
+    let ticket = match tickets.get_user_current_ticket(USER_ID).await? {
+        ObtainedTicket::NoTicketFound(ticket)
+        | ObtainedTicket::NewAfterInvalid(ticket)
+        | ObtainedTicket::StillValid(ticket) => ticket,
+    };
+    for bet in tickets.get_bets(ticket.id).await? {
+        tickets.discard_a_bet(ticket.id, bet.id).await?;
+    }
+    tickets
+        .place_a_bet(
+            ticket.id,
+            CreateBet::new(match_id, ticket.id, team_id, BetMarket::MatchWinner),
+            BettingLimits::default().max_selections,
+        )
+        .await?;
+    Ok(ticket.id)
+}
+
+/// Upcoming matches get new teams, ratios and starts, a live match is rescheduled
+/// voiding its submitted bets and discarding the open ones, and a followed match is deleted
+pub async fn run(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let teams = PgTeamRepo::new(pool);
+    let matches = PgMatchRepo::new(pool);
+    let tickets = PgBetAndTicketRepo::new(pool);
+    let favourites = PgFavouriteRepo::new(pool);
+    let submitted = PgSubmittedBetAndTicketRepo::new(pool);
+    let users = PgUserRepo::new(pool);
+
+    let run = unique_run();
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Editing", run, &["one", "two", "archived"]).await?;
+    let (one, two, archived) = (team_ids[0], team_ids[1], team_ids[2]);
+    let outsider = teams
+        .create(CreateTeam::new(
+            &format!("Editing outsider {}", run),
+            "",
+            "",
+        ))
+        .await?;
+    teams.set_archived(archived, true).await?;
+
+    let new_match = || {
+        CreateGameMatch::new(
+            game_id,
+            one,
+            two,
+            "1.8",
+            "2.1",
+            Utc::now() + Duration::hours(1),
+            "editing test",
+        )
+    };
+    let match_id = matches.create(new_match()).await?;
+
+    assert!(matches.change_teams(match_id, one, one).await.is_err());
+    assert!(matches.change_teams(match_id, one, outsider).await.is_err());
+    assert!(matches.change_teams(match_id, archived, two).await.is_err());
+    matches.change_teams(match_id, two, one).await?;
+    let game_match = matches.get(match_id).await?;
+    assert_eq!((game_match.team_one_id, game_match.team_two_id), (two, one));
+    println!("teams swapped, the same, outside and archived teams refused");
+
+    let match_update = game_match.to_update(Some("1.5"), None, None, Some("edited"))?;
+    matches.update(match_id, match_update).await?;
+    let game_match = matches.get(match_id).await?;
+    assert_eq!(
+        (
+            game_match.team_one_ratio.as_str(),
+            game_match.team_two_ratio.as_str(),
+            game_match.state.as_str()
+        ),
+        ("1.5", "2.1", "edited")
+    );
+    println!("ratio and state edited, the unset ratio kept");
+
+    assert!(game_match
+        .to_update(None, None, Some(Utc::now() - Duration::hours(1)), None)
+        .is_err());
+    let moved_start = Utc::now() + Duration::days(1);
+    let match_update = game_match.to_update(None, None, Some(moved_start), None)?;
+    matches.update(match_id, match_update).await?;
+    assert_eq!(
+        matches.get(match_id).await?.supposed_start_at,
+        moved_start.to_string()
+    );
+    println!("match moved to the future, the past refused");
+
+    // bets can only be placed on a live match
+    let live_id = matches.create(new_match()).await?;
+    matches
+        .create_event(live_id, GameMatchEventType::Live)
+        .await?;
+    users.add_balance(USER_ID, 10.0).await?;
+    let open_id = bet_on(&tickets, live_id, one).await?;
+    let submitted_id = tickets.submit_ticket(open_id, 10.0).await?;
+    let ticket_id = bet_on(&tickets, live_id, two).await?;
+    assert_eq!(tickets.get_open_ticket_holders(live_id).await?, [USER_ID]);
+    assert!(matches.change_teams(live_id, two, one).await.is_err());
+
+    // the live match is played again later, voiding the submitted bet
+    let balance_before = users.get_balance(USER_ID).await?.parse::<f64>()?;
+    let moved_start = Utc::now() + Duration::days(1);
+    assert_eq!(matches.reschedule(live_id, moved_start).await?, [USER_ID]);
+    assert!(matches
+        .newest_event(live_id)
+        .await?
+        .extract_event()?
+        .cmp_type(&GameMatchEventType::Upcoming));
+    assert_eq!(
+        matches.get(live_id).await?.supposed_start_at,
+        moved_start.to_string()
+    );
+    let voided = &submitted.get_bets(submitted_id).await?[0];
+    assert_eq!((voided.won, voided.bet_ratio.as_str()), (Some(true), "1"));
+    let settled = submitted.settle(USER_ID).await?;
+    assert!(settled
+        .iter()
+        .any(|ticket| ticket.id == submitted_id && ticket.won == Some(true)));
+    assert_eq!(
+        users.get_balance(USER_ID).await?.parse::<f64>()?,
+        balance_before + 10.0
+    );
+    // settled tickets are never paid out again
+    assert!(submitted.settle(USER_ID).await?.is_empty());
+    assert_eq!(
+        users.get_balance(USER_ID).await?.parse::<f64>()?,
+        balance_before + 10.0
+    );
+    println!("live match rescheduled, the submitted bet voided and the stake returned once");
+
+    assert_eq!(tickets.discard_bets_on_match(live_id).await?, [USER_ID]);
+    assert!(tickets.get_open_ticket_holders(live_id).await?.is_empty());
+    assert!(!tickets
+        .get_bets(ticket_id)
+        .await?
+        .iter()
+        .any(|bet| bet.game_match_id == live_id));
+    println!("bets on the rescheduled match discarded from the open ticket");
+
+    matches
+        .create_event(live_id, GameMatchEventType::Live)
+        .await?;
+    matches
+        .create_event(live_id, GameMatchEventType::Ended(one))
+        .await?;
+    assert!(matches
+        .reschedule(live_id, Utc::now() + Duration::days(2))
+        .await
+        .is_err());
+    println!("restarted match played again, the ended match kept");
+
+    favourites.add(USER_ID, Favourite::Match(match_id)).await?;
+    assert!(favourites
+        .get_match_followers(match_id)
+        .await?
+        .contains(&USER_ID));
+    let deleted = matches.delete(match_id).await?;
+    assert_eq!((deleted.id, deleted.game_id), (match_id, game_id));
+    assert!(matches.get(match_id).await.is_err());
+    assert!(!favourites
+        .get_all(USER_ID)
+        .await?
+        .contains(&Favourite::Match(match_id)));
+    println!("followed match deleted together with its favourites");
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use image::{DynamicImage, ImageOutputFormat};
+use std::{env, fs, io::Cursor, sync::Arc};
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game::{GameRepo, PgGameRepo},
+        repo::Repo,
+    },
+    db_models::game::CreateGame,
+};
+use grpc_layer::media_store::{MediaSettings, MediaStore};
+
+use crate::fixtures::unique_run;
+
+/// Encode a blank image of the size as PNG
+fn png(width: u32, height: u32) -> anyhow::Result<Vec<u8>> {
+    let mut content = Vec::new();
+    DynamicImage::new_rgb8(width, height)
+        .write_to(&mut Cursor::new(&mut content), ImageOutputFormat::Png)?;
+    Ok(content)
+}
+
+/// Images are checked, stored once under their content hash along with a thumbnail
+/// and referred to by the logo of a game
+pub async fn run(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let games = PgGameRepo::new(pool);
+
+    let run = unique_run();
+    let settings = MediaSettings {
+        directory: env::temp_dir().join(format!("media-test-{}", run)),
+        public_url: String::from("http://localhost/media/"),
+        ..MediaSettings::default()
+    };
+    let store = MediaStore::new(pool, settings.clone());
+
+    let image = store.prepare(png(300, 200)?).await?;
+    assert_eq!((image.width, image.height), (300, 200));
+    let thumbnail = image::load_from_memory(&image.thumbnail)?;
+    assert_eq!((thumbnail.width(), thumbnail.height()), (128, 85));
+    let asset = store.save(image).await?;
+    assert_eq!(asset.content_type, "image/png");
+    assert_eq!(asset.file_name, format!("{}.png", asset.content_hash));
+    assert!(settings.directory.join(&asset.file_name).exists());
+    assert!(settings.directory.join(&asset.thumbnail_name).exists());
+    assert_eq!(
+        store.url(&asset.file_name),
+        format!("http://localhost/media/{}", asset.file_name)
+    );
+
+    let again = store.save(store.prepare(png(300, 200)?).await?).await?;
+    assert_eq!(again.id, asset.id);
+    println!("image stored once along with its thumbnail");
+
+    assert!(store.prepare(Vec::new()).await.is_err());
+    assert!(store
+        .prepare(b"GIF89a, but not really".to_vec())
+        .await
+        .is_err());
+    assert!(store.prepare(b"plain text".to_vec()).await.is_err());
+    assert!(store.prepare(png(5000, 10)?).await.is_err());
+    let small_store = MediaStore::new(
+        pool,
+        MediaSettings {
+            max_bytes: 16,
+            ..settings.clone()
+        },
+    );
+    assert!(small_store.prepare(png(1, 1)?).await.is_err());
+    println!("empty, broken, unknown, too large and too big images rejected");
+
+    let game_id = games
+        .create(CreateGame::new(&format!("Media game {}", run), "", ""))
+        .await?;
+    let logo = store.resolve_url("", Some(asset.id)).await?;
+    games
+        .edit(
+            game_id,
+            CreateGame::new(&format!("Media game {}", run), "", &logo),
+        )
+        .await?;
+    games.set_logo_asset(game_id, Some(asset.id)).await?;
+    let game = games.get(game_id).await?;
+    assert_eq!(
+        (game.logo_url.as_str(), game.logo_asset_id),
+        (logo.as_str(), Some(asset.id))
+    );
+    assert_eq!(
+        store.resolve_url("http://elsewhere/logo.png", None).await?,
+        "http://elsewhere/logo.png"
+    );
+    assert!(store.resolve_url("", Some(-1)).await.is_err());
+    assert!(games.set_logo_asset(game_id, Some(-1)).await.is_err());
+    games.set_logo_asset(game_id, None).await?;
+    assert_eq!(games.get(game_id).await?.logo_asset_id, None);
+    games.delete(game_id).await?;
+    println!("game logo referring to the uploaded image");
+
+    fs::remove_dir_all(&settings.directory)?;
+    Ok(())
+}
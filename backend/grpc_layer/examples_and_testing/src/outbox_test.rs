@@ -0,0 +1,137 @@
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    runtime::Handle, sync::broadcast::Receiver, sync::oneshot, task::JoinHandle, time::Instant,
+};
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        event_outbox::{OutboxRepo, PgOutboxRepo},
+        repo::Repo,
+    },
+};
+use event_bus::{DomainEvent, EventBus, Published};
+use grpc_layer::fanout::{run_fanout, GAP_TIMEOUT};
+
+use crate::fixtures::unique_run;
+
+/// Collect the balance changes of the users delivered by the fan-out during the period,
+/// along with their positions in the outbox
+async fn delivered(
+    fanout: &mut Receiver<Published>,
+    period: Duration,
+    user_ids: &[i32],
+) -> anyhow::Result<Vec<(i32, u64)>> {
+    let deadline = Instant::now() + period;
+    let mut delivered = Vec::new();
+    while let Ok(received) = tokio::time::timeout_at(deadline, fanout.recv()).await {
+        let received = received?;
+        if let DomainEvent::BalanceChanged { user_id } = received.event {
+            if user_ids.contains(&user_id) {
+                delivered.push((user_id, received.position));
+            }
+        }
+    }
+    Ok(delivered)
+}
+
+/// Store an event of the user in a transaction which is kept uncommitted until told to commit
+///
+/// Returns
+/// ---
+/// - Ok((id, commit, committed)) with the ID of the event, the sender committing the transaction
+///   and the task returning the ID once committed
+/// - Err(_) if the event could not be stored
+async fn uncommitted(
+    pool: &Arc<PgPool>,
+    user_id: i32,
+) -> anyhow::Result<(
+    i32,
+    std::sync::mpsc::Sender<()>,
+    JoinHandle<anyhow::Result<i32>>,
+)> {
+    let (inserted, id) = oneshot::channel();
+    let (commit, committed) = std::sync::mpsc::channel::<()>();
+    let outbox = PgOutboxRepo::new(pool);
+    let transaction = tokio::task::spawn_blocking(move || {
+        Handle::current().block_on(outbox.transaction(move |connection| {
+            let payload = DomainEvent::BalanceChanged { user_id }.to_json()?;
+            let id = PgOutboxRepo::append_in(connection, &payload)?;
+            let _ = inserted.send(id);
+            committed.recv()?;
+            Ok((id, Vec::new()))
+        }))
+    });
+    Ok((id.await?, commit, transaction))
+}
+
+/// Start delivering the events of the outbox to a new fan-out bus, like a starting instance
+async fn start_fanout(
+    pool: &Arc<PgPool>,
+    database_url: &str,
+) -> (Receiver<Published>, JoinHandle<anyhow::Result<()>>) {
+    let bus = EventBus::default();
+    let fanout = bus.subscribe();
+    let reader = {
+        let pool = pool.clone();
+        let database_url = String::from(database_url);
+        tokio::spawn(async move { run_fanout(&pool, &database_url, bus).await })
+    };
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    (fanout, reader)
+}
+
+/// An event numbered first but committed last is delivered once it is committed,
+/// after the later event has been delivered, and neither of them is delivered twice.
+/// An event numbered before an instance starts and committed afterwards is delivered as well.
+pub async fn run(pool: &Arc<PgPool>, database_url: &str) -> anyhow::Result<()> {
+    let outbox = PgOutboxRepo::new(pool);
+    let (mut fanout, reader) = start_fanout(pool, database_url).await;
+
+    // users which do not exist, so the scenario can be run next to a server
+    let late_user_id = -((unique_run() % 1_000_000) as i32) * 2 - 1;
+    let early_user_id = late_user_id - 1;
+    let user_ids = [late_user_id, early_user_id];
+
+    // the late event takes its number first and is kept uncommitted
+    let (late_id, commit, late) = uncommitted(pool, late_user_id).await?;
+
+    let early_payload = DomainEvent::BalanceChanged {
+        user_id: early_user_id,
+    }
+    .to_json()?;
+    let early_id = outbox.append(&early_payload).await?;
+    assert!(early_id > late_id);
+
+    // the early event is delivered once the late one has been waited for
+    let before_commit =
+        delivered(&mut fanout, GAP_TIMEOUT + Duration::from_secs(2), &user_ids).await?;
+    assert_eq!(before_commit, vec![(early_user_id, early_id as u64)]);
+
+    commit.send(())?;
+    assert_eq!(late.await??, late_id);
+    let after_commit = delivered(&mut fanout, Duration::from_secs(3), &user_ids).await?;
+    assert_eq!(after_commit, vec![(late_user_id, late_id as u64)]);
+
+    reader.abort();
+    println!(
+        "outbox scenario passed: event {} delivered after event {}, both once",
+        late_id, early_id
+    );
+
+    // both events are numbered before the instance starts, the early one committed already
+    let (late_id, commit, late) = uncommitted(pool, late_user_id).await?;
+    let early_id = outbox.append(&early_payload).await?;
+    let (mut fanout, reader) = start_fanout(pool, database_url).await;
+    commit.send(())?;
+    assert_eq!(late.await??, late_id);
+    let after_commit = delivered(&mut fanout, Duration::from_secs(3), &user_ids).await?;
+    assert_eq!(after_commit, vec![(late_user_id, late_id as u64)]);
+
+    reader.abort();
+    println!(
+        "event {} committed after the instance started delivered, event {} committed before it not",
+        late_id, early_id
+    );
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game::GameRepo, game::PgGameRepo, game_match::MatchRepo, game_match::PgMatchRepo,
+        repo::Repo,
+    },
+    db_models::{game_match::CreateGameMatch, game_match_event::GameMatchEventType},
+};
+use event_bus::{
+    events::{MatchAlert, MatchAlertKind, MatchChange, MatchChanged, MatchPhase},
+    DomainEvent, EventBus, Published,
+};
+use grpc_layer::{
+    clock::ManualClock,
+    scheduler::{Scheduler, SchedulerSettings},
+};
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// Events of the match published since the last call
+fn published_for(events: &mut broadcast::Receiver<Published>, match_id: i32) -> Vec<DomainEvent> {
+    let mut published = Vec::new();
+    while let Ok(Published { event, .. }) = events.try_recv() {
+        let event_match_id = match &event {
+            DomainEvent::MatchChanged(MatchChanged { match_id, .. }) => *match_id,
+            DomainEvent::MatchAlert(MatchAlert { match_id, .. }) => *match_id,
+            _ => continue,
+        };
+        if event_match_id == match_id {
+            published.push(event);
+        }
+    }
+    published
+}
+
+async fn is_live(matches: &PgMatchRepo, match_id: i32) -> anyhow::Result<bool> {
+    Ok(matches.newest_event(match_id).await?.extract_event()? == GameMatchEventType::Live)
+}
+
+/// Create a match of the teams starting a minute from now
+async fn create_match(
+    matches: &PgMatchRepo,
+    game_id: i32,
+    (team_one_id, team_two_id): (i32, i32),
+) -> anyhow::Result<i32> {
+    matches
+        .create(CreateGameMatch::new(
+            game_id,
+            team_one_id,
+            team_two_id,
+            "1.5",
+            "2.5",
+            Utc::now() + Duration::minutes(1),
+            "scheduler test",
+        ))
+        .await
+}
+
+/// A match goes live at its scheduled start and gets reported once it runs too long,
+/// a match which is not started automatically gets reported as overdue
+pub async fn run(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let games = PgGameRepo::new(pool);
+    let matches = PgMatchRepo::new(pool);
+
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Scheduler", unique_run(), &["one", "two"]).await?;
+    let (team_one_id, team_two_id) = (team_ids[0], team_ids[1]);
+    let match_teams = (team_one_id, team_two_id);
+    let expected_duration =
+        Duration::minutes(games.get(game_id).await?.expected_duration_minutes.into());
+
+    let bus = EventBus::default();
+    let mut events = bus.subscribe();
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let mut scheduler = Scheduler::new(
+        pool,
+        bus.clone(),
+        clock.clone(),
+        SchedulerSettings::default(),
+    );
+
+    // auto-started match
+    let match_id = create_match(&matches, game_id, match_teams).await?;
+    scheduler.tick().await?;
+    anyhow::ensure!(
+        !is_live(&matches, match_id).await? && published_for(&mut events, match_id).is_empty(),
+        "scheduler: the match {} started before its time",
+        match_id
+    );
+
+    clock.advance(Duration::minutes(2));
+    scheduler.tick().await?;
+    let published = published_for(&mut events, match_id);
+    anyhow::ensure!(
+        is_live(&matches, match_id).await?
+            && published
+                == vec![DomainEvent::MatchChanged(MatchChanged {
+                    match_id,
+                    change: MatchChange::PhaseChanged(MatchPhase::Live),
+                })],
+        "scheduler: the match {} did not start at its time: {:?}",
+        match_id,
+        published
+    );
+
+    clock.advance(expected_duration);
+    scheduler.tick().await?;
+    scheduler.tick().await?;
+    let published = published_for(&mut events, match_id);
+    anyhow::ensure!(
+        published
+            == vec![DomainEvent::MatchAlert(MatchAlert {
+                match_id,
+                kind: MatchAlertKind::RunningLong,
+            })],
+        "scheduler: the long running match {} was not reported just once: {:?}",
+        match_id,
+        published
+    );
+    matches
+        .create_event(match_id, GameMatchEventType::Ended(team_one_id))
+        .await?;
+    println!(
+        "scheduler: match {} started in time and reported when running long",
+        match_id
+    );
+
+    // without auto-start, the match is left to the admins
+    let clock = Arc::new(ManualClock::new(Utc::now()));
+    let mut scheduler = Scheduler::new(
+        pool,
+        bus.clone(),
+        clock.clone(),
+        SchedulerSettings {
+            auto_start: false,
+            ..SchedulerSettings::default()
+        },
+    );
+    let match_id = create_match(&matches, game_id, match_teams).await?;
+    clock.advance(Duration::minutes(2));
+    scheduler.tick().await?;
+    let published = published_for(&mut events, match_id);
+    anyhow::ensure!(
+        !is_live(&matches, match_id).await?
+            && published
+                == vec![DomainEvent::MatchAlert(MatchAlert {
+                    match_id,
+                    kind: MatchAlertKind::Overdue,
+                })],
+        "scheduler: the overdue match {} was not reported: {:?}",
+        match_id,
+        published
+    );
+    matches
+        .create_event(match_id, GameMatchEventType::Live)
+        .await?;
+    matches
+        .create_event(match_id, GameMatchEventType::Ended(team_one_id))
+        .await?;
+    println!("scheduler: overdue match {} reported", match_id);
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        match_map::{MatchMapRepo, PgMatchMapRepo},
+        repo::Repo,
+        submitted_bet_and_ticket::{PgSubmittedBetAndTicketRepo, SubmittedBetAndTicketRepo},
+        user::{PgUserRepo, UserRepo},
+    },
+    db_models::{
+        bet::CreateBet,
+        bet_market::BetMarket,
+        game_match::{CreateGameMatch, SeriesFormat},
+        game_match_event::GameMatchEventType,
+        match_map::CreateMatchMap,
+        ticket::ObtainedTicket,
+    },
+};
+use grpc_layer::config::BettingLimits;
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// ID of the administrator account, used as the bettor
+const USER_ID: i32 = 0;
+
+async fn balance(users: &PgUserRepo) -> anyhow::Result<f64> {
+    Ok(users.get_balance(USER_ID).await?.parse()?)
+}
+
+/// Place a single bet into a new ticket and submit it
+///
+/// Returns
+/// ---
+/// - Ok(id) of the submitted ticket
+/// - Err(_) if the bet could not be placed or the ticket submitted
+async fn bet_on(
+    tickets: &PgBetAndTicketRepo,
+    match_id: i32,
+    team_id: i32,
+    market: BetMarket,
+    price: f64,
+) -> anyhow::Result<i32> {
+    let ticket = match tickets.get_user_current_ticket(USER_ID).await? {
+        ObtainedTicket::NoTicketFound(ticket)
+        | ObtainedTicket::NewAfterInvalid(ticket)
+        | ObtainedTicket::StillValid(ticket) => ticket,
+    };
+    let placed = tickets
+        .place_a_bet(
+            ticket.id,
+            CreateBet::new(match_id, ticket.id, team_id, market),
+            BettingLimits::default().max_selections,
+        )
+        .await;
+    if placed.is_err() {
+        for bet in tickets.get_bets(ticket.id).await? {
+            tickets.discard_a_bet(ticket.id, bet.id).await?;
+        }
+        placed?;
+    }
+    tickets.submit_ticket(ticket.id, price).await
+}
+
+/// A best-of-three match gets its maps finished one by one,
+/// deciding the map bets right away and the series score bets once the match ends
+pub async fn run(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let matches = PgMatchRepo::new(pool);
+    let maps = PgMatchMapRepo::new(pool);
+    let tickets = PgBetAndTicketRepo::new(pool);
+    let submitted = PgSubmittedBetAndTicketRepo::new(pool);
+    let users = PgUserRepo::new(pool);
+
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Series", unique_run(), &["one", "two"]).await?;
+    let (one, two) = (team_ids[0], team_ids[1]);
+
+    let mut create_match = CreateGameMatch::new(
+        game_id,
+        one,
+        two,
+        "1.8",
+        "1.8",
+        Utc::now() + Duration::hours(1),
+        "series test",
+    );
+    create_match.series_format = SeriesFormat::Bo3.to_string();
+    let match_id = matches.create(create_match).await?;
+    matches
+        .create_event(match_id, GameMatchEventType::Live)
+        .await?;
+
+    let (game_match, _) = matches.get_show_info(match_id).await?;
+    assert_eq!(game_match.extract_series_format()?, SeriesFormat::Bo3);
+    assert_eq!(
+        BetMarket::MapWinner { map_number: 1 }.ratio(&game_match, one)?,
+        1.8
+    );
+    assert_eq!(
+        BetMarket::SeriesScore { won: 2, lost: 0 }.ratio(&game_match, one)?,
+        3.6
+    );
+    println!("market ratios derived from the match ratios");
+
+    for (team_id, market) in [
+        (one, BetMarket::MapWinner { map_number: 4 }),
+        (one, BetMarket::SeriesScore { won: 2, lost: 2 }),
+        (one, BetMarket::SeriesScore { won: 3, lost: 0 }),
+        (two + 1000, BetMarket::MapWinner { map_number: 1 }),
+    ] {
+        assert!(bet_on(&tickets, match_id, team_id, market, 1.0)
+            .await
+            .is_err());
+    }
+    println!("bets on markets missing from the match rejected");
+
+    users.add_balance(USER_ID, 30.0).await?;
+    let before = balance(&users).await?;
+    let map_one_ticket = bet_on(
+        &tickets,
+        match_id,
+        one,
+        BetMarket::MapWinner { map_number: 1 },
+        10.0,
+    )
+    .await?;
+    let series_ticket = bet_on(
+        &tickets,
+        match_id,
+        one,
+        BetMarket::SeriesScore { won: 2, lost: 0 },
+        10.0,
+    )
+    .await?;
+    let map_three_ticket = bet_on(
+        &tickets,
+        match_id,
+        two,
+        BetMarket::MapWinner { map_number: 3 },
+        10.0,
+    )
+    .await?;
+    assert_eq!(balance(&users).await?, before - 30.0);
+
+    // only the next map of the match can be finished, by a team playing the match
+    assert!(maps
+        .finish(CreateMatchMap::new(match_id, 2, "Mirage", one, 16, 10))
+        .await
+        .is_err());
+    assert!(maps
+        .finish(CreateMatchMap::new(
+            match_id,
+            1,
+            "Inferno",
+            two + 1000,
+            16,
+            10
+        ))
+        .await
+        .is_err());
+    maps.finish(CreateMatchMap::new(match_id, 1, "Inferno", one, 16, 10))
+        .await?;
+    assert_eq!(submitted.get_bets(map_one_ticket).await?[0].won, Some(true));
+    assert_eq!(submitted.get_bets(series_ticket).await?[0].won, None);
+    submitted.settle(USER_ID).await?;
+    assert_eq!(balance(&users).await?, before - 30.0 + 18.0);
+    println!("map bet decided once its map finished");
+
+    // the first map is over, the second team cannot win without losing a map anymore
+    assert!(bet_on(
+        &tickets,
+        match_id,
+        one,
+        BetMarket::MapWinner { map_number: 1 },
+        1.0
+    )
+    .await
+    .is_err());
+    assert!(bet_on(
+        &tickets,
+        match_id,
+        two,
+        BetMarket::SeriesScore { won: 2, lost: 0 },
+        1.0
+    )
+    .await
+    .is_err());
+    assert!(matches
+        .create_event(match_id, GameMatchEventType::Ended(one))
+        .await
+        .is_err());
+
+    maps.finish(CreateMatchMap::new(match_id, 2, "Mirage", one, 16, 14))
+        .await?;
+    assert!(maps
+        .finish(CreateMatchMap::new(match_id, 3, "Nuke", two, 16, 3))
+        .await
+        .is_err());
+    assert_eq!(maps.get_maps(match_id).await?.len(), 2);
+    assert!(matches
+        .create_event(match_id, GameMatchEventType::Ended(two))
+        .await
+        .is_err());
+    matches
+        .create_event(match_id, GameMatchEventType::Ended(one))
+        .await?;
+
+    assert_eq!(submitted.get_bets(series_ticket).await?[0].won, Some(true));
+    let voided = &submitted.get_bets(map_three_ticket).await?[0];
+    assert_eq!((voided.won, voided.bet_ratio.as_str()), (Some(true), "1"));
+    submitted.settle(USER_ID).await?;
+    assert_eq!(
+        submitted.get_ticket(map_three_ticket).await?.won,
+        Some(true)
+    );
+    assert_eq!(balance(&users).await?, before - 30.0 + 18.0 + 36.0 + 10.0);
+    println!("series score bet settled and the bet on the unplayed map voided");
+
+    Ok(())
+}
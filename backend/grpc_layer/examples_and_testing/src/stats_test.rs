@@ -0,0 +1,128 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game::{GameRepo, PgGameRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+        team::{PgTeamRepo, TeamRepo},
+    },
+    db_models::{
+        game::CreateGame, game_match::CreateGameMatch, game_match_event::GameMatchEventType,
+    },
+};
+use grpc_layer::team_stats::{head_to_head, team_stats, TeamStatsCache};
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// Create a match of the teams and end it right away
+///
+/// Returns
+/// ---
+/// - Ok(id) of the ended match
+/// - Err(_) if the match could not be created or ended
+async fn play(
+    matches: &PgMatchRepo,
+    game_id: i32,
+    team_one_id: i32,
+    team_two_id: i32,
+    winner_id: i32,
+) -> anyhow::Result<i32> {
+    let match_id = matches
+        .create(CreateGameMatch::new(
+            game_id,
+            team_one_id,
+            team_two_id,
+            "1.8",
+            "1.8",
+            Utc::now() + Duration::hours(1),
+            "stats test",
+        ))
+        .await?;
+    matches
+        .create_event(match_id, GameMatchEventType::Live)
+        .await?;
+    matches
+        .create_event(match_id, GameMatchEventType::Ended(winner_id))
+        .await?;
+    Ok(match_id)
+}
+
+/// Teams play a few matches in two games,
+/// their form, win rates and head-to-head record are computed from the cached results
+pub async fn run(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let games = PgGameRepo::new(pool);
+    let teams = PgTeamRepo::new(pool);
+    let matches = PgMatchRepo::new(pool);
+    let cache = TeamStatsCache::new(pool);
+
+    let run = unique_run();
+    let (first_game, team_ids) =
+        create_game_with_teams(pool, "Stats", run, &["one", "two", "three"]).await?;
+    let (one, two, three) = (team_ids[0], team_ids[1], team_ids[2]);
+    let second_game = games
+        .create(CreateGame::new(
+            &format!("Other stats game {}", run),
+            "",
+            "",
+        ))
+        .await?;
+    for team_id in [one, two] {
+        teams.add_to_game(team_id, second_game).await?;
+    }
+
+    let first = play(&matches, first_game, one, two, one).await?;
+    let second = play(&matches, first_game, three, one, three).await?;
+    let third = play(&matches, second_game, two, one, two).await?;
+    // not ended, so it does not count
+    matches
+        .create(CreateGameMatch::new(
+            first_game,
+            one,
+            two,
+            "1.8",
+            "1.8",
+            Utc::now() + Duration::hours(1),
+            "stats test",
+        ))
+        .await?;
+
+    let results = cache.results(one).await?;
+    let played: Vec<i32> = results.iter().map(|result| result.match_id).collect();
+    assert_eq!(played, vec![third, second, first]);
+    let stats = team_stats(one, &results, 2);
+    assert_eq!((stats.played, stats.wins), (3, 1));
+    assert_eq!(stats.form.len(), 2);
+    assert!(!stats.form[0].won && stats.form[0].opponent_id == two);
+    let rates: Vec<(i32, i32, f32)> = stats
+        .games
+        .iter()
+        .map(|record| (record.game_id, record.played, record.win_rate()))
+        .collect();
+    assert_eq!(rates, vec![(first_game, 2, 0.5), (second_game, 1, 0.0)]);
+    println!("form and win rates per game computed");
+
+    let record = head_to_head(one, two, &results);
+    assert_eq!((record.team_one_wins, record.team_two_wins), (1, 1));
+    assert_eq!(record.matches.len(), 2);
+    assert!(head_to_head(two, three, &cache.results(two).await?)
+        .matches
+        .is_empty());
+    println!("head-to-head record computed");
+
+    // the cached results are kept until the match ending is announced
+    let fourth = play(&matches, second_game, one, two, one).await?;
+    assert_eq!(cache.results(one).await?.len(), 3);
+    cache.invalidate_match(fourth).await?;
+    assert_eq!(cache.results(one).await?[0].match_id, fourth);
+    assert_eq!(
+        head_to_head(two, one, &cache.results(two).await?).team_two_wins,
+        2
+    );
+    assert_eq!(cache.results(three).await?.len(), 1);
+    println!("cached results invalidated once a match of the team ended");
+
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game_match::{MatchRepo, PgMatchRepo},
+        match_timeline::{PgTimelineRepo, TimelineRepo},
+        outright::{OutrightRepo, PgOutrightRepo},
+        repo::Repo,
+        tournament::{PgTournamentRepo, TournamentRepo},
+        user::{PgUserRepo, UserRepo},
+    },
+    db_models::{
+        game_match::CreateGameMatch,
+        game_match_event::GameMatchEventType,
+        match_timeline_event::{Score, TimelineEventPayload},
+        outright_selection::CreateOutrightSelection,
+        stage::{CreateStage, StageType},
+        tournament::CreateTournament,
+    },
+};
+use grpc_layer::standings;
+
+use crate::fixtures::{create_game_with_teams, unique_run};
+
+/// ID of the administrator account, used as the bettor
+const USER_ID: i32 = 0;
+
+async fn balance(users: &PgUserRepo) -> anyhow::Result<f64> {
+    Ok(users.get_balance(USER_ID).await?.parse()?)
+}
+
+/// Play the match to its end, recording its final score in maps
+async fn play(
+    matches: &PgMatchRepo,
+    timeline: &PgTimelineRepo,
+    match_id: i32,
+    winner_id: i32,
+    score: (i32, i32),
+) -> anyhow::Result<()> {
+    matches
+        .create_event(match_id, GameMatchEventType::Live)
+        .await?;
+    timeline
+        .append(
+            match_id,
+            &TimelineEventPayload::ScoreChanged(Score {
+                team_one: score.0,
+                team_two: score.1,
+            }),
+        )
+        .await?;
+    matches
+        .create_event(match_id, GameMatchEventType::Ended(winner_id))
+        .await?;
+    Ok(())
+}
+
+/// A tournament gets its group stage standings computed from the ended matches
+/// and its outright bets settled once its winner is known
+pub async fn run(pool: &Arc<PgPool>) -> anyhow::Result<()> {
+    let matches = PgMatchRepo::new(pool);
+    let timeline = PgTimelineRepo::new(pool);
+    let tournaments = PgTournamentRepo::new(pool);
+    let outrights = PgOutrightRepo::new(pool);
+    let users = PgUserRepo::new(pool);
+
+    let run = unique_run();
+    let (game_id, team_ids) =
+        create_game_with_teams(pool, "Tournament", run, &["one", "two", "three"]).await?;
+    let (one, two, three) = (team_ids[0], team_ids[1], team_ids[2]);
+
+    let tournament_id = tournaments
+        .create(CreateTournament::new(
+            game_id,
+            &format!("Cup {}", run),
+            "",
+            Utc::now(),
+            Utc::now() + Duration::days(7),
+        )?)
+        .await?;
+    let group_id = tournaments
+        .create_stage(CreateStage::new(
+            tournament_id,
+            "Group A",
+            StageType::Group,
+            1,
+        ))
+        .await?;
+    let playoffs_id = tournaments
+        .create_stage(CreateStage::new(
+            tournament_id,
+            "Final",
+            StageType::Playoffs,
+            2,
+        ))
+        .await?;
+
+    let mut match_ids = Vec::new();
+    for (team_one, team_two) in [(one, two), (two, three), (three, one), (one, two)] {
+        match_ids.push(
+            matches
+                .create(CreateGameMatch::new(
+                    game_id,
+                    team_one,
+                    team_two,
+                    "1.8",
+                    "1.8",
+                    Utc::now() + Duration::hours(1),
+                    "tournament test",
+                ))
+                .await?,
+        );
+    }
+    for match_id in &match_ids[..3] {
+        tournaments.add_match(group_id, *match_id).await?;
+    }
+    tournaments.add_match(playoffs_id, match_ids[3]).await?;
+    assert!(tournaments
+        .add_match(playoffs_id, match_ids[0])
+        .await
+        .is_err());
+    let stage_and_match_ids = tournaments.get_matches(tournament_id).await?;
+    assert_eq!(stage_and_match_ids.len(), 4);
+    assert_eq!(stage_and_match_ids[3], (playoffs_id, match_ids[3]));
+    println!("stages filled with matches");
+
+    // one beats two 2:0, two beats three 2:1, three is yet to play one
+    play(&matches, &timeline, match_ids[0], one, (2, 0)).await?;
+    play(&matches, &timeline, match_ids[1], two, (2, 1)).await?;
+    let results = tournaments.get_stage_results(group_id).await?;
+    let scores = timeline.get_scores(&match_ids).await?;
+    let table = standings::compute(&results, &scores)?;
+    let ranked: Vec<(i32, i32, i32, i32, i32)> = table
+        .iter()
+        .map(|standing| {
+            (
+                standing.team_id,
+                standing.played,
+                standing.wins,
+                standing.score_for,
+                standing.score_against,
+            )
+        })
+        .collect();
+    assert_eq!(
+        ranked,
+        vec![(one, 1, 1, 2, 0), (two, 2, 1, 2, 3), (three, 1, 0, 1, 2)]
+    );
+    println!("group standings computed");
+
+    outrights
+        .set_selection(CreateOutrightSelection::new(tournament_id, one, 2.0)?)
+        .await?;
+    outrights
+        .set_selection(CreateOutrightSelection::new(tournament_id, two, 4.0)?)
+        .await?;
+    let favourites: Vec<i32> = outrights
+        .get_selections(tournament_id)
+        .await?
+        .iter()
+        .map(|selection| selection.team_id)
+        .collect();
+    assert_eq!(favourites, vec![one, two]);
+    assert!(outrights
+        .place_bet(USER_ID, tournament_id, three, 1.0)
+        .await
+        .is_err());
+
+    users.add_balance(USER_ID, 30.0).await?;
+    let before = balance(&users).await?;
+    outrights
+        .place_bet(USER_ID, tournament_id, one, 10.0)
+        .await?;
+    outrights
+        .place_bet(USER_ID, tournament_id, two, 20.0)
+        .await?;
+    assert_eq!(balance(&users).await?, before - 30.0);
+    assert!(outrights.settle(tournament_id).await.is_err());
+    assert!(tournaments.delete(tournament_id).await.is_err());
+    println!("outright bets placed");
+
+    assert!(tournaments.end(tournament_id, three + 1000).await.is_err());
+    let ended = tournaments.end(tournament_id, one).await?;
+    assert_eq!(ended.winner_id, Some(one));
+    assert!(tournaments.end(tournament_id, two).await.is_err());
+    assert!(outrights
+        .place_bet(USER_ID, tournament_id, one, 1.0)
+        .await
+        .is_err());
+
+    let settled = outrights.settle(tournament_id).await?;
+    let mut outcomes: Vec<(i32, Option<bool>)> =
+        settled.iter().map(|bet| (bet.team_id, bet.won)).collect();
+    outcomes.sort();
+    assert_eq!(outcomes, vec![(one, Some(true)), (two, Some(false))]);
+    assert_eq!(balance(&users).await?, before - 30.0 + 20.0);
+    assert!(outrights.settle(tournament_id).await?.is_empty());
+    assert_eq!(balance(&users).await?, before - 10.0);
+    println!("outright bets settled once");
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use bytes::BytesMut;
+use prost::Message;
+
+use crate::envelope::{envelope::Payload, Envelope, ResyncRequired};
+
+use ws_layer::{Clients, Msg, Topic};
+
+/// Version of the websocket envelope, raised on incompatible changes of the payloads
+pub const ENVELOPE_VERSION: u32 = 1;
+
+/// Build the websocket message wrapping the payload into an envelope
+/// with the position of the event it was derived from
+fn seal(sequence: u64, payload: Payload) -> Msg {
+    let envelope = Envelope {
+        version: ENVELOPE_VERSION,
+        sequence,
+        payload: Some(payload),
+    };
+    let mut buf = BytesMut::with_capacity(64);
+    let _ = envelope.encode(&mut buf);
+    Msg::binary(buf.freeze().to_vec())
+}
+
+/// Build the message telling a resuming websocket client to refetch its state
+///
+/// Params
+/// ---
+/// - sequence: highest sequence number published, the client continues from it
+pub fn resync_required(sequence: u64) -> Msg {
+    seal(sequence, Payload::ResyncRequired(ResyncRequired {}))
+}
+
+/// Wrap the payload into a sequenced envelope and send it
+/// to all websocket clients subscribed to at least one of the topics
+///
+/// Params
+/// ---
+/// - clients: connected websocket clients
+/// - topics: topics the payload belongs to
+/// - sequence: position of the event in the outbox, 0 for the payloads
+///   which are not replayed to the resuming clients
+/// - payload: the event we wish to send
+pub async fn broadcast(clients: &Clients, topics: &[Topic], sequence: u64, payload: Payload) {
+    ws_layer::publish(clients, topics, sequence, seal(sequence, payload)).await
+}
+
+/// Wrap the payload into a sequenced envelope and send it
+/// to all open connections of the user
+///
+/// Params
+/// ---
+/// - clients: connected websocket clients
+/// - user_id: ID of the user the payload is meant for
+/// - sequence: position of the event in the outbox, see `broadcast`
+/// - payload: the event we wish to send
+pub async fn send_private(clients: &Clients, user_id: i32, sequence: u64, payload: Payload) {
+    ws_layer::send_to_user(clients, user_id, sequence, seal(sequence, payload)).await
+}
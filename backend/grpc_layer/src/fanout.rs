@@ -0,0 +1,182 @@
+use chrono::{Duration as ChronoDuration, Utc};
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        event_outbox::{OutboxRepo, PgOutboxRepo},
+        repo::Repo,
+    },
+    listener::{self, OUTBOX_CHANNEL},
+};
+use event_bus::{DomainEvent, EventBus};
+
+/// Maximum number of events read from the outbox at once
+const BATCH_SIZE: i64 = 256;
+/// How often the outbox is read even without a notification,
+/// in case a notification gets lost while the listener reconnects
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a missing event is waited for before the later ones are delivered,
+/// events are numbered on insert, so a lower number may be committed later
+pub const GAP_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long a skipped event is still looked for, the numbers of the events
+/// rolled back are never committed
+const SKIPPED_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// Delay before listening again after the listening connection fails
+const LISTEN_RETRY_DELAY: Duration = Duration::from_secs(5);
+/// How often the delivered events are deleted from the outbox
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// How long the events are kept in the outbox
+const RETENTION_HOURS: i64 = 24;
+
+/// Reads the outbox in the order of the events, so every event is delivered exactly once
+/// by this instance. An event committed after the later ones have been waited for
+/// is delivered once it shows up, out of order.
+struct OutboxReader {
+    outbox: PgOutboxRepo,
+    fanout: EventBus,
+    last_id: i32,
+    gap_since: Option<Instant>,
+    /// IDs below `last_id` which have not been committed yet, with the time they were skipped
+    skipped: BTreeMap<i32, Instant>,
+}
+
+impl OutboxReader {
+    /// Start reading after the latest stored event,
+    /// older events have been delivered before this instance started.
+    /// The events numbered earlier may still be committed, so the gaps among the IDs
+    /// of the recent events are looked for like the events skipped while reading.
+    async fn new(pool: &Arc<PgPool>, fanout: EventBus) -> anyhow::Result<OutboxReader> {
+        let outbox = PgOutboxRepo::new(pool);
+        let created_before = (Utc::now() - ChronoDuration::from_std(SKIPPED_TIMEOUT)?).to_string();
+        let mut last_id = outbox.get_last_id_before(&created_before).await?;
+        let now = Instant::now();
+        let mut skipped = BTreeMap::new();
+        for id in outbox.get_ids_after(last_id).await? {
+            // without an older event, nothing before the first recent one is looked for
+            if last_id > 0 {
+                skipped.extend((last_id + 1..id).map(|skipped_id| (skipped_id, now)));
+            }
+            last_id = id;
+        }
+
+        Ok(OutboxReader {
+            outbox,
+            fanout,
+            last_id,
+            gap_since: None,
+            skipped,
+        })
+    }
+
+    /// Publish the events stored since the last read on the fan-out bus
+    async fn deliver_new(&mut self) -> anyhow::Result<()> {
+        self.deliver_skipped().await?;
+        loop {
+            let events = self.outbox.get_after(self.last_id, BATCH_SIZE).await?;
+            let count = events.len();
+
+            for event in events {
+                if event.id != self.last_id + 1 {
+                    let gap_since = *self.gap_since.get_or_insert_with(Instant::now);
+                    if gap_since.elapsed() < GAP_TIMEOUT {
+                        return Ok(());
+                    }
+                    let now = Instant::now();
+                    self.skipped
+                        .extend((self.last_id + 1..event.id).map(|id| (id, now)));
+                }
+                self.gap_since = None;
+                self.last_id = event.id;
+                self.publish(event.id, &event.payload);
+            }
+
+            if count < BATCH_SIZE as usize {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Publish the skipped events committed since, forgetting the ones waited for too long
+    async fn deliver_skipped(&mut self) -> anyhow::Result<()> {
+        self.skipped
+            .retain(|_, skipped_at| skipped_at.elapsed() < SKIPPED_TIMEOUT);
+        if self.skipped.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<i32> = self.skipped.keys().copied().collect();
+        for event in self.outbox.get_by_ids(&ids).await? {
+            self.skipped.remove(&event.id);
+            self.publish(event.id, &event.payload);
+        }
+        Ok(())
+    }
+
+    /// Publish the stored event on the fan-out bus, at its position in the outbox
+    fn publish(&self, id: i32, payload: &str) {
+        match DomainEvent::from_json(payload) {
+            Ok(domain_event) => self.fanout.publish_at(id as u64, domain_event),
+            Err(err) => println!("skipping invalid outbox event {}: {}", id, err),
+        }
+    }
+
+    /// Delete the events older than the retention period
+    async fn prune(&self) -> anyhow::Result<()> {
+        let created_before = (Utc::now() - ChronoDuration::hours(RETENTION_HOURS)).to_string();
+        self.outbox.delete_created_before(&created_before).await?;
+        Ok(())
+    }
+}
+
+/// Deliver the events stored in the outbox by all instances to the subscribers
+/// of the fan-out bus. The instances are woken up by Postgres notifications.
+///
+/// Params
+/// ---
+/// - pool: connection pool used for reading the outbox
+/// - database_url: connection string used for listening to the notifications
+/// - fanout: bus delivering the events to the local subscribers
+pub async fn run_fanout(
+    pool: &Arc<PgPool>,
+    database_url: &str,
+    fanout: EventBus,
+) -> anyhow::Result<()> {
+    let mut reader = OutboxReader::new(pool, fanout).await?;
+
+    let (wake_up, mut wake_ups) = mpsc::channel(1);
+    let database_url = String::from(database_url);
+    tokio::spawn(async move {
+        loop {
+            match listener::listen(&database_url, OUTBOX_CHANNEL, wake_up.clone()).await {
+                Ok(()) => break,
+                Err(err) => println!("outbox listener failed: {}", err),
+            }
+            tokio::time::sleep(LISTEN_RETRY_DELAY).await;
+        }
+    });
+
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+    let mut prune = tokio::time::interval(PRUNE_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = wake_ups.recv() => {}
+            _ = poll.tick() => {}
+            _ = prune.tick() => {
+                if let Err(err) = reader.prune().await {
+                    println!("failed to prune the outbox: {}", err);
+                }
+                continue;
+            }
+        }
+
+        if let Err(err) = reader.deliver_new().await {
+            println!("failed to read the outbox: {}", err);
+        }
+    }
+}
@@ -1,6 +1,9 @@
 pub mod bet;
+pub mod fixture_import;
 pub mod game;
 pub mod game_match;
+pub mod media;
 pub mod team;
 pub mod ticket;
+pub mod tournament;
 pub mod user;
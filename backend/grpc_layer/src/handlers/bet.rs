@@ -1,11 +1,11 @@
-use bytes::BytesMut;
-use prost::Message;
-use std::convert::*;
 use std::sync::Arc;
-use tonic::{Code, Request, Response, Status};
+use tonic::{Request, Response, Status};
 
 use crate::bet::bet_service_server::BetService;
-use crate::bet::{Bet, CreateBetReply, CreateBetRequest, DeleteBetReply, DeleteBetRequest};
+use crate::bet::{CreateBetReply, CreateBetRequest, DeleteBetReply, DeleteBetRequest};
+use crate::config::BettingLimits;
+use crate::publisher::Publisher;
+use crate::status::error_status;
 
 use database_layer::{
     connection::PgPool,
@@ -13,20 +13,22 @@ use database_layer::{
         bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
         repo::Repo,
     },
-    db_models::bet::CreateBet,
+    db_models::{bet::CreateBet, bet_market::BetMarket},
 };
-use ws_layer::Clients;
+use event_bus::{events::BetPlaced, DomainEvent, EventBus};
 
 pub struct MyBetService {
     repo: PgBetAndTicketRepo,
-    ws_clients: Clients,
+    publisher: Publisher,
+    limits: BettingLimits,
 }
 
 impl MyBetService {
-    pub fn new(pool: &Arc<PgPool>, ws_clients: Clients) -> MyBetService {
+    pub fn new(pool: &Arc<PgPool>, bus: EventBus, limits: BettingLimits) -> MyBetService {
         MyBetService {
             repo: PgBetAndTicketRepo::new(pool),
-            ws_clients: ws_clients,
+            publisher: Publisher::new(pool, bus),
+            limits,
         }
     }
 }
@@ -38,23 +40,35 @@ impl BetService for MyBetService {
         request: Request<CreateBetRequest>,
     ) -> Result<Response<CreateBetReply>, Status> {
         let request = request.into_inner();
-        let create_bet = CreateBet::new(request.match_id, request.ticket_id, request.team_id);
+        let market = request
+            .market
+            .as_ref()
+            .map(BetMarket::from)
+            .unwrap_or(BetMarket::MatchWinner);
+        let create_bet =
+            CreateBet::new(request.match_id, request.ticket_id, request.team_id, market);
 
-        match self.repo.place_a_bet(request.ticket_id, create_bet).await {
-            Ok(bet) => {
-                let bet = Bet::from(&bet);
-
-                let mut buf = BytesMut::with_capacity(64);
-                let _ = bet.encode(&mut buf);
-                for client in self.ws_clients.lock().await.values() {
-                    if let Some(sender) = &client.sender {
-                        let _ =
-                            sender.send(Ok(ws_layer::Msg::binary(buf.clone().freeze().to_vec())));
-                    }
-                }
-                Ok(Response::new(CreateBetReply { id: bet.id }))
-            }
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+        match self
+            .publisher
+            .commit(|connection, events| {
+                let bet = PgBetAndTicketRepo::place_a_bet_in(
+                    connection,
+                    request.ticket_id,
+                    create_bet,
+                    self.limits.max_selections,
+                )?;
+                events.push(DomainEvent::BetPlaced(BetPlaced {
+                    bet_id: bet.id,
+                    ticket_id: bet.ticket_id,
+                    match_id: bet.game_match_id,
+                    team_id: bet.team_id,
+                }));
+                Ok(bet.id)
+            })
+            .await
+        {
+            Ok(bet_id) => Ok(Response::new(CreateBetReply { id: bet_id })),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -66,7 +80,7 @@ impl BetService for MyBetService {
 
         match self.repo.discard_a_bet(request.ticket_id, request.id).await {
             Ok(()) => Ok(Response::new(DeleteBetReply {})),
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Err(err) => Err(error_status(err)),
         }
     }
 }
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::fixture_import::fixture_import_service_server::FixtureImportService;
+use crate::fixture_import::{ImportFixturesReply, ImportFixturesRequest, ImportFormat};
+use crate::importer::{self, FixtureImporter};
+use crate::status::error_status;
+
+use database_layer::connection::PgPool;
+use event_bus::EventBus;
+
+pub struct MyFixtureImportService {
+    importer: FixtureImporter,
+}
+
+impl MyFixtureImportService {
+    pub fn new(pool: &Arc<PgPool>, bus: EventBus) -> MyFixtureImportService {
+        MyFixtureImportService {
+            importer: FixtureImporter::new(pool, bus),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl FixtureImportService for MyFixtureImportService {
+    async fn import_fixtures(
+        &self,
+        request: Request<ImportFixturesRequest>,
+    ) -> Result<Response<ImportFixturesReply>, Status> {
+        let request = request.into_inner();
+        let format = match ImportFormat::from_i32(request.format) {
+            Some(ImportFormat::Csv) => importer::ImportFormat::Csv,
+            _ => importer::ImportFormat::Json,
+        };
+
+        match self
+            .importer
+            .import_content(&request.content, format, request.dry_run)
+            .await
+        {
+            Ok(report) => Ok(Response::new(ImportFixturesReply::from(&report))),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+}
@@ -1,9 +1,15 @@
 use std::convert::*;
 use std::sync::Arc;
-use tonic::{Code, Request, Response, Status};
+use tonic::{Request, Response, Status};
 
 use crate::game::game_service_server::GameService;
-use crate::game::{CreateGameReply, CreateGameRequest, Game, ListGamesReply, ListGamesRequest};
+use crate::game::{
+    ArchiveGameReply, ArchiveGameRequest, CreateGameReply, CreateGameRequest, DeleteGameReply,
+    DeleteGameRequest, Game, GetGameReply, GetGameRequest, ListGamesReply, ListGamesRequest,
+    UpdateGameReply, UpdateGameRequest,
+};
+use crate::media_store::MediaStore;
+use crate::status::error_status;
 
 use database_layer::{
     connection::PgPool,
@@ -12,31 +18,83 @@ use database_layer::{
         repo::Repo,
     },
     db_models::game::CreateGame,
+    error::DomainError,
 };
 
 pub struct MyGameService {
     repo: PgGameRepo,
+    media: Arc<MediaStore>,
 }
 
 impl MyGameService {
-    pub fn new(pool: &Arc<PgPool>) -> MyGameService {
+    pub fn new(pool: &Arc<PgPool>, media: Arc<MediaStore>) -> MyGameService {
         MyGameService {
             repo: PgGameRepo::new(pool),
+            media,
         }
     }
+
+    /// Create a game, its logo is the uploaded image when set
+    async fn create_game(
+        &self,
+        request: &CreateGameRequest,
+        mut create_game: CreateGame,
+    ) -> anyhow::Result<i32> {
+        create_game.logo = self
+            .media
+            .resolve_url(&request.logo_url, request.logo_asset_id)
+            .await?;
+        let game_id = self.repo.create(create_game).await?;
+        if request.logo_asset_id.is_some() {
+            self.repo
+                .set_logo_asset(game_id, request.logo_asset_id)
+                .await?;
+        }
+        Ok(game_id)
+    }
+
+    /// Edit a game, its logo is the uploaded image when set,
+    /// otherwise the reference to the previously uploaded logo is dropped
+    async fn update_game(
+        &self,
+        request: &UpdateGameRequest,
+        mut edited_game: CreateGame,
+    ) -> anyhow::Result<()> {
+        // fails for a missing game
+        self.repo.get(request.id).await?;
+        edited_game.logo = self
+            .media
+            .resolve_url(&request.logo_url, request.logo_asset_id)
+            .await?;
+        self.repo.edit(request.id, edited_game).await?;
+        self.repo
+            .set_logo_asset(request.id, request.logo_asset_id)
+            .await
+    }
+}
+
+/// Check the expected duration of a match of the game, if set
+fn validate_duration(expected_duration_minutes: Option<i32>) -> Result<(), DomainError> {
+    match expected_duration_minutes {
+        Some(minutes) if minutes <= 0 => Err(DomainError::validation(
+            "The expected duration of the game has to be positive",
+        )),
+        _ => Ok(()),
+    }
 }
 
 #[tonic::async_trait]
 impl GameService for MyGameService {
     async fn list_games(
         &self,
-        _: Request<ListGamesRequest>,
+        request: Request<ListGamesRequest>,
     ) -> Result<Response<ListGamesReply>, Status> {
-        match self.repo.get_all().await {
-            Ok(game_infos) => Ok(Response::new(ListGamesReply {
-                games: game_infos.iter().map(|game| Game::from(game)).collect(),
+        let request = request.into_inner();
+        match self.repo.get_all(request.include_archived).await {
+            Ok(games) => Ok(Response::new(ListGamesReply {
+                games: games.iter().map(Game::from).collect(),
             })),
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -45,11 +103,65 @@ impl GameService for MyGameService {
         request: Request<CreateGameRequest>,
     ) -> Result<Response<CreateGameReply>, Status> {
         let request = request.into_inner();
-        let create_game = CreateGame::new(&*request.name, "", &*request.logo_url);
+        validate_duration(request.expected_duration_minutes).map_err(error_status)?;
+        let mut create_game =
+            CreateGame::new(&request.name, &request.description, &request.logo_url);
+        create_game.expected_duration_minutes = request.expected_duration_minutes;
 
-        match self.repo.create(create_game).await {
+        match self.create_game(&request, create_game).await {
             Ok(game_id) => Ok(Response::new(CreateGameReply { id: game_id })),
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_game(
+        &self,
+        request: Request<GetGameRequest>,
+    ) -> Result<Response<GetGameReply>, Status> {
+        let request = request.into_inner();
+        match self.repo.get(request.id).await {
+            Ok(game) => Ok(Response::new(GetGameReply {
+                game: Some(Game::from(&game)),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn update_game(
+        &self,
+        request: Request<UpdateGameRequest>,
+    ) -> Result<Response<UpdateGameReply>, Status> {
+        let request = request.into_inner();
+        validate_duration(request.expected_duration_minutes).map_err(error_status)?;
+        let mut edited_game =
+            CreateGame::new(&request.name, &request.description, &request.logo_url);
+        edited_game.expected_duration_minutes = request.expected_duration_minutes;
+
+        match self.update_game(&request, edited_game).await {
+            Ok(()) => Ok(Response::new(UpdateGameReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn archive_game(
+        &self,
+        request: Request<ArchiveGameRequest>,
+    ) -> Result<Response<ArchiveGameReply>, Status> {
+        let request = request.into_inner();
+        match self.repo.set_archived(request.id, request.archived).await {
+            Ok(()) => Ok(Response::new(ArchiveGameReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn delete_game(
+        &self,
+        request: Request<DeleteGameRequest>,
+    ) -> Result<Response<DeleteGameReply>, Status> {
+        let request = request.into_inner();
+        match self.repo.delete(request.id).await {
+            Ok(_) => Ok(Response::new(DeleteGameReply {})),
+            Err(err) => Err(error_status(err)),
         }
     }
 }
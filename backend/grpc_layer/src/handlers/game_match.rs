@@ -1,40 +1,392 @@
-use bytes::BytesMut;
 use chrono::{DateTime, Utc};
-use prost::Message;
 use std::convert::*;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc,
+};
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Code, Request, Response, Status};
 
 use crate::game_match::match_service_server::MatchService;
 use crate::game_match::{
     ChangeStateReply, ChangeStateRequest, CreateGameEventReply, CreateGameEventRequest,
-    CreateMatchReply, CreateMatchRequest, GameEventType, ListMatchesReply, ListMatchesRequest,
-    Match,
+    CreateMatchReply, CreateMatchRequest, DeleteMatchReply, DeleteMatchRequest, GameEventType,
+    GetMatchTimelineReply, GetMatchTimelineRequest, ListMatchesReply, ListMatchesRequest,
+    MapResult, Match, MatchUpdate, MatchUpdateType, RecordTimelineEventReply,
+    RecordTimelineEventRequest, RescheduleMatchReply, RescheduleMatchRequest, Score, SeriesFormat,
+    StreamMatchUpdatesRequest, TimelineEvent, UpdateMatchReply, UpdateMatchRequest,
 };
+use crate::publisher::Publisher;
 use crate::repos::Repos;
+use crate::status::{error_status, invalid_argument};
 use crate::team::Team;
-use std::convert::*;
 
 use database_layer::{
     connection::PgPool,
-    db_access::{game_match::MatchRepo, team::TeamRepo},
+    db_access::{
+        bet_and_ticket::PgBetAndTicketRepo,
+        favourite::FavouriteRepo,
+        game_match::{MatchRepo, PgMatchRepo},
+        match_map::{MatchMapRepo, PgMatchMapRepo},
+        match_timeline::{PgTimelineRepo, TimelineRepo},
+        team::TeamRepo,
+    },
     db_models::{
-        game_match::CreateGameMatch,
+        game_match::{self, CreateGameMatch},
         game_match_event::{GameMatchEventFilter, GameMatchEventType},
+        match_map::{series_score, CreateMatchMap},
+        match_timeline_event::{self, TimelineEventPayload},
     },
+    error::DomainError,
 };
-use ws_layer::Clients;
+use event_bus::{
+    events::{MatchChange, MatchChanged, MatchPhase, TimelineEventRecorded},
+    DomainEvent, EventBus, Published,
+};
+
+/// Number of match updates waiting to be sent to a single streaming client
+const MATCH_UPDATES_BUFFER: usize = 64;
+
+/// Check the new ratio of a team, if set
+fn validate_ratio(ratio: Option<&str>) -> Result<(), DomainError> {
+    match ratio.map(str::parse::<f64>) {
+        Some(Ok(ratio)) if ratio > 0.0 => Ok(()),
+        None => Ok(()),
+        _ => Err(DomainError::validation(
+            "The ratio of a team has to be a positive number",
+        )),
+    }
+}
+
+/// Event letting the subscribers know the match has changed
+fn match_changed(match_id: i32, change: MatchChange) -> DomainEvent {
+    DomainEvent::MatchChanged(MatchChanged { match_id, change })
+}
+
+/// Event letting the subscribers know the timeline of the match has grown
+fn timeline_event_recorded(event_id: i32, match_id: i32) -> DomainEvent {
+    DomainEvent::TimelineEventRecorded(TimelineEventRecorded { event_id, match_id })
+}
 
 pub struct MyMatchService {
-    repos: Repos,
-    ws_clients: Clients,
+    repos: Arc<Repos>,
+    publisher: Publisher,
+    fanout: EventBus,
 }
 
 impl MyMatchService {
-    pub fn new(pool: &Arc<PgPool>, ws_clients: Clients) -> MyMatchService {
+    pub fn new(pool: &Arc<PgPool>, bus: EventBus, fanout: EventBus) -> MyMatchService {
         MyMatchService {
-            repos: Repos::new(pool),
-            ws_clients: ws_clients,
+            repos: Arc::new(Repos::new(pool)),
+            publisher: Publisher::new(pool, bus),
+            fanout,
+        }
+    }
+
+    /// Store the in-play event of a live match, the teams it refers to have to play the match.
+    /// A map result finishes the map of the series first, the score of the match
+    /// is then changed to the maps won by each team
+    ///
+    /// Params
+    /// ---
+    /// - match_id: ID of the match the event happened in
+    /// - payload: the event
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(id) with the ID of the stored event
+    /// - Err(_) if the match is not live, the teams do not play it, the map cannot be finished
+    ///   or an error occurred
+    async fn record_timeline_event(
+        &self,
+        match_id: i32,
+        payload: &TimelineEventPayload,
+    ) -> anyhow::Result<i32> {
+        let (game_match, newest_event) = self.repos.game_match.get_show_info(match_id).await?;
+        if newest_event.extract_event()? != GameMatchEventType::Live {
+            anyhow::bail!(DomainError::conflict(
+                "Timeline events can be recorded only for live matches!"
+            ));
+        }
+        let playing = [game_match.team_one_id, game_match.team_two_id];
+        if payload
+            .team_ids()
+            .iter()
+            .any(|team_id| !playing.contains(team_id))
+        {
+            anyhow::bail!(DomainError::validation(
+                "The team of the event does not play the match!"
+            ));
+        }
+
+        self.publisher
+            .commit(|connection, events| {
+                let finished_map = match payload {
+                    TimelineEventPayload::MapResult {
+                        map_number,
+                        map_name,
+                        winner_id,
+                        rounds,
+                    } => Some(PgMatchMapRepo::finish_in(
+                        connection,
+                        CreateMatchMap::new(
+                            match_id,
+                            *map_number,
+                            map_name,
+                            *winner_id,
+                            rounds.team_one,
+                            rounds.team_two,
+                        ),
+                    )?),
+                    _ => None,
+                };
+
+                let event = PgTimelineRepo::append_in(connection, match_id, payload)?;
+                events.push(timeline_event_recorded(event.id, match_id));
+
+                if let Some(finished_map) = finished_map {
+                    let maps = PgMatchMapRepo::get_maps_in(connection, match_id)?;
+                    let (team_one, team_two) = series_score(&game_match, &maps);
+                    let score = TimelineEventPayload::ScoreChanged(match_timeline_event::Score {
+                        team_one,
+                        team_two,
+                    });
+                    let score_event = PgTimelineRepo::append_in(connection, match_id, &score)?;
+                    events.push(timeline_event_recorded(score_event.id, match_id));
+                    events.push(match_changed(
+                        match_id,
+                        MatchChange::MapFinished {
+                            map_number: finished_map.map_number,
+                        },
+                    ));
+                }
+                Ok(event.id)
+            })
+            .await
+    }
+
+    /// Edit the teams, the ratios or the state of a match which has not started yet,
+    /// the unset properties are kept
+    ///
+    /// Params
+    /// ---
+    /// - request: the match and its new properties
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(()) if the match has been updated
+    /// - Err(_) if the match has started, the teams cannot play it or an error occurred
+    async fn update_match(&self, request: &UpdateMatchRequest) -> anyhow::Result<()> {
+        self.publisher
+            .commit(|connection, events| {
+                let game_match = PgMatchRepo::get_in(connection, request.match_id)?;
+
+                let team_one_id = request.team_one_id.unwrap_or(game_match.team_one_id);
+                let team_two_id = request.team_two_id.unwrap_or(game_match.team_two_id);
+                if (team_one_id, team_two_id) != (game_match.team_one_id, game_match.team_two_id) {
+                    PgMatchRepo::change_teams_in(
+                        connection,
+                        request.match_id,
+                        team_one_id,
+                        team_two_id,
+                    )?;
+                }
+
+                let match_update = game_match.to_update(
+                    request.team_one_ratio.as_deref(),
+                    request.team_two_ratio.as_deref(),
+                    None,
+                    request.state.as_deref(),
+                )?;
+                PgMatchRepo::update_in(connection, request.match_id, match_update)?;
+                events.push(match_changed(request.match_id, MatchChange::Updated));
+                Ok(())
+            })
+            .await
+    }
+
+    /// Move a match to another start. A live match is played again from the start,
+    /// its bets are discarded from the open tickets and its submitted bets voided,
+    /// as they were placed on the original course of the match
+    ///
+    /// Params
+    /// ---
+    /// - match_id: ID of the match
+    /// - supposed_start_at: the new start of the match
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(ticket_holders) with the number of the users whose bets have been discarded
+    /// - Err(_) if the match has ended, the start is in the past or an error occurred
+    async fn reschedule_match(
+        &self,
+        match_id: i32,
+        supposed_start_at: DateTime<Utc>,
+    ) -> anyhow::Result<usize> {
+        let followers = self.repos.favourite.get_match_followers(match_id).await?;
+        self.publisher
+            .commit(|connection, events| {
+                let voided_user_ids =
+                    PgMatchRepo::reschedule_in(connection, match_id, supposed_start_at)?;
+                let ticket_holders =
+                    PgBetAndTicketRepo::discard_bets_on_match_in(connection, match_id)?;
+                let mut affected_user_ids = followers;
+                affected_user_ids.extend(voided_user_ids);
+                affected_user_ids.extend(ticket_holders.iter());
+                affected_user_ids.sort_unstable();
+                affected_user_ids.dedup();
+                events.push(match_changed(
+                    match_id,
+                    MatchChange::Rescheduled { affected_user_ids },
+                ));
+                Ok(ticket_holders.len())
+            })
+            .await
+    }
+
+    /// Delete a match without submitted bets, which is not about to start,
+    /// the users who follow it or hold a bet on it in their open ticket are notified
+    async fn delete_match(&self, match_id: i32) -> anyhow::Result<()> {
+        // nobody can be found once the match is gone
+        let affected_user_ids = self.repos.get_affected_users(match_id).await?;
+        self.publisher
+            .commit(|connection, events| {
+                let deleted = PgMatchRepo::delete_in(connection, match_id)?;
+                events.push(match_changed(
+                    match_id,
+                    MatchChange::Deleted {
+                        game_id: deleted.game_id,
+                        affected_user_ids,
+                    },
+                ));
+                Ok(())
+            })
+            .await
+    }
+
+    /// Load the timeline of the match together with its current score
+    async fn get_timeline(&self, match_id: i32) -> anyhow::Result<GetMatchTimelineReply> {
+        let events = self.repos.timeline.get_timeline(match_id).await?;
+        let score = self
+            .repos
+            .timeline
+            .get_scores(&[match_id])
+            .await?
+            .get(&match_id)
+            .map(Score::from);
+
+        Ok(GetMatchTimelineReply {
+            events: events
+                .iter()
+                .map(TimelineEvent::try_from)
+                .collect::<anyhow::Result<Vec<TimelineEvent>>>()?,
+            score,
+        })
+    }
+}
+
+/// Matches whose updates the streaming client wishes to receive
+struct MatchUpdatesFilter {
+    game_ids: Vec<i32>,
+    match_ids: Vec<i32>,
+}
+
+impl MatchUpdatesFilter {
+    /// Check whether the updates of the match are wanted,
+    /// without any IDs the updates of all matches are
+    fn accepts(&self, game_match: &Match) -> bool {
+        (self.game_ids.is_empty() && self.match_ids.is_empty())
+            || self.game_ids.contains(&game_match.game_id)
+            || self.match_ids.contains(&game_match.id)
+    }
+
+    /// Check whether the updates of the match may be wanted,
+    /// so the matches surely not wanted are not loaded at all
+    fn may_accept(&self, match_id: i32) -> bool {
+        !self.game_ids.is_empty() || self.match_ids.is_empty() || self.match_ids.contains(&match_id)
+    }
+}
+
+/// Send the updates of the matches accepted by the filter to the streaming client,
+/// until it disconnects. A client falling too far behind gets an error
+/// and has to subscribe again.
+///
+/// Params
+/// ---
+/// - repos: repositories used for loading the changed matches
+/// - events: domain events published by all instances
+/// - filter: matches the client wishes to receive
+/// - sender: the stream of the client
+async fn stream_match_updates(
+    repos: Arc<Repos>,
+    mut events: broadcast::Receiver<Published>,
+    filter: MatchUpdatesFilter,
+    sender: mpsc::Sender<Result<MatchUpdate, Status>>,
+) {
+    loop {
+        let event = tokio::select! {
+            event = events.recv() => event,
+            _ = sender.closed() => return,
+        };
+        let match_changed = match event {
+            Ok(Published {
+                event: DomainEvent::MatchChanged(match_changed),
+                ..
+            }) => match_changed,
+            Ok(_) => continue,
+            Err(RecvError::Lagged(missed)) => {
+                let _ = sender
+                    .send(Err(Status::new(
+                        Code::DataLoss,
+                        format!("{} updates have been missed, subscribe again", missed),
+                    )))
+                    .await;
+                return;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        if !filter.may_accept(match_changed.match_id) {
+            continue;
+        }
+
+        // the deleted match cannot be loaded anymore
+        if let MatchChange::Deleted { game_id, .. } = match_changed.change {
+            let game_match = Match {
+                id: match_changed.match_id,
+                game_id,
+                ..Default::default()
+            };
+            if !filter.accepts(&game_match) {
+                continue;
+            }
+            let update = MatchUpdate {
+                update_type: MatchUpdateType::MatchRemoved.into(),
+                game_match: Some(game_match),
+            };
+            if sender.send(Ok(update)).await.is_err() {
+                return;
+            }
+            continue;
+        }
+
+        let update = match repos.get_filled_match(match_changed.match_id).await {
+            Ok(game_match) if filter.accepts(&game_match) => Ok(MatchUpdate {
+                update_type: match match_changed.change {
+                    MatchChange::RatiosChanged => MatchUpdateType::OddsChanged,
+                    _ => MatchUpdateType::MatchChanged,
+                }
+                .into(),
+                game_match: Some(game_match),
+            }),
+            Ok(_) => continue,
+            Err(err) => Err(error_status(err)),
+        };
+        if sender.send(update).await.is_err() {
+            return;
         }
     }
 }
@@ -46,7 +398,8 @@ impl MatchService for MyMatchService {
         request: Request<ListMatchesRequest>,
     ) -> Result<Response<ListMatchesReply>, Status> {
         let request = request.into_inner();
-        let game_match_event_type = match GameEventType::from_i32(request.game_event_type).unwrap()
+        let game_match_event_type = match GameEventType::from_i32(request.game_event_type)
+            .ok_or_else(|| invalid_argument("Unsupported game event type"))?
         {
             GameEventType::Upcoming => GameMatchEventFilter::Upcoming,
             GameEventType::Live => GameMatchEventFilter::Live,
@@ -59,16 +412,41 @@ impl MatchService for MyMatchService {
             .get_all_show_info(Some(game_match_event_type), None)
             .await
         {
-            Ok(game_matches) => {
+            Ok(mut game_matches) => {
+                if let Some(user_id) = request.favourites_of_user_id {
+                    let favourites = match self.repos.favourite.get_all(user_id).await {
+                        Ok(favourites) => Ok(favourites),
+                        Err(err) => Err(error_status(err)),
+                    }?;
+                    game_matches.retain(|(game_match, _)| {
+                        favourites
+                            .iter()
+                            .any(|favourite| favourite.covers(game_match))
+                    });
+                }
+
+                let match_ids: Vec<i32> = game_matches
+                    .iter()
+                    .map(|(game_match, _)| game_match.id)
+                    .collect();
+                let scores = match self.repos.timeline.get_scores(&match_ids).await {
+                    Ok(scores) => Ok(scores),
+                    Err(err) => Err(error_status(err)),
+                }?;
+                let maps = match self.repos.match_map.get_maps_of(&match_ids).await {
+                    Ok(maps) => Ok(maps),
+                    Err(err) => Err(error_status(err)),
+                }?;
+
                 let mut teams = HashMap::new();
                 for (game_match, _) in &game_matches {
-                    for team_id in vec![game_match.team_one_id, game_match.team_two_id] {
-                        if !teams.contains_key(&team_id) {
+                    for team_id in [game_match.team_one_id, game_match.team_two_id] {
+                        if let Entry::Vacant(entry) = teams.entry(team_id) {
                             let team = match self.repos.team.get(team_id).await {
                                 Ok(team) => Ok(Team::from(&team)),
-                                Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+                                Err(err) => Err(error_status(err)),
                             }?;
-                            teams.insert(team_id, team);
+                            entry.insert(team);
                         }
                     }
                 }
@@ -94,12 +472,17 @@ impl MatchService for MyMatchService {
                                 Some(teams.get(&game_match.team_two_id).unwrap().clone());
                             grpc_match.game_event_type = grpc_event_type.into();
                             grpc_match.winner_id = winner_id;
+                            grpc_match.score = scores.get(&game_match.id).map(Score::from);
+                            grpc_match.maps = maps
+                                .get(&game_match.id)
+                                .map(|maps| maps.iter().map(MapResult::from).collect())
+                                .unwrap_or_default();
                             grpc_match
                         })
                         .collect(),
                 }))
             }
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -108,34 +491,34 @@ impl MatchService for MyMatchService {
         request: Request<CreateMatchRequest>,
     ) -> Result<Response<CreateMatchReply>, Status> {
         let request = request.into_inner();
-        let create_match = CreateGameMatch::new(
+        let series_format = SeriesFormat::from_i32(request.series_format)
+            .ok_or_else(|| invalid_argument("Unsupported series format"))?;
+        let supposed_start_at = request
+            .supposed_start_at
+            .parse::<DateTime<Utc>>()
+            .map_err(|_| invalid_argument("The supposed start is not a valid date and time"))?;
+        let mut create_match = CreateGameMatch::new(
             request.game_id,
             request.team_one_id,
             request.team_two_id,
-            &*request.team_one_ratio,
-            &*request.team_two_ratio,
-            request.supposed_start_at.parse::<DateTime<Utc>>().unwrap(),
-            &*request.state,
+            &request.team_one_ratio,
+            &request.team_two_ratio,
+            supposed_start_at,
+            &request.state,
         );
+        create_match.series_format = game_match::SeriesFormat::from(series_format).to_string();
 
-        match self.repos.game_match.create(create_match).await {
-            Ok(match_id) => {
-                match self.repos.get_filled_match(match_id).await {
-                    Ok(game_match) => {
-                        let mut buf = BytesMut::with_capacity(64);
-                        let _ = game_match.encode(&mut buf);
-                        for client in self.ws_clients.lock().await.values() {
-                            if let Some(sender) = &client.sender {
-                                let _ = sender
-                                    .send(Ok(ws_layer::Msg::binary(buf.clone().freeze().to_vec())));
-                            }
-                        }
-                    }
-                    Err(err) => return Err(Status::new(Code::from_i32(13), err.to_string())),
-                }
-                Ok(Response::new(CreateMatchReply { id: match_id }))
-            }
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+        match self
+            .publisher
+            .commit(|connection, events| {
+                let match_id = PgMatchRepo::create_in(connection, create_match)?;
+                events.push(match_changed(match_id, MatchChange::Created));
+                Ok(match_id)
+            })
+            .await
+        {
+            Ok(match_id) => Ok(Response::new(CreateMatchReply { id: match_id })),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -145,35 +528,34 @@ impl MatchService for MyMatchService {
     ) -> Result<Response<CreateGameEventReply>, Status> {
         let request = request.into_inner();
         let winner_id = request.winner_id;
-        let game_match_event_type = match GameEventType::from_i32(request.game_event_type).unwrap()
+        let game_match_event_type = match GameEventType::from_i32(request.game_event_type)
+            .ok_or_else(|| invalid_argument("Unsupported game event type"))?
         {
             GameEventType::Upcoming => GameMatchEventType::Upcoming,
             GameEventType::Live => GameMatchEventType::Live,
-            GameEventType::Ended => GameMatchEventType::Ended(winner_id.unwrap()),
+            GameEventType::Ended => GameMatchEventType::Ended(
+                winner_id.ok_or_else(|| invalid_argument("An ended match needs a winner"))?,
+            ),
+        };
+        let phase = match game_match_event_type {
+            GameMatchEventType::Live => MatchPhase::Live,
+            GameMatchEventType::Ended(winner_id) => MatchPhase::Ended { winner_id },
+            _ => MatchPhase::Upcoming,
         };
         match self
-            .repos
-            .game_match
-            .create_event(request.match_id, game_match_event_type)
+            .publisher
+            .commit(|connection, events| {
+                PgMatchRepo::create_event_in(connection, request.match_id, game_match_event_type)?;
+                events.push(match_changed(
+                    request.match_id,
+                    MatchChange::PhaseChanged(phase),
+                ));
+                Ok(())
+            })
             .await
         {
-            Ok(_) => {
-                match self.repos.get_filled_match(request.match_id).await {
-                    Ok(game_match) => {
-                        let mut buf = BytesMut::with_capacity(64);
-                        let _ = game_match.encode(&mut buf);
-                        for client in self.ws_clients.lock().await.values() {
-                            if let Some(sender) = &client.sender {
-                                let _ = sender
-                                    .send(Ok(ws_layer::Msg::binary(buf.clone().freeze().to_vec())));
-                            }
-                        }
-                    }
-                    Err(err) => return Err(Status::new(Code::from_i32(13), err.to_string())),
-                }
-                Ok(Response::new(CreateGameEventReply {}))
-            }
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Ok(()) => Ok(Response::new(CreateGameEventReply {})),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -183,28 +565,119 @@ impl MatchService for MyMatchService {
     ) -> Result<Response<ChangeStateReply>, Status> {
         let request = request.into_inner();
         match self
-            .repos
-            .game_match
-            .update_status(request.match_id, &request.state)
+            .publisher
+            .commit(|connection, events| {
+                PgMatchRepo::update_status_in(connection, request.match_id, &request.state)?;
+                events.push(match_changed(request.match_id, MatchChange::StateChanged));
+                Ok(())
+            })
             .await
         {
-            Ok(()) => {
-                match self.repos.get_filled_match(request.match_id).await {
-                    Ok(game_match) => {
-                        let mut buf = BytesMut::with_capacity(64);
-                        let _ = game_match.encode(&mut buf);
-                        for client in self.ws_clients.lock().await.values() {
-                            if let Some(sender) = &client.sender {
-                                let _ = sender
-                                    .send(Ok(ws_layer::Msg::binary(buf.clone().freeze().to_vec())));
-                            }
-                        }
-                    }
-                    Err(err) => return Err(Status::new(Code::from_i32(13), err.to_string())),
-                }
-                Ok(Response::new(ChangeStateReply {}))
+            Ok(()) => Ok(Response::new(ChangeStateReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn update_match(
+        &self,
+        request: Request<UpdateMatchRequest>,
+    ) -> Result<Response<UpdateMatchReply>, Status> {
+        let request = request.into_inner();
+        validate_ratio(request.team_one_ratio.as_deref()).map_err(error_status)?;
+        validate_ratio(request.team_two_ratio.as_deref()).map_err(error_status)?;
+        match self.update_match(&request).await {
+            Ok(()) => Ok(Response::new(UpdateMatchReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn reschedule_match(
+        &self,
+        request: Request<RescheduleMatchRequest>,
+    ) -> Result<Response<RescheduleMatchReply>, Status> {
+        let request = request.into_inner();
+        let supposed_start_at = match request.supposed_start_at.parse::<DateTime<Utc>>() {
+            Ok(start) if start > Utc::now() => start,
+            Ok(_) => return Err(invalid_argument("The match cannot be moved to the past")),
+            Err(err) => return Err(invalid_argument(err.to_string())),
+        };
+
+        match self
+            .reschedule_match(request.match_id, supposed_start_at)
+            .await
+        {
+            Ok(ticket_holders) => Ok(Response::new(RescheduleMatchReply {
+                discarded_tickets: ticket_holders as i32,
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn delete_match(
+        &self,
+        request: Request<DeleteMatchRequest>,
+    ) -> Result<Response<DeleteMatchReply>, Status> {
+        let request = request.into_inner();
+        match self.delete_match(request.match_id).await {
+            Ok(()) => Ok(Response::new(DeleteMatchReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    type StreamMatchUpdatesStream = ReceiverStream<Result<MatchUpdate, Status>>;
+
+    async fn stream_match_updates(
+        &self,
+        request: Request<StreamMatchUpdatesRequest>,
+    ) -> Result<Response<Self::StreamMatchUpdatesStream>, Status> {
+        let request = request.into_inner();
+        let filter = MatchUpdatesFilter {
+            game_ids: request.game_ids,
+            match_ids: request.match_ids,
+        };
+
+        let (sender, receiver) = mpsc::channel(MATCH_UPDATES_BUFFER);
+        tokio::spawn(stream_match_updates(
+            self.repos.clone(),
+            self.fanout.subscribe(),
+            filter,
+            sender,
+        ));
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn record_timeline_event(
+        &self,
+        request: Request<RecordTimelineEventRequest>,
+    ) -> Result<Response<RecordTimelineEventReply>, Status> {
+        let request = request.into_inner();
+        let (match_id, payload) = match request.event {
+            Some(TimelineEvent {
+                match_id,
+                payload: Some(payload),
+                ..
+            }) => (match_id, TimelineEventPayload::from(&payload)),
+            _ => {
+                return Err(invalid_argument(
+                    "The timeline event is missing its payload",
+                ))
             }
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+        };
+
+        match self.record_timeline_event(match_id, &payload).await {
+            Ok(id) => Ok(Response::new(RecordTimelineEventReply { id })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_match_timeline(
+        &self,
+        request: Request<GetMatchTimelineRequest>,
+    ) -> Result<Response<GetMatchTimelineReply>, Status> {
+        let request = request.into_inner();
+        match self.get_timeline(request.match_id).await {
+            Ok(reply) => Ok(Response::new(reply)),
+            Err(err) => Err(error_status(err)),
         }
     }
 }
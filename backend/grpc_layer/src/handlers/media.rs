@@ -0,0 +1,68 @@
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::media::media_service_server::MediaService;
+use crate::media::{
+    GetAssetReply, GetAssetRequest, MediaAsset, UploadImageReply, UploadImageRequest,
+};
+use crate::media_store::MediaStore;
+use crate::status::{error_status, invalid_argument};
+
+use database_layer::db_models::media_asset;
+
+pub struct MyMediaService {
+    store: Arc<MediaStore>,
+}
+
+impl MyMediaService {
+    pub fn new(store: Arc<MediaStore>) -> MyMediaService {
+        MyMediaService { store }
+    }
+
+    /// Describe the stored image, along with the URLs it is served under
+    fn to_reply(&self, asset: &media_asset::MediaAsset) -> MediaAsset {
+        MediaAsset {
+            id: asset.id,
+            url: self.store.url(&asset.file_name),
+            thumbnail_url: self.store.url(&asset.thumbnail_name),
+            content_type: asset.content_type.clone(),
+            byte_size: asset.byte_size,
+            width: asset.width,
+            height: asset.height,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl MediaService for MyMediaService {
+    async fn upload_image(
+        &self,
+        request: Request<UploadImageRequest>,
+    ) -> Result<Response<UploadImageReply>, Status> {
+        let request = request.into_inner();
+        let image = match self.store.prepare(request.content).await {
+            Ok(image) => image,
+            Err(err) => return Err(invalid_argument(err.to_string())),
+        };
+
+        match self.store.save(image).await {
+            Ok(asset) => Ok(Response::new(UploadImageReply {
+                asset: Some(self.to_reply(&asset)),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_asset(
+        &self,
+        request: Request<GetAssetRequest>,
+    ) -> Result<Response<GetAssetReply>, Status> {
+        let request = request.into_inner();
+        match self.store.get(request.id).await {
+            Ok(asset) => Ok(Response::new(GetAssetReply {
+                asset: Some(self.to_reply(&asset)),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+}
@@ -1,33 +1,206 @@
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
-use tonic::{Code, Request, Response, Status};
+use tonic::{Request, Response, Status};
 
+use crate::media_store::MediaStore;
+use crate::status::error_status;
 use crate::team::team_service_server::TeamService;
 use crate::team::{
-    AddTeamToGameReply, AddTeamToGameRequest, CreateTeamReply, CreateTeamRequest, GetTeamReply,
-    GetTeamRequest, Team,
+    self, AddTeamToGameReply, AddTeamToGameRequest, ArchiveTeamReply, ArchiveTeamRequest,
+    CreatePlayerReply, CreatePlayerRequest, CreateTeamReply, CreateTeamRequest, DeletePlayerReply,
+    DeletePlayerRequest, DeleteTeamReply, DeleteTeamRequest, GetHeadToHeadReply,
+    GetHeadToHeadRequest, GetPlayerReply, GetPlayerRequest, GetRosterHistoryReply,
+    GetRosterHistoryRequest, GetRosterReply, GetRosterRequest, GetTeamReply, GetTeamRequest,
+    GetTeamStatsReply, GetTeamStatsRequest, JoinRosterReply, JoinRosterRequest, LeaveRosterReply,
+    LeaveRosterRequest, ListPlayersReply, ListPlayersRequest, ListTeamsReply, ListTeamsRequest,
+    Player, RemoveTeamFromGameReply, RemoveTeamFromGameRequest, RosterMember, Team,
+    UpdatePlayerReply, UpdatePlayerRequest, UpdateTeamReply, UpdateTeamRequest,
 };
+use crate::team_stats::{self, TeamStatsCache, FORM_LENGTH};
 
 use database_layer::{
     connection::PgPool,
     db_access::{
+        game::{GameRepo, PgGameRepo},
+        player::{PgPlayerRepo, PlayerRepo},
         repo::Repo,
         team::{PgTeamRepo, TeamRepo},
     },
-    db_models::team::CreateTeam,
+    db_models::{
+        player::{self, CreatePlayer},
+        roster_membership::{CreateRosterMembership, RosterMembership},
+        team::CreateTeam,
+    },
+    error::DomainError,
 };
 
 pub struct MyTeamService {
     repo: PgTeamRepo,
+    games: PgGameRepo,
+    players: PgPlayerRepo,
+    stats: Arc<TeamStatsCache>,
+    media: Arc<MediaStore>,
 }
 
 impl MyTeamService {
-    pub fn new(pool: &Arc<PgPool>) -> MyTeamService {
+    pub fn new(
+        pool: &Arc<PgPool>,
+        stats: Arc<TeamStatsCache>,
+        media: Arc<MediaStore>,
+    ) -> MyTeamService {
         MyTeamService {
             repo: PgTeamRepo::new(pool),
+            games: PgGameRepo::new(pool),
+            players: PgPlayerRepo::new(pool),
+            stats,
+            media,
+        }
+    }
+
+    /// Create a team, its logo is the uploaded image when set
+    async fn create_team(&self, request: &CreateTeamRequest) -> anyhow::Result<i32> {
+        let logo = self
+            .media
+            .resolve_url(&request.logo, request.logo_asset_id)
+            .await?;
+        let team_id = self
+            .repo
+            .create(CreateTeam::new(&request.name, &request.description, &logo))
+            .await?;
+        if request.logo_asset_id.is_some() {
+            self.repo
+                .set_logo_asset(team_id, request.logo_asset_id)
+                .await?;
         }
+        Ok(team_id)
+    }
+
+    /// Edit a team, its logo is the uploaded image when set,
+    /// otherwise the reference to the previously uploaded logo is dropped
+    async fn update_team(&self, request: &UpdateTeamRequest) -> anyhow::Result<()> {
+        // fails for a missing team
+        self.repo.get(request.id).await?;
+        let logo = self
+            .media
+            .resolve_url(&request.logo, request.logo_asset_id)
+            .await?;
+        self.repo
+            .edit(
+                request.id,
+                CreateTeam::new(&request.name, &request.description, &logo),
+            )
+            .await?;
+        self.repo
+            .set_logo_asset(request.id, request.logo_asset_id)
+            .await
+    }
+
+    /// Compute the form and the win rates of a team from its cached results
+    async fn get_team_stats(
+        &self,
+        team_id: i32,
+        form_length: usize,
+    ) -> anyhow::Result<team_stats::TeamStats> {
+        // fails for a missing team
+        self.repo.get(team_id).await?;
+        let results = self.stats.results(team_id).await?;
+        Ok(team_stats::team_stats(team_id, &results, form_length))
+    }
+
+    /// Compute the statistics of both teams and their matches against each other
+    async fn get_head_to_head(
+        &self,
+        team_one_id: i32,
+        team_two_id: i32,
+        form_length: usize,
+    ) -> anyhow::Result<GetHeadToHeadReply> {
+        let team_one = self.get_team_stats(team_one_id, form_length).await?;
+        let team_two = self.get_team_stats(team_two_id, form_length).await?;
+        let team_one_results = self.stats.results(team_one_id).await?;
+        let head_to_head = team_stats::head_to_head(team_one_id, team_two_id, &team_one_results);
+
+        Ok(GetHeadToHeadReply {
+            team_one: Some(team::TeamStats::from(&team_one)),
+            team_two: Some(team::TeamStats::from(&team_two)),
+            head_to_head: Some(team::HeadToHead::from(&head_to_head)),
+        })
+    }
+
+    /// Load the team along with the games it plays
+    async fn get_team(&self, team_id: i32) -> anyhow::Result<GetTeamReply> {
+        let team = self.repo.get(team_id).await?;
+        let games = self.repo.games_played(team_id).await?;
+
+        Ok(GetTeamReply {
+            team: Some(Team::from(&team)),
+            game_ids: games.iter().map(|game| game.id).collect(),
+        })
+    }
+
+    /// List the teams, optionally only those playing the game
+    async fn list_teams(
+        &self,
+        game_id: Option<i32>,
+        include_archived: bool,
+    ) -> anyhow::Result<Vec<Team>> {
+        let mut teams = self.repo.get_all(include_archived).await?;
+        if let Some(game_id) = game_id {
+            let playing: Vec<i32> = self
+                .games
+                .get_teams_playing(game_id)
+                .await?
+                .iter()
+                .map(|team| team.id)
+                .collect();
+            teams.retain(|team| playing.contains(&team.id));
+        }
+        Ok(teams.iter().map(Team::from).collect())
+    }
+
+    /// Load the player along with the teams they have been in
+    async fn get_player(&self, player_id: i32) -> anyhow::Result<GetPlayerReply> {
+        let player = self.players.get(player_id).await?;
+        let history = self.players.get_player_history(player_id).await?;
+
+        Ok(GetPlayerReply {
+            player: Some(Player::from(&player)),
+            history: history
+                .iter()
+                .map(|membership| RosterMember::from((membership, &player)))
+                .collect(),
+        })
     }
 }
 
+/// Read the optional time of a request, the current time is used if unset
+fn time_or_now(time: &Option<String>) -> Result<DateTime<Utc>, DomainError> {
+    match time {
+        Some(time) => time
+            .parse::<DateTime<Utc>>()
+            .map_err(|err| DomainError::validation(err.to_string())),
+        None => Ok(Utc::now()),
+    }
+}
+
+/// Read the optional form length of a request, the default one is used if unset
+fn form_length(form_length: Option<i32>) -> Result<usize, DomainError> {
+    match form_length {
+        Some(length) if length < 0 => Err(DomainError::validation(
+            "The form length cannot be negative",
+        )),
+        Some(length) => Ok(length as usize),
+        None => Ok(FORM_LENGTH),
+    }
+}
+
+/// Convert the memberships along with their players
+fn roster_members(members: &[(RosterMembership, player::Player)]) -> Vec<RosterMember> {
+    members
+        .iter()
+        .map(|(membership, player)| RosterMember::from((membership, player)))
+        .collect()
+}
+
 #[tonic::async_trait]
 impl TeamService for MyTeamService {
     async fn get_team(
@@ -35,16 +208,9 @@ impl TeamService for MyTeamService {
         request: Request<GetTeamRequest>,
     ) -> Result<Response<GetTeamReply>, Status> {
         let request = request.into_inner();
-        match self.repo.get(request.id).await {
-            Ok(team) => Ok(Response::new(GetTeamReply {
-                team: Some(Team {
-                    id: team.id,
-                    name: team.name,
-                    description: team.description,
-                    logo: team.logo,
-                }),
-            })),
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+        match self.get_team(request.id).await {
+            Ok(reply) => Ok(Response::new(reply)),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -53,11 +219,9 @@ impl TeamService for MyTeamService {
         request: Request<CreateTeamRequest>,
     ) -> Result<Response<CreateTeamReply>, Status> {
         let request = request.into_inner();
-        let create_team = CreateTeam::new(&request.name, &request.description, &request.logo);
-
-        match self.repo.create(create_team).await {
+        match self.create_team(&request).await {
             Ok(team_id) => Ok(Response::new(CreateTeamReply { id: team_id })),
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -72,7 +236,245 @@ impl TeamService for MyTeamService {
             .await
         {
             Ok(()) => Ok(Response::new(AddTeamToGameReply {})),
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn remove_team_from_game(
+        &self,
+        request: Request<RemoveTeamFromGameRequest>,
+    ) -> Result<Response<RemoveTeamFromGameReply>, Status> {
+        let request = request.into_inner();
+        match self
+            .repo
+            .remove_from_game(request.team_id, request.game_id)
+            .await
+        {
+            Ok(()) => Ok(Response::new(RemoveTeamFromGameReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn list_teams(
+        &self,
+        request: Request<ListTeamsRequest>,
+    ) -> Result<Response<ListTeamsReply>, Status> {
+        let request = request.into_inner();
+        match self
+            .list_teams(request.game_id, request.include_archived)
+            .await
+        {
+            Ok(teams) => Ok(Response::new(ListTeamsReply { teams })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn update_team(
+        &self,
+        request: Request<UpdateTeamRequest>,
+    ) -> Result<Response<UpdateTeamReply>, Status> {
+        let request = request.into_inner();
+        match self.update_team(&request).await {
+            Ok(()) => Ok(Response::new(UpdateTeamReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn archive_team(
+        &self,
+        request: Request<ArchiveTeamRequest>,
+    ) -> Result<Response<ArchiveTeamReply>, Status> {
+        let request = request.into_inner();
+        match self.repo.set_archived(request.id, request.archived).await {
+            Ok(()) => Ok(Response::new(ArchiveTeamReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn delete_team(
+        &self,
+        request: Request<DeleteTeamRequest>,
+    ) -> Result<Response<DeleteTeamReply>, Status> {
+        let request = request.into_inner();
+        match self.repo.delete(request.id).await {
+            Ok(_) => Ok(Response::new(DeleteTeamReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn create_player(
+        &self,
+        request: Request<CreatePlayerRequest>,
+    ) -> Result<Response<CreatePlayerReply>, Status> {
+        let request = request.into_inner();
+        let create_player = CreatePlayer::new(
+            request.game_id,
+            &request.nickname,
+            &request.real_name,
+            &request.country,
+            &request.photo,
+        );
+
+        match self.players.create(create_player).await {
+            Ok(id) => Ok(Response::new(CreatePlayerReply { id })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_player(
+        &self,
+        request: Request<GetPlayerRequest>,
+    ) -> Result<Response<GetPlayerReply>, Status> {
+        let request = request.into_inner();
+        match self.get_player(request.id).await {
+            Ok(reply) => Ok(Response::new(reply)),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn list_players(
+        &self,
+        request: Request<ListPlayersRequest>,
+    ) -> Result<Response<ListPlayersReply>, Status> {
+        let request = request.into_inner();
+        match self.players.get_all(request.game_id).await {
+            Ok(players) => Ok(Response::new(ListPlayersReply {
+                players: players.iter().map(Player::from).collect(),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn update_player(
+        &self,
+        request: Request<UpdatePlayerRequest>,
+    ) -> Result<Response<UpdatePlayerReply>, Status> {
+        let request = request.into_inner();
+        let edited_player = CreatePlayer::new(
+            request.game_id,
+            &request.nickname,
+            &request.real_name,
+            &request.country,
+            &request.photo,
+        );
+
+        match self.players.edit(request.id, edited_player).await {
+            Ok(()) => Ok(Response::new(UpdatePlayerReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn delete_player(
+        &self,
+        request: Request<DeletePlayerRequest>,
+    ) -> Result<Response<DeletePlayerReply>, Status> {
+        let request = request.into_inner();
+        match self.players.delete(request.id).await {
+            Ok(()) => Ok(Response::new(DeletePlayerReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn join_roster(
+        &self,
+        request: Request<JoinRosterRequest>,
+    ) -> Result<Response<JoinRosterReply>, Status> {
+        let request = request.into_inner();
+        let joined_at = time_or_now(&request.joined_at).map_err(error_status)?;
+        let new_membership = CreateRosterMembership::new(
+            request.team_id,
+            request.player_id,
+            &request.role,
+            joined_at,
+        );
+
+        let joined = match self.players.join_team(new_membership).await {
+            Ok(membership) => membership,
+            Err(err) => return Err(error_status(err)),
+        };
+        match self.players.get(joined.player_id).await {
+            Ok(player) => Ok(Response::new(JoinRosterReply {
+                member: Some(RosterMember::from((&joined, &player))),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn leave_roster(
+        &self,
+        request: Request<LeaveRosterRequest>,
+    ) -> Result<Response<LeaveRosterReply>, Status> {
+        let request = request.into_inner();
+        let left_at = time_or_now(&request.left_at).map_err(error_status)?;
+
+        let left = match self.players.leave_team(request.player_id, left_at).await {
+            Ok(membership) => membership,
+            Err(err) => return Err(error_status(err)),
+        };
+        match self.players.get(left.player_id).await {
+            Ok(player) => Ok(Response::new(LeaveRosterReply {
+                member: Some(RosterMember::from((&left, &player))),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_roster(
+        &self,
+        request: Request<GetRosterRequest>,
+    ) -> Result<Response<GetRosterReply>, Status> {
+        let request = request.into_inner();
+        let at = time_or_now(&request.at).map_err(error_status)?;
+
+        match self.players.get_roster(request.team_id, at).await {
+            Ok(members) => Ok(Response::new(GetRosterReply {
+                members: roster_members(&members),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_roster_history(
+        &self,
+        request: Request<GetRosterHistoryRequest>,
+    ) -> Result<Response<GetRosterHistoryReply>, Status> {
+        let request = request.into_inner();
+        match self.players.get_team_history(request.team_id).await {
+            Ok(members) => Ok(Response::new(GetRosterHistoryReply {
+                members: roster_members(&members),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_team_stats(
+        &self,
+        request: Request<GetTeamStatsRequest>,
+    ) -> Result<Response<GetTeamStatsReply>, Status> {
+        let request = request.into_inner();
+        let form_length = form_length(request.form_length).map_err(error_status)?;
+
+        match self.get_team_stats(request.team_id, form_length).await {
+            Ok(stats) => Ok(Response::new(GetTeamStatsReply {
+                stats: Some(team::TeamStats::from(&stats)),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_head_to_head(
+        &self,
+        request: Request<GetHeadToHeadRequest>,
+    ) -> Result<Response<GetHeadToHeadReply>, Status> {
+        let request = request.into_inner();
+        let form_length = form_length(request.form_length).map_err(error_status)?;
+
+        match self
+            .get_head_to_head(request.team_one_id, request.team_two_id, form_length)
+            .await
+        {
+            Ok(reply) => Ok(Response::new(reply)),
+            Err(err) => Err(error_status(err)),
         }
     }
 }
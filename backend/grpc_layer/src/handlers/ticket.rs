@@ -1,35 +1,45 @@
-use bytes::BytesMut;
-use prost::Message;
 use std::sync::Arc;
-use tonic::{Code, Request, Response, Status};
+use tonic::{Request, Response, Status};
 
-use crate::bet::Bet;
+use crate::bet::{Bet, BetMarket};
+use crate::config::BettingLimits;
+use crate::publisher::Publisher;
 use crate::repos::Repos;
+use crate::status::{error_status, invalid_argument};
 use crate::ticket::ticket_service_server::TicketService;
 use crate::ticket::{
-    GetCurrentTicketReply, GetCurrentTicketRequest, ListTicketsReply, ListTicketsRequest,
-    SubmitTicketReply, SubmitTicketRequest, Ticket,
+    CopySharedTicketReply, CopySharedTicketRequest, GetCurrentTicketReply, GetCurrentTicketRequest,
+    GetSharedTicketReply, GetSharedTicketRequest, ListTicketsReply, ListTicketsRequest,
+    RejectedSelection, Selection, ShareTicketReply, ShareTicketRequest, SubmitTicketReply,
+    SubmitTicketRequest, Ticket,
 };
 
 use database_layer::{
     connection::PgPool,
     db_access::{
-        bet_and_ticket::BetAndTicketRepo, submitted_bet_and_ticket::SubmittedBetAndTicketRepo,
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        submitted_bet_and_ticket::{PgSubmittedBetAndTicketRepo, SubmittedBetAndTicketRepo},
+        ticket_share::{PgTicketShareRepo, TicketShareRepo},
     },
-    db_models::ticket::ObtainedTicket,
+    db_models::{ticket::ObtainedTicket, ticket_share_code::SharedTicket},
+};
+use event_bus::{
+    events::{BetPlaced, MatchChange, MatchChanged, SubmittedSelection, TicketSubmitted},
+    DomainEvent, EventBus,
 };
-use ws_layer::Clients;
 
 pub struct MyTicketService {
     repos: Repos,
-    ws_clients: Clients,
+    publisher: Publisher,
+    limits: BettingLimits,
 }
 
 impl MyTicketService {
-    pub fn new(pool: &Arc<PgPool>, ws_clients: Clients) -> MyTicketService {
+    pub fn new(pool: &Arc<PgPool>, bus: EventBus, limits: BettingLimits) -> MyTicketService {
         MyTicketService {
             repos: Repos::new(pool),
-            ws_clients: ws_clients,
+            publisher: Publisher::new(pool, bus),
+            limits,
         }
     }
 }
@@ -53,12 +63,12 @@ impl TicketService for MyTicketService {
                 match self.repos.bet_ticket.get_bets(ticket.id).await {
                     Ok(bets) => Ok(Response::new(GetCurrentTicketReply {
                         ticket_id: ticket.id,
-                        bets: bets.iter().map(|bet| Bet::from(bet)).collect(),
+                        bets: bets.iter().map(Bet::from).collect(),
                     })),
-                    Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+                    Err(err) => Err(error_status(err)),
                 }
             }
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -85,12 +95,16 @@ impl TicketService for MyTicketService {
                                 ticket_id: bet.submitted_ticket_id,
                                 match_id: bet.game_match_id,
                                 team_id: bet.team_id,
+                                market: bet
+                                    .extract_market()
+                                    .ok()
+                                    .map(|market| BetMarket::from(&market)),
                             })
                             .collect(),
                     })
                     .collect(),
             })),
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -99,54 +113,151 @@ impl TicketService for MyTicketService {
         request: Request<SubmitTicketRequest>,
     ) -> Result<Response<SubmitTicketReply>, Status> {
         let request = request.into_inner();
+        if let Err(err) = self.limits.check_stake(request.price_paid.into()) {
+            return Err(invalid_argument(err.to_string()));
+        }
+        match self
+            .publisher
+            .commit(|connection, events| {
+                let submitted_ticket_id = PgBetAndTicketRepo::submit_ticket_in(
+                    connection,
+                    request.ticket_id,
+                    request.price_paid.into(),
+                )?;
+                let submitted_bets =
+                    PgSubmittedBetAndTicketRepo::get_bets_in(connection, submitted_ticket_id)?;
+                let selections: Vec<SubmittedSelection> = submitted_bets
+                    .iter()
+                    .map(|bet| SubmittedSelection {
+                        match_id: bet.game_match_id,
+                        team_id: bet.team_id,
+                        ratio: bet.bet_ratio.clone(),
+                    })
+                    .collect();
+                for bet in submitted_bets {
+                    Repos::change_ratios_in(connection, bet.game_match_id, bet.team_id)?;
+                    events.push(DomainEvent::MatchChanged(MatchChanged {
+                        match_id: bet.game_match_id,
+                        change: MatchChange::RatiosChanged,
+                    }));
+                }
+
+                let ticket =
+                    PgSubmittedBetAndTicketRepo::get_ticket_in(connection, submitted_ticket_id)?;
+                events.push(DomainEvent::TicketSubmitted(TicketSubmitted {
+                    ticket_id: ticket.id,
+                    user_id: ticket.user_id,
+                    price_paid: ticket.price_paid.clone(),
+                    selections,
+                }));
+                events.push(DomainEvent::BalanceChanged {
+                    user_id: ticket.user_id,
+                });
+                Ok(())
+            })
+            .await
+        {
+            Ok(()) => Ok(Response::new(SubmitTicketReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn share_ticket(
+        &self,
+        request: Request<ShareTicketRequest>,
+    ) -> Result<Response<ShareTicketReply>, Status> {
+        let request = request.into_inner();
+        let shared_ticket = if request.submitted {
+            SharedTicket::Submitted(request.ticket_id)
+        } else {
+            SharedTicket::Open(request.ticket_id)
+        };
+
         match self
+            .repos
+            .ticket_share
+            .share(request.user_id, shared_ticket)
+            .await
+        {
+            Ok(code) => Ok(Response::new(ShareTicketReply { code })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_shared_ticket(
+        &self,
+        request: Request<GetSharedTicketRequest>,
+    ) -> Result<Response<GetSharedTicketReply>, Status> {
+        let request = request.into_inner();
+        match self.repos.ticket_share.get_selections(&request.code).await {
+            Ok(selections) => Ok(Response::new(GetSharedTicketReply {
+                selections: selections.iter().map(Selection::from).collect(),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn copy_shared_ticket(
+        &self,
+        request: Request<CopySharedTicketRequest>,
+    ) -> Result<Response<CopySharedTicketReply>, Status> {
+        let request = request.into_inner();
+        let open_ticket = match self
             .repos
             .bet_ticket
-            .submit_ticket(request.ticket_id, request.price_paid.into())
+            .get_user_current_ticket(request.user_id)
             .await
         {
-            Ok(submitted_ticket_id) => {
-                match self
-                    .repos
-                    .sub_bet_ticket
-                    .get_bets(submitted_ticket_id)
-                    .await
-                {
-                    Ok(submitted_bets) => {
-                        for bet in submitted_bets {
-                            match self
-                                .repos
-                                .change_ratios(bet.game_match_id, bet.team_id)
-                                .await
-                            {
-                                Ok(_) => {}
-                                Err(err) => {
-                                    return Err(Status::new(Code::from_i32(13), err.to_string()))
-                                }
-                            }
-                            match self.repos.get_filled_match(bet.game_match_id).await {
-                                Ok(game_match) => {
-                                    let mut buf = BytesMut::with_capacity(64);
-                                    let _ = game_match.encode(&mut buf);
-                                    for client in self.ws_clients.lock().await.values() {
-                                        if let Some(sender) = &client.sender {
-                                            let _ = sender.send(Ok(ws_layer::Msg::binary(
-                                                buf.clone().freeze().to_vec(),
-                                            )));
-                                        }
-                                    }
-                                }
-                                Err(err) => {
-                                    return Err(Status::new(Code::from_i32(13), err.to_string()))
-                                }
-                            }
-                        }
-                        Ok(Response::new(SubmitTicketReply {}))
-                    }
-                    Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Ok(ObtainedTicket::StillValid(ticket))
+            | Ok(ObtainedTicket::NoTicketFound(ticket))
+            | Ok(ObtainedTicket::NewAfterInvalid(ticket)) => ticket,
+            Err(err) => return Err(error_status(err)),
+        };
+
+        let max_selections = self.limits.max_selections;
+        match self
+            .publisher
+            .commit(|connection, events| {
+                let copied_ticket = PgTicketShareRepo::copy_to_ticket_in(
+                    connection,
+                    &request.code,
+                    open_ticket.id,
+                    max_selections,
+                )?;
+                for (bet, _) in copied_ticket.copied.iter() {
+                    events.push(DomainEvent::BetPlaced(BetPlaced {
+                        bet_id: bet.id,
+                        ticket_id: bet.ticket_id,
+                        match_id: bet.game_match_id,
+                        team_id: bet.team_id,
+                    }));
                 }
-            }
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+                Ok(copied_ticket)
+            })
+            .await
+        {
+            Ok(copied_ticket) => Ok(Response::new(CopySharedTicketReply {
+                ticket_id: copied_ticket.ticket_id,
+                bets: copied_ticket
+                    .copied
+                    .iter()
+                    .map(|(bet, _)| Bet::from(bet))
+                    .collect(),
+                copied: copied_ticket
+                    .copied
+                    .iter()
+                    .map(|(_, selection)| Selection::from(selection))
+                    .collect(),
+                rejected: copied_ticket
+                    .rejected
+                    .iter()
+                    .map(|(selection, reason)| RejectedSelection {
+                        selection: Some(Selection::from(selection)),
+                        reason: reason.clone(),
+                    })
+                    .collect(),
+            })),
+            Err(err) => Err(error_status(err)),
         }
     }
 }
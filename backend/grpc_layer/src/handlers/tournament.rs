@@ -0,0 +1,474 @@
+use chrono::{DateTime, Utc};
+use std::convert::TryFrom;
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+use crate::config::BettingLimits;
+use crate::publisher::Publisher;
+use crate::repos::Repos;
+use crate::standings;
+use crate::status::{error_status, invalid_argument};
+use crate::team::Team;
+use crate::tournament::tournament_service_server::TournamentService;
+use crate::tournament::{
+    AddMatchToStageReply, AddMatchToStageRequest, CreateStageReply, CreateStageRequest,
+    CreateTournamentReply, CreateTournamentRequest, DeleteStageReply, DeleteStageRequest,
+    DeleteTournamentReply, DeleteTournamentRequest, EndTournamentReply, EndTournamentRequest,
+    GetStandingsReply, GetStandingsRequest, GetTournamentReply, GetTournamentRequest,
+    ListOutrightBetsReply, ListOutrightBetsRequest, ListTournamentMatchesReply,
+    ListTournamentMatchesRequest, ListTournamentsReply, ListTournamentsRequest, OutrightBet,
+    OutrightSelection, PlaceOutrightBetReply, PlaceOutrightBetRequest, RemoveMatchFromStageReply,
+    RemoveMatchFromStageRequest, RemoveOutrightSelectionReply, RemoveOutrightSelectionRequest,
+    SetOutrightSelectionReply, SetOutrightSelectionRequest, Stage, StageMatches, StageType,
+    Standing, Tournament, UpdateStageReply, UpdateStageRequest, UpdateTournamentReply,
+    UpdateTournamentRequest,
+};
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        match_timeline::TimelineRepo,
+        outright::{OutrightRepo, PgOutrightRepo},
+        team::TeamRepo,
+        tournament::{PgTournamentRepo, TournamentRepo},
+    },
+    db_models::{
+        outright_selection::CreateOutrightSelection, stage::CreateStage,
+        tournament::CreateTournament,
+    },
+};
+use event_bus::{events::TournamentEnded, DomainEvent, EventBus};
+
+pub struct MyTournamentService {
+    repos: Repos,
+    publisher: Publisher,
+    limits: BettingLimits,
+}
+
+impl MyTournamentService {
+    pub fn new(pool: &Arc<PgPool>, bus: EventBus, limits: BettingLimits) -> MyTournamentService {
+        MyTournamentService {
+            repos: Repos::new(pool),
+            publisher: Publisher::new(pool, bus),
+            limits,
+        }
+    }
+
+    /// Load the tournament along with its stages and the teams which can be bet on to win it
+    async fn get_tournament(&self, tournament_id: i32) -> anyhow::Result<GetTournamentReply> {
+        let tournament = self.repos.tournament.get(tournament_id).await?;
+        let stages = self.repos.tournament.get_stages(tournament_id).await?;
+
+        let mut outrights = Vec::new();
+        for selection in self.repos.outright.get_selections(tournament_id).await? {
+            let team = self.repos.team.get(selection.team_id).await?;
+            outrights.push(OutrightSelection {
+                team: Some(Team::from(&team)),
+                ratio: selection.ratio,
+            });
+        }
+
+        Ok(GetTournamentReply {
+            tournament: Some(Tournament::from(&tournament)),
+            stages: stages
+                .iter()
+                .map(Stage::try_from)
+                .collect::<anyhow::Result<Vec<Stage>>>()?,
+            outrights,
+        })
+    }
+
+    /// Load the matches of the tournament grouped by its stages
+    async fn get_tournament_matches(
+        &self,
+        tournament_id: i32,
+    ) -> anyhow::Result<Vec<StageMatches>> {
+        let stages = self.repos.tournament.get_stages(tournament_id).await?;
+        let stage_and_match_ids = self.repos.tournament.get_matches(tournament_id).await?;
+
+        let mut grouped = Vec::new();
+        for stage in stages.iter() {
+            let mut game_matches = Vec::new();
+            for (_, match_id) in stage_and_match_ids
+                .iter()
+                .filter(|(stage_id, _)| *stage_id == stage.id)
+            {
+                game_matches.push(self.repos.get_filled_match(*match_id).await?);
+            }
+            grouped.push(StageMatches {
+                stage: Some(Stage::try_from(stage)?),
+                game_matches,
+            });
+        }
+        Ok(grouped)
+    }
+
+    /// Compute the standings of the stage from the results of its ended matches
+    async fn get_standings(&self, stage_id: i32) -> anyhow::Result<Vec<Standing>> {
+        let results = self.repos.tournament.get_stage_results(stage_id).await?;
+        let match_ids: Vec<i32> = results
+            .iter()
+            .map(|(game_match, _)| game_match.id)
+            .collect();
+        let scores = self.repos.timeline.get_scores(&match_ids).await?;
+
+        let mut grpc_standings = Vec::new();
+        for standing in standings::compute(&results, &scores)? {
+            let team = self.repos.team.get(standing.team_id).await?;
+            grpc_standings.push(Standing {
+                team: Some(Team::from(&team)),
+                played: standing.played,
+                wins: standing.wins,
+                losses: standing.losses,
+                score_for: standing.score_for,
+                score_against: standing.score_against,
+            });
+        }
+        Ok(grpc_standings)
+    }
+}
+
+/// Build the tournament insert structure from the fields of a request
+fn create_tournament(
+    game_id: i32,
+    name: &str,
+    description: &str,
+    starts_at: &str,
+    ends_at: &str,
+) -> anyhow::Result<CreateTournament> {
+    CreateTournament::new(
+        game_id,
+        name,
+        description,
+        starts_at.parse::<DateTime<Utc>>()?,
+        ends_at.parse::<DateTime<Utc>>()?,
+    )
+}
+
+/// Build the stage insert structure from the fields of a request
+fn create_stage(
+    tournament_id: i32,
+    name: &str,
+    stage_type: i32,
+    position: i32,
+) -> anyhow::Result<CreateStage> {
+    let stage_type = match StageType::from_i32(stage_type) {
+        Some(stage_type) => stage_type,
+        None => anyhow::bail!("Unsupported stage type"),
+    };
+    Ok(CreateStage::new(
+        tournament_id,
+        name,
+        stage_type.into(),
+        position,
+    ))
+}
+
+#[tonic::async_trait]
+impl TournamentService for MyTournamentService {
+    async fn list_tournaments(
+        &self,
+        request: Request<ListTournamentsRequest>,
+    ) -> Result<Response<ListTournamentsReply>, Status> {
+        let request = request.into_inner();
+        match self.repos.tournament.get_all(request.game_id).await {
+            Ok(tournaments) => Ok(Response::new(ListTournamentsReply {
+                tournaments: tournaments.iter().map(Tournament::from).collect(),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_tournament(
+        &self,
+        request: Request<GetTournamentRequest>,
+    ) -> Result<Response<GetTournamentReply>, Status> {
+        let request = request.into_inner();
+        match self.get_tournament(request.id).await {
+            Ok(reply) => Ok(Response::new(reply)),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn create_tournament(
+        &self,
+        request: Request<CreateTournamentRequest>,
+    ) -> Result<Response<CreateTournamentReply>, Status> {
+        let request = request.into_inner();
+        let create_tournament = match create_tournament(
+            request.game_id,
+            &request.name,
+            &request.description,
+            &request.starts_at,
+            &request.ends_at,
+        ) {
+            Ok(create_tournament) => create_tournament,
+            Err(err) => return Err(invalid_argument(err.to_string())),
+        };
+
+        match self.repos.tournament.create(create_tournament).await {
+            Ok(id) => Ok(Response::new(CreateTournamentReply { id })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn update_tournament(
+        &self,
+        request: Request<UpdateTournamentRequest>,
+    ) -> Result<Response<UpdateTournamentReply>, Status> {
+        let request = request.into_inner();
+        let edited_tournament = match create_tournament(
+            request.game_id,
+            &request.name,
+            &request.description,
+            &request.starts_at,
+            &request.ends_at,
+        ) {
+            Ok(edited_tournament) => edited_tournament,
+            Err(err) => return Err(invalid_argument(err.to_string())),
+        };
+
+        match self
+            .repos
+            .tournament
+            .edit(request.id, edited_tournament)
+            .await
+        {
+            Ok(()) => Ok(Response::new(UpdateTournamentReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn delete_tournament(
+        &self,
+        request: Request<DeleteTournamentRequest>,
+    ) -> Result<Response<DeleteTournamentReply>, Status> {
+        let request = request.into_inner();
+        match self.repos.tournament.delete(request.id).await {
+            Ok(()) => Ok(Response::new(DeleteTournamentReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn end_tournament(
+        &self,
+        request: Request<EndTournamentRequest>,
+    ) -> Result<Response<EndTournamentReply>, Status> {
+        let request = request.into_inner();
+        match self
+            .publisher
+            .commit(|connection, events| {
+                let tournament =
+                    PgTournamentRepo::end_in(connection, request.id, request.winner_id)?;
+                events.push(DomainEvent::TournamentEnded(TournamentEnded {
+                    tournament_id: tournament.id,
+                    winner_id: request.winner_id,
+                }));
+                Ok(tournament)
+            })
+            .await
+        {
+            Ok(tournament) => Ok(Response::new(EndTournamentReply {
+                tournament: Some(Tournament::from(&tournament)),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn create_stage(
+        &self,
+        request: Request<CreateStageRequest>,
+    ) -> Result<Response<CreateStageReply>, Status> {
+        let request = request.into_inner();
+        let create_stage = match create_stage(
+            request.tournament_id,
+            &request.name,
+            request.stage_type,
+            request.position,
+        ) {
+            Ok(create_stage) => create_stage,
+            Err(err) => return Err(invalid_argument(err.to_string())),
+        };
+
+        match self.repos.tournament.create_stage(create_stage).await {
+            Ok(id) => Ok(Response::new(CreateStageReply { id })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn update_stage(
+        &self,
+        request: Request<UpdateStageRequest>,
+    ) -> Result<Response<UpdateStageReply>, Status> {
+        let request = request.into_inner();
+        let stage = match self.repos.tournament.get_stage(request.id).await {
+            Ok(stage) => stage,
+            Err(err) => return Err(error_status(err)),
+        };
+        let edited_stage = match create_stage(
+            stage.tournament_id,
+            &request.name,
+            request.stage_type,
+            request.position,
+        ) {
+            Ok(edited_stage) => edited_stage,
+            Err(err) => return Err(invalid_argument(err.to_string())),
+        };
+
+        match self
+            .repos
+            .tournament
+            .edit_stage(request.id, edited_stage)
+            .await
+        {
+            Ok(()) => Ok(Response::new(UpdateStageReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn delete_stage(
+        &self,
+        request: Request<DeleteStageRequest>,
+    ) -> Result<Response<DeleteStageReply>, Status> {
+        let request = request.into_inner();
+        match self.repos.tournament.delete_stage(request.id).await {
+            Ok(()) => Ok(Response::new(DeleteStageReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn add_match_to_stage(
+        &self,
+        request: Request<AddMatchToStageRequest>,
+    ) -> Result<Response<AddMatchToStageReply>, Status> {
+        let request = request.into_inner();
+        match self
+            .repos
+            .tournament
+            .add_match(request.stage_id, request.match_id)
+            .await
+        {
+            Ok(()) => Ok(Response::new(AddMatchToStageReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn remove_match_from_stage(
+        &self,
+        request: Request<RemoveMatchFromStageRequest>,
+    ) -> Result<Response<RemoveMatchFromStageReply>, Status> {
+        let request = request.into_inner();
+        match self
+            .repos
+            .tournament
+            .remove_match(request.stage_id, request.match_id)
+            .await
+        {
+            Ok(()) => Ok(Response::new(RemoveMatchFromStageReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn list_tournament_matches(
+        &self,
+        request: Request<ListTournamentMatchesRequest>,
+    ) -> Result<Response<ListTournamentMatchesReply>, Status> {
+        let request = request.into_inner();
+        match self.get_tournament_matches(request.tournament_id).await {
+            Ok(stages) => Ok(Response::new(ListTournamentMatchesReply { stages })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn get_standings(
+        &self,
+        request: Request<GetStandingsRequest>,
+    ) -> Result<Response<GetStandingsReply>, Status> {
+        let request = request.into_inner();
+        match self.get_standings(request.stage_id).await {
+            Ok(standings) => Ok(Response::new(GetStandingsReply { standings })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn set_outright_selection(
+        &self,
+        request: Request<SetOutrightSelectionRequest>,
+    ) -> Result<Response<SetOutrightSelectionReply>, Status> {
+        let request = request.into_inner();
+        let selection = match CreateOutrightSelection::new(
+            request.tournament_id,
+            request.team_id,
+            request.ratio as f64,
+        ) {
+            Ok(selection) => selection,
+            Err(err) => return Err(invalid_argument(err.to_string())),
+        };
+
+        match self.repos.outright.set_selection(selection).await {
+            Ok(()) => Ok(Response::new(SetOutrightSelectionReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn remove_outright_selection(
+        &self,
+        request: Request<RemoveOutrightSelectionRequest>,
+    ) -> Result<Response<RemoveOutrightSelectionReply>, Status> {
+        let request = request.into_inner();
+        match self
+            .repos
+            .outright
+            .remove_selection(request.tournament_id, request.team_id)
+            .await
+        {
+            Ok(()) => Ok(Response::new(RemoveOutrightSelectionReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn place_outright_bet(
+        &self,
+        request: Request<PlaceOutrightBetRequest>,
+    ) -> Result<Response<PlaceOutrightBetReply>, Status> {
+        let request = request.into_inner();
+        if let Err(err) = self.limits.check_stake(request.price_paid as f64) {
+            return Err(invalid_argument(err.to_string()));
+        }
+        match self
+            .publisher
+            .commit(|connection, events| {
+                let bet = PgOutrightRepo::place_bet_in(
+                    connection,
+                    request.user_id,
+                    request.tournament_id,
+                    request.team_id,
+                    request.price_paid as f64,
+                )?;
+                events.push(DomainEvent::BalanceChanged {
+                    user_id: bet.user_id,
+                });
+                Ok(bet)
+            })
+            .await
+        {
+            Ok(bet) => Ok(Response::new(PlaceOutrightBetReply {
+                bet: Some(OutrightBet::from(&bet)),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn list_outright_bets(
+        &self,
+        request: Request<ListOutrightBetsRequest>,
+    ) -> Result<Response<ListOutrightBetsReply>, Status> {
+        let request = request.into_inner();
+        match self.repos.outright.get_bets(request.user_id).await {
+            Ok(bets) => Ok(Response::new(ListOutrightBetsReply {
+                bets: bets.iter().map(OutrightBet::from).collect(),
+            })),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+}
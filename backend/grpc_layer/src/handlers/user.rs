@@ -1,33 +1,75 @@
 use std::sync::Arc;
-use tonic::{Code, Request, Response, Status};
+use tonic::{Request, Response, Status};
 
+use crate::media_store::MediaStore;
+use crate::status::{domain_status, error_status, invalid_argument};
 use crate::user::user_service_server::UserService;
 use crate::user::{
-    Address, AuthUserReply, AuthUserRequest, CreateUserReply, CreateUserRequest, EditUserReply,
-    EditUserRequest, GetUserReply, GetUserRequest, User,
+    AddFavouriteReply, AddFavouriteRequest, Address, AuthUserReply, AuthUserRequest,
+    CreateUserReply, CreateUserRequest, EditUserReply, EditUserRequest, Favourite, FavouriteType,
+    GetUserReply, GetUserRequest, ListFavouritesReply, ListFavouritesRequest, LogoutUserReply,
+    LogoutUserRequest, RemoveFavouriteReply, RemoveFavouriteRequest, User,
 };
 
 use database_layer::{
     connection::PgPool,
     db_access::{
+        favourite::{FavouriteRepo, PgFavouriteRepo},
         repo::Repo,
+        session::{PgSessionRepo, SessionRepo},
         user::{PgUserRepo, UserRepo},
     },
-    db_models::{user::CreateUser, user_address::CreateUserAddress},
+    db_models::{
+        user::CreateUser, user_address::CreateUserAddress, user_favourite::Favourite as DbFavourite,
+    },
+    error::DomainError,
 };
 
 pub struct MyUserService {
     repo: PgUserRepo,
+    favourite_repo: PgFavouriteRepo,
+    session_repo: PgSessionRepo,
+    media: Arc<MediaStore>,
 }
 
 impl MyUserService {
-    pub fn new(pool: &Arc<PgPool>) -> MyUserService {
+    pub fn new(pool: &Arc<PgPool>, media: Arc<MediaStore>) -> MyUserService {
         MyUserService {
             repo: PgUserRepo::new(pool),
+            favourite_repo: PgFavouriteRepo::new(pool),
+            session_repo: PgSessionRepo::new(pool),
+            media,
+        }
+    }
+
+    /// Get the URL of the photo, which is the uploaded image when set
+    async fn photo_url(
+        &self,
+        photo: Option<&str>,
+        photo_asset_id: Option<i32>,
+    ) -> Result<Option<String>, Status> {
+        match (photo, photo_asset_id) {
+            (None, None) => Ok(None),
+            (photo, photo_asset_id) => self
+                .media
+                .resolve_url(photo.unwrap_or_default(), photo_asset_id)
+                .await
+                .map(Some)
+                .map_err(error_status),
         }
     }
 }
 
+fn favourite_from_request(favourite: Option<Favourite>) -> Result<DbFavourite, DomainError> {
+    let favourite = favourite.ok_or_else(|| DomainError::validation("favourite is None"))?;
+    match FavouriteType::from_i32(favourite.favourite_type) {
+        Some(FavouriteType::Team) => Ok(DbFavourite::Team(favourite.id)),
+        Some(FavouriteType::Game) => Ok(DbFavourite::Game(favourite.id)),
+        Some(FavouriteType::Match) => Ok(DbFavourite::Match(favourite.id)),
+        None => Err(DomainError::validation("Unsupported favourite type")),
+    }
+}
+
 #[tonic::async_trait]
 impl UserService for MyUserService {
     async fn auth_user(
@@ -38,7 +80,17 @@ impl UserService for MyUserService {
         match self.repo.get_by_email(&request.email).await {
             Ok(Some(user)) => {
                 if user.user_password != request.password {
-                    return Err(Status::new(Code::from_i32(13), "Wrong password"));
+                    return Err(domain_status(DomainError::unauthenticated(
+                        "Wrong password",
+                    )));
+                }
+                let session_token = match self.session_repo.create(user.id).await {
+                    Ok(token) => token,
+                    Err(err) => return Err(error_status(err)),
+                };
+                // the login does not fail because of the sessions of others
+                if let Err(err) = self.session_repo.delete_expired().await {
+                    println!("failed to delete the expired sessions: {}", err);
                 }
                 match self.repo.get_current_address(user.id).await {
                     Ok(address) => Ok(Response::new(AuthUserReply {
@@ -53,6 +105,7 @@ impl UserService for MyUserService {
                             phone_number: user.phone_number,
                             balance: user.balance,
                             photo: user.photo,
+                            photo_asset_id: user.photo_asset_id,
                             address: Some(Address {
                                 street_name: address.street_name,
                                 street_number: address.street_number,
@@ -63,12 +116,26 @@ impl UserService for MyUserService {
                                 valid_from: address.valid_from,
                             }),
                         }),
+                        session_token,
                     })),
-                    Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+                    Err(err) => Err(error_status(err)),
                 }
             }
-            Ok(None) => Err(Status::new(Code::from_i32(13), "Email doesn't exist")),
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Ok(None) => Err(domain_status(DomainError::unauthenticated(
+                "Email doesn't exist",
+            ))),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn logout_user(
+        &self,
+        request: Request<LogoutUserRequest>,
+    ) -> Result<Response<LogoutUserReply>, Status> {
+        let request = request.into_inner();
+        match self.session_repo.delete(&request.session_token).await {
+            Ok(()) => Ok(Response::new(LogoutUserReply {})),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -91,6 +158,7 @@ impl UserService for MyUserService {
                         phone_number: user.phone_number,
                         balance: user.balance,
                         photo: user.photo,
+                        photo_asset_id: user.photo_asset_id,
                         address: Some(Address {
                             street_name: address.street_name,
                             street_number: address.street_number,
@@ -102,9 +170,9 @@ impl UserService for MyUserService {
                         }),
                     }),
                 })),
-                Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+                Err(err) => Err(error_status(err)),
             },
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Err(err) => Err(error_status(err)),
         }
     }
 
@@ -113,10 +181,13 @@ impl UserService for MyUserService {
         request: Request<CreateUserRequest>,
     ) -> Result<Response<CreateUserReply>, Status> {
         let request = request.into_inner();
-        if let None = request.address {
-            return Err(Status::new(Code::from_i32(13), "address is None"));
-        }
-        let address = request.address.unwrap();
+        let address = match request.address {
+            Some(address) => address,
+            None => return Err(invalid_argument("address is None")),
+        };
+        let photo = self
+            .photo_url(request.photo.as_deref(), request.photo_asset_id)
+            .await?;
 
         let create_user = CreateUser::new(
             &request.first_name,
@@ -126,7 +197,7 @@ impl UserService for MyUserService {
             &request.date_of_birth,
             &request.email,
             &request.phone_number,
-            request.photo.as_deref(),
+            photo.as_deref(),
         );
         let create_user_address = CreateUserAddress::new(
             &address.street_name,
@@ -137,10 +208,20 @@ impl UserService for MyUserService {
             &address.country,
         );
 
-        match self.repo.create(create_user, create_user_address).await {
-            Ok((user_id, _)) => Ok(Response::new(CreateUserReply { id: user_id })),
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+        let user_id = match self.repo.create(create_user, create_user_address).await {
+            Ok((user_id, _)) => user_id,
+            Err(err) => return Err(error_status(err)),
+        };
+        if request.photo_asset_id.is_some() {
+            if let Err(err) = self
+                .repo
+                .set_photo_asset(user_id, request.photo_asset_id)
+                .await
+            {
+                return Err(error_status(err));
+            }
         }
+        Ok(Response::new(CreateUserReply { id: user_id }))
     }
 
     async fn edit_user(
@@ -148,6 +229,9 @@ impl UserService for MyUserService {
         request: Request<EditUserRequest>,
     ) -> Result<Response<EditUserReply>, Status> {
         let request = request.into_inner();
+        let photo = self
+            .photo_url(request.photo.as_deref(), request.photo_asset_id)
+            .await?;
         match self.repo.get(request.id).await {
             Ok(user) => {
                 let create_user = user.edit_user(
@@ -158,14 +242,74 @@ impl UserService for MyUserService {
                     request.date_of_birth.as_deref(),
                     request.email.as_deref(),
                     request.phone_number.as_deref(),
-                    Some(request.photo.as_deref()), // TODO
+                    Some(photo.as_deref()), // TODO
                 );
-                match self.repo.edit(user.id, create_user).await {
+                if let Err(err) = self.repo.edit(user.id, create_user).await {
+                    return Err(error_status(err));
+                }
+                // the photo has been replaced, so an uploaded one is referred to only when set
+                match self
+                    .repo
+                    .set_photo_asset(user.id, request.photo_asset_id)
+                    .await
+                {
                     Ok(()) => Ok(Response::new(EditUserReply {})),
-                    Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+                    Err(err) => Err(error_status(err)),
                 }
             }
-            Err(err) => Err(Status::new(Code::from_i32(13), err.to_string())),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn add_favourite(
+        &self,
+        request: Request<AddFavouriteRequest>,
+    ) -> Result<Response<AddFavouriteReply>, Status> {
+        let request = request.into_inner();
+        let favourite = favourite_from_request(request.favourite).map_err(error_status)?;
+
+        match self.favourite_repo.add(request.user_id, favourite).await {
+            Ok(_) => Ok(Response::new(AddFavouriteReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn remove_favourite(
+        &self,
+        request: Request<RemoveFavouriteRequest>,
+    ) -> Result<Response<RemoveFavouriteReply>, Status> {
+        let request = request.into_inner();
+        let favourite = favourite_from_request(request.favourite).map_err(error_status)?;
+
+        match self.favourite_repo.remove(request.user_id, favourite).await {
+            Ok(()) => Ok(Response::new(RemoveFavouriteReply {})),
+            Err(err) => Err(error_status(err)),
+        }
+    }
+
+    async fn list_favourites(
+        &self,
+        request: Request<ListFavouritesRequest>,
+    ) -> Result<Response<ListFavouritesReply>, Status> {
+        let request = request.into_inner();
+        match self.favourite_repo.get_all(request.user_id).await {
+            Ok(favourites) => Ok(Response::new(ListFavouritesReply {
+                favourites: favourites
+                    .into_iter()
+                    .map(|favourite| {
+                        let (favourite_type, id) = match favourite {
+                            DbFavourite::Team(id) => (FavouriteType::Team, id),
+                            DbFavourite::Game(id) => (FavouriteType::Game, id),
+                            DbFavourite::Match(id) => (FavouriteType::Match, id),
+                        };
+                        Favourite {
+                            favourite_type: favourite_type.into(),
+                            id,
+                        }
+                    })
+                    .collect(),
+            })),
+            Err(err) => Err(error_status(err)),
         }
     }
 }
@@ -0,0 +1,984 @@
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
+
+use crate::publisher::Publisher;
+use crate::repos::Repos;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        external_reference::{ExternalReferenceRepo, PgExternalReferenceRepo},
+        game::{GameRepo, PgGameRepo},
+        game_match::{MatchRepo, PgMatchRepo},
+        team::{PgTeamRepo, TeamRepo},
+    },
+    db_models::{
+        external_reference::ExternalEntity, game::CreateGame, game_match::CreateGameMatch,
+        game_match_event::GameMatchEventType, team::CreateTeam,
+    },
+    error::DomainError,
+    type_storing::time_handling::TimeHandling,
+};
+use event_bus::{
+    events::{MatchChange, MatchChanged},
+    DomainEvent, EventBus,
+};
+
+/// Format of the imported file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    Json,
+    Csv,
+}
+
+impl ImportFormat {
+    /// Tell the format of the file by its extension
+    ///
+    /// Params
+    /// ---
+    /// - path: path to the imported file
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(format) if the file is a `.json` or a `.csv` file
+    /// - Err(_) otherwise
+    pub fn from_path(path: &str) -> anyhow::Result<ImportFormat> {
+        let extension = path.rsplit('.').next().unwrap_or_default();
+        match extension.to_lowercase().as_str() {
+            "json" => Ok(ImportFormat::Json),
+            "csv" => Ok(ImportFormat::Csv),
+            _ => anyhow::bail!(
+                "Cannot tell the format of '{}', expected a .json or a .csv file",
+                path
+            ),
+        }
+    }
+}
+
+/// A game of the imported file
+#[derive(Debug, Clone, Deserialize)]
+pub struct GameRecord {
+    pub external_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub logo: String,
+    pub expected_duration_minutes: Option<i32>,
+}
+
+/// A team of the imported file, with the external IDs of the games it plays
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeamRecord {
+    pub external_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub logo: String,
+    #[serde(default)]
+    pub games: Vec<String>,
+}
+
+/// A match of the imported file, the game and the teams are given by their external IDs
+#[derive(Debug, Clone, Deserialize)]
+pub struct FixtureRecord {
+    pub external_id: String,
+    pub game: String,
+    pub team_one: String,
+    pub team_two: String,
+    pub team_one_ratio: f64,
+    pub team_two_ratio: f64,
+    /// RFC 3339 timestamp, e.g. `2022-03-20T18:00:00Z`
+    pub supposed_start_at: String,
+    #[serde(default)]
+    pub state: String,
+}
+
+/// A record of the imported file along with its position in the file
+#[derive(Debug, Clone)]
+pub struct Row<T> {
+    /// e.g. `line 4` for CSV files, `fixtures[2]` for JSON files
+    pub row: String,
+    pub record: T,
+}
+
+/// Layout of the JSON files, the rows are kept as values so a broken row
+/// does not prevent reading the others
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonFile {
+    #[serde(default)]
+    games: Vec<serde_json::Value>,
+    #[serde(default)]
+    teams: Vec<serde_json::Value>,
+    #[serde(default)]
+    fixtures: Vec<serde_json::Value>,
+}
+
+/// Layout of the CSV files, every line holds a game, a team or a fixture
+/// as told by its `record` column, the columns not used by the record are left empty.
+/// The games played by a team are separated by semicolons.
+#[derive(Deserialize)]
+struct CsvRecord {
+    record: String,
+    external_id: String,
+    name: Option<String>,
+    description: Option<String>,
+    logo: Option<String>,
+    expected_duration_minutes: Option<i32>,
+    games: Option<String>,
+    game: Option<String>,
+    team_one: Option<String>,
+    team_two: Option<String>,
+    team_one_ratio: Option<f64>,
+    team_two_ratio: Option<f64>,
+    supposed_start_at: Option<String>,
+    state: Option<String>,
+}
+
+/// Event announcing the created or updated match
+fn match_changed(match_id: i32, change: MatchChange) -> DomainEvent {
+    DomainEvent::MatchChanged(MatchChanged { match_id, change })
+}
+
+/// Get the value of a column the record cannot do without
+fn required<T>(value: Option<T>, column: &str) -> anyhow::Result<T> {
+    match value {
+        Some(value) => Ok(value),
+        None => anyhow::bail!("The column '{}' is missing", column),
+    }
+}
+
+/// Contents of an imported file
+#[derive(Debug, Clone, Default)]
+pub struct FixtureFile {
+    pub games: Vec<Row<GameRecord>>,
+    pub teams: Vec<Row<TeamRecord>>,
+    pub fixtures: Vec<Row<FixtureRecord>>,
+}
+
+impl FixtureFile {
+    /// Read the imported file, the rows which cannot be read are reported and left out
+    ///
+    /// Params
+    /// ---
+    /// - content: content of the file
+    /// - format: format of the file
+    ///
+    /// Returns
+    /// ---
+    /// - Ok((file, row_errors)) with the readable rows and the errors of the others
+    /// - Err(_) if the file cannot be read at all
+    pub fn parse(
+        content: &str,
+        format: ImportFormat,
+    ) -> anyhow::Result<(FixtureFile, Vec<ImportRowError>)> {
+        match format {
+            ImportFormat::Json => FixtureFile::parse_json(content),
+            ImportFormat::Csv => FixtureFile::parse_csv(content),
+        }
+    }
+
+    fn parse_json(content: &str) -> anyhow::Result<(FixtureFile, Vec<ImportRowError>)> {
+        let json_file: JsonFile = serde_json::from_str(content)?;
+        let mut errors = Vec::new();
+        let file = FixtureFile {
+            games: FixtureFile::json_rows("games", json_file.games, &mut errors),
+            teams: FixtureFile::json_rows("teams", json_file.teams, &mut errors),
+            fixtures: FixtureFile::json_rows("fixtures", json_file.fixtures, &mut errors),
+        };
+        Ok((file, errors))
+    }
+
+    fn json_rows<T: DeserializeOwned>(
+        section: &str,
+        values: Vec<serde_json::Value>,
+        errors: &mut Vec<ImportRowError>,
+    ) -> Vec<Row<T>> {
+        let mut rows = Vec::new();
+        for (index, value) in values.into_iter().enumerate() {
+            let row = format!("{}[{}]", section, index);
+            let external_id = value
+                .get("external_id")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            match serde_json::from_value(value) {
+                Ok(record) => rows.push(Row { row, record }),
+                Err(err) => errors.push(ImportRowError {
+                    row,
+                    external_id,
+                    message: err.to_string(),
+                }),
+            }
+        }
+        rows
+    }
+
+    fn parse_csv(content: &str) -> anyhow::Result<(FixtureFile, Vec<ImportRowError>)> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(content.as_bytes());
+        let headers = reader.headers()?.clone();
+
+        let mut file = FixtureFile::default();
+        let mut errors = Vec::new();
+        for result in reader.records() {
+            let string_record = match result {
+                Ok(string_record) => string_record,
+                Err(err) => {
+                    let row = err.position().map_or_else(
+                        || String::from("unknown line"),
+                        |p| format!("line {}", p.line()),
+                    );
+                    errors.push(ImportRowError {
+                        row,
+                        external_id: String::new(),
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let row = string_record.position().map_or_else(
+                || String::from("unknown line"),
+                |p| format!("line {}", p.line()),
+            );
+            let external_id = headers
+                .iter()
+                .position(|header| header == "external_id")
+                .and_then(|index| string_record.get(index))
+                .unwrap_or_default()
+                .to_string();
+
+            if let Err(err) = file.add_csv_record(row.clone(), &string_record, &headers) {
+                errors.push(ImportRowError {
+                    row,
+                    external_id,
+                    message: err.to_string(),
+                });
+            }
+        }
+        Ok((file, errors))
+    }
+
+    fn add_csv_record(
+        &mut self,
+        row: String,
+        string_record: &csv::StringRecord,
+        headers: &csv::StringRecord,
+    ) -> anyhow::Result<()> {
+        let csv_record: CsvRecord = string_record.deserialize(Some(headers))?;
+        match csv_record.record.to_lowercase().as_str() {
+            "game" => self.games.push(Row {
+                row,
+                record: GameRecord {
+                    external_id: csv_record.external_id,
+                    name: required(csv_record.name, "name")?,
+                    description: csv_record.description.unwrap_or_default(),
+                    logo: csv_record.logo.unwrap_or_default(),
+                    expected_duration_minutes: csv_record.expected_duration_minutes,
+                },
+            }),
+            "team" => self.teams.push(Row {
+                row,
+                record: TeamRecord {
+                    external_id: csv_record.external_id,
+                    name: required(csv_record.name, "name")?,
+                    description: csv_record.description.unwrap_or_default(),
+                    logo: csv_record.logo.unwrap_or_default(),
+                    games: csv_record
+                        .games
+                        .unwrap_or_default()
+                        .split(';')
+                        .map(str::trim)
+                        .filter(|game| !game.is_empty())
+                        .map(String::from)
+                        .collect(),
+                },
+            }),
+            "fixture" => self.fixtures.push(Row {
+                row,
+                record: FixtureRecord {
+                    external_id: csv_record.external_id,
+                    game: required(csv_record.game, "game")?,
+                    team_one: required(csv_record.team_one, "team_one")?,
+                    team_two: required(csv_record.team_two, "team_two")?,
+                    team_one_ratio: required(csv_record.team_one_ratio, "team_one_ratio")?,
+                    team_two_ratio: required(csv_record.team_two_ratio, "team_two_ratio")?,
+                    supposed_start_at: required(csv_record.supposed_start_at, "supposed_start_at")?,
+                    state: csv_record.state.unwrap_or_default(),
+                },
+            }),
+            other => anyhow::bail!(
+                "Unknown record '{}', expected 'game', 'team' or 'fixture'",
+                other
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// A row which could not be imported
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRowError {
+    pub row: String,
+    pub external_id: String,
+    pub message: String,
+}
+
+/// What the import has done (or would do, in a dry run) with the records of one kind
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ImportCounts {
+    pub created: u32,
+    pub updated: u32,
+    pub unchanged: u32,
+}
+
+impl ImportCounts {
+    fn count(&mut self, change: Change) {
+        match change {
+            Change::Created => self.created += 1,
+            Change::Updated => self.updated += 1,
+            Change::Unchanged => self.unchanged += 1,
+        }
+    }
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub games: ImportCounts,
+    pub teams: ImportCounts,
+    pub fixtures: ImportCounts,
+    /// teams newly registered to play a game
+    pub links_created: u32,
+    pub errors: Vec<ImportRowError>,
+    pub created_matches: Vec<i32>,
+    pub updated_matches: Vec<i32>,
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.dry_run {
+            writeln!(f, "Dry run, nothing has been changed")?;
+        }
+        for (kind, counts) in [
+            ("games", self.games),
+            ("teams", self.teams),
+            ("fixtures", self.fixtures),
+        ] {
+            writeln!(
+                f,
+                "{}: {} created, {} updated, {} unchanged",
+                kind, counts.created, counts.updated, counts.unchanged
+            )?;
+        }
+        writeln!(f, "team and game links created: {}", self.links_created)?;
+        writeln!(f, "rows failed: {}", self.errors.len())?;
+        for error in self.errors.iter() {
+            writeln!(
+                f,
+                "  {} ({}): {}",
+                error.row, error.external_id, error.message
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Change {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+/// What has become of a game or a team referenced by an external ID
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Resolved {
+    Id(i32),
+    /// not created, as this is a dry run
+    Pending,
+    /// its row could not be imported
+    Failed,
+}
+
+/// State of a single import
+struct ImportRun {
+    dry_run: bool,
+    report: ImportReport,
+    games: HashMap<String, Resolved>,
+    teams: HashMap<String, Resolved>,
+    fixtures: HashSet<String>,
+    /// (team, game) external ID pairs of the links declared in the file
+    declared_links: HashSet<(String, String)>,
+}
+
+impl ImportRun {
+    fn row_failed(&mut self, row: &str, external_id: &str, err: anyhow::Error) {
+        self.report.errors.push(ImportRowError {
+            row: String::from(row),
+            external_id: String::from(external_id),
+            message: err.to_string(),
+        });
+    }
+}
+
+/// Creates and updates the games, teams and matches of an imported file.
+///
+/// The records are matched by their external IDs, so importing a file again updates
+/// the records instead of duplicating them. The games and teams which have no external ID yet
+/// are matched by their names, so the records created by hand are not duplicated either.
+/// Every row is imported on its own, a broken row is reported and the others are imported anyway.
+/// Every row is stored in a single transaction along with its external ID,
+/// the created and updated matches are announced like the admins' edits are.
+pub struct FixtureImporter {
+    repos: Repos,
+    publisher: Publisher,
+}
+
+impl FixtureImporter {
+    pub fn new(pool: &Arc<PgPool>, bus: EventBus) -> FixtureImporter {
+        FixtureImporter {
+            repos: Repos::new(pool),
+            publisher: Publisher::new(pool, bus),
+        }
+    }
+
+    /// Read the file and import its rows
+    ///
+    /// Params
+    /// ---
+    /// - content: content of the file
+    /// - format: format of the file
+    /// - dry_run: only validate the rows and count what would be changed
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(report) with the counts and the errors of the rows which could not be read or imported
+    /// - Err(_) if the file cannot be read at all or the existing records could not be read
+    pub async fn import_content(
+        &self,
+        content: &str,
+        format: ImportFormat,
+        dry_run: bool,
+    ) -> anyhow::Result<ImportReport> {
+        let (file, mut errors) = FixtureFile::parse(content, format)
+            .map_err(|err| DomainError::validation(err.to_string()))?;
+        let mut report = self.import(&file, dry_run).await?;
+        errors.append(&mut report.errors);
+        report.errors = errors;
+        Ok(report)
+    }
+
+    /// Import the games, then the teams and finally the fixtures of the file
+    ///
+    /// Params
+    /// ---
+    /// - file: the parsed file
+    /// - dry_run: only validate the rows and count what would be changed
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(report) with the counts and the errors of the rows which could not be imported
+    /// - Err(_) if the existing records could not be read
+    pub async fn import(&self, file: &FixtureFile, dry_run: bool) -> anyhow::Result<ImportReport> {
+        let mut run = ImportRun {
+            dry_run,
+            report: ImportReport {
+                dry_run,
+                ..ImportReport::default()
+            },
+            games: HashMap::new(),
+            teams: HashMap::new(),
+            fixtures: HashSet::new(),
+            declared_links: HashSet::new(),
+        };
+
+        for Row { row, record } in file.games.iter() {
+            if let Err(err) = FixtureImporter::check_external_id(&record.external_id, &run.games) {
+                run.row_failed(row, &record.external_id, err);
+                continue;
+            }
+            let resolved = match self.import_game(&mut run, record).await {
+                Ok((resolved, change)) => {
+                    run.report.games.count(change);
+                    resolved
+                }
+                Err(err) => {
+                    run.row_failed(row, &record.external_id, err);
+                    Resolved::Failed
+                }
+            };
+            run.games.insert(record.external_id.clone(), resolved);
+        }
+
+        for Row { row, record } in file.teams.iter() {
+            if let Err(err) = FixtureImporter::check_external_id(&record.external_id, &run.teams) {
+                run.row_failed(row, &record.external_id, err);
+                continue;
+            }
+            let resolved = match self.import_team(&mut run, record).await {
+                Ok((resolved, change)) => {
+                    run.report.teams.count(change);
+                    resolved
+                }
+                Err(err) => {
+                    run.row_failed(row, &record.external_id, err);
+                    Resolved::Failed
+                }
+            };
+            run.teams.insert(record.external_id.clone(), resolved);
+        }
+
+        for Row { row, record } in file.fixtures.iter() {
+            if record.external_id.trim().is_empty() {
+                run.row_failed(
+                    row,
+                    &record.external_id,
+                    anyhow::anyhow!("The external ID is missing"),
+                );
+                continue;
+            }
+            if !run.fixtures.insert(record.external_id.clone()) {
+                run.row_failed(
+                    row,
+                    &record.external_id,
+                    anyhow::anyhow!("The external ID is used by another row of the file"),
+                );
+                continue;
+            }
+            match self.import_fixture(&mut run, record).await {
+                Ok(change) => run.report.fixtures.count(change),
+                Err(err) => run.row_failed(row, &record.external_id, err),
+            }
+        }
+
+        Ok(run.report)
+    }
+
+    /// Check the external ID is given and not used by another row of the same kind
+    fn check_external_id(
+        external_id: &str,
+        imported: &HashMap<String, Resolved>,
+    ) -> anyhow::Result<()> {
+        if external_id.trim().is_empty() {
+            anyhow::bail!("The external ID is missing");
+        }
+        if imported.contains_key(external_id) {
+            anyhow::bail!("The external ID is used by another row of the file");
+        }
+        Ok(())
+    }
+
+    /// Find a record imported before by its external ID
+    async fn find_imported(
+        &self,
+        entity: ExternalEntity,
+        external_id: &str,
+    ) -> anyhow::Result<Option<i32>> {
+        let internal_id = match self
+            .repos
+            .external_reference
+            .find(entity, external_id)
+            .await?
+        {
+            Some(internal_id) => internal_id,
+            None => return Ok(None),
+        };
+
+        // the record might have been deleted since
+        let exists = match entity {
+            ExternalEntity::Game => self.repos.game.get(internal_id).await.is_ok(),
+            ExternalEntity::Team => self.repos.team.get(internal_id).await.is_ok(),
+            ExternalEntity::Match => self.repos.game_match.get(internal_id).await.is_ok(),
+        };
+        Ok(if exists { Some(internal_id) } else { None })
+    }
+
+    /// Get the game or the team referenced by a row, either from the file or imported before
+    async fn resolve(
+        &self,
+        run: &mut ImportRun,
+        entity: ExternalEntity,
+        external_id: &str,
+    ) -> anyhow::Result<Resolved> {
+        let (imported, kind) = match entity {
+            ExternalEntity::Game => (&run.games, "game"),
+            _ => (&run.teams, "team"),
+        };
+        match imported.get(external_id) {
+            Some(Resolved::Failed) => {
+                anyhow::bail!("The {} '{}' could not be imported", kind, external_id)
+            }
+            Some(resolved) => return Ok(*resolved),
+            None => {}
+        }
+
+        match self.find_imported(entity, external_id).await? {
+            Some(internal_id) => {
+                let resolved = Resolved::Id(internal_id);
+                match entity {
+                    ExternalEntity::Game => run.games.insert(String::from(external_id), resolved),
+                    _ => run.teams.insert(String::from(external_id), resolved),
+                };
+                Ok(resolved)
+            }
+            None => anyhow::bail!("Unknown {} '{}'", kind, external_id),
+        }
+    }
+
+    /// Create or update a game
+    // `Option::is_none_or` suggested instead of `map_or` needs Rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    async fn import_game(
+        &self,
+        run: &mut ImportRun,
+        record: &GameRecord,
+    ) -> anyhow::Result<(Resolved, Change)> {
+        if record.name.trim().is_empty() {
+            anyhow::bail!("The name of the game is missing");
+        }
+        if let Some(minutes) = record.expected_duration_minutes {
+            if minutes <= 0 {
+                anyhow::bail!("The expected duration of the game has to be positive");
+            }
+        }
+
+        let mut existing_id = self
+            .find_imported(ExternalEntity::Game, &record.external_id)
+            .await?;
+        if existing_id.is_none() {
+            existing_id = self
+                .repos
+                .game
+                .get_all(true)
+                .await?
+                .into_iter()
+                .find(|game| game.name == record.name)
+                .map(|game| game.id);
+        }
+
+        let mut edited_game = CreateGame::new(&record.name, &record.description, &record.logo);
+        edited_game.expected_duration_minutes = record.expected_duration_minutes;
+
+        let change = match existing_id {
+            Some(game_id) => {
+                let game = self.repos.game.get(game_id).await?;
+                let unchanged = game.name == record.name
+                    && game.description == record.description
+                    && game.logo_url == record.logo
+                    && record
+                        .expected_duration_minutes
+                        .map_or(true, |minutes| minutes == game.expected_duration_minutes);
+                if unchanged {
+                    Change::Unchanged
+                } else {
+                    Change::Updated
+                }
+            }
+            None => Change::Created,
+        };
+        if run.dry_run {
+            return Ok((existing_id.map_or(Resolved::Pending, Resolved::Id), change));
+        }
+
+        let game_id = self
+            .publisher
+            .commit(|connection, _| {
+                let game_id = match existing_id {
+                    Some(game_id) => {
+                        if change == Change::Updated {
+                            PgGameRepo::edit_in(connection, game_id, edited_game)?;
+                        }
+                        game_id
+                    }
+                    None => PgGameRepo::create_in(connection, edited_game)?,
+                };
+                PgExternalReferenceRepo::link_in(
+                    connection,
+                    ExternalEntity::Game,
+                    &record.external_id,
+                    game_id,
+                )?;
+                Ok(game_id)
+            })
+            .await?;
+        Ok((Resolved::Id(game_id), change))
+    }
+
+    /// Create or update a team and register it to play its games
+    async fn import_team(
+        &self,
+        run: &mut ImportRun,
+        record: &TeamRecord,
+    ) -> anyhow::Result<(Resolved, Change)> {
+        if record.name.trim().is_empty() {
+            anyhow::bail!("The name of the team is missing");
+        }
+
+        // every game has to be known before anything is changed
+        let mut games = Vec::new();
+        for game in record.games.iter() {
+            let resolved = self.resolve(run, ExternalEntity::Game, game).await?;
+            games.push((game, resolved));
+        }
+
+        let mut existing_id = self
+            .find_imported(ExternalEntity::Team, &record.external_id)
+            .await?;
+        if existing_id.is_none() {
+            existing_id = self
+                .repos
+                .team
+                .get_all(true)
+                .await?
+                .into_iter()
+                .find(|team| team.name == record.name)
+                .map(|team| team.id);
+        }
+
+        let edited_team = CreateTeam::new(&record.name, &record.description, &record.logo);
+        let change = match existing_id {
+            Some(team_id) => {
+                let team = self.repos.team.get(team_id).await?;
+                let unchanged = team.name == record.name
+                    && team.description == record.description
+                    && team.logo == record.logo;
+                if unchanged {
+                    Change::Unchanged
+                } else {
+                    Change::Updated
+                }
+            }
+            None => Change::Created,
+        };
+
+        let played: HashSet<i32> = match existing_id {
+            Some(team_id) => self
+                .repos
+                .team
+                .games_played(team_id)
+                .await?
+                .into_iter()
+                .map(|game| game.id)
+                .collect(),
+            None => HashSet::new(),
+        };
+
+        let mut links_created = 0;
+        let mut new_games = Vec::new();
+        for (game, resolved_game) in games {
+            run.declared_links
+                .insert((record.external_id.clone(), game.clone()));
+            // otherwise the game would be created, so would be the link
+            if let Resolved::Id(game_id) = resolved_game {
+                if played.contains(&game_id) {
+                    continue;
+                }
+                new_games.push(game_id);
+            }
+            links_created += 1;
+        }
+
+        if run.dry_run {
+            run.report.links_created += links_created;
+            return Ok((existing_id.map_or(Resolved::Pending, Resolved::Id), change));
+        }
+
+        let team_id = self
+            .publisher
+            .commit(|connection, _| {
+                let team_id = match existing_id {
+                    Some(team_id) => {
+                        if change == Change::Updated {
+                            PgTeamRepo::edit_in(connection, team_id, edited_team)?;
+                        }
+                        team_id
+                    }
+                    None => PgTeamRepo::create_in(connection, edited_team)?,
+                };
+                PgExternalReferenceRepo::link_in(
+                    connection,
+                    ExternalEntity::Team,
+                    &record.external_id,
+                    team_id,
+                )?;
+                for game_id in new_games.iter() {
+                    PgTeamRepo::add_to_game_in(connection, team_id, *game_id)?;
+                }
+                Ok(team_id)
+            })
+            .await?;
+        run.report.links_created += links_created;
+        Ok((Resolved::Id(team_id), change))
+    }
+
+    /// Check the team plays the game, either registered before or by the file
+    async fn check_plays(
+        &self,
+        run: &ImportRun,
+        team: (&str, Resolved),
+        game: (&str, Resolved),
+    ) -> anyhow::Result<()> {
+        if run
+            .declared_links
+            .contains(&(String::from(team.0), String::from(game.0)))
+        {
+            return Ok(());
+        }
+        if let (Resolved::Id(team_id), Resolved::Id(game_id)) = (team.1, game.1) {
+            let played = self.repos.team.games_played(team_id).await?;
+            if played.iter().any(|played_game| played_game.id == game_id) {
+                return Ok(());
+            }
+        }
+        anyhow::bail!("The team '{}' does not play the game '{}'", team.0, game.0)
+    }
+
+    /// Create or update a match
+    async fn import_fixture(
+        &self,
+        run: &mut ImportRun,
+        record: &FixtureRecord,
+    ) -> anyhow::Result<Change> {
+        if record.team_one == record.team_two {
+            anyhow::bail!("A team cannot play against itself");
+        }
+        for ratio in [record.team_one_ratio, record.team_two_ratio] {
+            if !ratio.is_finite() || ratio < 1.0 {
+                anyhow::bail!("The ratio {} is invalid, it has to be at least 1", ratio);
+            }
+        }
+        let supposed_start_at = match record.supposed_start_at.parse::<DateTime<Utc>>() {
+            Ok(supposed_start_at) => supposed_start_at,
+            Err(_) => anyhow::bail!(
+                "Invalid start '{}', expected a time like 2022-03-20T18:00:00Z",
+                record.supposed_start_at
+            ),
+        };
+
+        let game = self
+            .resolve(run, ExternalEntity::Game, &record.game)
+            .await?;
+        let team_one = self
+            .resolve(run, ExternalEntity::Team, &record.team_one)
+            .await?;
+        let team_two = self
+            .resolve(run, ExternalEntity::Team, &record.team_two)
+            .await?;
+        self.check_plays(run, (&record.team_one, team_one), (&record.game, game))
+            .await?;
+        self.check_plays(run, (&record.team_two, team_two), (&record.game, game))
+            .await?;
+
+        let team_one_ratio = record.team_one_ratio.to_string();
+        let team_two_ratio = record.team_two_ratio.to_string();
+
+        let existing_id = self
+            .find_imported(ExternalEntity::Match, &record.external_id)
+            .await?;
+        let match_id = match existing_id {
+            Some(match_id) => match_id,
+            None => {
+                if supposed_start_at < Utc::now() {
+                    anyhow::bail!("The match cannot be scheduled in the past");
+                }
+                if run.dry_run {
+                    return Ok(Change::Created);
+                }
+                // the game and the teams exist, as this is not a dry run
+                let (game_id, team_one_id, team_two_id) = match (game, team_one, team_two) {
+                    (
+                        Resolved::Id(game_id),
+                        Resolved::Id(team_one_id),
+                        Resolved::Id(team_two_id),
+                    ) => (game_id, team_one_id, team_two_id),
+                    _ => anyhow::bail!("The game or the teams of the match have not been created"),
+                };
+                let match_id = self
+                    .publisher
+                    .commit(|connection, events| {
+                        let match_id = PgMatchRepo::create_in(
+                            connection,
+                            CreateGameMatch::new(
+                                game_id,
+                                team_one_id,
+                                team_two_id,
+                                &team_one_ratio,
+                                &team_two_ratio,
+                                supposed_start_at,
+                                &record.state,
+                            ),
+                        )?;
+                        PgExternalReferenceRepo::link_in(
+                            connection,
+                            ExternalEntity::Match,
+                            &record.external_id,
+                            match_id,
+                        )?;
+                        events.push(match_changed(match_id, MatchChange::Created));
+                        Ok(match_id)
+                    })
+                    .await?;
+                run.report.created_matches.push(match_id);
+                return Ok(Change::Created);
+            }
+        };
+
+        let game_match = self.repos.game_match.get(match_id).await?;
+        if game != Resolved::Id(game_match.game_id)
+            || team_one != Resolved::Id(game_match.team_one_id)
+            || team_two != Resolved::Id(game_match.team_two_id)
+        {
+            anyhow::bail!("The game and the teams of an imported match cannot be changed");
+        }
+
+        let ratios_changed = game_match.team_one_ratio.parse::<f64>().ok()
+            != Some(record.team_one_ratio)
+            || game_match.team_two_ratio.parse::<f64>().ok() != Some(record.team_two_ratio);
+        let start_changed =
+            TimeHandling::load_timestamp(&game_match.supposed_start_at)? != supposed_start_at;
+        let state_changed = game_match.state != record.state;
+        if !ratios_changed && !start_changed && !state_changed {
+            return Ok(Change::Unchanged);
+        }
+
+        let match_update = game_match.to_update(
+            Some(&team_one_ratio),
+            Some(&team_two_ratio),
+            if start_changed {
+                Some(supposed_start_at)
+            } else {
+                None
+            },
+            Some(&record.state),
+        )?;
+        if run.dry_run {
+            let newest_event = self.repos.game_match.newest_event(match_id).await?;
+            if !newest_event
+                .extract_event()?
+                .cmp_type(&GameMatchEventType::Upcoming)
+            {
+                anyhow::bail!("Only the matches which have not started yet can be updated");
+            }
+        } else {
+            self.publisher
+                .commit(|connection, events| {
+                    PgMatchRepo::update_in(connection, match_id, match_update)?;
+                    events.push(match_changed(match_id, MatchChange::Updated));
+                    Ok(())
+                })
+                .await?;
+            run.report.updated_matches.push(match_id);
+        }
+        Ok(Change::Updated)
+    }
+}
@@ -1,14 +1,32 @@
-use anyhow::Context;
-use database_layer::connection::db_connect_create_pool;
+use database_layer::{
+    connection::{db_connect_create_pool, db_connect_create_pool_with_size, PgPool},
+    db_access::{repo::Repo, session::PgSessionRepo},
+};
+use event_bus::EventBus;
 use futures::try_join;
-use std::{collections::HashMap, sync::Arc};
+use std::sync::Arc;
 use tokio::sync::Mutex;
 use tonic::transport::Server;
 
-use ws_layer::RouteClients;
+use config::Config;
+use ws_layer::ClientRegistry;
 
+mod broadcast;
+pub mod clock;
+pub mod config;
+pub mod fanout;
 mod handlers;
+pub mod importer;
+pub mod match_feed;
+pub mod media_store;
+mod publisher;
 mod repos;
+pub mod scheduler;
+mod settlement;
+pub mod standings;
+pub mod status;
+pub mod subscribers;
+pub mod team_stats;
 mod transform;
 
 mod bet {
@@ -29,69 +47,194 @@ mod user {
 mod team {
     tonic::include_proto!("team");
 }
+mod tournament {
+    tonic::include_proto!("tournament");
+}
+// the generated `envelope` message holds its payload in a module of the same name
+#[allow(clippy::module_inception)]
+pub mod envelope {
+    tonic::include_proto!("envelope");
+}
+mod fixture_import {
+    tonic::include_proto!("fixture_import");
+}
+mod media {
+    tonic::include_proto!("media");
+}
+mod error {
+    tonic::include_proto!("error");
+}
 
 use bet::bet_service_server::BetServiceServer;
+use fixture_import::fixture_import_service_server::FixtureImportServiceServer;
 use game::game_service_server::GameServiceServer;
 use game_match::match_service_server::MatchServiceServer;
+use media::media_service_server::MediaServiceServer;
 use team::team_service_server::TeamServiceServer;
 use ticket::ticket_service_server::TicketServiceServer;
+use tournament::tournament_service_server::TournamentServiceServer;
 use user::user_service_server::UserServiceServer;
 
 async fn serve_grpc_server(
-    server_address: &str,
-    database_url: &str,
-    ws_route_clients: RouteClients,
+    config: &Config,
+    db_conn_pool: Arc<PgPool>,
+    bus: EventBus,
+    fanout: EventBus,
+    team_stats: Arc<team_stats::TeamStatsCache>,
+    media_store: Arc<media_store::MediaStore>,
 ) -> anyhow::Result<()> {
-    let db_conn_pool = Arc::new(db_connect_create_pool(&database_url).await?);
-    let bet_clients = ws_route_clients
-        .lock()
-        .await
-        .get("bet")
-        .context("bet clients are absent")?
-        .clone();
-    let match_clients = ws_route_clients
-        .lock()
-        .await
-        .get("match")
-        .context("match clients are absent")?
-        .clone();
-
-    let bet_service = handlers::bet::MyBetService::new(&db_conn_pool, bet_clients);
+    let limits = config.betting.clone();
+    let bet_service = handlers::bet::MyBetService::new(&db_conn_pool, bus.clone(), limits.clone());
     let ticket_service =
-        handlers::ticket::MyTicketService::new(&db_conn_pool, match_clients.clone());
-    let game_match_service =
-        handlers::game_match::MyMatchService::new(&db_conn_pool, match_clients);
-    let game_service = handlers::game::MyGameService::new(&db_conn_pool);
-    let user_service = handlers::user::MyUserService::new(&db_conn_pool);
-    let team_service = handlers::team::MyTeamService::new(&db_conn_pool);
+        handlers::ticket::MyTicketService::new(&db_conn_pool, bus.clone(), limits.clone());
+    let fixture_import_service =
+        handlers::fixture_import::MyFixtureImportService::new(&db_conn_pool, bus.clone());
+    let tournament_service =
+        handlers::tournament::MyTournamentService::new(&db_conn_pool, bus.clone(), limits);
+    let game_match_service = handlers::game_match::MyMatchService::new(&db_conn_pool, bus, fanout);
+    let game_service = handlers::game::MyGameService::new(&db_conn_pool, media_store.clone());
+    let user_service = handlers::user::MyUserService::new(&db_conn_pool, media_store.clone());
+    let team_service =
+        handlers::team::MyTeamService::new(&db_conn_pool, team_stats, media_store.clone());
+    let media_service = handlers::media::MyMediaService::new(media_store);
+
+    // browsers of the configured origins only, any origin when none is configured
+    let web = match config.server.cors_origins.is_empty() {
+        true => tonic_web::config(),
+        false => tonic_web::config().allow_origins(config.server.cors_origins.clone()),
+    };
 
     Server::builder()
         .accept_http1(true)
-        .add_service(tonic_web::enable(BetServiceServer::new(bet_service)))
-        .add_service(tonic_web::enable(TicketServiceServer::new(ticket_service)))
-        .add_service(tonic_web::enable(MatchServiceServer::new(
-            game_match_service,
-        )))
-        .add_service(tonic_web::enable(GameServiceServer::new(game_service)))
-        .add_service(tonic_web::enable(UserServiceServer::new(user_service)))
-        .add_service(tonic_web::enable(TeamServiceServer::new(team_service)))
-        .serve(server_address.parse()?)
+        .add_service(web.enable(BetServiceServer::new(bet_service)))
+        .add_service(web.enable(TicketServiceServer::new(ticket_service)))
+        .add_service(web.enable(MatchServiceServer::new(game_match_service)))
+        .add_service(web.enable(GameServiceServer::new(game_service)))
+        .add_service(web.enable(UserServiceServer::new(user_service)))
+        .add_service(web.enable(TeamServiceServer::new(team_service)))
+        .add_service(web.enable(FixtureImportServiceServer::new(fixture_import_service)))
+        .add_service(web.enable(TournamentServiceServer::new(tournament_service)))
+        .add_service(web.enable(MediaServiceServer::new(media_service)))
+        .serve(config.server.grpc_address)
         .await?;
     Ok(())
 }
 
-pub async fn run_grpc_server(server_address: &str, database_url: &str) -> anyhow::Result<()> {
-    let ws_route_clients = Arc::new(Mutex::new(HashMap::new()));
-    {
-        let bet_clients = Arc::new(Mutex::new(HashMap::new()));
-        let match_clients = Arc::new(Mutex::new(HashMap::new()));
-        let mut ws_route_clients_locked = ws_route_clients.lock().await;
-        ws_route_clients_locked.insert("bet".into(), bet_clients);
-        ws_route_clients_locked.insert("match".into(), match_clients);
-    }
+/// Run the gRPC server along with the websocket server, the scheduler and the event subscribers
+///
+/// Params
+/// ---
+/// - config: configuration of the backend, see `Config::load`
+///
+/// Returns
+/// ---
+/// - Ok(()) once all the servers have stopped
+/// - Err(_) if one of them failed
+pub async fn run_grpc_server(config: Config) -> anyhow::Result<()> {
+    let database_url = config.database.url.as_str();
+    let db_conn_pool =
+        Arc::new(db_connect_create_pool_with_size(database_url, config.database.pool_size).await?);
+    let ws_clients = Arc::new(Mutex::new(ClientRegistry::new(
+        config.ws_settings(),
+        broadcast::resync_required,
+    )));
+
+    let ws_sessions = Arc::new(PgSessionRepo::new(&db_conn_pool));
+
+    // events published by this instance, settled and stored in the outbox along with their changes
+    let bus = EventBus::default();
+
+    // events published by all instances, read from the outbox
+    let fanout = EventBus::default();
+    // the websocket messages of an event are all sent before the ones of the next event,
+    // so a resuming client continues after the position of the last event it has received
+    fanout.attach(
+        "websocket forwarder and notifications",
+        (
+            subscribers::ws::WsForwarder::new(
+                &db_conn_pool,
+                ws_clients.clone(),
+                config.alerts.admin_user_ids.clone(),
+            ),
+            subscribers::notifications::Notifier::new(&db_conn_pool, ws_clients.clone()),
+        ),
+    );
+    let latest_bets = Arc::new(subscribers::latest_bets::LatestBetsFeed::new(
+        &db_conn_pool,
+        ws_clients.clone(),
+        config.latest_bets.clone(),
+    ));
+    fanout.attach("latest bets", latest_bets.clone());
+    tokio::spawn(latest_bets.run());
+    let team_stats = Arc::new(team_stats::TeamStatsCache::new(&db_conn_pool));
+    fanout.attach(
+        "team stats",
+        subscribers::team_stats::TeamStatsInvalidator::new(team_stats.clone()),
+    );
 
-    let ws_server_coro = ws_layer::run_ws_server(ws_route_clients.clone());
-    let grpc_server_coro = serve_grpc_server(server_address, database_url, ws_route_clients);
-    try_join!(ws_server_coro, grpc_server_coro)?;
+    // the uploaded images are served along with the websockets
+    let media_store = Arc::new(media_store::MediaStore::new(
+        &db_conn_pool,
+        config.media_settings(),
+    ));
+    let ws_server_coro = ws_layer::run_ws_server(
+        ws_clients,
+        ws_sessions,
+        media_store.settings().directory.clone(),
+        config.server.ws_address,
+        config.server.cors_origins.clone(),
+    );
+    let fanout_coro = fanout::run_fanout(&db_conn_pool, database_url, fanout.clone());
+    let scheduler = scheduler::Scheduler::new(
+        &db_conn_pool,
+        bus.clone(),
+        Arc::new(clock::SystemClock),
+        config.scheduler_settings(),
+    );
+    let scheduler_coro = scheduler.run(database_url);
+    // a recorded feed of the data provider, if configured, replayed through the bus
+    let feed_coro = match_feed::replay_configured(&db_conn_pool, bus.clone(), &config);
+    let grpc_server_coro = serve_grpc_server(
+        &config,
+        db_conn_pool.clone(),
+        bus,
+        fanout,
+        team_stats,
+        media_store,
+    );
+    try_join!(
+        ws_server_coro,
+        fanout_coro,
+        scheduler_coro,
+        feed_coro,
+        grpc_server_coro
+    )?;
     Ok(())
 }
+
+/// Import a file of games, teams and fixtures without running the server,
+/// the created and updated matches are announced through the outbox to the running instances
+///
+/// Params
+/// ---
+/// - database_url: connection string of the database
+/// - content: content of the imported file
+/// - format: format of the imported file
+/// - dry_run: only validate the file and report what would be changed
+///
+/// Returns
+/// ---
+/// - Ok(report) with the counts and the errors of the rows which could not be imported
+/// - Err(_) if the file or the database could not be read
+pub async fn import_fixtures(
+    database_url: &str,
+    content: &str,
+    format: importer::ImportFormat,
+    dry_run: bool,
+) -> anyhow::Result<importer::ImportReport> {
+    let db_conn_pool = Arc::new(db_connect_create_pool(database_url).await?);
+    // nothing reacts to the events within this process, they are only stored in the outbox
+    importer::FixtureImporter::new(&db_conn_pool, EventBus::default())
+        .import_content(content, format, dry_run)
+        .await
+}
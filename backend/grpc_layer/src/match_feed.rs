@@ -0,0 +1,491 @@
+use serde::Deserialize;
+use std::{fmt, sync::Arc, time::Duration, vec::IntoIter};
+
+use crate::config::Config;
+use crate::importer::{FixtureFile, FixtureImporter, FixtureRecord, Row};
+use crate::publisher::Publisher;
+use crate::repos::Repos;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        external_reference::ExternalReferenceRepo,
+        game_match::{MatchRepo, PgMatchRepo},
+        match_timeline::{PgTimelineRepo, TimelineRepo},
+    },
+    db_models::{
+        external_reference::ExternalEntity,
+        game_match_event::GameMatchEventType,
+        match_timeline_event::{Score, TimelineEventPayload},
+    },
+};
+use event_bus::{
+    events::{MatchChange, MatchChanged, MatchPhase, TimelineEventRecorded},
+    DomainEvent, EventBus,
+};
+
+/// Event announcing the change of the match
+fn match_changed(match_id: i32, change: MatchChange) -> DomainEvent {
+    DomainEvent::MatchChanged(MatchChanged { match_id, change })
+}
+
+/// A message of the data provider, the matches and the teams are given by their external IDs
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FeedMessage {
+    /// a new or changed match, imported like a fixture of an imported file
+    Fixture(FixtureRecord),
+    /// the match has started
+    Live { match_id: String },
+    /// new odds of the match
+    Odds {
+        match_id: String,
+        team_one_ratio: f64,
+        team_two_ratio: f64,
+    },
+    /// new score of a live match
+    Score {
+        match_id: String,
+        team_one: i32,
+        team_two: i32,
+    },
+    /// the match has ended, the winner is given by the external ID of the team
+    Ended { match_id: String, winner: String },
+}
+
+impl FeedMessage {
+    /// External ID of the match the message is about
+    pub fn match_id(&self) -> &str {
+        match self {
+            FeedMessage::Fixture(record) => &record.external_id,
+            FeedMessage::Live { match_id }
+            | FeedMessage::Odds { match_id, .. }
+            | FeedMessage::Score { match_id, .. }
+            | FeedMessage::Ended { match_id, .. } => match_id,
+        }
+    }
+}
+
+/// Source of the messages of a data provider
+#[event_bus::async_trait]
+pub trait MatchFeed: Send {
+    /// Wait for the next message of the provider
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(Some(row)) with the message and its position in the feed
+    /// - Ok(None) once the feed has ended
+    /// - Err(_) if the message could not be read, the feed goes on with the next one
+    async fn next_message(&mut self) -> anyhow::Result<Option<Row<FeedMessage>>>;
+}
+
+/// Line of a recorded feed
+#[derive(Deserialize)]
+struct FeedLine {
+    /// time since the previous message, waited for by a paced replay
+    #[serde(default)]
+    delay_ms: u64,
+    #[serde(flatten)]
+    message: FeedMessage,
+}
+
+/// Replays a feed recorded as a JSON-lines file, e.g.
+/// `{"type": "odds", "match_id": "m-1", "team_one_ratio": 1.6, "team_two_ratio": 2.3}`,
+/// so the ingestion can be run without the data provider. Empty lines are left out.
+pub struct FileReplayFeed {
+    lines: IntoIter<(usize, String)>,
+    paced: bool,
+}
+
+impl FileReplayFeed {
+    /// Create a replay of the recorded feed
+    ///
+    /// Params
+    /// ---
+    /// - content: content of the recorded file
+    /// - paced: wait the `delay_ms` of the messages, otherwise they are replayed at once
+    ///
+    /// Returns
+    /// ---
+    /// - new replay, starting with the first line
+    pub fn new(content: &str, paced: bool) -> FileReplayFeed {
+        let lines: Vec<(usize, String)> = content
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, String::from(line)))
+            .collect();
+        FileReplayFeed {
+            lines: lines.into_iter(),
+            paced,
+        }
+    }
+
+    /// Create a replay of the recorded feed file
+    ///
+    /// Params
+    /// ---
+    /// - path: path to the JSON-lines file
+    /// - paced: wait the `delay_ms` of the messages, otherwise they are replayed at once
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(replay) starting with the first line of the file
+    /// - Err(_) if the file cannot be read
+    pub fn open(path: &str, paced: bool) -> anyhow::Result<FileReplayFeed> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(FileReplayFeed::new(&content, paced))
+    }
+}
+
+#[event_bus::async_trait]
+impl MatchFeed for FileReplayFeed {
+    async fn next_message(&mut self) -> anyhow::Result<Option<Row<FeedMessage>>> {
+        for (number, line) in self.lines.by_ref() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let row = format!("line {}", number);
+            let feed_line: FeedLine = match serde_json::from_str(&line) {
+                Ok(feed_line) => feed_line,
+                Err(err) => anyhow::bail!("{}: {}", row, err),
+            };
+            if self.paced && feed_line.delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(feed_line.delay_ms)).await;
+            }
+            return Ok(Some(Row {
+                row,
+                record: feed_line.message,
+            }));
+        }
+        Ok(None)
+    }
+}
+
+/// What has been done with a message
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ingested {
+    Applied,
+    /// the message has been applied before, e.g. by an earlier replay, or is outdated
+    Unchanged,
+}
+
+/// Outcome of an ingested feed
+#[derive(Debug, Clone, Default)]
+pub struct FeedReport {
+    pub applied: u32,
+    pub unchanged: u32,
+    /// messages which could not be read or applied
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for FeedReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "feed messages: {} applied, {} unchanged, {} failed",
+            self.applied,
+            self.unchanged,
+            self.errors.len()
+        )?;
+        for error in self.errors.iter() {
+            writeln!(f, "  {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies the messages of a data provider to the matches: imports the fixtures,
+/// starts and ends the matches, and records their odds and scores,
+/// publishing the changes like the admins' edits are.
+///
+/// The matches and the teams are found by their external IDs,
+/// so they have to be imported (or announced by a fixture of the feed) first.
+/// A message applied before, or outdated as the match has moved on since,
+/// leaves the match unchanged, so a feed can be replayed.
+pub struct FeedIngester {
+    repos: Repos,
+    importer: FixtureImporter,
+    publisher: Publisher,
+}
+
+impl FeedIngester {
+    pub fn new(pool: &Arc<PgPool>, bus: EventBus) -> FeedIngester {
+        FeedIngester {
+            repos: Repos::new(pool),
+            importer: FixtureImporter::new(pool, bus.clone()),
+            publisher: Publisher::new(pool, bus),
+        }
+    }
+
+    /// Apply all messages of the feed, until it ends.
+    /// A broken message is reported and the others are applied anyway.
+    pub async fn run<F: MatchFeed + ?Sized>(&self, feed: &mut F) -> FeedReport {
+        let mut report = FeedReport::default();
+        loop {
+            let Row { row, record } = match feed.next_message().await {
+                Ok(Some(row)) => row,
+                Ok(None) => break,
+                Err(err) => {
+                    report.errors.push(err.to_string());
+                    continue;
+                }
+            };
+            match self.apply(&row, &record).await {
+                Ok(Ingested::Applied) => report.applied += 1,
+                Ok(Ingested::Unchanged) => report.unchanged += 1,
+                Err(err) => report
+                    .errors
+                    .push(format!("{} ({}): {}", row, record.match_id(), err)),
+            }
+        }
+        report
+    }
+
+    /// Apply a single message
+    ///
+    /// Params
+    /// ---
+    /// - row: position of the message in the feed
+    /// - message: the message of the provider
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(ingested) telling whether the message has changed anything
+    /// - Err(_) if the message refers to unknown records, does not suit the match or an error occurred
+    pub async fn apply(&self, row: &str, message: &FeedMessage) -> anyhow::Result<Ingested> {
+        match message {
+            FeedMessage::Fixture(record) => self.import_fixture(row, record).await,
+            FeedMessage::Live { match_id } => self.start(self.find_match(match_id).await?).await,
+            FeedMessage::Odds {
+                match_id,
+                team_one_ratio,
+                team_two_ratio,
+            } => {
+                let match_id = self.find_match(match_id).await?;
+                self.change_odds(match_id, *team_one_ratio, *team_two_ratio)
+                    .await
+            }
+            FeedMessage::Score {
+                match_id,
+                team_one,
+                team_two,
+            } => {
+                let score = Score {
+                    team_one: *team_one,
+                    team_two: *team_two,
+                };
+                self.change_score(self.find_match(match_id).await?, score)
+                    .await
+            }
+            FeedMessage::Ended { match_id, winner } => {
+                let match_id = self.find_match(match_id).await?;
+                let winner_id = match self
+                    .repos
+                    .external_reference
+                    .find(ExternalEntity::Team, winner)
+                    .await?
+                {
+                    Some(team_id) => team_id,
+                    None => anyhow::bail!("Unknown team '{}'", winner),
+                };
+                self.end(match_id, winner_id).await
+            }
+        }
+    }
+
+    /// Find the match known under the external ID
+    async fn find_match(&self, external_id: &str) -> anyhow::Result<i32> {
+        match self
+            .repos
+            .external_reference
+            .find(ExternalEntity::Match, external_id)
+            .await?
+        {
+            Some(match_id) => Ok(match_id),
+            None => anyhow::bail!("Unknown match '{}'", external_id),
+        }
+    }
+
+    /// Create or update the match, its game and teams have to be known
+    async fn import_fixture(&self, row: &str, record: &FixtureRecord) -> anyhow::Result<Ingested> {
+        // the fixture of a started match is outdated by the live messages
+        if let Some(match_id) = self
+            .repos
+            .external_reference
+            .find(ExternalEntity::Match, &record.external_id)
+            .await?
+        {
+            if let Ok(newest_event) = self.repos.game_match.newest_event(match_id).await {
+                if newest_event.extract_event()? != GameMatchEventType::Upcoming {
+                    return Ok(Ingested::Unchanged);
+                }
+            }
+        }
+
+        let file = FixtureFile {
+            fixtures: vec![Row {
+                row: String::from(row),
+                record: record.clone(),
+            }],
+            ..FixtureFile::default()
+        };
+        let report = self.importer.import(&file, false).await?;
+        if let Some(error) = report.errors.first() {
+            anyhow::bail!("{}", error.message);
+        }
+
+        if report.created_matches.is_empty() && report.updated_matches.is_empty() {
+            return Ok(Ingested::Unchanged);
+        }
+        Ok(Ingested::Applied)
+    }
+
+    /// Move the upcoming match to live, the ones started or over already are left as they are
+    async fn start(&self, match_id: i32) -> anyhow::Result<Ingested> {
+        let newest_event = self.repos.game_match.newest_event(match_id).await?;
+        if newest_event.extract_event()? != GameMatchEventType::Upcoming {
+            return Ok(Ingested::Unchanged);
+        }
+
+        self.publisher
+            .commit(|connection, events| {
+                PgMatchRepo::create_event_in(connection, match_id, GameMatchEventType::Live)?;
+                events.push(match_changed(
+                    match_id,
+                    MatchChange::PhaseChanged(MatchPhase::Live),
+                ));
+                Ok(())
+            })
+            .await?;
+        Ok(Ingested::Applied)
+    }
+
+    /// Set the ratios of the match, unless it is over already
+    async fn change_odds(
+        &self,
+        match_id: i32,
+        team_one_ratio: f64,
+        team_two_ratio: f64,
+    ) -> anyhow::Result<Ingested> {
+        for ratio in [team_one_ratio, team_two_ratio] {
+            if !ratio.is_finite() || ratio < 1.0 {
+                anyhow::bail!("The ratio {} is invalid, it has to be at least 1", ratio);
+            }
+        }
+        let newest_event = self.repos.game_match.newest_event(match_id).await?;
+        match newest_event.extract_event()? {
+            GameMatchEventType::Ended(_) | GameMatchEventType::Cancelled => {
+                return Ok(Ingested::Unchanged)
+            }
+            _ => {}
+        }
+
+        if self.repos.game_match.get_ratios(match_id).await? == (team_one_ratio, team_two_ratio) {
+            return Ok(Ingested::Unchanged);
+        }
+        self.publisher
+            .commit(|connection, events| {
+                PgMatchRepo::set_ratios_in(connection, match_id, team_one_ratio, team_two_ratio)?;
+                events.push(match_changed(match_id, MatchChange::RatiosChanged));
+                Ok(())
+            })
+            .await?;
+        Ok(Ingested::Applied)
+    }
+
+    /// Record the new score of the live match on its timeline
+    async fn change_score(&self, match_id: i32, score: Score) -> anyhow::Result<Ingested> {
+        if score.team_one < 0 || score.team_two < 0 {
+            anyhow::bail!("The score cannot be negative");
+        }
+        let newest_event = self.repos.game_match.newest_event(match_id).await?;
+        match newest_event.extract_event()? {
+            GameMatchEventType::Live | GameMatchEventType::Overtime => {}
+            GameMatchEventType::Upcoming => {
+                anyhow::bail!("The score can be changed only for live matches")
+            }
+            _ => return Ok(Ingested::Unchanged),
+        }
+
+        let current = self.repos.timeline.get_scores(&[match_id]).await?;
+        if current.get(&match_id) == Some(&score) {
+            return Ok(Ingested::Unchanged);
+        }
+        self.publisher
+            .commit(|connection, events| {
+                let event = PgTimelineRepo::append_in(
+                    connection,
+                    match_id,
+                    &TimelineEventPayload::ScoreChanged(score),
+                )?;
+                events.push(DomainEvent::TimelineEventRecorded(TimelineEventRecorded {
+                    event_id: event.id,
+                    match_id,
+                }));
+                Ok(())
+            })
+            .await?;
+        Ok(Ingested::Applied)
+    }
+
+    /// End the match with the winner, settling the bets on it
+    async fn end(&self, match_id: i32, winner_id: i32) -> anyhow::Result<Ingested> {
+        let (game_match, newest_event) = self.repos.game_match.get_show_info(match_id).await?;
+        if winner_id != game_match.team_one_id && winner_id != game_match.team_two_id {
+            anyhow::bail!("The winner does not play the match");
+        }
+        match newest_event.extract_event()? {
+            GameMatchEventType::Ended(id) if id == winner_id => return Ok(Ingested::Unchanged),
+            GameMatchEventType::Ended(_) => {
+                anyhow::bail!("The match has already ended with another winner")
+            }
+            GameMatchEventType::Cancelled => anyhow::bail!("The match has been cancelled"),
+            _ => {}
+        }
+
+        self.publisher
+            .commit(|connection, events| {
+                PgMatchRepo::create_event_in(
+                    connection,
+                    match_id,
+                    GameMatchEventType::Ended(winner_id),
+                )?;
+                events.push(match_changed(
+                    match_id,
+                    MatchChange::PhaseChanged(MatchPhase::Ended { winner_id }),
+                ));
+                Ok(())
+            })
+            .await?;
+        Ok(Ingested::Applied)
+    }
+}
+
+/// Replay the recorded feed of the configuration, if any, once the server has started.
+/// The messages are replayed at once, unless the feed is configured as paced.
+///
+/// Params
+/// ---
+/// - pool: the database connection pool
+/// - bus: bus the changes of the matches are published to
+/// - config: configuration of the backend
+///
+/// Returns
+/// ---
+/// - Ok(()) once the feed has been replayed, or right away if there is none
+/// - Err(_) if the feed file cannot be read
+pub async fn replay_configured(
+    pool: &Arc<PgPool>,
+    bus: EventBus,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let path = match config.feed_file() {
+        Some(path) => path.to_string_lossy().into_owned(),
+        None => return Ok(()),
+    };
+
+    let mut feed = FileReplayFeed::open(&path, config.feed.paced)?;
+    let report = FeedIngester::new(pool, bus).run(&mut feed).await;
+    print!("replayed {}: {}", path, report);
+    Ok(())
+}
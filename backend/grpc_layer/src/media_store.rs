@@ -0,0 +1,235 @@
+use image::{io::Limits, io::Reader as ImageReader, ImageFormat, ImageOutputFormat};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        media_asset::{MediaAssetRepo, PgMediaAssetRepo},
+        repo::Repo,
+    },
+    db_models::media_asset::{CreateMediaAsset, MediaAsset},
+};
+
+/// Image formats accepted for upload
+const ACCEPTED_FORMATS: [ImageFormat; 4] = [
+    ImageFormat::Png,
+    ImageFormat::Jpeg,
+    ImageFormat::Gif,
+    ImageFormat::WebP,
+];
+
+/// Tells apart the temporary files written at the same time
+static TEMPORARY_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// Where the uploaded images are stored and which ones are accepted
+#[derive(Debug, Clone)]
+pub struct MediaSettings {
+    /// directory the images are stored in, served under `public_url`
+    pub directory: PathBuf,
+    /// URL the stored images are linked under
+    pub public_url: String,
+    /// largest accepted file
+    pub max_bytes: usize,
+    /// largest accepted width and height of an image
+    pub max_dimension: u32,
+    /// thumbnails fit into a square of this size
+    pub thumbnail_size: u32,
+}
+
+impl Default for MediaSettings {
+    fn default() -> Self {
+        MediaSettings {
+            directory: PathBuf::from("media"),
+            public_url: String::from("http://127.0.0.1:50052/media"),
+            max_bytes: 5 * 1024 * 1024,
+            max_dimension: 4096,
+            thumbnail_size: 128,
+        }
+    }
+}
+
+/// An uploaded image which has passed the checks, along with its thumbnail
+pub struct PreparedImage {
+    pub content: Vec<u8>,
+    /// hex SHA-256 of the content
+    pub content_hash: String,
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    /// PNG thumbnail of the image
+    pub thumbnail: Vec<u8>,
+}
+
+/// Check the uploaded file is an accepted image and make its thumbnail
+///
+/// Params
+/// ---
+/// - content: the uploaded file
+/// - settings: limits of the accepted images
+///
+/// Returns
+/// ---
+/// - Ok(image) ready to be stored
+/// - Err(_) if the file is too large, it is not an accepted image or it cannot be decoded
+pub fn prepare_image(content: Vec<u8>, settings: &MediaSettings) -> anyhow::Result<PreparedImage> {
+    if content.is_empty() {
+        anyhow::bail!("The image is empty");
+    }
+    if content.len() > settings.max_bytes {
+        anyhow::bail!(
+            "The image has {} bytes, at most {} bytes are accepted",
+            content.len(),
+            settings.max_bytes
+        );
+    }
+
+    let format = image::guess_format(&content)
+        .ok()
+        .filter(|format| ACCEPTED_FORMATS.contains(format))
+        .ok_or_else(|| anyhow::anyhow!("Only PNG, JPEG, GIF and WebP images are accepted"))?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(settings.max_dimension);
+    limits.max_image_height = Some(settings.max_dimension);
+    let mut reader = ImageReader::with_format(Cursor::new(&content), format);
+    reader.limits(limits);
+    let image = reader.decode()?;
+
+    let mut thumbnail = Vec::new();
+    image
+        .thumbnail(settings.thumbnail_size, settings.thumbnail_size)
+        .write_to(&mut Cursor::new(&mut thumbnail), ImageOutputFormat::Png)?;
+
+    Ok(PreparedImage {
+        content_hash: format!("{:x}", Sha256::digest(&content)),
+        format,
+        width: image.width(),
+        height: image.height(),
+        thumbnail,
+        content,
+    })
+}
+
+/// Write the file unless it exists already, the stored files are named after their content,
+/// so an existing file has the same content. The file is written under a temporary name first,
+/// so a half-written file is never served.
+fn write_once(directory: &Path, file_name: &str, content: &[u8]) -> anyhow::Result<()> {
+    let path = directory.join(file_name);
+    if path.exists() {
+        return Ok(());
+    }
+
+    let temporary_path = directory.join(format!(
+        ".{}.{}.{}",
+        file_name,
+        process::id(),
+        TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&temporary_path, content)?;
+    if let Err(err) = fs::rename(&temporary_path, &path) {
+        let _ = fs::remove_file(&temporary_path);
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+/// Stores the uploaded images in the local media directory under their content hash
+pub struct MediaStore {
+    settings: MediaSettings,
+    assets: PgMediaAssetRepo,
+}
+
+impl MediaStore {
+    pub fn new(pool: &Arc<PgPool>, settings: MediaSettings) -> MediaStore {
+        MediaStore {
+            settings,
+            assets: PgMediaAssetRepo::new(pool),
+        }
+    }
+
+    pub fn settings(&self) -> &MediaSettings {
+        &self.settings
+    }
+
+    /// Check the uploaded file and make its thumbnail, off the async runtime
+    pub async fn prepare(&self, content: Vec<u8>) -> anyhow::Result<PreparedImage> {
+        let settings = self.settings.clone();
+        tokio::task::spawn_blocking(move || prepare_image(content, &settings)).await?
+    }
+
+    /// Store the image and its thumbnail, the same image is stored only once
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(asset) with the stored image, or the one stored before with the same content
+    /// - Err(_) if the files could not be written or an error occurred
+    pub async fn save(&self, image: PreparedImage) -> anyhow::Result<MediaAsset> {
+        let new_asset = CreateMediaAsset::new(
+            &image.content_hash,
+            image.format.to_mime_type(),
+            image.format.extensions_str()[0],
+            image.content.len() as i32,
+            image.width as i32,
+            image.height as i32,
+        );
+
+        let directory = self.settings.directory.clone();
+        let (file_name, thumbnail_name) = (
+            new_asset.file_name.clone(),
+            new_asset.thumbnail_name.clone(),
+        );
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            fs::create_dir_all(&directory)?;
+            write_once(&directory, &file_name, &image.content)?;
+            write_once(&directory, &thumbnail_name, &image.thumbnail)
+        })
+        .await??;
+
+        self.assets.create(new_asset).await
+    }
+
+    /// Get a stored image by its ID
+    pub async fn get(&self, asset_id: i32) -> anyhow::Result<MediaAsset> {
+        self.assets.get(asset_id).await
+    }
+
+    /// URL the stored file is served under
+    pub fn url(&self, file_name: &str) -> String {
+        format!(
+            "{}/{}",
+            self.settings.public_url.trim_end_matches('/'),
+            file_name
+        )
+    }
+
+    /// Pick the URL of a logo or a photo, which is either an uploaded image or an external URL
+    ///
+    /// Params
+    /// ---
+    /// - url: the external URL
+    /// - asset_id: ID of the uploaded image, preferred when set
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(url) of the uploaded image if set, the external URL otherwise
+    /// - Err(_) if the uploaded image does not exist or an error occurred
+    pub async fn resolve_url(&self, url: &str, asset_id: Option<i32>) -> anyhow::Result<String> {
+        match asset_id {
+            Some(asset_id) => {
+                let asset = self.get(asset_id).await?;
+                Ok(self.url(&asset.file_name))
+            }
+            None => Ok(String::from(url)),
+        }
+    }
+}
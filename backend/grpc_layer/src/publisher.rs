@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use crate::settlement;
+
+use database_layer::{
+    connection::{PgConnection, PgPool},
+    db_access::{
+        event_outbox::{OutboxRepo, PgOutboxRepo},
+        repo::Repo,
+    },
+};
+use event_bus::{DomainEvent, EventBus};
+
+/// Stores the events in the outbox in the same transaction as the changes they describe,
+/// so an event is delivered by the fan-out of every instance exactly when its change is committed.
+/// The tickets and bets decided by the changes are settled in the same transaction.
+/// Once committed, the events are also published on the bus of this instance.
+#[derive(Clone)]
+pub struct Publisher {
+    outbox: Arc<PgOutboxRepo>,
+    bus: EventBus,
+}
+
+impl Publisher {
+    pub fn new(pool: &Arc<PgPool>, bus: EventBus) -> Publisher {
+        Publisher {
+            outbox: Arc::new(PgOutboxRepo::new(pool)),
+            bus,
+        }
+    }
+
+    /// Make the changes and store the events describing them in a single transaction
+    ///
+    /// Params
+    /// ---
+    /// - changes: makes the changes on the given connection, pushing the events describing them,
+    ///   the events of the settled tickets and bets are appended to them
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(result) with the result of the changes, once committed together with the events
+    /// - Err(_) if the changes failed or an error occurred, neither the changes nor the events
+    ///   are committed then
+    pub async fn commit<T, F>(&self, changes: F) -> anyhow::Result<T>
+    where
+        T: Send,
+        F: FnOnce(&PgConnection, &mut Vec<DomainEvent>) -> anyhow::Result<T> + Send,
+    {
+        let (result, events) = self
+            .outbox
+            .transaction(|connection| {
+                let mut events = Vec::new();
+                let result = changes(connection, &mut events)?;
+                settlement::settle_in(connection, &mut events)?;
+                let payloads = events
+                    .iter()
+                    .map(DomainEvent::to_json)
+                    .collect::<anyhow::Result<Vec<String>>>()?;
+                Ok(((result, events), payloads))
+            })
+            .await?;
+
+        for event in events {
+            self.bus.publish(event);
+        }
+        Ok(result)
+    }
+
+    /// Publish an event which does not describe any stored change, e.g. an alert
+    pub async fn announce(&self, event: DomainEvent) -> anyhow::Result<()> {
+        self.commit(|_, events| {
+            events.push(event);
+            Ok(())
+        })
+        .await
+    }
+}
@@ -1,18 +1,28 @@
 use std::convert::*;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
 
-use crate::game_match::{GameEventType, Match};
+use crate::game_match::{GameEventType, MapResult, Match, Score};
 use crate::team::Team;
 
 use database_layer::{
-    connection::PgPool,
+    connection::{PgConnection, PgPool},
     db_access::{
-        bet_and_ticket::PgBetAndTicketRepo,
+        bet_and_ticket::{BetAndTicketRepo, PgBetAndTicketRepo},
+        external_reference::PgExternalReferenceRepo,
+        favourite::{FavouriteRepo, PgFavouriteRepo},
         game::PgGameRepo,
         game_match::{MatchRepo, PgMatchRepo},
+        match_map::{MatchMapRepo, PgMatchMapRepo},
+        match_timeline::{PgTimelineRepo, TimelineRepo},
+        outright::PgOutrightRepo,
         repo::Repo,
         submitted_bet_and_ticket::PgSubmittedBetAndTicketRepo,
         team::{PgTeamRepo, TeamRepo},
+        ticket_share::PgTicketShareRepo,
+        tournament::PgTournamentRepo,
         user::PgUserRepo,
     },
     db_models::game_match_event::GameMatchEventType,
@@ -25,6 +35,13 @@ pub struct Repos {
     pub team: PgTeamRepo,
     pub bet_ticket: PgBetAndTicketRepo,
     pub sub_bet_ticket: PgSubmittedBetAndTicketRepo,
+    pub ticket_share: PgTicketShareRepo,
+    pub favourite: PgFavouriteRepo,
+    pub timeline: PgTimelineRepo,
+    pub external_reference: PgExternalReferenceRepo,
+    pub tournament: PgTournamentRepo,
+    pub outright: PgOutrightRepo,
+    pub match_map: PgMatchMapRepo,
 }
 
 impl Repos {
@@ -36,6 +53,13 @@ impl Repos {
             team: PgTeamRepo::new(pool),
             bet_ticket: PgBetAndTicketRepo::new(pool),
             sub_bet_ticket: PgSubmittedBetAndTicketRepo::new(pool),
+            ticket_share: PgTicketShareRepo::new(pool),
+            favourite: PgFavouriteRepo::new(pool),
+            timeline: PgTimelineRepo::new(pool),
+            external_reference: PgExternalReferenceRepo::new(pool),
+            tournament: PgTournamentRepo::new(pool),
+            outright: PgOutrightRepo::new(pool),
+            match_map: PgMatchMapRepo::new(pool),
         }
     }
 
@@ -55,27 +79,50 @@ impl Repos {
         };
 
         let mut teams = HashMap::new();
-        for team_id in vec![game_match.team_one_id, game_match.team_two_id] {
-            if !teams.contains_key(&team_id) {
-                let team = self.team.get(team_id).await?;
-                teams.insert(team_id, team);
+        for team_id in [game_match.team_one_id, game_match.team_two_id] {
+            if let Entry::Vacant(entry) = teams.entry(team_id) {
+                entry.insert(self.team.get(team_id).await?);
             }
         }
         let mut grpc_match = Match::from(&game_match);
-        grpc_match.team_one = Some(Team::from(
-            teams.get(&game_match.team_one_id).unwrap().clone(),
-        ));
-        grpc_match.team_two = Some(Team::from(
-            teams.get(&game_match.team_two_id).unwrap().clone(),
-        ));
+        grpc_match.team_one = Some(Team::from(teams.get(&game_match.team_one_id).unwrap()));
+        grpc_match.team_two = Some(Team::from(teams.get(&game_match.team_two_id).unwrap()));
         grpc_match.game_event_type = grpc_event_type.into();
         grpc_match.winner_id = winner_id;
+        grpc_match.score = self
+            .timeline
+            .get_scores(&[match_id])
+            .await?
+            .get(&match_id)
+            .map(Score::from);
+        grpc_match.maps = self
+            .match_map
+            .get_maps(match_id)
+            .await?
+            .iter()
+            .map(MapResult::from)
+            .collect();
         Ok(grpc_match)
     }
 
-    pub async fn change_ratios(&self, match_id: i32, team_id: i32) -> anyhow::Result<()> {
-        let (mut ratio1, mut ratio2) = self.game_match.get_ratios(match_id).await?;
-        let (game_match, _) = self.game_match.get_show_info(match_id).await?;
+    /// Get IDs of the users following the match or holding a bet on it in their open ticket
+    pub async fn get_affected_users(&self, match_id: i32) -> anyhow::Result<Vec<i32>> {
+        let mut users = self.favourite.get_match_followers(match_id).await?;
+        users.extend(self.bet_ticket.get_open_ticket_holders(match_id).await?);
+        users.sort_unstable();
+        users.dedup();
+        Ok(users)
+    }
+
+    /// Shift the ratios of the match away from the team which has just been bet on,
+    /// made within the transaction of the caller
+    pub fn change_ratios_in(
+        connection: &PgConnection,
+        match_id: i32,
+        team_id: i32,
+    ) -> anyhow::Result<()> {
+        let (mut ratio1, mut ratio2) = PgMatchRepo::get_ratios_in(connection, match_id)?;
+        let game_match = PgMatchRepo::get_in(connection, match_id)?;
 
         if game_match.team_one_id == team_id {
             ratio1 *= 0.95;
@@ -86,7 +133,6 @@ impl Repos {
         }
         ratio1 = (ratio1 * 100.0).round() / 100.0;
         ratio2 = (ratio2 * 100.0).round() / 100.0;
-        self.game_match.set_ratios(match_id, ratio1, ratio2).await?;
-        Ok(())
+        PgMatchRepo::set_ratios_in(connection, match_id, ratio1, ratio2)
     }
 }
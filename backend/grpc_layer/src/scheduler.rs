@@ -0,0 +1,225 @@
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::clock::Clock;
+use crate::publisher::Publisher;
+use crate::repos::Repos;
+
+use database_layer::{
+    advisory_lock::{AdvisoryLock, SCHEDULER_LOCK},
+    connection::PgPool,
+    db_access::{
+        game::GameRepo,
+        game_match::{MatchRepo, PgMatchRepo},
+    },
+    db_models::game_match_event::{GameMatchEventFilter, GameMatchEventType},
+    type_storing::time_handling::TimeHandling,
+};
+use event_bus::{
+    events::{MatchAlert, MatchAlertKind, MatchChange, MatchChanged, MatchPhase},
+    DomainEvent, EventBus,
+};
+
+/// Tunables of the match scheduler
+#[derive(Debug, Clone)]
+pub struct SchedulerSettings {
+    /// how often the matches are checked
+    pub interval: Duration,
+    /// whether the matches are moved to live at their scheduled start,
+    /// otherwise they are only reported to the admins as overdue
+    pub auto_start: bool,
+    /// how late a match is still started automatically,
+    /// the later ones are left to the admins and reported as overdue
+    pub start_grace: ChronoDuration,
+}
+
+impl Default for SchedulerSettings {
+    fn default() -> Self {
+        SchedulerSettings {
+            interval: Duration::from_secs(10),
+            auto_start: true,
+            start_grace: ChronoDuration::minutes(15),
+        }
+    }
+}
+
+/// Moves the upcoming matches to live at their scheduled start and reports
+/// the matches needing the attention of the admins: the ones which have not started
+/// in time and the live ones running past the expected duration of their game.
+///
+/// Bets are accepted only on live matches, so there is no pre-match betting to close,
+/// the betting of a match opens once the scheduler (or an admin) moves it to live.
+///
+/// Every instance runs its own scheduler, but only the one holding the scheduler lock
+/// checks the matches, so the matches are started and reported just once.
+pub struct Scheduler {
+    repos: Repos,
+    publisher: Publisher,
+    clock: Arc<dyn Clock>,
+    settings: SchedulerSettings,
+    /// alerts already sent, so every match is reported just once for every reason
+    alerted: HashSet<(i32, MatchAlertKind)>,
+}
+
+impl Scheduler {
+    pub fn new(
+        pool: &Arc<PgPool>,
+        bus: EventBus,
+        clock: Arc<dyn Clock>,
+        settings: SchedulerSettings,
+    ) -> Scheduler {
+        Scheduler {
+            repos: Repos::new(pool),
+            publisher: Publisher::new(pool, bus),
+            clock,
+            settings,
+            alerted: HashSet::new(),
+        }
+    }
+
+    /// Check the matches periodically, forever, whenever this instance holds the scheduler lock
+    ///
+    /// Params
+    /// ---
+    /// - database_url: connection string used for holding the lock
+    pub async fn run(mut self, database_url: &str) -> anyhow::Result<()> {
+        let mut lock: Option<AdvisoryLock> = None;
+        let mut interval = tokio::time::interval(self.settings.interval);
+        loop {
+            interval.tick().await;
+
+            if !lock.as_ref().is_some_and(AdvisoryLock::is_held) {
+                // the alerts of the previous holder are not known
+                self.alerted.clear();
+                lock = match AdvisoryLock::try_acquire(database_url, SCHEDULER_LOCK).await {
+                    Ok(lock) => lock,
+                    Err(err) => {
+                        println!("failed to take the scheduler lock: {}", err);
+                        None
+                    }
+                };
+                if lock.is_none() {
+                    continue;
+                }
+            }
+
+            if let Err(err) = self.tick().await {
+                println!("match scheduler failed: {}", err);
+            }
+        }
+    }
+
+    /// Check the matches once, at the current time of the clock
+    pub async fn tick(&mut self) -> anyhow::Result<()> {
+        let now = self.clock.now();
+        let mut still_alerting = HashSet::new();
+        self.start_due(now, &mut still_alerting).await?;
+        self.check_running_long(now, &mut still_alerting).await?;
+
+        // forget the matches which have been started or ended in the meantime
+        self.alerted.retain(|alert| still_alerting.contains(alert));
+        Ok(())
+    }
+
+    /// Start the upcoming matches whose scheduled start has come,
+    /// report the ones which are too late to be started automatically
+    async fn start_due(
+        &mut self,
+        now: DateTime<Utc>,
+        still_alerting: &mut HashSet<(i32, MatchAlertKind)>,
+    ) -> anyhow::Result<()> {
+        let upcoming = self
+            .repos
+            .game_match
+            .get_all_show_info(Some(GameMatchEventFilter::Upcoming), None)
+            .await?;
+
+        for (game_match, _) in upcoming.iter() {
+            let supposed_start_at = TimeHandling::load_timestamp(&game_match.supposed_start_at)?;
+            if supposed_start_at > now {
+                continue;
+            }
+
+            if self.settings.auto_start && now - supposed_start_at <= self.settings.start_grace {
+                if let Err(err) = self
+                    .publisher
+                    .commit(|connection, events| {
+                        PgMatchRepo::create_event_in(
+                            connection,
+                            game_match.id,
+                            GameMatchEventType::Live,
+                        )?;
+                        events.push(DomainEvent::MatchChanged(MatchChanged {
+                            match_id: game_match.id,
+                            change: MatchChange::PhaseChanged(MatchPhase::Live),
+                        }));
+                        Ok(())
+                    })
+                    .await
+                {
+                    // a single broken match must not keep the others from starting
+                    println!("failed to start the match {}: {}", game_match.id, err);
+                }
+            } else {
+                self.alert(game_match.id, MatchAlertKind::Overdue, still_alerting)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Report the live matches running past the expected duration of their game
+    async fn check_running_long(
+        &mut self,
+        now: DateTime<Utc>,
+        still_alerting: &mut HashSet<(i32, MatchAlertKind)>,
+    ) -> anyhow::Result<()> {
+        let live = self
+            .repos
+            .game_match
+            .get_all_show_info(Some(GameMatchEventFilter::Live), None)
+            .await?;
+
+        let mut expected_durations: HashMap<i32, ChronoDuration> = HashMap::new();
+        for (game_match, live_event) in live.iter() {
+            let expected_duration = match expected_durations.entry(game_match.game_id) {
+                Entry::Occupied(entry) => *entry.get(),
+                Entry::Vacant(entry) => {
+                    let game = self.repos.game.get(game_match.game_id).await?;
+                    *entry.insert(ChronoDuration::minutes(
+                        game.expected_duration_minutes.into(),
+                    ))
+                }
+            };
+
+            // the newest event of a live match is the one which has started it
+            let live_since = TimeHandling::load_timestamp(&live_event.created_at)?;
+            if now - live_since > expected_duration {
+                self.alert(game_match.id, MatchAlertKind::RunningLong, still_alerting)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Let the admins know about the match, unless they already know
+    async fn alert(
+        &mut self,
+        match_id: i32,
+        kind: MatchAlertKind,
+        still_alerting: &mut HashSet<(i32, MatchAlertKind)>,
+    ) -> anyhow::Result<()> {
+        still_alerting.insert((match_id, kind));
+        if !self.alerted.contains(&(match_id, kind)) {
+            self.publisher
+                .announce(DomainEvent::MatchAlert(MatchAlert { match_id, kind }))
+                .await?;
+            self.alerted.insert((match_id, kind));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeSet;
+
+use database_layer::{
+    connection::PgConnection,
+    db_access::{outright::PgOutrightRepo, submitted_bet_and_ticket::PgSubmittedBetAndTicketRepo},
+};
+use event_bus::{
+    events::{MatchChange, MatchPhase, OutrightSettled, TicketSettled},
+    DomainEvent,
+};
+
+/// Settle the tickets containing a bet on an ended match, a finished map or a restarted match
+/// and the outright bets on an ended tournament, within the transaction which made the change.
+/// The settlement is committed along with the change, so nothing is left unsettled
+/// when the events are lost or the process stops right after the commit.
+///
+/// Params
+/// ---
+/// - connection: connection of the transaction making the change
+/// - events: events describing the change, the settled tickets and bets
+///   and the changed balances of the winners are appended
+///
+/// Returns
+/// ---
+/// - Ok(()) once everything affected by the change has been settled
+/// - Err(_) if an error occurred, the change is rolled back then
+pub fn settle_in(connection: &PgConnection, events: &mut Vec<DomainEvent>) -> anyhow::Result<()> {
+    let mut settled_events = Vec::new();
+    for event in events.iter() {
+        match event {
+            DomainEvent::MatchChanged(match_changed) => match match_changed.change {
+                MatchChange::PhaseChanged(MatchPhase::Ended { .. })
+                | MatchChange::MapFinished { .. }
+                | MatchChange::Rescheduled { .. } => {
+                    settle_tickets_in(connection, match_changed.match_id, &mut settled_events)?
+                }
+                _ => {}
+            },
+            DomainEvent::TournamentEnded(ended) => {
+                settle_outrights_in(connection, ended.tournament_id, &mut settled_events)?
+            }
+            _ => {}
+        }
+    }
+    events.extend(settled_events);
+    Ok(())
+}
+
+/// Settle the tickets of all users who bet on the match,
+/// pushing every settled ticket and the changed balances of the winners
+fn settle_tickets_in(
+    connection: &PgConnection,
+    match_id: i32,
+    events: &mut Vec<DomainEvent>,
+) -> anyhow::Result<()> {
+    for user_id in PgSubmittedBetAndTicketRepo::get_unsettled_users_in(connection, match_id)? {
+        let settled_tickets = PgSubmittedBetAndTicketRepo::settle_in(connection, user_id)?;
+        for ticket in settled_tickets.iter() {
+            events.push(DomainEvent::TicketSettled(TicketSettled {
+                ticket_id: ticket.id,
+                user_id,
+                won: ticket.won.unwrap_or_default(),
+                price_paid: String::from(&ticket.price_paid),
+                total_ratio: String::from(&ticket.total_ratio),
+            }));
+        }
+
+        if settled_tickets
+            .iter()
+            .any(|ticket| ticket.won == Some(true))
+        {
+            events.push(DomainEvent::BalanceChanged { user_id });
+        }
+    }
+    Ok(())
+}
+
+/// Settle the outright bets on the tournament,
+/// pushing every settled bet and the changed balances of the winners
+fn settle_outrights_in(
+    connection: &PgConnection,
+    tournament_id: i32,
+    events: &mut Vec<DomainEvent>,
+) -> anyhow::Result<()> {
+    let settled_bets = PgOutrightRepo::settle_in(connection, tournament_id)?;
+    for bet in settled_bets.iter() {
+        events.push(DomainEvent::OutrightSettled(OutrightSettled {
+            bet_id: bet.id,
+            tournament_id,
+            user_id: bet.user_id,
+            won: bet.won.unwrap_or_default(),
+            price_paid: String::from(&bet.price_paid),
+            bet_ratio: String::from(&bet.bet_ratio),
+        }));
+    }
+
+    let winners: BTreeSet<i32> = settled_bets
+        .iter()
+        .filter(|bet| bet.won == Some(true))
+        .map(|bet| bet.user_id)
+        .collect();
+    for user_id in winners {
+        events.push(DomainEvent::BalanceChanged { user_id });
+    }
+    Ok(())
+}
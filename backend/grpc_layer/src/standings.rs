@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use database_layer::db_models::{
+    game_match::GameMatch,
+    game_match_event::{GameMatchEvent, GameMatchEventType},
+    match_timeline_event::Score,
+};
+
+/// Results of a team in the ended matches of a stage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamStanding {
+    pub team_id: i32,
+    pub played: i32,
+    pub wins: i32,
+    pub losses: i32,
+    /// maps, goals or points won, from the recorded scores
+    pub score_for: i32,
+    /// maps, goals or points lost, from the recorded scores
+    pub score_against: i32,
+}
+
+impl TeamStanding {
+    fn new(team_id: i32) -> TeamStanding {
+        TeamStanding {
+            team_id,
+            played: 0,
+            wins: 0,
+            losses: 0,
+            score_for: 0,
+            score_against: 0,
+        }
+    }
+
+    fn score_difference(&self) -> i32 {
+        self.score_for - self.score_against
+    }
+}
+
+/// Compute the standings of a stage from the results of its matches.
+/// Only the ended matches count, the teams of the other matches are listed without any results.
+/// The teams are ranked by their wins, then by their losses, then by their score difference.
+///
+/// Params
+/// ---
+/// - results: the matches of the stage along with their newest events
+/// - scores: the latest recorded scores of the matches, by the match ID
+///
+/// Returns
+/// ---
+/// - Ok(standings) ordered from the leader
+/// - Err(_) if an event of a match could not be read
+pub fn compute(
+    results: &[(GameMatch, GameMatchEvent)],
+    scores: &HashMap<i32, Score>,
+) -> anyhow::Result<Vec<TeamStanding>> {
+    let mut standings: HashMap<i32, TeamStanding> = HashMap::new();
+    for (game_match, newest_event) in results {
+        for team_id in [game_match.team_one_id, game_match.team_two_id] {
+            standings
+                .entry(team_id)
+                .or_insert_with(|| TeamStanding::new(team_id));
+        }
+
+        let winner_id = match newest_event.extract_event()? {
+            GameMatchEventType::Ended(winner_id) => winner_id,
+            _ => continue,
+        };
+        let score = scores.get(&game_match.id);
+        for (team_id, won_score, lost_score) in [
+            (
+                game_match.team_one_id,
+                score.map(|score| score.team_one),
+                score.map(|score| score.team_two),
+            ),
+            (
+                game_match.team_two_id,
+                score.map(|score| score.team_two),
+                score.map(|score| score.team_one),
+            ),
+        ] {
+            if let Some(standing) = standings.get_mut(&team_id) {
+                standing.played += 1;
+                if team_id == winner_id {
+                    standing.wins += 1;
+                } else {
+                    standing.losses += 1;
+                }
+                standing.score_for += won_score.unwrap_or_default();
+                standing.score_against += lost_score.unwrap_or_default();
+            }
+        }
+    }
+
+    let mut standings: Vec<TeamStanding> = standings.into_values().collect();
+    standings.sort_by(|one, two| {
+        two.wins
+            .cmp(&one.wins)
+            .then(one.losses.cmp(&two.losses))
+            .then(two.score_difference().cmp(&one.score_difference()))
+            .then(one.team_id.cmp(&two.team_id))
+    });
+    Ok(standings)
+}
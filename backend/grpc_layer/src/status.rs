@@ -0,0 +1,143 @@
+use database_layer::error::DomainError;
+use prost::Message;
+use tonic::{Code, Status};
+
+use crate::error::{ErrorDetail, ErrorReason};
+
+/// Status telling the client what went wrong, the reason and the values describing it
+/// are in the details, so the frontend can show its own message
+///
+/// Params
+/// ---
+/// - err: the failure of the request
+///
+/// Returns
+/// ---
+/// - status with the code of the kind of the failure, the internal failures are only logged
+///   and reported to the client without their description
+pub fn domain_status(err: DomainError) -> Status {
+    let (code, reason) = match &err {
+        DomainError::NotFound(_) => (Code::NotFound, ErrorReason::NotFound),
+        DomainError::Validation(_) => (Code::InvalidArgument, ErrorReason::Validation),
+        DomainError::Conflict(_) => (Code::FailedPrecondition, ErrorReason::Conflict),
+        DomainError::InsufficientFunds { .. } => {
+            (Code::FailedPrecondition, ErrorReason::InsufficientFunds)
+        }
+        DomainError::BettingClosed(_) => (Code::FailedPrecondition, ErrorReason::BettingClosed),
+        DomainError::Unauthenticated(_) => (Code::Unauthenticated, ErrorReason::Unauthenticated),
+        DomainError::Internal(_) => (Code::Internal, ErrorReason::Internal),
+    };
+    let message = match &err {
+        DomainError::Internal(cause) => {
+            println!("request failed: {}", cause);
+            String::from("Internal server error")
+        }
+        _ => err.to_string(),
+    };
+
+    let detail = ErrorDetail {
+        reason: reason.into(),
+        message: message.clone(),
+        params: err
+            .params()
+            .into_iter()
+            .map(|(name, value)| (String::from(name), value))
+            .collect(),
+    };
+    Status::with_details(code, message, detail.encode_to_vec().into())
+}
+
+/// Status of an error returned by a repository, see `DomainError::of`
+pub fn error_status(err: impl Into<anyhow::Error>) -> Status {
+    domain_status(DomainError::of(&err.into()))
+}
+
+/// Status of a malformed request
+pub fn invalid_argument(message: impl Into<String>) -> Status {
+    domain_status(DomainError::validation(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detail(status: &Status) -> ErrorDetail {
+        ErrorDetail::decode(status.details()).unwrap()
+    }
+
+    #[test]
+    fn domain_errors_mapped_to_their_codes() {
+        let cases = [
+            (
+                DomainError::not_found("missing"),
+                Code::NotFound,
+                ErrorReason::NotFound,
+            ),
+            (
+                DomainError::validation("malformed"),
+                Code::InvalidArgument,
+                ErrorReason::Validation,
+            ),
+            (
+                DomainError::conflict("duplicate"),
+                Code::FailedPrecondition,
+                ErrorReason::Conflict,
+            ),
+            (
+                DomainError::InsufficientFunds {
+                    balance: 1.0,
+                    required: 2.0,
+                },
+                Code::FailedPrecondition,
+                ErrorReason::InsufficientFunds,
+            ),
+            (
+                DomainError::betting_closed("ended"),
+                Code::FailedPrecondition,
+                ErrorReason::BettingClosed,
+            ),
+            (
+                DomainError::unauthenticated("wrong"),
+                Code::Unauthenticated,
+                ErrorReason::Unauthenticated,
+            ),
+            (
+                DomainError::internal("unreachable"),
+                Code::Internal,
+                ErrorReason::Internal,
+            ),
+        ];
+        for (err, code, reason) in cases {
+            let status = domain_status(err);
+            assert_eq!(status.code(), code);
+            assert_eq!(detail(&status).reason, reason as i32);
+        }
+    }
+
+    #[test]
+    fn message_and_params_sent_in_the_details() {
+        let status = domain_status(DomainError::InsufficientFunds {
+            balance: 1.5,
+            required: 10.0,
+        });
+        let detail = detail(&status);
+        assert_eq!(detail.message, status.message());
+        assert_eq!(detail.params["balance"], "1.50");
+        assert_eq!(detail.params["required"], "10.00");
+    }
+
+    #[test]
+    fn internal_description_hidden() {
+        let status = error_status(anyhow::anyhow!("connection refused"));
+        assert_eq!(status.code(), Code::Internal);
+        assert_eq!(status.message(), "Internal server error");
+        assert_eq!(detail(&status).message, "Internal server error");
+    }
+
+    #[test]
+    fn domain_errors_of_the_repositories_kept() {
+        let wrapped = anyhow::Error::new(DomainError::conflict("duplicate"));
+        assert_eq!(error_status(wrapped).code(), Code::FailedPrecondition);
+        assert_eq!(invalid_argument("malformed").code(), Code::InvalidArgument);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod latest_bets;
+pub mod notifications;
+pub mod team_stats;
+pub mod ws;
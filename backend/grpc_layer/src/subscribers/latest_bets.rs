@@ -0,0 +1,186 @@
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+
+use crate::broadcast::broadcast;
+use crate::config::LatestBetsConfig;
+use crate::envelope::{envelope::Payload, LatestBet, LatestBets, StakeBucket};
+use crate::repos::Repos;
+use crate::team::Team;
+
+use database_layer::connection::PgPool;
+use event_bus::{events::SubmittedSelection, DomainEvent, Subscriber};
+use ws_layer::{Clients, Topic};
+
+/// How often a batch of the latest bets is sent at most
+const FLUSH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Selections of the same team in the same match, submitted since the previous batch
+struct Aggregated {
+    match_id: i32,
+    team_id: i32,
+    /// ratio of the newest selection
+    ratio: String,
+    /// highest stake bucket of the selections
+    stake: StakeBucket,
+    count: u32,
+}
+
+/// Selections waiting for the next batch, the most recently bet on at the back
+#[derive(Default)]
+struct Pending {
+    selections: Vec<Aggregated>,
+    omitted: u32,
+}
+
+impl Pending {
+    /// Add the selection to the ones of the same team in the same match, moving them to the back.
+    /// The least recently bet on are left out once there are more than fit into a batch.
+    fn add(&mut self, selection: &SubmittedSelection, stake: StakeBucket, max_batch_size: usize) {
+        let position = self.selections.iter().position(|aggregated| {
+            aggregated.match_id == selection.match_id && aggregated.team_id == selection.team_id
+        });
+        let aggregated = match position {
+            Some(position) => {
+                let mut aggregated = self.selections.remove(position);
+                aggregated.ratio = selection.ratio.clone();
+                aggregated.stake = aggregated.stake.max(stake);
+                aggregated.count += 1;
+                aggregated
+            }
+            None => Aggregated {
+                match_id: selection.match_id,
+                team_id: selection.team_id,
+                ratio: selection.ratio.clone(),
+                stake,
+                count: 1,
+            },
+        };
+        self.selections.push(aggregated);
+
+        if self.selections.len() > max_batch_size {
+            let left_out = self.selections.remove(0);
+            self.omitted += left_out.count;
+        }
+    }
+}
+
+/// Publishes the anonymised selections of the submitted tickets to the subscribers
+/// of the latest bets topic. Selections are sent in batches, at most one batch
+/// per interval, the selections of the same team in the same match are sent as one,
+/// so busy periods do not flood the clients.
+pub struct LatestBetsFeed {
+    repos: Repos,
+    ws_clients: Clients,
+    settings: LatestBetsConfig,
+    pending: Mutex<Pending>,
+}
+
+/// Get the bucket of the stake, so the exact amount stays private
+fn stake_bucket(price_paid: &str, settings: &LatestBetsConfig) -> StakeBucket {
+    match price_paid.parse::<f64>().unwrap_or_default() {
+        stake if stake < settings.small_stake => StakeBucket::Small,
+        stake if stake < settings.medium_stake => StakeBucket::Medium,
+        stake if stake < settings.large_stake => StakeBucket::Large,
+        _ => StakeBucket::HighRoller,
+    }
+}
+
+impl LatestBetsFeed {
+    pub fn new(
+        pool: &Arc<PgPool>,
+        ws_clients: Clients,
+        settings: LatestBetsConfig,
+    ) -> LatestBetsFeed {
+        LatestBetsFeed {
+            repos: Repos::new(pool),
+            ws_clients,
+            settings,
+            pending: Mutex::new(Pending::default()),
+        }
+    }
+
+    /// Send the pending selections every interval
+    pub async fn run(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            self.flush().await;
+        }
+    }
+
+    /// Send the pending selections as a single batch, the most recently bet on first
+    pub async fn flush(&self) {
+        let pending = std::mem::take(&mut *self.pending.lock().await);
+        if pending.selections.is_empty() {
+            return;
+        }
+
+        let mut latest_bets = LatestBets {
+            bets: Vec::with_capacity(pending.selections.len()),
+            omitted: pending.omitted,
+        };
+        for aggregated in pending.selections.iter().rev() {
+            match self.describe(aggregated).await {
+                Ok(latest_bet) => latest_bets.bets.push(latest_bet),
+                Err(err) => {
+                    println!(
+                        "failed to describe the selection of the team {} in the match {}: {}",
+                        aggregated.team_id, aggregated.match_id, err
+                    );
+                    latest_bets.omitted += aggregated.count;
+                }
+            }
+        }
+
+        broadcast(
+            &self.ws_clients,
+            &[Topic::LatestBets],
+            // a batch sums up many events, it is not replayed to the resuming clients
+            0,
+            Payload::LatestBets(latest_bets),
+        )
+        .await;
+    }
+
+    /// Add the names of the match and the selected team to the selections
+    async fn describe(&self, selection: &Aggregated) -> anyhow::Result<LatestBet> {
+        let game_match = self.repos.get_filled_match(selection.match_id).await?;
+        let name_of = |team: &Option<Team>| {
+            team.as_ref()
+                .map(|team| team.name.clone())
+                .unwrap_or_default()
+        };
+        let selected_team = match &game_match.team_one {
+            Some(team) if team.id == selection.team_id => &game_match.team_one,
+            _ => &game_match.team_two,
+        };
+
+        Ok(LatestBet {
+            match_id: selection.match_id,
+            match_name: format!(
+                "{} vs {}",
+                name_of(&game_match.team_one),
+                name_of(&game_match.team_two)
+            ),
+            team_id: selection.team_id,
+            team_name: name_of(selected_team),
+            ratio: selection.ratio.clone(),
+            stake: selection.stake.into(),
+            count: selection.count,
+        })
+    }
+}
+
+#[event_bus::async_trait]
+impl Subscriber for LatestBetsFeed {
+    async fn handle(&self, event: &DomainEvent) -> anyhow::Result<()> {
+        if let DomainEvent::TicketSubmitted(ticket) = event {
+            let stake = stake_bucket(&ticket.price_paid, &self.settings);
+            let mut pending = self.pending.lock().await;
+            for selection in ticket.selections.iter() {
+                pending.add(selection, stake, self.settings.max_batch_size);
+            }
+        }
+        Ok(())
+    }
+}
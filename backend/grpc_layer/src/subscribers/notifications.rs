@@ -0,0 +1,195 @@
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+use crate::broadcast::broadcast;
+use crate::envelope::envelope::Payload;
+use crate::game_match::{Match, MatchNotification, MatchNotificationType};
+use crate::repos::Repos;
+
+use database_layer::{connection::PgPool, db_access::favourite::FavouriteRepo};
+use event_bus::{
+    events::{MatchChange, MatchChanged, MatchPhase},
+    DomainEvent, Subscriber,
+};
+use ws_layer::{Clients, Topic};
+
+/// Relative change of a ratio since the last notification
+/// which is considered a big odds move
+const BIG_ODDS_MOVE: f64 = 0.2;
+
+/// Sends targeted notifications about followed matches
+/// to the account topics of the followers
+pub struct Notifier {
+    repos: Repos,
+    ws_clients: Clients,
+    notified_ratios: Mutex<HashMap<i32, (f64, f64)>>,
+}
+
+impl Notifier {
+    pub fn new(pool: &Arc<PgPool>, ws_clients: Clients) -> Notifier {
+        Notifier {
+            repos: Repos::new(pool),
+            ws_clients,
+            notified_ratios: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Notify the followers of the match
+    async fn notify(
+        &self,
+        notification_type: MatchNotificationType,
+        game_match: &Match,
+        position: u64,
+    ) -> anyhow::Result<()> {
+        let followers = self
+            .repos
+            .favourite
+            .get_match_followers(game_match.id)
+            .await?;
+        self.notify_users(notification_type, game_match, &followers, position)
+            .await;
+        Ok(())
+    }
+
+    /// Send the notification about the match to the account topics of the users
+    async fn notify_users(
+        &self,
+        notification_type: MatchNotificationType,
+        game_match: &Match,
+        user_ids: &[i32],
+        position: u64,
+    ) {
+        if user_ids.is_empty() {
+            return;
+        }
+
+        let notification = MatchNotification {
+            notification_type: notification_type.into(),
+            game_match: Some(game_match.clone()),
+        };
+        let topics: Vec<Topic> = user_ids.iter().copied().map(Topic::User).collect();
+        broadcast(
+            &self.ws_clients,
+            &topics,
+            position,
+            Payload::MatchNotification(notification),
+        )
+        .await;
+    }
+
+    /// Notify the followers of the match, if its ratios have moved a lot
+    /// since the last notification (or since the first time we saw them)
+    async fn notify_odds_move(&self, game_match: &Match, position: u64) -> anyhow::Result<()> {
+        let ratios = (
+            game_match.team_one_ratio.parse::<f64>()?,
+            game_match.team_two_ratio.parse::<f64>()?,
+        );
+
+        {
+            let mut notified_ratios = self.notified_ratios.lock().await;
+            let (notified_one, notified_two) =
+                *notified_ratios.entry(game_match.id).or_insert(ratios);
+
+            let is_big_move = (ratios.0 - notified_one).abs() / notified_one >= BIG_ODDS_MOVE
+                || (ratios.1 - notified_two).abs() / notified_two >= BIG_ODDS_MOVE;
+            if !is_big_move {
+                return Ok(());
+            }
+            notified_ratios.insert(game_match.id, ratios);
+        }
+
+        self.notify(MatchNotificationType::OddsMoved, game_match, position)
+            .await
+    }
+
+    /// Notify the followers about the match going live, ending or its ratios moving a lot,
+    /// the users holding a bet on the match are notified about it being edited, moved or deleted too
+    async fn notify_change(
+        &self,
+        match_changed: &MatchChanged,
+        position: u64,
+    ) -> anyhow::Result<()> {
+        // the ratios of an ended or deleted match do not move anymore
+        if let MatchChange::PhaseChanged(MatchPhase::Ended { .. }) | MatchChange::Deleted { .. } =
+            match_changed.change
+        {
+            self.notified_ratios
+                .lock()
+                .await
+                .remove(&match_changed.match_id);
+        }
+
+        let notification_type = match &match_changed.change {
+            MatchChange::PhaseChanged(MatchPhase::Live) => Some(MatchNotificationType::MatchLive),
+            MatchChange::PhaseChanged(MatchPhase::Ended { .. }) => {
+                Some(MatchNotificationType::MatchEnded)
+            }
+            MatchChange::RatiosChanged => None,
+            MatchChange::Updated => {
+                let game_match = self.repos.get_filled_match(match_changed.match_id).await?;
+                let affected_user_ids = self.repos.get_affected_users(game_match.id).await?;
+                self.notify_users(
+                    MatchNotificationType::MatchUpdated,
+                    &game_match,
+                    &affected_user_ids,
+                    position,
+                )
+                .await;
+                return Ok(());
+            }
+            MatchChange::Rescheduled { affected_user_ids } => {
+                let game_match = self.repos.get_filled_match(match_changed.match_id).await?;
+                self.notify_users(
+                    MatchNotificationType::MatchRescheduled,
+                    &game_match,
+                    affected_user_ids,
+                    position,
+                )
+                .await;
+                return Ok(());
+            }
+            MatchChange::Deleted {
+                game_id,
+                affected_user_ids,
+            } => {
+                // the deleted match cannot be loaded anymore
+                let game_match = Match {
+                    id: match_changed.match_id,
+                    game_id: *game_id,
+                    ..Default::default()
+                };
+                self.notify_users(
+                    MatchNotificationType::MatchDeleted,
+                    &game_match,
+                    affected_user_ids,
+                    position,
+                )
+                .await;
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+
+        let game_match = self.repos.get_filled_match(match_changed.match_id).await?;
+        match notification_type {
+            Some(notification_type) => self.notify(notification_type, &game_match, position).await,
+            None => self.notify_odds_move(&game_match, position).await,
+        }
+    }
+}
+
+#[event_bus::async_trait]
+impl Subscriber for Notifier {
+    async fn handle(&self, event: &DomainEvent) -> anyhow::Result<()> {
+        self.handle_at(0, event).await
+    }
+
+    async fn handle_at(&self, position: u64, event: &DomainEvent) -> anyhow::Result<()> {
+        match event {
+            DomainEvent::MatchChanged(match_changed) => {
+                self.notify_change(match_changed, position).await
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use crate::team_stats::TeamStatsCache;
+
+use event_bus::{
+    events::{MatchChange, MatchPhase},
+    DomainEvent, Subscriber,
+};
+
+/// Drops the cached team statistics once a match of the teams ends
+pub struct TeamStatsInvalidator {
+    cache: Arc<TeamStatsCache>,
+}
+
+impl TeamStatsInvalidator {
+    pub fn new(cache: Arc<TeamStatsCache>) -> TeamStatsInvalidator {
+        TeamStatsInvalidator { cache }
+    }
+}
+
+#[event_bus::async_trait]
+impl Subscriber for TeamStatsInvalidator {
+    async fn handle(&self, event: &DomainEvent) -> anyhow::Result<()> {
+        match event {
+            DomainEvent::MatchChanged(match_changed) => match match_changed.change {
+                MatchChange::PhaseChanged(MatchPhase::Ended { .. }) => {
+                    self.cache.invalidate_match(match_changed.match_id).await
+                }
+                _ => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::{convert::TryFrom, sync::Arc};
+
+use crate::broadcast::{broadcast, send_private};
+use crate::envelope::{
+    envelope::Payload, BalanceChanged, MatchAlert, MatchAlertType, MatchDeleted, OddsChange,
+    OutrightSettled, TicketSettled,
+};
+use crate::game_match::TimelineEvent;
+use crate::repos::Repos;
+use crate::tournament::Tournament;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game_match::MatchRepo, match_timeline::TimelineRepo, tournament::TournamentRepo,
+        user::UserRepo,
+    },
+};
+use event_bus::{
+    events::{
+        self, MatchAlertKind, MatchChange, MatchChanged, TimelineEventRecorded, TournamentEnded,
+    },
+    DomainEvent, Subscriber,
+};
+use ws_layer::{Clients, Topic};
+
+/// Forwards the domain events to the websocket clients
+pub struct WsForwarder {
+    repos: Repos,
+    ws_clients: Clients,
+    /// users receiving the alerts about the matches, see `AlertsConfig`
+    admin_user_ids: Vec<i32>,
+}
+
+impl WsForwarder {
+    pub fn new(pool: &Arc<PgPool>, ws_clients: Clients, admin_user_ids: Vec<i32>) -> WsForwarder {
+        WsForwarder {
+            repos: Repos::new(pool),
+            ws_clients,
+            admin_user_ids,
+        }
+    }
+
+    /// Send the current state of the match, or just its ratios when only they have changed
+    async fn forward_match(
+        &self,
+        match_changed: &MatchChanged,
+        position: u64,
+    ) -> anyhow::Result<()> {
+        // the deleted match cannot be loaded anymore
+        if let MatchChange::Deleted { game_id, .. } = match_changed.change {
+            broadcast(
+                &self.ws_clients,
+                &[Topic::Match(match_changed.match_id), Topic::Game(game_id)],
+                position,
+                Payload::MatchDeleted(MatchDeleted {
+                    match_id: match_changed.match_id,
+                    game_id,
+                }),
+            )
+            .await;
+            return Ok(());
+        }
+
+        let game_match = self.repos.get_filled_match(match_changed.match_id).await?;
+        let payload = match match_changed.change {
+            MatchChange::RatiosChanged => Payload::OddsChange(OddsChange::from(&game_match)),
+            _ => Payload::MatchUpdate(game_match.clone()),
+        };
+        broadcast(
+            &self.ws_clients,
+            &[Topic::Match(game_match.id), Topic::Game(game_match.game_id)],
+            position,
+            payload,
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Let the admins know about the match needing their attention
+    async fn forward_alert(&self, alert: &events::MatchAlert, position: u64) -> anyhow::Result<()> {
+        let game_match = self.repos.get_filled_match(alert.match_id).await?;
+        let alert_type = match alert.kind {
+            MatchAlertKind::Overdue => MatchAlertType::Overdue,
+            MatchAlertKind::RunningLong => MatchAlertType::RunningLong,
+        };
+        for admin_user_id in self.admin_user_ids.iter() {
+            send_private(
+                &self.ws_clients,
+                *admin_user_id,
+                position,
+                Payload::MatchAlert(MatchAlert {
+                    alert_type: alert_type.into(),
+                    game_match: Some(game_match.clone()),
+                }),
+            )
+            .await;
+        }
+        Ok(())
+    }
+
+    /// Send the in-play event to the followers of the match and its game
+    async fn forward_timeline_event(
+        &self,
+        recorded: &TimelineEventRecorded,
+        position: u64,
+    ) -> anyhow::Result<()> {
+        let event = self.repos.timeline.get(recorded.event_id).await?;
+        let game_match = self.repos.game_match.get(recorded.match_id).await?;
+        broadcast(
+            &self.ws_clients,
+            &[Topic::Match(game_match.id), Topic::Game(game_match.game_id)],
+            position,
+            Payload::TimelineEvent(TimelineEvent::try_from(&event)?),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Let the followers of the game know about the winner of the tournament
+    async fn forward_tournament_end(
+        &self,
+        ended: &TournamentEnded,
+        position: u64,
+    ) -> anyhow::Result<()> {
+        let tournament = self.repos.tournament.get(ended.tournament_id).await?;
+        broadcast(
+            &self.ws_clients,
+            &[Topic::Game(tournament.game_id)],
+            position,
+            Payload::TournamentEnded(Tournament::from(&tournament)),
+        )
+        .await;
+        Ok(())
+    }
+}
+
+#[event_bus::async_trait]
+impl Subscriber for WsForwarder {
+    async fn handle(&self, event: &DomainEvent) -> anyhow::Result<()> {
+        self.handle_at(0, event).await
+    }
+
+    async fn handle_at(&self, position: u64, event: &DomainEvent) -> anyhow::Result<()> {
+        match event {
+            DomainEvent::MatchChanged(match_changed) => {
+                self.forward_match(match_changed, position).await?
+            }
+            DomainEvent::TimelineEventRecorded(recorded) => {
+                self.forward_timeline_event(recorded, position).await?
+            }
+            DomainEvent::MatchAlert(alert) => self.forward_alert(alert, position).await?,
+            DomainEvent::TicketSettled(ticket_settled) => {
+                send_private(
+                    &self.ws_clients,
+                    ticket_settled.user_id,
+                    position,
+                    Payload::TicketSettled(TicketSettled::from(ticket_settled)),
+                )
+                .await
+            }
+            DomainEvent::TournamentEnded(ended) => {
+                self.forward_tournament_end(ended, position).await?
+            }
+            DomainEvent::OutrightSettled(outright_settled) => {
+                send_private(
+                    &self.ws_clients,
+                    outright_settled.user_id,
+                    position,
+                    Payload::OutrightSettled(OutrightSettled::from(outright_settled)),
+                )
+                .await
+            }
+            DomainEvent::BalanceChanged { user_id } => {
+                let balance = self.repos.user.get_balance(*user_id).await?;
+                send_private(
+                    &self.ws_clients,
+                    *user_id,
+                    position,
+                    Payload::BalanceChanged(BalanceChanged { balance }),
+                )
+                .await
+            }
+            // submitted tickets are published by the latest bets feed, open bets are private
+            DomainEvent::BetPlaced(_) | DomainEvent::TicketSubmitted(_) => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::Mutex;
+
+use database_layer::{
+    connection::PgPool,
+    db_access::{
+        game_match::{MatchRepo, PgMatchRepo},
+        repo::Repo,
+    },
+    db_models::{
+        game_match::GameMatch,
+        game_match_event::{GameMatchEvent, GameMatchEventType},
+    },
+};
+
+/// Number of the latest results in the form of a team, unless requested otherwise
+pub const FORM_LENGTH: usize = 5;
+
+/// An ended match from the point of view of one of its teams
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamResult {
+    pub match_id: i32,
+    pub game_id: i32,
+    pub opponent_id: i32,
+    pub won: bool,
+}
+
+/// Results of a team in the matches of a single game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub game_id: i32,
+    pub played: i32,
+    pub wins: i32,
+}
+
+impl GameRecord {
+    pub fn win_rate(&self) -> f32 {
+        if self.played == 0 {
+            return 0.0;
+        }
+        self.wins as f32 / self.played as f32
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamStats {
+    pub team_id: i32,
+    /// the latest results, ordered from the newest
+    pub form: Vec<TeamResult>,
+    pub played: i32,
+    pub wins: i32,
+    /// ordered by the game ID
+    pub games: Vec<GameRecord>,
+}
+
+/// Matches two teams have played against each other
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadToHead {
+    pub team_one_id: i32,
+    pub team_two_id: i32,
+    pub team_one_wins: i32,
+    pub team_two_wins: i32,
+    /// from the point of view of the first team, ordered from the newest
+    pub matches: Vec<TeamResult>,
+}
+
+/// Convert the ended matches of a team into its results
+///
+/// Params
+/// ---
+/// - team_id: ID of the team
+/// - ended_matches: the matches of the team along with their `Ended` events, ordered from the newest
+///
+/// Returns
+/// ---
+/// - Ok(results) in the order of the matches, the matches without a winner are left out
+/// - Err(_) if an event of a match could not be read
+pub fn results_of(
+    team_id: i32,
+    ended_matches: &[(GameMatch, GameMatchEvent)],
+) -> anyhow::Result<Vec<TeamResult>> {
+    let mut results = Vec::new();
+    for (game_match, ended) in ended_matches {
+        let winner_id = match ended.extract_event()? {
+            GameMatchEventType::Ended(winner_id) => winner_id,
+            _ => continue,
+        };
+        let opponent_id = if game_match.team_one_id == team_id {
+            game_match.team_two_id
+        } else {
+            game_match.team_one_id
+        };
+        results.push(TeamResult {
+            match_id: game_match.id,
+            game_id: game_match.game_id,
+            opponent_id,
+            won: winner_id == team_id,
+        });
+    }
+    Ok(results)
+}
+
+/// Compute the form and the win rates of a team
+///
+/// Params
+/// ---
+/// - team_id: ID of the team
+/// - results: results of the team, ordered from the newest
+/// - form_length: number of the latest results in the form
+pub fn team_stats(team_id: i32, results: &[TeamResult], form_length: usize) -> TeamStats {
+    let mut games: HashMap<i32, GameRecord> = HashMap::new();
+    for result in results {
+        let record = games.entry(result.game_id).or_insert(GameRecord {
+            game_id: result.game_id,
+            played: 0,
+            wins: 0,
+        });
+        record.played += 1;
+        record.wins += result.won as i32;
+    }
+    let mut games: Vec<GameRecord> = games.into_values().collect();
+    games.sort_by_key(|record| record.game_id);
+
+    TeamStats {
+        team_id,
+        form: results.iter().take(form_length).cloned().collect(),
+        played: results.len() as i32,
+        wins: results.iter().filter(|result| result.won).count() as i32,
+        games,
+    }
+}
+
+/// Pick the matches of the first team against the second one
+///
+/// Params
+/// ---
+/// - team_one_id: ID of the first team
+/// - team_two_id: ID of the second team
+/// - team_one_results: results of the first team, ordered from the newest
+pub fn head_to_head(
+    team_one_id: i32,
+    team_two_id: i32,
+    team_one_results: &[TeamResult],
+) -> HeadToHead {
+    let matches: Vec<TeamResult> = team_one_results
+        .iter()
+        .filter(|result| result.opponent_id == team_two_id)
+        .cloned()
+        .collect();
+    let team_one_wins = matches.iter().filter(|result| result.won).count() as i32;
+
+    HeadToHead {
+        team_one_id,
+        team_two_id,
+        team_one_wins,
+        team_two_wins: matches.len() as i32 - team_one_wins,
+        matches,
+    }
+}
+
+/// Results of the teams, loaded once and kept until the teams play another match
+pub struct TeamStatsCache {
+    game_match: PgMatchRepo,
+    results: Mutex<HashMap<i32, Arc<Vec<TeamResult>>>>,
+    /// increased by every invalidation, so the results loaded meanwhile are not kept
+    generation: Mutex<u64>,
+}
+
+impl TeamStatsCache {
+    pub fn new(pool: &Arc<PgPool>) -> TeamStatsCache {
+        TeamStatsCache {
+            game_match: PgMatchRepo::new(pool),
+            results: Mutex::new(HashMap::new()),
+            generation: Mutex::new(0),
+        }
+    }
+
+    /// Get the results of a team, from the cache if possible
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(results) ordered from the newest
+    /// - Err(_) if the matches of the team could not be loaded
+    pub async fn results(&self, team_id: i32) -> anyhow::Result<Arc<Vec<TeamResult>>> {
+        if let Some(results) = self.results.lock().await.get(&team_id) {
+            return Ok(results.clone());
+        }
+
+        let generation = *self.generation.lock().await;
+        let ended_matches = self.game_match.get_team_results(team_id).await?;
+        let results = Arc::new(results_of(team_id, &ended_matches)?);
+
+        let current_generation = self.generation.lock().await;
+        if *current_generation == generation {
+            self.results.lock().await.insert(team_id, results.clone());
+        }
+        Ok(results)
+    }
+
+    /// Drop the cached results of the teams
+    pub async fn invalidate(&self, team_ids: &[i32]) {
+        let mut generation = self.generation.lock().await;
+        *generation += 1;
+        let mut results = self.results.lock().await;
+        for team_id in team_ids {
+            results.remove(team_id);
+        }
+    }
+
+    /// Drop the cached results of both teams of a match which has ended
+    pub async fn invalidate_match(&self, match_id: i32) -> anyhow::Result<()> {
+        let game_match = self.game_match.get(match_id).await?;
+        self.invalidate(&[game_match.team_one_id, game_match.team_two_id])
+            .await;
+        Ok(())
+    }
+}
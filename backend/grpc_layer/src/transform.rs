@@ -1,19 +1,79 @@
 use std::convert::*;
 
 use crate::bet;
+use crate::envelope::{OddsChange, OutrightSettled, TicketSettled};
+use crate::fixture_import;
 use crate::game;
-use crate::game_match::{GameEventType, Match};
+use crate::game_match::{
+    self, timeline_event::Payload, GameEventType, Kill, MapResult, Match, Objective, RoundResult,
+    Score, TimelineEvent,
+};
+use crate::importer;
 use crate::team;
+use crate::team_stats;
+use crate::ticket;
+use crate::tournament;
 
-use database_layer::db_models::{bet::Bet, game_match::GameMatch, team::Team};
-use database_layer::result_types::GameInfo;
+use database_layer::db_models::{
+    bet::Bet,
+    bet_market::BetMarket,
+    game::Game,
+    game_match::{GameMatch, SeriesFormat},
+    match_map::MatchMap,
+    match_timeline_event::{self, MatchTimelineEvent, TimelineEventPayload},
+    outright_bet::OutrightBet,
+    player::Player,
+    roster_membership::RosterMembership,
+    stage::{Stage, StageType},
+    team::Team,
+    ticket_share_code::SharedSelection,
+    tournament::Tournament,
+};
+use event_bus::events;
 
-impl From<&'_ GameInfo> for game::Game {
-    fn from(game: &'_ GameInfo) -> Self {
+impl From<&'_ Game> for game::Game {
+    fn from(game: &'_ Game) -> Self {
         game::Game {
             id: game.id,
             name: String::from(&game.name),
             logo_url: String::from(&game.logo_url),
+            description: String::from(&game.description),
+            expected_duration_minutes: game.expected_duration_minutes,
+            archived: game.archived,
+            logo_asset_id: game.logo_asset_id,
+        }
+    }
+}
+
+impl From<&'_ Match> for OddsChange {
+    fn from(game_match: &'_ Match) -> Self {
+        OddsChange {
+            match_id: game_match.id,
+            team_one_ratio: String::from(&game_match.team_one_ratio),
+            team_two_ratio: String::from(&game_match.team_two_ratio),
+        }
+    }
+}
+
+impl From<&'_ events::TicketSettled> for TicketSettled {
+    fn from(ticket: &'_ events::TicketSettled) -> Self {
+        TicketSettled {
+            ticket_id: ticket.ticket_id,
+            won: ticket.won,
+            price_paid: String::from(&ticket.price_paid),
+            total_ratio: String::from(&ticket.total_ratio),
+        }
+    }
+}
+
+impl From<&'_ events::OutrightSettled> for OutrightSettled {
+    fn from(bet: &'_ events::OutrightSettled) -> Self {
+        OutrightSettled {
+            bet_id: bet.bet_id,
+            tournament_id: bet.tournament_id,
+            won: bet.won,
+            price_paid: String::from(&bet.price_paid),
+            bet_ratio: String::from(&bet.bet_ratio),
         }
     }
 }
@@ -31,6 +91,81 @@ impl From<&'_ GameMatch> for Match {
             state: game_match.state.clone(),
             winner_id: None,
             game_event_type: GameEventType::Upcoming.into(),
+            score: None,
+            series_format: game_match
+                .extract_series_format()
+                .map(game_match::SeriesFormat::from)
+                .unwrap_or(game_match::SeriesFormat::Bo1)
+                .into(),
+            maps: Vec::new(),
+        }
+    }
+}
+
+impl From<SeriesFormat> for game_match::SeriesFormat {
+    fn from(series_format: SeriesFormat) -> Self {
+        match series_format {
+            SeriesFormat::Bo1 => game_match::SeriesFormat::Bo1,
+            SeriesFormat::Bo3 => game_match::SeriesFormat::Bo3,
+            SeriesFormat::Bo5 => game_match::SeriesFormat::Bo5,
+        }
+    }
+}
+
+impl From<game_match::SeriesFormat> for SeriesFormat {
+    fn from(series_format: game_match::SeriesFormat) -> Self {
+        match series_format {
+            game_match::SeriesFormat::Bo1 => SeriesFormat::Bo1,
+            game_match::SeriesFormat::Bo3 => SeriesFormat::Bo3,
+            game_match::SeriesFormat::Bo5 => SeriesFormat::Bo5,
+        }
+    }
+}
+
+impl From<&'_ MatchMap> for MapResult {
+    fn from(map: &'_ MatchMap) -> Self {
+        MapResult {
+            map_number: map.map_number,
+            map_name: map.map_name.clone(),
+            winner_id: map.winner_id,
+            rounds: Some(Score {
+                team_one: map.team_one_score,
+                team_two: map.team_two_score,
+            }),
+        }
+    }
+}
+
+impl From<&'_ BetMarket> for bet::BetMarket {
+    fn from(market: &'_ BetMarket) -> Self {
+        bet::BetMarket {
+            market: match *market {
+                BetMarket::MatchWinner => None,
+                BetMarket::MapWinner { map_number } => {
+                    Some(bet::bet_market::Market::MapWinner(map_number))
+                }
+                BetMarket::SeriesScore { won, lost } => {
+                    Some(bet::bet_market::Market::SeriesScore(bet::SeriesScore {
+                        won,
+                        lost,
+                    }))
+                }
+            },
+        }
+    }
+}
+
+impl From<&'_ bet::BetMarket> for BetMarket {
+    fn from(market: &'_ bet::BetMarket) -> Self {
+        match &market.market {
+            None => BetMarket::MatchWinner,
+            Some(bet::bet_market::Market::MapWinner(map_number)) => BetMarket::MapWinner {
+                map_number: *map_number,
+            },
+            Some(bet::bet_market::Market::SeriesScore(score)) => BetMarket::SeriesScore {
+                won: score.won,
+                lost: score.lost,
+            },
         }
     }
 }
@@ -42,6 +177,81 @@ impl From<&'_ Team> for team::Team {
             name: team.name.clone(),
             description: team.description.clone(),
             logo: team.logo.clone(),
+            archived: team.archived,
+            logo_asset_id: team.logo_asset_id,
+        }
+    }
+}
+
+impl From<&'_ Player> for team::Player {
+    fn from(player: &'_ Player) -> Self {
+        team::Player {
+            id: player.id,
+            game_id: player.game_id,
+            nickname: player.nickname.clone(),
+            real_name: player.real_name.clone(),
+            country: player.country.clone(),
+            photo: player.photo.clone(),
+        }
+    }
+}
+
+impl From<(&'_ RosterMembership, &'_ Player)> for team::RosterMember {
+    fn from((membership, player): (&'_ RosterMembership, &'_ Player)) -> Self {
+        team::RosterMember {
+            player: Some(team::Player::from(player)),
+            team_id: membership.team_id,
+            role: membership.role.clone(),
+            joined_at: membership.joined_at.clone(),
+            left_at: membership.left_at.clone(),
+        }
+    }
+}
+
+impl From<&'_ team_stats::TeamResult> for team::MatchResult {
+    fn from(result: &'_ team_stats::TeamResult) -> Self {
+        team::MatchResult {
+            match_id: result.match_id,
+            game_id: result.game_id,
+            opponent_id: result.opponent_id,
+            won: result.won,
+        }
+    }
+}
+
+impl From<&'_ team_stats::TeamStats> for team::TeamStats {
+    fn from(stats: &'_ team_stats::TeamStats) -> Self {
+        team::TeamStats {
+            team_id: stats.team_id,
+            form: stats.form.iter().map(team::MatchResult::from).collect(),
+            played: stats.played,
+            wins: stats.wins,
+            games: stats
+                .games
+                .iter()
+                .map(|record| team::GameRecord {
+                    game_id: record.game_id,
+                    played: record.played,
+                    wins: record.wins,
+                    win_rate: record.win_rate(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&'_ team_stats::HeadToHead> for team::HeadToHead {
+    fn from(head_to_head: &'_ team_stats::HeadToHead) -> Self {
+        team::HeadToHead {
+            team_one_id: head_to_head.team_one_id,
+            team_two_id: head_to_head.team_two_id,
+            team_one_wins: head_to_head.team_one_wins,
+            team_two_wins: head_to_head.team_two_wins,
+            matches: head_to_head
+                .matches
+                .iter()
+                .map(team::MatchResult::from)
+                .collect(),
         }
     }
 }
@@ -53,6 +263,219 @@ impl From<&'_ Bet> for bet::Bet {
             match_id: bet.game_match_id,
             ticket_id: bet.ticket_id,
             team_id: bet.team_id,
+            market: bet
+                .extract_market()
+                .ok()
+                .map(|market| bet::BetMarket::from(&market)),
+        }
+    }
+}
+
+impl From<&'_ SharedSelection> for ticket::Selection {
+    fn from(selection: &'_ SharedSelection) -> Self {
+        ticket::Selection {
+            match_id: selection.game_match_id,
+            team_id: selection.team_id,
+            ratio: selection.ratio.clone(),
+            market: Some(bet::BetMarket::from(&selection.market)),
+        }
+    }
+}
+
+impl From<&'_ match_timeline_event::Score> for Score {
+    fn from(score: &'_ match_timeline_event::Score) -> Self {
+        Score {
+            team_one: score.team_one,
+            team_two: score.team_two,
+        }
+    }
+}
+
+impl From<&'_ Score> for match_timeline_event::Score {
+    fn from(score: &'_ Score) -> Self {
+        match_timeline_event::Score {
+            team_one: score.team_one,
+            team_two: score.team_two,
+        }
+    }
+}
+
+impl From<&'_ TimelineEventPayload> for Payload {
+    fn from(payload: &'_ TimelineEventPayload) -> Self {
+        match payload {
+            TimelineEventPayload::ScoreChanged(score) => Payload::ScoreChanged(Score::from(score)),
+            TimelineEventPayload::MapResult {
+                map_number,
+                map_name,
+                winner_id,
+                rounds,
+            } => Payload::MapResult(MapResult {
+                map_number: *map_number,
+                map_name: map_name.clone(),
+                winner_id: *winner_id,
+                rounds: Some(Score::from(rounds)),
+            }),
+            TimelineEventPayload::RoundResult {
+                map_number,
+                round_number,
+                winner_id,
+            } => Payload::RoundResult(RoundResult {
+                map_number: *map_number,
+                round_number: *round_number,
+                winner_id: *winner_id,
+            }),
+            TimelineEventPayload::Kill {
+                team_id,
+                player,
+                victim,
+            } => Payload::Kill(Kill {
+                team_id: *team_id,
+                player: player.clone(),
+                victim: victim.clone(),
+            }),
+            TimelineEventPayload::Objective { team_id, objective } => {
+                Payload::Objective(Objective {
+                    team_id: *team_id,
+                    objective: objective.clone(),
+                })
+            }
+        }
+    }
+}
+
+impl From<&'_ Payload> for TimelineEventPayload {
+    fn from(payload: &'_ Payload) -> Self {
+        match payload {
+            Payload::ScoreChanged(score) => {
+                TimelineEventPayload::ScoreChanged(match_timeline_event::Score::from(score))
+            }
+            Payload::MapResult(map_result) => TimelineEventPayload::MapResult {
+                map_number: map_result.map_number,
+                map_name: map_result.map_name.clone(),
+                winner_id: map_result.winner_id,
+                rounds: map_result
+                    .rounds
+                    .as_ref()
+                    .map(match_timeline_event::Score::from)
+                    .unwrap_or(match_timeline_event::Score {
+                        team_one: 0,
+                        team_two: 0,
+                    }),
+            },
+            Payload::RoundResult(round_result) => TimelineEventPayload::RoundResult {
+                map_number: round_result.map_number,
+                round_number: round_result.round_number,
+                winner_id: round_result.winner_id,
+            },
+            Payload::Kill(kill) => TimelineEventPayload::Kill {
+                team_id: kill.team_id,
+                player: kill.player.clone(),
+                victim: kill.victim.clone(),
+            },
+            Payload::Objective(objective) => TimelineEventPayload::Objective {
+                team_id: objective.team_id,
+                objective: objective.objective.clone(),
+            },
+        }
+    }
+}
+
+impl TryFrom<&'_ MatchTimelineEvent> for TimelineEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(event: &'_ MatchTimelineEvent) -> anyhow::Result<Self> {
+        Ok(TimelineEvent {
+            id: event.id,
+            match_id: event.game_match_id,
+            created_at: event.created_at.clone(),
+            payload: Some(Payload::from(&event.extract_payload()?)),
+        })
+    }
+}
+
+impl From<importer::ImportCounts> for fixture_import::ImportCounts {
+    fn from(counts: importer::ImportCounts) -> Self {
+        fixture_import::ImportCounts {
+            created: counts.created,
+            updated: counts.updated,
+            unchanged: counts.unchanged,
+        }
+    }
+}
+
+impl From<&'_ importer::ImportReport> for fixture_import::ImportFixturesReply {
+    fn from(report: &'_ importer::ImportReport) -> Self {
+        fixture_import::ImportFixturesReply {
+            games: Some(report.games.into()),
+            teams: Some(report.teams.into()),
+            fixtures: Some(report.fixtures.into()),
+            links_created: report.links_created,
+            errors: report
+                .errors
+                .iter()
+                .map(|error| fixture_import::ImportRowError {
+                    row: error.row.clone(),
+                    external_id: error.external_id.clone(),
+                    message: error.message.clone(),
+                })
+                .collect(),
+            dry_run: report.dry_run,
+        }
+    }
+}
+
+impl From<&'_ Tournament> for tournament::Tournament {
+    fn from(tournament: &'_ Tournament) -> Self {
+        tournament::Tournament {
+            id: tournament.id,
+            game_id: tournament.game_id,
+            name: tournament.name.clone(),
+            description: tournament.description.clone(),
+            starts_at: tournament.starts_at.clone(),
+            ends_at: tournament.ends_at.clone(),
+            winner_id: tournament.winner_id,
+            ended_at: tournament.ended_at.clone(),
+        }
+    }
+}
+
+impl From<tournament::StageType> for StageType {
+    fn from(stage_type: tournament::StageType) -> Self {
+        match stage_type {
+            tournament::StageType::Group => StageType::Group,
+            tournament::StageType::Playoffs => StageType::Playoffs,
+        }
+    }
+}
+
+impl TryFrom<&'_ Stage> for tournament::Stage {
+    type Error = anyhow::Error;
+
+    fn try_from(stage: &'_ Stage) -> anyhow::Result<Self> {
+        let stage_type = match stage.extract_type()? {
+            StageType::Group => tournament::StageType::Group,
+            StageType::Playoffs => tournament::StageType::Playoffs,
+        };
+        Ok(tournament::Stage {
+            id: stage.id,
+            tournament_id: stage.tournament_id,
+            name: stage.name.clone(),
+            stage_type: stage_type.into(),
+            position: stage.position,
+        })
+    }
+}
+
+impl From<&'_ OutrightBet> for tournament::OutrightBet {
+    fn from(bet: &'_ OutrightBet) -> Self {
+        tournament::OutrightBet {
+            id: bet.id,
+            tournament_id: bet.tournament_id,
+            team_id: bet.team_id,
+            bet_ratio: bet.bet_ratio.clone(),
+            price_paid: bet.price_paid.clone(),
+            placed_at: bet.placed_at.clone(),
+            won: bet.won,
         }
     }
 }
@@ -0,0 +1,15 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // the simulator only calls the services, so only the clients are generated
+    tonic_build::configure().build_server(false).compile(
+        &[
+            "../../proto/bet.proto",
+            "../../proto/ticket.proto",
+            "../../proto/game_match.proto",
+            "../../proto/game.proto",
+            "../../proto/user.proto",
+            "../../proto/team.proto",
+        ],
+        &["../../proto"],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use tonic::transport::Channel;
+
+pub mod bet {
+    tonic::include_proto!("bet");
+}
+pub mod ticket {
+    tonic::include_proto!("ticket");
+}
+pub mod game_match {
+    tonic::include_proto!("game_match");
+}
+pub mod game {
+    tonic::include_proto!("game");
+}
+pub mod user {
+    tonic::include_proto!("user");
+}
+pub mod team {
+    tonic::include_proto!("team");
+}
+
+use bet::bet_service_client::BetServiceClient;
+use game::game_service_client::GameServiceClient;
+use game_match::match_service_client::MatchServiceClient;
+use team::team_service_client::TeamServiceClient;
+use ticket::ticket_service_client::TicketServiceClient;
+use user::user_service_client::UserServiceClient;
+
+/// Clients of the services driven by the simulator, all sharing a single connection
+#[derive(Clone)]
+pub struct Api {
+    pub bet: BetServiceClient<Channel>,
+    pub ticket: TicketServiceClient<Channel>,
+    pub game_match: MatchServiceClient<Channel>,
+    pub game: GameServiceClient<Channel>,
+    pub user: UserServiceClient<Channel>,
+    pub team: TeamServiceClient<Channel>,
+}
+
+impl Api {
+    /// Connect to the gRPC server
+    ///
+    /// Params
+    /// ---
+    /// - server_address: address the server listens on, e.g. `127.0.0.1:5430`
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(api) once connected
+    /// - Err(_) if the server cannot be reached
+    pub async fn connect(server_address: &str) -> anyhow::Result<Api> {
+        let channel = Channel::from_shared(format!("http://{}", server_address))?
+            .connect()
+            .await?;
+        Ok(Api {
+            bet: BetServiceClient::new(channel.clone()),
+            ticket: TicketServiceClient::new(channel.clone()),
+            game_match: MatchServiceClient::new(channel.clone()),
+            game: GameServiceClient::new(channel.clone()),
+            user: UserServiceClient::new(channel.clone()),
+            team: TeamServiceClient::new(channel),
+        })
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::Duration;
+use rand::seq::SliceRandom;
+
+use crate::api::{
+    bet::{CreateBetRequest, DeleteBetRequest},
+    game_match::{GameEventType, ListMatchesRequest},
+    ticket::{GetCurrentTicketRequest, SubmitTicketRequest},
+    user::{Address, AuthUserRequest, CreateUserRequest},
+    Api,
+};
+use crate::clock::SimClock;
+
+/// Password of all simulated bettors
+const PASSWORD: &str = "simulator";
+
+/// Tunables of the simulated bettors, the durations are in the simulated time
+#[derive(Debug, Clone)]
+pub struct BettorSettings {
+    /// how long a bettor waits between bets on average
+    pub pause: Duration,
+    /// the highest stake of a ticket, the stakes are between 1 and this
+    pub max_stake: u32,
+}
+
+impl Default for BettorSettings {
+    fn default() -> Self {
+        BettorSettings {
+            pause: Duration::minutes(5),
+            max_stake: 5,
+        }
+    }
+}
+
+/// Log in the simulated bettors, registering the ones which do not exist yet
+///
+/// Params
+/// ---
+/// - api: clients of the server
+/// - count: number of the bettors
+///
+/// Returns
+/// ---
+/// - Ok(user_ids) of the bettors
+/// - Err(_) if a bettor can neither log in nor be registered
+pub async fn register(api: &mut Api, count: usize) -> anyhow::Result<Vec<i32>> {
+    let mut user_ids = Vec::new();
+    for number in 1..=count {
+        let email = format!("bettor{}@simulator.local", number);
+        let auth = api
+            .user
+            .auth_user(AuthUserRequest {
+                email: email.clone(),
+                password: String::from(PASSWORD),
+            })
+            .await;
+        if let Ok(Some(user)) = auth.map(|reply| reply.into_inner().user) {
+            user_ids.push(user.id);
+            continue;
+        }
+
+        let user_id = api
+            .user
+            .create_user(CreateUserRequest {
+                first_name: String::from("Simulated"),
+                last_name: format!("Bettor {}", number),
+                password: String::from(PASSWORD),
+                civil_id_number: format!("SIM{:06}", number),
+                date_of_birth: String::from("1990-01-01"),
+                email,
+                phone_number: String::from("+420000000000"),
+                photo: None,
+                address: Some(Address {
+                    street_name: String::from("Simulated"),
+                    street_number: number.to_string(),
+                    city: String::from("Brno"),
+                    area: None,
+                    postal_code: String::from("60200"),
+                    country: String::from("Czech Republic"),
+                    valid_from: String::new(),
+                }),
+                photo_asset_id: None,
+            })
+            .await?
+            .into_inner()
+            .id;
+        user_ids.push(user_id);
+    }
+    Ok(user_ids)
+}
+
+/// Bets on the live matches, so the odds move and the tickets get settled
+pub struct Bettor {
+    api: Api,
+    clock: SimClock,
+    settings: BettorSettings,
+    user_id: i32,
+}
+
+impl Bettor {
+    pub fn new(api: Api, clock: SimClock, settings: BettorSettings, user_id: i32) -> Bettor {
+        Bettor {
+            api,
+            clock,
+            settings,
+            user_id,
+        }
+    }
+
+    /// Keep betting until the simulation is stopped, a failed bet is reported and left out
+    pub async fn run(mut self) {
+        loop {
+            let pause = self.settings.pause.num_milliseconds() as f64 * 2.0 * rand::random::<f64>();
+            self.clock.sleep(Duration::milliseconds(pause as i64)).await;
+            if let Err(err) = self.bet().await {
+                println!("bettor {} could not bet: {}", self.user_id, err);
+            }
+        }
+    }
+
+    /// Submit a ticket with a bet on a random live match, favouring the favourites
+    async fn bet(&mut self) -> anyhow::Result<()> {
+        let live = self
+            .api
+            .game_match
+            .list_matches(ListMatchesRequest {
+                game_event_type: GameEventType::Live.into(),
+                favourites_of_user_id: None,
+            })
+            .await?
+            .into_inner()
+            .game_matches;
+        let game_match = match live.choose(&mut rand::thread_rng()) {
+            Some(game_match) => game_match.clone(),
+            None => return Ok(()),
+        };
+        let (team_one, team_two) = match (game_match.team_one, game_match.team_two) {
+            (Some(team_one), Some(team_two)) => (team_one, team_two),
+            _ => anyhow::bail!("The match {} is missing its teams", game_match.id),
+        };
+
+        // the lower the ratio, the likelier the team is to win and to be picked
+        let ratio_one: f64 = game_match.team_one_ratio.parse()?;
+        let ratio_two: f64 = game_match.team_two_ratio.parse()?;
+        let chance_one = (1.0 / ratio_one) / (1.0 / ratio_one + 1.0 / ratio_two);
+        let team_id = if rand::random::<f64>() < chance_one {
+            team_one.id
+        } else {
+            team_two.id
+        };
+
+        let ticket_id = self
+            .api
+            .ticket
+            .get_current_ticket(GetCurrentTicketRequest {
+                user_id: self.user_id,
+            })
+            .await?
+            .into_inner()
+            .ticket_id;
+        let bet_id = self
+            .api
+            .bet
+            .create_bet(CreateBetRequest {
+                ticket_id,
+                match_id: game_match.id,
+                team_id,
+                market: None,
+            })
+            .await?
+            .into_inner()
+            .id;
+
+        let stake = 1 + rand::random::<u32>() % self.settings.max_stake.max(1);
+        let submitted = self
+            .api
+            .ticket
+            .submit_ticket(SubmitTicketRequest {
+                ticket_id,
+                price_paid: stake as f32,
+            })
+            .await;
+        if let Err(status) = submitted {
+            // e.g. not enough balance, the open ticket is left empty for the next bet
+            self.api
+                .bet
+                .delete_bet(DeleteBetRequest {
+                    id: bet_id,
+                    ticket_id,
+                })
+                .await?;
+            return Err(status.into());
+        }
+        Ok(())
+    }
+}
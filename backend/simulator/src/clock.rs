@@ -0,0 +1,31 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// Time of the simulation, running `speed` times faster than the real time
+#[derive(Debug, Clone, Copy)]
+pub struct SimClock {
+    speed: f64,
+}
+
+impl SimClock {
+    pub fn new(speed: f64) -> SimClock {
+        SimClock { speed }
+    }
+
+    /// Real time the simulated duration takes
+    pub fn real(&self, simulated: Duration) -> std::time::Duration {
+        let millis = simulated.num_milliseconds().max(0) as f64 / self.speed;
+        std::time::Duration::from_millis(millis.round() as u64)
+    }
+
+    /// Real moment the simulated duration from now ends at,
+    /// e.g. the scheduled start of a match shown to the users
+    pub fn after(&self, simulated: Duration) -> DateTime<Utc> {
+        let real = Duration::from_std(self.real(simulated)).unwrap_or_else(|_| Duration::zero());
+        Utc::now() + real
+    }
+
+    /// Wait for the simulated duration to pass
+    pub async fn sleep(&self, simulated: Duration) {
+        tokio::time::sleep(self.real(simulated)).await;
+    }
+}
@@ -0,0 +1,142 @@
+use dotenv::dotenv;
+use rand::seq::SliceRandom;
+use std::{env, sync::Arc};
+
+mod api;
+mod bettors;
+mod clock;
+mod matches;
+mod seed;
+
+use api::Api;
+use bettors::{Bettor, BettorSettings};
+use clock::SimClock;
+use matches::{MatchSettings, MatchSimulation};
+use seed::{Seed, SeededGame, DEFAULT_SEED};
+
+const USAGE: &str = "Usage: simulator [--speed <times>] [--matches <count>] [--bettors <count>] \
+                     [--rounds <count>] [--seed <file.json>]";
+
+/// Options of the simulation, given on the command line
+struct Options {
+    /// how many times faster than the real time the simulation runs
+    speed: f64,
+    /// matches played at the same time
+    matches: usize,
+    bettors: usize,
+    /// matches played one after another at every slot, endlessly when not set
+    rounds: Option<usize>,
+    seed_path: Option<String>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> anyhow::Result<Options> {
+        let mut options = Options {
+            speed: 60.0,
+            matches: 3,
+            bettors: 10,
+            rounds: None,
+            seed_path: None,
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let value = match args.next() {
+                Some(value) => value,
+                None => anyhow::bail!("{}", USAGE),
+            };
+            match arg.as_str() {
+                "--speed" => options.speed = value.parse()?,
+                "--matches" => options.matches = value.parse()?,
+                "--bettors" => options.bettors = value.parse()?,
+                "--rounds" => options.rounds = Some(value.parse()?),
+                "--seed" => options.seed_path = Some(value.clone()),
+                _ => anyhow::bail!("{}", USAGE),
+            }
+        }
+        if !options.speed.is_finite() || options.speed <= 0.0 {
+            anyhow::bail!("The speed has to be positive");
+        }
+        Ok(options)
+    }
+}
+
+/// Play matches of random games at the slot, one after another
+async fn run_slot(
+    mut simulation: MatchSimulation,
+    games: Arc<Vec<SeededGame>>,
+    rounds: Option<usize>,
+) {
+    let mut played = 0;
+    while rounds.is_none_or(|rounds| played < rounds) {
+        let game = games.choose(&mut rand::thread_rng()).unwrap().clone();
+        if let Err(err) = simulation.play(&game).await {
+            println!("simulated match of {} failed: {}", game.name, err);
+        }
+        played += 1;
+    }
+}
+
+/// Simulates matches and bettors against a running server, so the odds movement,
+/// the settlement and the websockets can be seen without clicking through the administration.
+///
+/// The games and teams of the seed data are created unless they exist. Every match is created
+/// as upcoming, started at its scheduled start, scores during the expected duration of its game
+/// and ends, all on a clock running `--speed` times faster than the real one.
+/// The bettors meanwhile bet on the live matches and submit their tickets.
+///
+/// The server is reached at `SERVER_ADDRESS`, the same variable the server listens on.
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv().ok();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = Options::parse(&args)?;
+    let server_address =
+        env::var("SERVER_ADDRESS").unwrap_or_else(|_| String::from("127.0.0.1:5430"));
+
+    let seed = match options.seed_path.as_deref() {
+        Some(path) => Seed::parse(&std::fs::read_to_string(path)?)?,
+        None => Seed::parse(DEFAULT_SEED)?,
+    };
+    let mut api = Api::connect(&server_address).await?;
+    let games = Arc::new(seed.apply(&mut api).await?);
+    let user_ids = bettors::register(&mut api, options.bettors).await?;
+    println!(
+        "simulating {} games and {} bettors at {}x speed",
+        games.len(),
+        user_ids.len(),
+        options.speed
+    );
+
+    let clock = SimClock::new(options.speed);
+    for user_id in user_ids {
+        let bettor = Bettor::new(api.clone(), clock, BettorSettings::default(), user_id);
+        tokio::spawn(bettor.run());
+    }
+
+    let match_settings = MatchSettings::default();
+    let mut slots = Vec::new();
+    for _ in 0..options.matches {
+        let simulation = MatchSimulation::new(api.clone(), clock, match_settings.clone());
+        slots.push(tokio::spawn(run_slot(
+            simulation,
+            games.clone(),
+            options.rounds,
+        )));
+        // the matches are spread out, so they do not all start and end together
+        clock.sleep(match_settings.lead / 2).await;
+    }
+
+    let all_played = async {
+        for slot in slots {
+            slot.await?;
+        }
+        Ok::<(), anyhow::Error>(())
+    };
+    tokio::select! {
+        result = all_played => result?,
+        _ = tokio::signal::ctrl_c() => {
+            println!("simulation stopped, the matches being played are left as they are");
+        }
+    }
+    Ok(())
+}
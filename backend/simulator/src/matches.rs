@@ -0,0 +1,237 @@
+use chrono::{Duration, SecondsFormat};
+use rand::seq::SliceRandom;
+use std::future::Future;
+use tonic::{Code, Status};
+
+use crate::api::{
+    game_match::{
+        timeline_event::Payload, CreateGameEventRequest, CreateMatchRequest, GameEventType,
+        RecordTimelineEventRequest, Score, SeriesFormat, TimelineEvent,
+    },
+    Api,
+};
+use crate::clock::SimClock;
+use crate::seed::SeededGame;
+
+/// Tunables of the simulated matches, the durations are in the simulated time
+#[derive(Debug, Clone)]
+pub struct MatchSettings {
+    /// how long a match is upcoming before it starts
+    pub lead: Duration,
+    /// chance of a team scoring in a minute of the match
+    pub scoring_chance: f64,
+    /// share of the stakes kept by the bookmaker, lowering the ratios
+    pub margin: f64,
+}
+
+impl Default for MatchSettings {
+    fn default() -> Self {
+        MatchSettings {
+            lead: Duration::minutes(10),
+            scoring_chance: 0.1,
+            margin: 0.08,
+        }
+    }
+}
+
+/// How many times a call failing for a passing reason is made, e.g. while the server restarts
+const ATTEMPTS: usize = 3;
+/// Simulated time waited for before the call is made again
+const RETRY_DELAY: Duration = Duration::minutes(1);
+
+/// Ratio paying off the chance of winning, lowered by the margin
+fn fair_ratio(chance: f64, margin: f64) -> String {
+    let ratio = ((1.0 - margin) / chance).max(1.01);
+    format!("{:.2}", ratio)
+}
+
+/// Plays a single match of the game from its creation to its end:
+/// the match is created as upcoming, started at its scheduled start, scores during the expected
+/// duration of the game and ends with the winner, all through the API like the admins would
+pub struct MatchSimulation {
+    api: Api,
+    clock: SimClock,
+    settings: MatchSettings,
+}
+
+impl MatchSimulation {
+    pub fn new(api: Api, clock: SimClock, settings: MatchSettings) -> MatchSimulation {
+        MatchSimulation {
+            api,
+            clock,
+            settings,
+        }
+    }
+
+    /// Play a match of two random teams of the game
+    ///
+    /// Params
+    /// ---
+    /// - game: the game played, with at least two teams
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(()) once the match has ended
+    /// - Err(_) if a call to the server failed
+    pub async fn play(&mut self, game: &SeededGame) -> anyhow::Result<()> {
+        let teams: Vec<i32> = game
+            .team_ids
+            .choose_multiple(&mut rand::thread_rng(), 2)
+            .copied()
+            .collect();
+        let (team_one_id, team_two_id) = (teams[0], teams[1]);
+        // chance of the first team winning a point, and so the match
+        let strength = 0.25 + rand::random::<f64>() * 0.5;
+
+        let supposed_start_at = self.clock.after(self.settings.lead);
+        let match_id = self
+            .api
+            .game_match
+            .create_match(CreateMatchRequest {
+                game_id: game.id,
+                team_one_id,
+                team_two_id,
+                team_one_ratio: fair_ratio(strength, self.settings.margin),
+                team_two_ratio: fair_ratio(1.0 - strength, self.settings.margin),
+                supposed_start_at: supposed_start_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                state: String::from("Simulated"),
+                series_format: SeriesFormat::Bo1.into(),
+            })
+            .await?
+            .into_inner()
+            .id;
+        println!("match {} of {} created", match_id, game.name);
+
+        self.clock.sleep(self.settings.lead).await;
+        self.start(match_id).await?;
+        println!("match {} started", match_id);
+
+        let mut score = Score {
+            team_one: 0,
+            team_two: 0,
+        };
+        for _ in 0..game.expected_duration_minutes {
+            self.clock.sleep(Duration::minutes(1)).await;
+            if rand::random::<f64>() < self.settings.scoring_chance {
+                if rand::random::<f64>() < strength {
+                    score.team_one += 1;
+                } else {
+                    score.team_two += 1;
+                }
+                // a lost score is made up for by the next one
+                if let Err(err) = self.record_score(match_id, score.clone()).await {
+                    println!("score of match {} skipped: {}", match_id, err);
+                }
+            }
+        }
+
+        // a draw is decided by a final point
+        if score.team_one == score.team_two {
+            if rand::random::<f64>() < strength {
+                score.team_one += 1;
+            } else {
+                score.team_two += 1;
+            }
+            self.record_score(match_id, score.clone()).await?;
+        }
+        let winner_id = if score.team_one > score.team_two {
+            team_one_id
+        } else {
+            team_two_id
+        };
+        self.create_game_event(CreateGameEventRequest {
+            game_event_type: GameEventType::Ended.into(),
+            match_id,
+            winner_id: Some(winner_id),
+        })
+        .await?;
+        println!(
+            "match {} ended {}:{}",
+            match_id, score.team_one, score.team_two
+        );
+        Ok(())
+    }
+
+    /// Move the match to live, unless the scheduler of the server has done so already
+    async fn start(&mut self, match_id: i32) -> anyhow::Result<()> {
+        let started = self
+            .create_game_event(CreateGameEventRequest {
+                game_event_type: GameEventType::Live.into(),
+                match_id,
+                winner_id: None,
+            })
+            .await;
+        match started {
+            // the match has gone live already
+            Err(status) if status.code() == Code::FailedPrecondition => Ok(()),
+            started => Ok(started?),
+        }
+    }
+
+    async fn create_game_event(&mut self, request: CreateGameEventRequest) -> Result<(), Status> {
+        let client = self.api.game_match.clone();
+        retried(self.clock, || {
+            let mut client = client.clone();
+            let request = request.clone();
+            async move { client.create_game_event(request).await }
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn record_score(&mut self, match_id: i32, score: Score) -> Result<(), Status> {
+        let client = self.api.game_match.clone();
+        let request = RecordTimelineEventRequest {
+            event: Some(TimelineEvent {
+                id: 0,
+                match_id,
+                created_at: String::new(),
+                payload: Some(Payload::ScoreChanged(score)),
+            }),
+        };
+        retried(self.clock, || {
+            let mut client = client.clone();
+            let request = request.clone();
+            async move { client.record_timeline_event(request).await }
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+/// Make the call again while it fails for a reason which may pass
+///
+/// Params
+/// ---
+/// - clock: clock of the simulation, waited on between the attempts
+/// - call: makes the call
+///
+/// Returns
+/// ---
+/// - Ok(reply) once the call succeeds
+/// - Err(status) if the call has been refused, or failed every attempt
+async fn retried<T, F, R>(clock: SimClock, mut call: F) -> Result<T, Status>
+where
+    F: FnMut() -> R,
+    R: Future<Output = Result<T, Status>>,
+{
+    let mut attempt = 1;
+    loop {
+        match call().await {
+            Err(status) if attempt < ATTEMPTS && is_passing(&status) => {
+                println!("call failed, trying again: {}", status.message());
+                clock.sleep(RETRY_DELAY).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether the call may succeed when made again
+fn is_passing(status: &Status) -> bool {
+    matches!(
+        status.code(),
+        Code::Unavailable | Code::DeadlineExceeded | Code::Aborted | Code::Internal
+    )
+}
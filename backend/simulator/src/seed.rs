@@ -0,0 +1,176 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::api::{
+    game::{CreateGameRequest, ListGamesRequest},
+    team::{AddTeamToGameRequest, CreateTeamRequest, ListTeamsRequest},
+    Api,
+};
+
+/// Seed data shipped with the simulator, used unless another file is given
+pub const DEFAULT_SEED: &str = include_str!("../seed.json");
+
+/// A game of the seed data along with the teams playing it
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeedGame {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub expected_duration_minutes: i32,
+    pub teams: Vec<String>,
+}
+
+/// Games and teams the simulated matches are played by
+#[derive(Debug, Clone, Deserialize)]
+pub struct Seed {
+    pub games: Vec<SeedGame>,
+}
+
+/// A game known to the server, with the IDs of the teams playing it
+#[derive(Debug, Clone)]
+pub struct SeededGame {
+    pub id: i32,
+    pub name: String,
+    pub expected_duration_minutes: i32,
+    pub team_ids: Vec<i32>,
+}
+
+impl Seed {
+    /// Read the seed data
+    ///
+    /// Params
+    /// ---
+    /// - content: content of the seed JSON file
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(seed) if every game has a positive duration and at least two teams
+    /// - Err(_) otherwise
+    pub fn parse(content: &str) -> anyhow::Result<Seed> {
+        let seed: Seed = serde_json::from_str(content)?;
+        if seed.games.is_empty() {
+            anyhow::bail!("The seed data contains no games");
+        }
+        for game in seed.games.iter() {
+            if game.expected_duration_minutes <= 0 {
+                anyhow::bail!(
+                    "The duration of the game '{}' has to be positive",
+                    game.name
+                );
+            }
+            if game.teams.len() < 2 {
+                anyhow::bail!("The game '{}' needs at least two teams", game.name);
+            }
+        }
+        Ok(seed)
+    }
+
+    /// Make sure the games and teams exist on the server, creating the missing ones.
+    /// The records are matched by their names, so the seed can be applied repeatedly.
+    ///
+    /// Params
+    /// ---
+    /// - api: clients of the server
+    ///
+    /// Returns
+    /// ---
+    /// - Ok(games) with the IDs of the games and their teams
+    /// - Err(_) if a call to the server failed
+    pub async fn apply(&self, api: &mut Api) -> anyhow::Result<Vec<SeededGame>> {
+        let mut game_ids: HashMap<String, i32> = api
+            .game
+            .list_games(ListGamesRequest {
+                include_archived: false,
+            })
+            .await?
+            .into_inner()
+            .games
+            .into_iter()
+            .map(|game| (game.name, game.id))
+            .collect();
+        let mut team_ids: HashMap<String, i32> = api
+            .team
+            .list_teams(ListTeamsRequest {
+                game_id: None,
+                include_archived: false,
+            })
+            .await?
+            .into_inner()
+            .teams
+            .into_iter()
+            .map(|team| (team.name, team.id))
+            .collect();
+
+        let mut seeded = Vec::new();
+        for seed_game in self.games.iter() {
+            let game_id = match game_ids.get(&seed_game.name) {
+                Some(game_id) => *game_id,
+                None => {
+                    let game_id = api
+                        .game
+                        .create_game(CreateGameRequest {
+                            name: seed_game.name.clone(),
+                            logo_url: String::new(),
+                            expected_duration_minutes: Some(seed_game.expected_duration_minutes),
+                            description: seed_game.description.clone(),
+                            logo_asset_id: None,
+                        })
+                        .await?
+                        .into_inner()
+                        .id;
+                    game_ids.insert(seed_game.name.clone(), game_id);
+                    game_id
+                }
+            };
+
+            let playing: Vec<i32> = api
+                .team
+                .list_teams(ListTeamsRequest {
+                    game_id: Some(game_id),
+                    include_archived: false,
+                })
+                .await?
+                .into_inner()
+                .teams
+                .into_iter()
+                .map(|team| team.id)
+                .collect();
+
+            let mut seeded_team_ids = Vec::new();
+            for team_name in seed_game.teams.iter() {
+                let team_id = match team_ids.get(team_name) {
+                    Some(team_id) => *team_id,
+                    None => {
+                        let team_id = api
+                            .team
+                            .create_team(CreateTeamRequest {
+                                name: team_name.clone(),
+                                description: String::new(),
+                                logo: String::new(),
+                                logo_asset_id: None,
+                            })
+                            .await?
+                            .into_inner()
+                            .id;
+                        team_ids.insert(team_name.clone(), team_id);
+                        team_id
+                    }
+                };
+                if !playing.contains(&team_id) {
+                    api.team
+                        .add_team_to_game(AddTeamToGameRequest { team_id, game_id })
+                        .await?;
+                }
+                seeded_team_ids.push(team_id);
+            }
+
+            seeded.push(SeededGame {
+                id: game_id,
+                name: seed_game.name.clone(),
+                expected_duration_minutes: seed_game.expected_duration_minutes,
+                team_ids: seeded_team_ids,
+            });
+        }
+        Ok(seeded)
+    }
+}
@@ -1,12 +1,39 @@
 use dotenv::dotenv;
-use grpc_layer;
+use grpc_layer::{config::Config, importer::ImportFormat};
 use std::env;
 
+/// Import a file of games, teams and fixtures
+///
+/// Usage: `backend import <file.json|file.csv> [--dry-run]`
+async fn import(database_url: &str, args: &[String]) -> anyhow::Result<()> {
+    let path = match args.iter().find(|arg| !arg.starts_with("--")) {
+        Some(path) => path,
+        None => anyhow::bail!("Usage: backend import <file.json|file.csv> [--dry-run]"),
+    };
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+
+    let content = std::fs::read_to_string(path)?;
+    let report = grpc_layer::import_fixtures(
+        database_url,
+        &content,
+        ImportFormat::from_path(path)?,
+        dry_run,
+    )
+    .await?;
+    print!("{}", report);
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenv().ok();
-    let database_url = env::var("DATABASE_URL")?;
-    let server_address = env::var("SERVER_ADDRESS")?;
-    grpc_layer::run_grpc_server(&server_address, &database_url).await?;
+    // `config.toml` or the file named by `CONFIG_FILE`, overridden by the environment
+    let config = Config::load()?;
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("import") {
+        return import(&config.database.url, &args[1..]).await;
+    }
+
+    grpc_layer::run_grpc_server(config).await?;
     Ok(())
 }
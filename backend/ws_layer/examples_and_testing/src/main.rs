@@ -1,28 +1,271 @@
-use ws_layer;
-
-use futures::join;
-use std::{collections::HashMap, sync::Arc};
-use tokio::sync::Mutex;
-
-async fn send_loop(clients: ws_layer::Clients) {
-    loop {
-        for client in clients.lock().await.values() {
-            if let Some(sender) = &client.sender {
-                sender.send(Ok(ws_layer::Msg::text("pong")));
-            }
+use ws_layer::subscription::{
+    SubscriptionAction, SubscriptionRequest, Topic as SubscriptionTopic, TopicType,
+};
+use ws_layer::{Client, ClientRegistry, Clients, Topic, WsSettings};
+
+use async_trait::async_trait;
+use database_layer::db_access::session::SessionRepo;
+use futures::{SinkExt, StreamExt};
+use prost::Message;
+use std::{ops::RangeInclusive, sync::Arc, time::Duration};
+use tokio::sync::{mpsc, Mutex};
+use tokio_tungstenite::{connect_async, tungstenite};
+
+/// Session verification accepting only the token "valid", which belongs to the user 1
+struct FakeSessions;
+
+#[async_trait]
+impl SessionRepo for FakeSessions {
+    async fn create(&self, _desired_user_id: i32) -> anyhow::Result<String> {
+        Ok(String::from("valid"))
+    }
+
+    async fn get_user_id(&self, desired_token: &str) -> anyhow::Result<Option<i32>> {
+        Ok(if desired_token == "valid" {
+            Some(1)
+        } else {
+            None
+        })
+    }
+
+    async fn delete(&self, _desired_token: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn delete_expired(&self) -> anyhow::Result<usize> {
+        Ok(0)
+    }
+}
+
+/// Short timeouts and tiny queues, so the simulation does not take long
+fn settings() -> WsSettings {
+    WsSettings {
+        heartbeat_interval: Duration::from_millis(100),
+        heartbeat_timeout: Duration::from_millis(300),
+        queue_capacity: 4,
+        max_dropped_messages: 3,
+        replay_capacity: 8,
+    }
+}
+
+/// Resync message of the simulation, carrying just the sequence number
+fn resync(sequence: u64) -> ws_layer::Msg {
+    ws_layer::Msg::text(format!("resync {}", sequence))
+}
+
+fn new_clients() -> Clients {
+    Arc::new(Mutex::new(ClientRegistry::new(settings(), resync)))
+}
+
+/// Register a client subscribed to the announcements, returning the receiving end of its queue
+async fn add_client(clients: &Clients, client_id: &str) -> mpsc::Receiver<ws_layer::Msg> {
+    let (sender, receiver) = mpsc::channel(settings().queue_capacity);
+    clients.lock().await.clients.insert(
+        String::from(client_id),
+        Client {
+            client_id: String::from(client_id),
+            user_id: None,
+            topics: vec![Topic::Announcements].into_iter().collect(),
+            sender: Some(sender),
+            dropped_messages: 0,
+        },
+    );
+    receiver
+}
+
+/// Connect a websocket client and resume it from the desired sequence number,
+/// returning the text of the messages received within a short while
+async fn resume_over_ws(last_sequence: u64) -> anyhow::Result<Vec<String>> {
+    let (socket, _) = connect_async("ws://127.0.0.1:50052/ws").await?;
+    let (mut sender, mut receiver) = socket.split();
+
+    let request = SubscriptionRequest {
+        action: SubscriptionAction::Subscribe.into(),
+        topics: vec![SubscriptionTopic {
+            topic_type: TopicType::Announcements.into(),
+            id: 0,
+        }],
+        last_sequence,
+    };
+    sender
+        .send(tungstenite::Message::Binary(request.encode_to_vec()))
+        .await?;
+
+    let mut received = Vec::new();
+    while let Ok(Some(message)) =
+        tokio::time::timeout(Duration::from_millis(50), receiver.next()).await
+    {
+        if let tungstenite::Message::Text(text) = message? {
+            received.push(text);
         }
     }
+    Ok(received)
+}
+
+/// Publish an announcement for each of the sequence numbers, carrying just the number
+async fn publish_announcements(clients: &Clients, sequences: RangeInclusive<u64>) {
+    for sequence in sequences {
+        ws_layer::publish(
+            clients,
+            &[Topic::Announcements],
+            sequence,
+            ws_layer::Msg::text(sequence.to_string()),
+        )
+        .await;
+    }
+}
+
+/// A client which never drains its queue loses messages and gets evicted eventually
+async fn slow_client() -> anyhow::Result<()> {
+    let settings = settings();
+    let clients = new_clients();
+    let receiver = add_client(&clients, "slow").await;
+
+    // fill the queue
+    publish_announcements(&clients, 1..=settings.queue_capacity as u64).await;
+    let metrics = ws_layer::metrics(&clients).await;
+    anyhow::ensure!(
+        metrics.connected_clients == 1 && metrics.dropped_messages == 0,
+        "slow client: messages were dropped before the queue got full: {:?}",
+        metrics
+    );
+
+    // overflow the queue
+    let overflow = settings.queue_capacity + settings.max_dropped_messages;
+    publish_announcements(
+        &clients,
+        settings.queue_capacity as u64 + 1..=overflow as u64,
+    )
+    .await;
+    let metrics = ws_layer::metrics(&clients).await;
+    anyhow::ensure!(
+        metrics.connected_clients == 0
+            && metrics.dropped_messages == settings.max_dropped_messages as u64
+            && metrics.evicted_clients == 1,
+        "slow client: the client was not evicted: {:?}",
+        metrics
+    );
+
+    // the receiver has to live until now, otherwise the client counts as disconnected
+    drop(receiver);
+    println!(
+        "slow client: evicted after {} dropped messages",
+        metrics.dropped_messages
+    );
+    Ok(())
+}
+
+/// A client which stops answering pings gets disconnected, a client which reads stays connected
+async fn dead_and_healthy_clients(clients: &Clients) -> anyhow::Result<()> {
+    let invalid = connect_async("ws://127.0.0.1:50052/ws?token=invalid").await;
+    anyhow::ensure!(
+        invalid.is_err(),
+        "invalid token: the connection was accepted"
+    );
+
+    // the dead client never reads, so it never answers the pings
+    let (_dead, _) = connect_async("ws://127.0.0.1:50052/ws").await?;
+
+    // reading answers the pings of the server
+    let (healthy, _) = connect_async("ws://127.0.0.1:50052/ws?token=valid").await?;
+    let (_healthy_sender, mut healthy_rcv) = healthy.split();
+    tokio::spawn(async move { while healthy_rcv.next().await.is_some() {} });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let metrics = ws_layer::metrics(clients).await;
+    anyhow::ensure!(
+        metrics.connected_clients == 2,
+        "heartbeat: clients did not connect: {:?}",
+        metrics
+    );
+
+    tokio::time::sleep(settings().heartbeat_timeout * 3).await;
+    let metrics = ws_layer::metrics(clients).await;
+    anyhow::ensure!(
+        metrics.connected_clients == 1 && metrics.timed_out_clients == 1,
+        "heartbeat: the dead client was not disconnected: {:?}",
+        metrics
+    );
+
+    println!("heartbeat: dead client disconnected, healthy client still connected");
+    Ok(())
+}
+
+/// A reconnecting client gets the messages it missed, or the resync message
+/// when the missed messages are no longer kept, there are too many of them,
+/// or the client comes from an instance which has published more
+async fn resuming_clients(clients: &Clients) -> anyhow::Result<()> {
+    // the replay buffer keeps the sequences 3 to 10
+    publish_announcements(clients, 1..=10).await;
+
+    let replayed = resume_over_ws(7).await?;
+    anyhow::ensure!(
+        replayed == vec!["8", "9", "10"],
+        "replay: unexpected messages after the sequence 7: {:?}",
+        replayed
+    );
+
+    let up_to_date = resume_over_ws(10).await?;
+    anyhow::ensure!(
+        up_to_date.is_empty(),
+        "replay: an up to date client received messages: {:?}",
+        up_to_date
+    );
+
+    // the sequence 2 is no longer kept
+    let forgotten = resume_over_ws(1).await?;
+    anyhow::ensure!(
+        forgotten == vec!["resync 10"],
+        "replay: no resync after a forgotten sequence: {:?}",
+        forgotten
+    );
+
+    // five missed messages do not fit into the queue of four
+    let too_many = resume_over_ws(5).await?;
+    anyhow::ensure!(
+        too_many == vec!["resync 10"],
+        "replay: no resync after too many missed messages: {:?}",
+        too_many
+    );
+
+    // another instance has published the sequence 11 already
+    let ahead = resume_over_ws(11).await?;
+    anyhow::ensure!(
+        ahead == vec!["resync 10"],
+        "replay: no resync after a sequence of another instance: {:?}",
+        ahead
+    );
+
+    // the sequence 9 is published late, a client which has received 10 may have missed it
+    publish_announcements(clients, 9..=9).await;
+    let late = resume_over_ws(10).await?;
+    anyhow::ensure!(
+        late == vec!["resync 10"],
+        "replay: no resync after a late message: {:?}",
+        late
+    );
+
+    println!("replay: missed messages replayed, resync after a large gap or an unknown sequence");
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let route_clients = Arc::new(Mutex::new(HashMap::new()));
-    {
-        let mut route_clients_locked = route_clients.lock().await;
-        route_clients_locked.insert("bet".into(), Arc::new(Mutex::new(HashMap::new())));
-    }
-    let server_coro = ws_layer::run_ws_server(route_clients.clone());
-    let send_coro = send_loop(route_clients.lock().await.get("bet").unwrap().clone());
-    join!(server_coro, send_coro);
+    slow_client().await?;
+
+    let clients = new_clients();
+    tokio::spawn(ws_layer::run_ws_server(
+        clients.clone(),
+        Arc::new(FakeSessions),
+        std::env::temp_dir(),
+        ([127, 0, 0, 1], 50052).into(),
+        Vec::new(),
+    ));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    dead_and_healthy_clients(&clients).await?;
+    resuming_clients(&clients).await?;
+
+    println!("{}", ws_layer::metrics(&clients).await.render());
     Ok(())
 }
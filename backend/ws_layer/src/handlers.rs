@@ -1,19 +1,56 @@
-use crate::{Client, Clients, Result};
+use crate::subscription::{SubscriptionAction, SubscriptionRequest};
+use crate::{Client, Clients, Result, Sessions, Topic};
 use futures::{FutureExt, StreamExt};
-use tokio::sync::mpsc;
-use tokio_stream::wrappers::UnboundedReceiverStream;
+use prost::Message;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio_stream::wrappers::ReceiverStream;
 use uuid::Uuid;
+use warp::http::StatusCode;
 use warp::ws::WebSocket;
 use warp::Reply;
 
-pub async fn ws_handler(ws: warp::ws::Ws, clients: Clients) -> Result<impl Reply> {
-    Ok(ws.on_upgrade(move |socket| ws_callback(socket, clients)))
+/// Upgrade the connection to a websocket.
+/// Connections with a session token in the `token` query parameter get bound
+/// to the user of the session, connections without it stay anonymous.
+pub async fn ws_handler(
+    ws: warp::ws::Ws,
+    query: HashMap<String, String>,
+    clients: Clients,
+    sessions: Sessions,
+) -> Result<Box<dyn Reply>> {
+    let user_id = match query.get("token") {
+        Some(token) => match sessions.get_user_id(token).await {
+            Ok(Some(user_id)) => Some(user_id),
+            Ok(None) => {
+                return Ok(Box::new(warp::reply::with_status(
+                    "invalid session token",
+                    StatusCode::UNAUTHORIZED,
+                )))
+            }
+            Err(e) => {
+                println!("error verifying session token: {}", e);
+                return Ok(Box::new(StatusCode::INTERNAL_SERVER_ERROR));
+            }
+        },
+        None => None,
+    };
+    Ok(Box::new(ws.on_upgrade(move |socket| {
+        ws_callback(socket, clients, user_id)
+    })))
+}
+
+/// Show the metrics of the websocket connections
+pub async fn metrics_handler(clients: Clients) -> Result<impl Reply> {
+    Ok(crate::metrics(&clients).await.render())
 }
 
-pub async fn ws_callback(ws: WebSocket, clients: Clients) {
+pub async fn ws_callback(ws: WebSocket, clients: Clients, user_id: Option<i32>) {
+    let settings = clients.lock().await.settings.clone();
     let (client_ws_sender, mut client_ws_rcv) = ws.split();
-    let (client_sender, client_rcv) = mpsc::unbounded_channel();
-    let client_rcv = UnboundedReceiverStream::new(client_rcv);
+    let (client_sender, client_rcv) = mpsc::channel(settings.queue_capacity);
+    let client_rcv = ReceiverStream::new(client_rcv).map(Ok);
     tokio::task::spawn(client_rcv.forward(client_ws_sender).map(|result| {
         if let Err(e) = result {
             println!("error sending websocket msg: {}", e);
@@ -22,11 +59,107 @@ pub async fn ws_callback(ws: WebSocket, clients: Clients) {
     let uuid = Uuid::new_v4().to_simple().to_string();
     let new_client = Client {
         client_id: uuid.clone(),
+        user_id,
+        // the private channel of the user
+        topics: user_id
+            .into_iter()
+            .map(Topic::User)
+            .collect::<HashSet<Topic>>(),
         sender: Some(client_sender),
+        dropped_messages: 0,
+    };
+    clients
+        .lock()
+        .await
+        .clients
+        .insert(uuid.clone(), new_client);
+
+    let mut heartbeat = tokio::time::interval(settings.heartbeat_interval);
+    let mut last_seen = Instant::now();
+    loop {
+        tokio::select! {
+            result = client_ws_rcv.next() => match result {
+                Some(Ok(msg)) => {
+                    // any message (including pongs) proves the client is alive
+                    last_seen = Instant::now();
+                    if msg.is_binary() {
+                        match SubscriptionRequest::decode(msg.as_bytes()) {
+                            Ok(request) => handle_subscription(&clients, &uuid, request).await,
+                            Err(e) => println!("invalid subscription request: {}", e),
+                        }
+                    }
+                }
+                Some(Err(e)) => {
+                    println!("error receiving websocket msg: {}", e);
+                    break;
+                }
+                None => break,
+            },
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > settings.heartbeat_timeout {
+                    println!("websocket client {} timed out", uuid);
+                    clients.lock().await.metrics.timed_out_clients += 1;
+                    break;
+                }
+                if !ping(&clients, &uuid).await {
+                    // the client has been evicted
+                    break;
+                }
+            }
+        }
+    }
+
+    clients.lock().await.clients.remove(&uuid);
+}
+
+/// Queue a ping for the client, a full queue is left to the slow client eviction
+///
+/// Returns
+/// ---
+/// - false if the client is no longer connected
+async fn ping(clients: &Clients, client_id: &str) -> bool {
+    let registry = clients.lock().await;
+    match registry
+        .clients
+        .get(client_id)
+        .and_then(|client| client.sender.as_ref())
+    {
+        Some(sender) => !matches!(
+            sender.try_send(warp::ws::Message::ping(Vec::new())),
+            Err(TrySendError::Closed(_))
+        ),
+        None => false,
+    }
+}
+
+/// Change the topics of the client according to the subscription request,
+/// topics the client is not allowed to subscribe to are skipped.
+/// A request carrying the last received sequence number resumes the client
+/// after a reconnect, the messages it missed are replayed.
+async fn handle_subscription(clients: &Clients, client_id: &str, request: SubscriptionRequest) {
+    let mut registry = clients.lock().await;
+    let client = match registry.clients.get_mut(client_id) {
+        Some(client) => client,
+        None => return,
     };
-    clients.lock().await.insert(uuid.clone(), new_client);
 
-    while let Some(_) = client_ws_rcv.next().await {}
+    for topic in request.topics.iter().filter_map(Topic::from_proto) {
+        match SubscriptionAction::from_i32(request.action) {
+            Some(SubscriptionAction::Subscribe) if topic.allowed_for(client.user_id) => {
+                client.topics.insert(topic);
+            }
+            Some(SubscriptionAction::Subscribe) => {
+                println!("client {} cannot subscribe to {:?}", client_id, topic);
+            }
+            Some(SubscriptionAction::Unsubscribe) => {
+                client.topics.remove(&topic);
+            }
+            None => {}
+        }
+    }
 
-    clients.lock().await.remove(&uuid);
+    // the topics have to be changed first, so the replay covers the new subscriptions
+    if request.last_sequence > 0 {
+        registry.resume(client_id, request.last_sequence);
+    }
 }
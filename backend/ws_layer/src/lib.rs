@@ -1,54 +1,119 @@
-use anyhow::Context;
-use std::{collections::HashMap, convert::Infallible, sync::Arc};
-use tokio::sync::{mpsc, Mutex};
+use std::{collections::HashMap, convert::Infallible, net::SocketAddr, path::PathBuf, sync::Arc};
+use tokio::sync::Mutex;
 use warp::{ws::Message, Filter, Rejection};
 
+use database_layer::db_access::session::SessionRepo;
+
 mod handlers;
+mod registry;
+mod topic;
 
-#[derive(Debug, Clone)]
-pub struct Client {
-    pub client_id: String,
-    pub sender: Option<mpsc::UnboundedSender<std::result::Result<Message, warp::Error>>>,
+pub mod subscription {
+    tonic::include_proto!("subscription");
 }
 
-pub type Clients = Arc<Mutex<HashMap<String, Client>>>;
-pub type RouteClients = Arc<Mutex<HashMap<String, Clients>>>;
+pub use registry::{Client, ClientRegistry, Published, WsMetrics, WsSettings};
+pub use topic::Topic;
+
+/// Verifies the session tokens of the connecting clients
+pub type Sessions = Arc<dyn SessionRepo + Send + Sync>;
+
+pub type Clients = Arc<Mutex<ClientRegistry>>;
 type Result<T> = std::result::Result<T, Rejection>;
 
 pub type Msg = Message;
 
-pub async fn run_ws_server(route_clients: RouteClients) -> anyhow::Result<()> {
-    let bet_clients = route_clients
-        .lock()
-        .await
-        .get("bet")
-        .context("bet clients are absent")?
-        .clone();
-    let match_clients = route_clients
-        .lock()
-        .await
-        .get("match")
-        .context("match clients are absent")?
-        .clone();
-
-    let bet_route = warp::path("bet")
+/// Serve the websockets, the metrics and the uploaded images
+///
+/// Params
+/// ---
+/// - clients: connected websocket clients
+/// - sessions: verifies the session tokens of the connecting clients
+/// - media_directory: directory of the uploaded images, served under `/media`
+/// - address: address the server listens on
+/// - cors_origins: origins allowed to call the server from a browser, any origin when empty
+pub async fn run_ws_server(
+    clients: Clients,
+    sessions: Sessions,
+    media_directory: PathBuf,
+    address: SocketAddr,
+    cors_origins: Vec<String>,
+) -> anyhow::Result<()> {
+    let ws_route = warp::path("ws")
         .and(warp::ws())
-        .and(with_clients(bet_clients))
+        .and(warp::query::<HashMap<String, String>>())
+        .and(with_clients(clients.clone()))
+        .and(with_sessions(sessions))
         .and_then(handlers::ws_handler);
 
-    let match_route = warp::path("match")
-        .and(warp::ws())
-        .and(with_clients(match_clients))
-        .and_then(handlers::ws_handler);
+    let metrics_route = warp::path("metrics")
+        .and(warp::get())
+        .and(with_clients(clients))
+        .and_then(handlers::metrics_handler);
+
+    // the stored files are named after their content, so they never change
+    let media_route = warp::path("media")
+        .and(warp::get())
+        .and(warp::fs::dir(media_directory))
+        .map(|file| {
+            warp::reply::with_header(file, "cache-control", "public, max-age=31536000, immutable")
+        });
 
-    let routes = bet_route
-        .or(match_route)
-        .with(warp::cors().allow_any_origin());
+    let cors = match cors_origins.is_empty() {
+        true => warp::cors().allow_any_origin(),
+        false => warp::cors().allow_origins(cors_origins.iter().map(String::as_str)),
+    };
+    let routes = ws_route.or(metrics_route).or(media_route).with(cors);
 
-    warp::serve(routes).run(([127, 0, 0, 1], 50052)).await;
+    warp::serve(routes).run(address).await;
     Ok(())
 }
 
 fn with_clients(clients: Clients) -> impl Filter<Extract = (Clients,), Error = Infallible> + Clone {
     warp::any().map(move || clients.clone())
 }
+
+fn with_sessions(
+    sessions: Sessions,
+) -> impl Filter<Extract = (Sessions,), Error = Infallible> + Clone {
+    warp::any().map(move || sessions.clone())
+}
+
+/// Send the message to all connections subscribed to at least one of the topics.
+/// Slow clients lose the message instead of blocking the others.
+/// The message is kept for the clients resuming after a reconnect.
+///
+/// Params
+/// ---
+/// - clients: connected websocket clients
+/// - topics: topics the message belongs to
+/// - sequence: position of the event the message was derived from, shared by all instances,
+///   0 for the messages which are not kept for the resuming clients
+/// - message: the message carrying the sequence number
+pub async fn publish(clients: &Clients, topics: &[Topic], sequence: u64, message: Msg) {
+    let mut registry = clients.lock().await;
+    registry.deliver(&message, |client| {
+        topics.iter().any(|topic| client.topics.contains(topic))
+    });
+    if sequence > 0 {
+        registry.record(sequence, topics, message);
+    }
+}
+
+/// Send the message to all open connections of the user,
+/// every authenticated connection is subscribed to the account topic of its user
+///
+/// Params
+/// ---
+/// - clients: connected websocket clients
+/// - user_id: ID of the user we wish to send the message to
+/// - sequence: position of the event the message was derived from, see `publish`
+/// - message: the message carrying the sequence number
+pub async fn send_to_user(clients: &Clients, user_id: i32, sequence: u64, message: Msg) {
+    publish(clients, &[Topic::User(user_id)], sequence, message).await
+}
+
+/// Get the current metrics of the websocket connections
+pub async fn metrics(clients: &Clients) -> WsMetrics {
+    clients.lock().await.metrics()
+}
@@ -0,0 +1,413 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
+};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use warp::ws::Message;
+
+use crate::Topic;
+
+/// Tunables of the websocket connections
+#[derive(Debug, Clone)]
+pub struct WsSettings {
+    /// how often the server pings every client
+    pub heartbeat_interval: Duration,
+    /// how long a client may stay silent (not even answering pings) before it is disconnected
+    pub heartbeat_timeout: Duration,
+    /// number of messages waiting to be sent to a single client
+    pub queue_capacity: usize,
+    /// number of messages dropped in a row (because of the full queue)
+    /// after which the client is disconnected
+    pub max_dropped_messages: usize,
+    /// number of the latest published messages kept for the clients resuming after a reconnect
+    pub replay_capacity: usize,
+}
+
+impl Default for WsSettings {
+    fn default() -> Self {
+        WsSettings {
+            heartbeat_interval: Duration::from_secs(15),
+            heartbeat_timeout: Duration::from_secs(45),
+            queue_capacity: 256,
+            max_dropped_messages: 64,
+            replay_capacity: 1024,
+        }
+    }
+}
+
+/// Counters describing the health of the websocket connections
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WsMetrics {
+    pub connected_clients: usize,
+    /// messages not delivered because the queue of the client was full
+    pub dropped_messages: u64,
+    /// clients disconnected because they could not keep up with the messages
+    pub evicted_clients: u64,
+    /// clients disconnected because they stopped answering pings
+    pub timed_out_clients: u64,
+    /// messages sent again to the clients resuming after a reconnect
+    pub replayed_messages: u64,
+    /// resuming clients told to refetch their state, because the missed messages were not kept
+    pub resyncs: u64,
+}
+
+impl WsMetrics {
+    /// Render the metrics in the Prometheus text format
+    pub fn render(&self) -> String {
+        format!(
+            "ws_connected_clients {}\nws_dropped_messages_total {}\nws_evicted_clients_total {}\nws_timed_out_clients_total {}\nws_replayed_messages_total {}\nws_resyncs_total {}\n",
+            self.connected_clients,
+            self.dropped_messages,
+            self.evicted_clients,
+            self.timed_out_clients,
+            self.replayed_messages,
+            self.resyncs
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    pub client_id: String,
+    pub user_id: Option<i32>,
+    pub topics: HashSet<Topic>,
+    pub sender: Option<mpsc::Sender<Message>>,
+    /// number of messages dropped in a row because the queue was full
+    pub dropped_messages: usize,
+}
+
+/// A published message kept for the clients resuming after a reconnect
+#[derive(Debug, Clone)]
+pub struct Published {
+    pub sequence: u64,
+    pub topics: Vec<Topic>,
+    pub message: Message,
+    /// published after a message with a higher sequence number,
+    /// a client may have missed it even though it has received a higher one
+    pub late: bool,
+}
+
+/// Connected clients together with the highest sequence number published
+/// and the latest published messages. The sequence numbers are the positions
+/// of the events the messages were derived from, shared by all instances,
+/// so a client may resume on another instance than the one it was connected to.
+#[derive(Debug)]
+pub struct ClientRegistry {
+    pub clients: HashMap<String, Client>,
+    pub sequence: u64,
+    pub replay: VecDeque<Published>,
+    /// highest sequence number of the messages no longer kept
+    pub forgotten: u64,
+    /// builds the message telling a resuming client to refetch its state,
+    /// from the sequence number of the last published message
+    pub resync: fn(u64) -> Message,
+    pub settings: WsSettings,
+    pub metrics: WsMetrics,
+}
+
+impl ClientRegistry {
+    /// Create an empty registry
+    ///
+    /// Params
+    /// ---
+    /// - settings: settings used for new connections
+    /// - resync: builds the message telling a resuming client to refetch its state
+    pub fn new(settings: WsSettings, resync: fn(u64) -> Message) -> ClientRegistry {
+        ClientRegistry {
+            clients: HashMap::new(),
+            sequence: 0,
+            replay: VecDeque::with_capacity(settings.replay_capacity),
+            forgotten: 0,
+            resync,
+            settings,
+            metrics: WsMetrics::default(),
+        }
+    }
+
+    /// Get the current metrics of the connections
+    pub fn metrics(&self) -> WsMetrics {
+        WsMetrics {
+            connected_clients: self.clients.len(),
+            ..self.metrics.clone()
+        }
+    }
+
+    /// Queue the message for all clients accepted by the filter.
+    /// Clients with a full queue lose the message, when they lose too many
+    /// messages in a row, they get disconnected. Disconnecting happens
+    /// by dropping the sender, which closes the websocket.
+    pub(crate) fn deliver<F>(&mut self, message: &Message, filter: F)
+    where
+        F: Fn(&Client) -> bool,
+    {
+        let max_dropped_messages = self.settings.max_dropped_messages;
+        let mut evicted: Vec<String> = Vec::new();
+        let mut closed: Vec<String> = Vec::new();
+
+        for client in self.clients.values_mut().filter(|client| filter(client)) {
+            let sender = match &client.sender {
+                Some(sender) => sender,
+                None => continue,
+            };
+
+            match sender.try_send(message.clone()) {
+                Ok(()) => client.dropped_messages = 0,
+                Err(TrySendError::Full(_)) => {
+                    client.dropped_messages += 1;
+                    self.metrics.dropped_messages += 1;
+                    if client.dropped_messages >= max_dropped_messages {
+                        evicted.push(client.client_id.clone());
+                    }
+                }
+                Err(TrySendError::Closed(_)) => closed.push(client.client_id.clone()),
+            }
+        }
+
+        for client_id in evicted.iter() {
+            println!("evicting slow websocket client {}", client_id);
+            self.clients.remove(client_id);
+            self.metrics.evicted_clients += 1;
+        }
+        for client_id in closed.iter() {
+            self.clients.remove(client_id);
+        }
+    }
+
+    /// Keep the published message for the clients resuming after a reconnect,
+    /// the oldest kept message is forgotten when the replay buffer is full
+    pub(crate) fn record(&mut self, sequence: u64, topics: &[Topic], message: Message) {
+        let late = sequence < self.sequence;
+        self.sequence = self.sequence.max(sequence);
+        if self.settings.replay_capacity == 0 {
+            self.forgotten = self.sequence;
+            return;
+        }
+        while self.replay.len() >= self.settings.replay_capacity {
+            if let Some(forgotten) = self.replay.pop_front() {
+                self.forgotten = self.forgotten.max(forgotten.sequence);
+            }
+        }
+        self.replay.push_back(Published {
+            sequence,
+            topics: topics.to_vec(),
+            message,
+            late,
+        });
+    }
+
+    /// Queue the messages the client missed since the highest sequence number it received,
+    /// limited to the topics it is subscribed to. When some of the missed messages are
+    /// no longer kept, the sequence number is unknown to this instance, or the messages
+    /// would not fit into the queue of the client, the client is told to refetch its state instead.
+    pub(crate) fn resume(&mut self, client_id: &str, last_sequence: u64) {
+        let client = match self.clients.get(client_id) {
+            Some(client) => client,
+            None => return,
+        };
+        let sender = match &client.sender {
+            Some(sender) => sender,
+            None => return,
+        };
+
+        let subscribed = |published: &&Published| {
+            published
+                .topics
+                .iter()
+                .any(|topic| client.topics.contains(topic))
+        };
+        // a sequence ahead of ours comes from an instance which has published more,
+        // the messages this instance has not published yet would be sent twice
+        let known = last_sequence <= self.sequence;
+        let kept = last_sequence >= self.forgotten;
+        // a message published late may have been missed even by a client with a higher sequence
+        let late_missed = self
+            .replay
+            .iter()
+            .filter(subscribed)
+            .any(|published| published.late && published.sequence <= last_sequence);
+
+        let missed: Vec<&Message> = self
+            .replay
+            .iter()
+            .filter(subscribed)
+            .filter(|published| published.sequence > last_sequence)
+            .map(|published| &published.message)
+            .collect();
+
+        if !known || !kept || late_missed || missed.len() > sender.capacity() {
+            let _ = sender.try_send((self.resync)(self.sequence));
+            self.metrics.resyncs += 1;
+            return;
+        }
+
+        for message in missed.iter() {
+            let _ = sender.try_send((*message).clone());
+        }
+        self.metrics.replayed_messages += missed.len() as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc::Receiver;
+
+    fn resync(sequence: u64) -> Message {
+        Message::text(format!("resync {}", sequence))
+    }
+
+    fn registry(queue_capacity: usize, replay_capacity: usize) -> ClientRegistry {
+        ClientRegistry::new(
+            WsSettings {
+                queue_capacity,
+                max_dropped_messages: 2,
+                replay_capacity,
+                ..WsSettings::default()
+            },
+            resync,
+        )
+    }
+
+    /// Connect a client subscribed to the announcements
+    fn connect(registry: &mut ClientRegistry, client_id: &str) -> Receiver<Message> {
+        let (sender, receiver) = mpsc::channel(registry.settings.queue_capacity);
+        registry.clients.insert(
+            String::from(client_id),
+            Client {
+                client_id: String::from(client_id),
+                user_id: None,
+                topics: [Topic::Announcements].iter().copied().collect(),
+                sender: Some(sender),
+                dropped_messages: 0,
+            },
+        );
+        receiver
+    }
+
+    fn publish(registry: &mut ClientRegistry, sequence: u64) {
+        let message = Message::text(sequence.to_string());
+        registry.deliver(&message, |client| {
+            client.topics.contains(&Topic::Announcements)
+        });
+        registry.record(sequence, &[Topic::Announcements], message);
+    }
+
+    fn received(receiver: &mut Receiver<Message>) -> Vec<String> {
+        let mut messages = Vec::new();
+        while let Ok(message) = receiver.try_recv() {
+            messages.push(String::from(message.to_str().unwrap()));
+        }
+        messages
+    }
+
+    #[test]
+    fn slow_client_evicted_after_dropping_messages_in_a_row() {
+        let mut registry = registry(1, 16);
+        let mut slow = connect(&mut registry, "slow");
+        let mut fast = connect(&mut registry, "fast");
+
+        publish(&mut registry, 1);
+        assert_eq!(received(&mut fast), ["1"]);
+        publish(&mut registry, 2);
+        assert_eq!(received(&mut fast), ["2"]);
+        assert!(registry.clients.contains_key("slow"));
+        publish(&mut registry, 3);
+        assert_eq!(received(&mut fast), ["3"]);
+
+        assert!(!registry.clients.contains_key("slow"));
+        assert!(registry.clients.contains_key("fast"));
+        assert_eq!(received(&mut slow), ["1"]);
+        let metrics = registry.metrics();
+        assert_eq!(metrics.dropped_messages, 2);
+        assert_eq!(metrics.evicted_clients, 1);
+        assert_eq!(metrics.connected_clients, 1);
+    }
+
+    #[test]
+    fn delivered_message_resets_the_dropped_messages() {
+        let mut registry = registry(1, 16);
+        let mut client = connect(&mut registry, "client");
+
+        publish(&mut registry, 1);
+        publish(&mut registry, 2);
+        assert_eq!(received(&mut client), ["1"]);
+        publish(&mut registry, 3);
+        publish(&mut registry, 4);
+        assert_eq!(received(&mut client), ["3"]);
+        assert!(registry.clients.contains_key("client"));
+    }
+
+    #[test]
+    fn closed_client_removed_without_eviction() {
+        let mut registry = registry(4, 16);
+        drop(connect(&mut registry, "gone"));
+
+        publish(&mut registry, 1);
+        assert!(registry.clients.is_empty());
+        assert_eq!(registry.metrics().evicted_clients, 0);
+    }
+
+    #[test]
+    fn missed_messages_replayed() {
+        let mut registry = registry(8, 16);
+        for sequence in 1..=3 {
+            publish(&mut registry, sequence);
+        }
+        let mut client = connect(&mut registry, "client");
+
+        registry.resume("client", 1);
+        assert_eq!(received(&mut client), ["2", "3"]);
+        assert_eq!(registry.metrics().replayed_messages, 2);
+        registry.resume("client", 3);
+        assert!(received(&mut client).is_empty());
+    }
+
+    #[test]
+    fn resync_when_the_missed_messages_are_forgotten() {
+        let mut registry = registry(8, 2);
+        for sequence in 1..=4 {
+            publish(&mut registry, sequence);
+        }
+        assert_eq!(registry.forgotten, 2);
+        let mut client = connect(&mut registry, "client");
+
+        registry.resume("client", 2);
+        assert_eq!(received(&mut client), ["3", "4"]);
+        registry.resume("client", 1);
+        assert_eq!(received(&mut client), ["resync 4"]);
+        assert_eq!(registry.metrics().resyncs, 1);
+    }
+
+    #[test]
+    fn resync_when_the_sequence_is_unknown() {
+        let mut registry = registry(8, 16);
+        publish(&mut registry, 1);
+        let mut client = connect(&mut registry, "client");
+
+        registry.resume("client", 5);
+        assert_eq!(received(&mut client), ["resync 1"]);
+    }
+
+    #[test]
+    fn resync_when_a_late_message_may_be_missed() {
+        let mut registry = registry(8, 16);
+        publish(&mut registry, 2);
+        publish(&mut registry, 1);
+        let mut client = connect(&mut registry, "client");
+
+        registry.resume("client", 2);
+        assert_eq!(received(&mut client), ["resync 2"]);
+    }
+
+    #[test]
+    fn resync_when_the_missed_messages_do_not_fit_the_queue() {
+        let mut registry = registry(2, 16);
+        for sequence in 1..=3 {
+            publish(&mut registry, sequence);
+        }
+        let mut client = connect(&mut registry, "client");
+
+        registry.resume("client", 0);
+        assert_eq!(received(&mut client), ["resync 3"]);
+    }
+}
@@ -0,0 +1,52 @@
+use crate::subscription::{self, TopicType};
+
+/// Topic a websocket client can subscribe to,
+/// every published event is delivered only to the subscribers of its topics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topic {
+    Match(i32),
+    Game(i32),
+    User(i32),
+    Announcements,
+    /// the anonymised feed of the submitted tickets
+    LatestBets,
+}
+
+impl Topic {
+    /// Convert the topic received from the client
+    ///
+    /// Params
+    /// ---
+    /// - topic: topic from the subscription request
+    ///
+    /// Returns
+    /// ---
+    /// - Some(topic) if the topic type is known
+    /// - None otherwise
+    pub fn from_proto(topic: &subscription::Topic) -> Option<Topic> {
+        match TopicType::from_i32(topic.topic_type)? {
+            TopicType::Match => Some(Topic::Match(topic.id)),
+            TopicType::Game => Some(Topic::Game(topic.id)),
+            TopicType::User => Some(Topic::User(topic.id)),
+            TopicType::Announcements => Some(Topic::Announcements),
+            TopicType::LatestBets => Some(Topic::LatestBets),
+        }
+    }
+
+    /// Check whether the connection of the user may subscribe to the topic,
+    /// the account topic of a user is available only to the user themself
+    ///
+    /// Params
+    /// ---
+    /// - user_id: ID of the user bound to the connection, if any
+    ///
+    /// Returns
+    /// ---
+    /// - true if the subscription is allowed
+    pub fn allowed_for(&self, user_id: Option<i32>) -> bool {
+        match *self {
+            Topic::User(id) => user_id == Some(id),
+            _ => true,
+        }
+    }
+}
@@ -17,4 +17,16 @@ fn main() {
     rust_grpc_web::configure()
         .compile(&["../proto/bet.proto"], &["../proto/"])
         .unwrap();
+    rust_grpc_web::configure()
+        .compile(&["../proto/subscription.proto"], &["../proto/"])
+        .unwrap();
+    rust_grpc_web::configure()
+        .compile(&["../proto/tournament.proto"], &["../proto/"])
+        .unwrap();
+    rust_grpc_web::configure()
+        .compile(&["../proto/envelope.proto"], &["../proto/"])
+        .unwrap();
+    rust_grpc_web::configure()
+        .compile(&["../proto/error.proto"], &["../proto/"])
+        .unwrap();
 }
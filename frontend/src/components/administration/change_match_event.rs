@@ -3,6 +3,7 @@ use crate::{
         auth::input_number::{NumberInput, NumberType},
         loading_animation::LoadingAnimation,
     },
+    config::GRPC_URL,
     types::{
         grpc_types::game_match::{
             match_service_client, CreateGameEventReply, CreateGameEventRequest, GameEventType,
@@ -68,8 +69,7 @@ impl Component for ChangeMatchEvent {
                 }
                 ctx.link().send_message(Msg::SetLoading(true));
 
-                let grpc_client =
-                    match_service_client::MatchService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = match_service_client::MatchService::new(String::from(GRPC_URL));
 
                 let match_id = self.match_id.0 as i32;
                 let game_event_type = match self.event_type {
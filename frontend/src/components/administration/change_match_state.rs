@@ -6,6 +6,7 @@ use crate::{
         },
         loading_animation::LoadingAnimation,
     },
+    config::GRPC_URL,
     types::{
         grpc_types::game_match::{match_service_client, ChangeStateReply, ChangeStateRequest},
         Field, SubmitResult,
@@ -62,8 +63,7 @@ impl Component for ChangeMatchState {
                 }
                 ctx.link().send_message(Msg::SetLoading(true));
 
-                let grpc_client =
-                    match_service_client::MatchService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = match_service_client::MatchService::new(String::from(GRPC_URL));
 
                 let match_id = self.match_id.0 as i32;
                 let state = self.match_state.0.trim().to_string();
@@ -71,10 +71,7 @@ impl Component for ChangeMatchState {
                 ctx.link().send_future(async move {
                     Msg::ReceiveResponse(
                         grpc_client
-                            .change_state(ChangeStateRequest {
-                                match_id,
-                                state,
-                            })
+                            .change_state(ChangeStateRequest { match_id, state })
                             .await,
                     )
                 });
@@ -1,5 +1,6 @@
 use crate::{
     components::{auth::input::TextInput, loading_animation::LoadingAnimation},
+    config::GRPC_URL,
     types::{
         grpc_types::game::{game_service_client, CreateGameReply, CreateGameRequest},
         CreateGameFormData, Field, SubmitResult,
@@ -48,15 +49,21 @@ impl Component for CreateGameForm {
                 }
                 ctx.link().send_message(Msg::SetLoading(true));
 
-                let grpc_client =
-                    game_service_client::GameService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = game_service_client::GameService::new(String::from(GRPC_URL));
                 let name = self.data.name.0.trim().to_string();
                 let logo_url = self.data.logo_url.0.trim().to_string();
 
                 ctx.link().send_future(async move {
                     Msg::ReceiveResponse(
                         grpc_client
-                            .create_game(CreateGameRequest { name, logo_url })
+                            .create_game(CreateGameRequest {
+                                name,
+                                logo_url,
+                                // the default duration of the server
+                                expected_duration_minutes: None,
+                                description: String::new(),
+                                logo_asset_id: None,
+                            })
                             .await,
                     )
                 });
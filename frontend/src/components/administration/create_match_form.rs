@@ -6,8 +6,11 @@ use crate::{
         },
         loading_animation::LoadingAnimation,
     },
+    config::GRPC_URL,
     types::{
-        grpc_types::game_match::{match_service_client, CreateMatchReply, CreateMatchRequest},
+        grpc_types::game_match::{
+            match_service_client, CreateMatchReply, CreateMatchRequest, SeriesFormat,
+        },
         CreateMatchFormData, Field, SubmitResult,
     },
 };
@@ -73,8 +76,7 @@ impl Component for CreateMatchForm {
                 };
                 let datetime_utc = datetime.with_timezone(&Utc);
 
-                let grpc_client =
-                    match_service_client::MatchService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = match_service_client::MatchService::new(String::from(GRPC_URL));
                 let game_id = self.data.game_id.0 as i32;
                 let team_one_id = self.data.team_one_id.0 as i32;
                 let team_two_id = self.data.team_two_id.0 as i32;
@@ -93,6 +95,7 @@ impl Component for CreateMatchForm {
                                 team_two_ratio,
                                 supposed_start_at,
                                 state: "unknown".to_string(),
+                                series_format: SeriesFormat::Bo1.into(),
                             })
                             .await,
                     )
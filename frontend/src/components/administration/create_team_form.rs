@@ -1,5 +1,6 @@
 use crate::{
     components::{auth::input::TextInput, loading_animation::LoadingAnimation},
+    config::GRPC_URL,
     types::{
         grpc_types::team::{team_service_client, CreateTeamReply, CreateTeamRequest},
         CreateTeamFormData, Field, SubmitResult,
@@ -51,8 +52,7 @@ impl Component for CreateTeamForm {
                 }
                 ctx.link().send_message(Msg::SetLoading(true));
 
-                let grpc_client =
-                    team_service_client::TeamService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = team_service_client::TeamService::new(String::from(GRPC_URL));
                 let name = self.data.name.0.trim().to_string();
                 let description = self.data.description.0.trim().to_string();
                 let logo = self.data.logo_url.0.trim().to_string();
@@ -64,6 +64,7 @@ impl Component for CreateTeamForm {
                                 name,
                                 description,
                                 logo,
+                                logo_asset_id: None,
                             })
                             .await,
                     )
@@ -3,6 +3,7 @@ use crate::{
         auth::input_number::{NumberInput, NumberType},
         loading_animation::LoadingAnimation,
     },
+    config::GRPC_URL,
     types::{
         grpc_types::team::{team_service_client, AddTeamToGameReply, AddTeamToGameRequest},
         SubmitResult,
@@ -53,8 +54,7 @@ impl Component for CreateTeamPlaysGameForm {
                 }
                 ctx.link().send_message(Msg::SetLoading(true));
 
-                let grpc_client =
-                    team_service_client::TeamService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = team_service_client::TeamService::new(String::from(GRPC_URL));
 
                 let team_id: i32 = self.team_id.0 as i32;
                 let game_id: i32 = self.game_id.0 as i32;
@@ -1,5 +1,7 @@
 use crate::{
     components::auth::input::{InputType, TextInput},
+    config::GRPC_URL,
+    services::error_message,
     store::{UserRequest, UserStore},
     types::{
         grpc_types::user::{user_service_client, AuthUserReply, AuthUserRequest},
@@ -49,8 +51,7 @@ impl Component for LoginForm {
                     warn!("Inserted login data are not valid");
                     return false;
                 }
-                let grpc_client =
-                    user_service_client::UserService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = user_service_client::UserService::new(String::from(GRPC_URL));
                 let email = self.data.email.trim().to_string();
                 let password = self.data.password.trim().to_string();
 
@@ -66,10 +67,11 @@ impl Component for LoginForm {
             Msg::SetPassword((new_data, _, _)) => self.data.password = new_data,
             Msg::UserStore(_) => {}
             Msg::ReceiveResponse(Ok(user)) => {
-                self.user_store.send(UserRequest::Login(user.user));
+                self.user_store
+                    .send(UserRequest::Login(user.user, user.session_token));
             }
             Msg::ReceiveResponse(Err(err)) => {
-                self.error = err.to_string();
+                self.error = error_message(&err);
                 let link = ctx.link().clone();
                 Timeout::new(5000, move || link.send_message(Msg::ResetError)).forget();
             }
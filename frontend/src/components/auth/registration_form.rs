@@ -1,6 +1,7 @@
 use super::input::InputType;
 use crate::{
     components::{auth::input::TextInput, loading_animation::LoadingAnimation},
+    config::GRPC_URL,
     types::{
         grpc_types::user::{user_service_client, Address, CreateUserReply, CreateUserRequest},
         Field, MainRoute, SubmitResult, UserRegistrationFormData,
@@ -73,8 +74,7 @@ impl Component for RegistrationForm {
                 }
                 ctx.link().send_message(Msg::SetLoading(true));
 
-                let grpc_client =
-                    user_service_client::UserService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = user_service_client::UserService::new(String::from(GRPC_URL));
 
                 let reg_data = self.data.clone();
 
@@ -90,6 +90,7 @@ impl Component for RegistrationForm {
                                 email: reg_data.email.0.trim().to_string(),
                                 phone_number: reg_data.phone_number.0.trim().to_string(),
                                 photo: None,
+                                photo_asset_id: None,
                                 address: Some(Address {
                                     street_name: reg_data.address.street_name.0.trim().to_string(),
                                     street_number: reg_data
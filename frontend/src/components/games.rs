@@ -1,5 +1,5 @@
 use crate::components::loading_animation::LoadingAnimation;
-use crate::store::{GamesRequest, GamesStore};
+use crate::store::{GamesRequest, GamesStore, MatchesRequest, MatchesStore, UserStore};
 use crate::types::grpc_types::game::{game_service_client, Game, ListGamesReply, ListGamesRequest};
 use log::info;
 use yew::prelude::*;
@@ -10,9 +10,12 @@ use yew_agent::{
 
 pub enum Msg {
     GamesStore(ReadOnly<GamesStore>),
+    UserStore(ReadOnly<UserStore>),
+    MatchesStore(ReadOnly<MatchesStore>),
     Fetch,
     FilterAdd(i32),
     FilterRemove(i32),
+    ToggleFavourites,
 }
 
 pub struct Games {
@@ -20,7 +23,13 @@ pub struct Games {
     filter_ids: Vec<i32>,
     is_loading: bool,
     is_error: bool,
+    /// ID of the logged in user
+    user_id: Option<i32>,
+    /// whether only the matches followed by the user are shown
+    favourites_only: bool,
     games_store: Box<dyn Bridge<StoreWrapper<GamesStore>>>,
+    user_store: Box<dyn Bridge<StoreWrapper<UserStore>>>,
+    matches_store: Box<dyn Bridge<StoreWrapper<MatchesStore>>>,
 }
 
 impl Component for Games {
@@ -34,7 +43,11 @@ impl Component for Games {
             filter_ids: Vec::new(),
             is_loading: false,
             is_error: false,
+            user_id: None,
+            favourites_only: false,
             games_store: GamesStore::bridge(ctx.link().callback(Msg::GamesStore)),
+            user_store: UserStore::bridge(ctx.link().callback(Msg::UserStore)),
+            matches_store: MatchesStore::bridge(ctx.link().callback(Msg::MatchesStore)),
         }
     }
 
@@ -47,6 +60,19 @@ impl Component for Games {
                 self.is_loading = state.is_loading.clone();
                 self.is_error = state.is_error.clone();
             }
+            Msg::UserStore(state) => {
+                let state = state.borrow();
+                self.user_id = state.user.as_ref().map(|user| user.id);
+                // the favourites of a logged out user are not shown anymore
+                if self.user_id.is_none() && self.favourites_only {
+                    self.matches_store
+                        .send(MatchesRequest::ShowFavourites(None));
+                }
+            }
+            Msg::MatchesStore(state) => {
+                let state = state.borrow();
+                self.favourites_only = state.favourites_of_user_id.is_some();
+            }
             Msg::Fetch => {
                 self.games_store.send(GamesRequest::Fetch);
             }
@@ -56,6 +82,14 @@ impl Component for Games {
             Msg::FilterRemove(id) => {
                 self.games_store.send(GamesRequest::FilterRemove(id));
             }
+            Msg::ToggleFavourites => {
+                let favourites_of_user_id = match self.favourites_only {
+                    true => None,
+                    false => self.user_id,
+                };
+                self.matches_store
+                    .send(MatchesRequest::ShowFavourites(favourites_of_user_id));
+            }
         }
         true
     }
@@ -66,6 +100,15 @@ impl Component for Games {
             <div class="h-full flex flex-col">
                 <div class="font-bold mb-2">{"Games"}</div>
 
+                if self.user_id.is_some() {
+                    <div
+                        onclick={ctx.link().callback(|_| Msg::ToggleFavourites)}
+                        class={format!("rounded-md p-1 mb-2 text-black font-bold cursor-pointer {}",
+                                if self.favourites_only {"bg-yellow"} else {"bg-white"})}>
+                        {"My favourites"}
+                    </div>
+                }
+
                 if self.is_loading {
                     <LoadingAnimation color="white" />
                 }
@@ -85,7 +128,7 @@ impl Component for Games {
                                         <li
                                             key={ game.id }
                                             onclick={ if disabled { ctx.link().callback(move |_|Msg::FilterRemove(game_id)) } else { ctx.link().callback(move |_|Msg::FilterAdd(game_id)) } }
-                                            class={format!("flex flex-row gap-2 rounded-md p-1 text-black text-left font-bold cursor-pointer {}", 
+                                            class={format!("flex flex-row gap-2 rounded-md p-1 text-black text-left font-bold cursor-pointer {}",
                                                     if disabled {"bg-gray-400"} else {"bg-white"})}>
                                             if game.logo_url != "" {
                                                 <div class="w-6 h-6 my-auto">
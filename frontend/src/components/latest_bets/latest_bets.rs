@@ -1,15 +1,86 @@
-use gloo::console::info;
-use wasm_sockets::{self, Message, WebSocketError};
+use crate::components::latest_bets::latest_bets_item::LatestBetsItem;
+use crate::config::WS_URL;
+use crate::types::grpc_types::{
+    envelope::{envelope::Payload, Envelope, LatestBet, LatestBets as LatestBetsBatch},
+    subscription::{SubscriptionAction, SubscriptionRequest, Topic, TopicType},
+};
+use bytes::BytesMut;
+use gloo_timers::callback::Timeout;
+use prost::Message as ProstMessage;
+use wasm_sockets::{self, Message};
 use yew::prelude::*;
 
+/// Number of the latest bets shown
+const MAX_SHOWN_BETS: usize = 20;
+
+/// Delay before reconnecting a dropped websocket, in milliseconds
+const WS_RECONNECT_DELAY: u32 = 2000;
+
 pub enum Msg {
-    Connect,
-    ReceiveBets(String),
+    Connected,
+    Closed,
+    Reconnect,
+    ReceiveBatch(LatestBetsBatch),
 }
 
 pub struct LatestBets {
-    client: Option<wasm_sockets::EventClient>,
-    latest_tickets: Vec<String>,
+    client: wasm_sockets::EventClient,
+    latest_bets: Vec<LatestBet>,
+    /// bets left out by the server during busy periods
+    omitted: u32,
+}
+
+impl LatestBets {
+    /// Open the websocket connection, the feed is public, so no session token is needed
+    fn connect(ctx: &Context<Self>) -> wasm_sockets::EventClient {
+        let mut client = wasm_sockets::EventClient::new(WS_URL).unwrap();
+
+        let connected_callback = ctx.link().callback(|_| Msg::Connected);
+        client.set_on_connection(Some(Box::new(move |_: &wasm_sockets::EventClient| {
+            connected_callback.emit(());
+        })));
+
+        let closed_callback = ctx.link().callback(|_| Msg::Closed);
+        client.set_on_close(Some(Box::new(move || {
+            closed_callback.emit(());
+        })));
+
+        let callback = ctx.link().callback(Msg::ReceiveBatch);
+        client.set_on_message(Some(Box::new(
+            move |_: &wasm_sockets::EventClient, message: wasm_sockets::Message| {
+                if let Message::Binary(data) = message {
+                    let mut buf = BytesMut::with_capacity(64);
+                    buf.extend_from_slice(&data);
+                    if let Ok(Envelope {
+                        payload: Some(Payload::LatestBets(batch)),
+                        ..
+                    }) = Envelope::decode(buf)
+                    {
+                        callback.emit(batch);
+                    }
+                };
+            },
+        )));
+
+        client
+    }
+
+    fn subscribe(&self) {
+        let request = SubscriptionRequest {
+            action: SubscriptionAction::Subscribe.into(),
+            topics: vec![Topic {
+                topic_type: TopicType::LatestBets.into(),
+                id: 0,
+            }],
+            // missed bets are not worth replaying, the feed shows only the newest ones
+            last_sequence: 0,
+        };
+        let mut buf = BytesMut::with_capacity(16);
+        let _ = request.encode(&mut buf);
+        if let Err(err) = self.client.send_binary(buf.to_vec()) {
+            log::error!("Subscribing to the latest bets failed: {:?}", err);
+        }
+    }
 }
 
 impl Component for LatestBets {
@@ -17,58 +88,69 @@ impl Component for LatestBets {
     type Properties = ();
 
     fn create(ctx: &Context<Self>) -> Self {
-        // ctx.link().send_message(Msg::Connect);
-
         Self {
-            latest_tickets: Vec::new(),
-            client: None,
+            client: Self::connect(ctx),
+            latest_bets: Vec::new(),
+            omitted: 0,
         }
     }
 
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
-        info!("updated");
         match msg {
-            Msg::Connect => {
-                let mut client =
-                    wasm_sockets::EventClient::new("ws://127.0.0.1:50052/bet").unwrap();
-
-                let callback = ctx.link().callback(|text: String| Msg::ReceiveBets(text));
-
-                client.set_on_message(Some(Box::new(
-                    move |_: &wasm_sockets::EventClient, message: wasm_sockets::Message| {
-                        // replace to Message::Binary
-                        if let Message::Text(text) = message {
-                            callback.emit(text);
-                        };
-                    },
-                )));
-
-                self.client = Some(client);
-
-                true
+            Msg::Connected => {
+                self.subscribe();
+                false
+            }
+            Msg::Closed => {
+                let link = ctx.link().clone();
+                Timeout::new(WS_RECONNECT_DELAY, move || {
+                    link.send_message(Msg::Reconnect)
+                })
+                .forget();
+                false
             }
-            Msg::ReceiveBets(text) => {
-                self.latest_tickets.push(text);
+            Msg::Reconnect => {
+                self.client.set_on_connection(None);
+                self.client.set_on_message(None);
+                self.client.set_on_close(None);
+                self.client = Self::connect(ctx);
+                false
+            }
+            Msg::ReceiveBatch(batch) => {
+                // the batch is ordered newest first, just like the shown bets
+                let mut latest_bets = batch.bets;
+                latest_bets.append(&mut self.latest_bets);
+                latest_bets.truncate(MAX_SHOWN_BETS);
+                self.latest_bets = latest_bets;
+                self.omitted = batch.omitted;
                 true
             }
         }
     }
 
-    fn view(&self, ctx: &Context<Self>) -> Html {
+    fn view(&self, _ctx: &Context<Self>) -> Html {
         html! {
          <div class="bg-dark-blue text-white rounded-md p-2 h-2/6">
             <div class="h-1/5">
                 <div class="font-bold text-center">{"Latest bets"}</div>
-                <button class="btn btn-primary" onclick={ ctx.link().callback(|_| Msg::Connect) }>{"Connect"}</button>
+                {
+                    if self.omitted > 0 {
+                        html! {
+                            <div class="text-xs text-center">
+                                { format!("and {} more in the last few seconds", self.omitted) }
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
             </div>
             <div class="h-4/5">
                 <ul class="h-50 flex flex-col gap-1.5 overflow-y-auto max-h-full">
                     {
-                        self.latest_tickets.clone().into_iter().map(|ticket| {
+                        self.latest_bets.iter().map(|bet| {
                             html! {
-                                <li key={ticket.clone()} class="text-black font-bold rounded-md bg-white p-1 text-left cursor-pointer">
-                                    { ticket }
-                                </li>
+                                <LatestBetsItem bet={bet.clone()} />
                             }
                         }).collect::<Html>()
                     }
@@ -1,12 +1,36 @@
+use crate::types::grpc_types::envelope::{LatestBet, StakeBucket};
 use yew::prelude::*;
 
 pub enum Msg {}
 
-pub struct LatestTicketItem {}
+#[derive(Properties, PartialEq)]
+pub struct LatestBetsItemProps {
+    pub bet: LatestBet,
+}
+
+pub struct LatestBetsItem {}
+
+/// Readable label of the stake size, the exact stake is never published
+fn stake_label(stake: i32) -> &'static str {
+    match StakeBucket::from_i32(stake) {
+        Some(StakeBucket::Small) | None => "small stake",
+        Some(StakeBucket::Medium) => "medium stake",
+        Some(StakeBucket::Large) => "large stake",
+        Some(StakeBucket::HighRoller) => "high roller",
+    }
+}
+
+/// The selected team with its ratio, along with the number of bets when there are more of them
+fn selection_label(bet: &LatestBet) -> String {
+    match bet.count {
+        0 | 1 => format!("{} @ {}", bet.team_name, bet.ratio),
+        count => format!("{} @ {} ({} bets)", bet.team_name, bet.ratio, count),
+    }
+}
 
-impl Component for LatestTicketItem {
+impl Component for LatestBetsItem {
     type Message = Msg;
-    type Properties = ();
+    type Properties = LatestBetsItemProps;
 
     fn create(_ctx: &Context<Self>) -> Self {
         Self {}
@@ -16,9 +40,16 @@ impl Component for LatestTicketItem {
         false
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let bet = &ctx.props().bet;
         html! {
-         <div>{"latest ticket item"}</div>
+            <li class="text-black rounded-md bg-white p-1 text-left">
+                <div class="font-bold">{ &bet.match_name }</div>
+                <div class="flex flex-row justify-between text-sm">
+                    <span>{ selection_label(bet) }</span>
+                    <span>{ stake_label(bet.stake) }</span>
+                </div>
+            </li>
         }
     }
 }
@@ -1,10 +1,7 @@
 use yew::prelude::*;
 
 use crate::components::{
-    footer::Footer,
-    games::Games,
-    header::header::Header,
-    latest_bets::latest_bets::LatestBets,
+    footer::Footer, games::Games, header::header::Header, latest_bets::latest_bets::LatestBets,
     ticket::ticket::Ticket,
 };
 pub enum Msg {}
@@ -43,7 +40,7 @@ impl Component for Layout {
                     </section>
                     <section class="w-3/12 lg:w-2/12 bg-light-grey p-2 flex flex-col justify-between gap-2 transition-all">
                         <Ticket />
-                        // <LatestBets />
+                        <LatestBets />
                     </section>
                 </main>
             </>
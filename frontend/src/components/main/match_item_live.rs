@@ -1,5 +1,6 @@
+use super::match_stats::MatchStats;
 use crate::store::{TicketRequest, TicketStore};
-use crate::types::grpc_types::team::Team;
+use crate::types::grpc_types::{game_match::Score, team::Team};
 use yew::prelude::*;
 use yew_agent::{
     utils::store::{Bridgeable, ReadOnly, StoreWrapper},
@@ -37,6 +38,9 @@ pub struct MatchItemLiveProps {
     pub team_one_ratio: String,
     pub team_two_ratio: String,
     pub state: String,
+    /// the latest score, none before the first score change
+    #[prop_or_default]
+    pub score: Option<Score>,
 }
 
 impl Component for MatchItemLive {
@@ -54,6 +58,7 @@ impl Component for MatchItemLive {
             team_one_ratio,
             team_two_ratio,
             state,
+            ..
         } = ctx.props().clone();
 
         Self {
@@ -93,6 +98,11 @@ impl Component for MatchItemLive {
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        // the score changes during the match, so it is always taken from the current props
+        let score = match &ctx.props().score {
+            Some(score) => format!("{} : {}", score.team_one, score.team_two),
+            None => String::from("- : -"),
+        };
         html! {
             <div class="px-2 bg-white rounded-md flex flex-col lg:flex-row lg:gap-2 pb-2 lg:relative">
                 <div class="lg:w-1/3">
@@ -101,6 +111,8 @@ impl Component for MatchItemLive {
                         {" "}
                         <span class="text-yellow">{self.state.clone()}</span>
                         {" "}
+                        <span class="font-mono">{score}</span>
+                        {" "}
                         <span>{self.team_two_name.clone()}</span>
                     </div>
                     // <div class="mt-5 w-full">{"Time -> 25:31"}</div>
@@ -117,6 +129,7 @@ impl Component for MatchItemLive {
                             <span>{self.team_two_ratio.clone()}</span>
                         </button>
                     </div>
+                    <MatchStats team_one_id={self.team_one_id} team_two_id={self.team_two_id} />
                 </div>
             </div>
         }
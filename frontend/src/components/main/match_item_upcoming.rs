@@ -1,5 +1,6 @@
+use super::match_stats::MatchStats;
 use crate::types::grpc_types::team::Team;
-use chrono::{NaiveDateTime};
+use chrono::NaiveDateTime;
 use yew::prelude::*;
 
 pub enum Msg {}
@@ -7,6 +8,8 @@ pub enum Msg {}
 pub struct MatchItemUpcoming {
     id: i32,
     game_id: i32,
+    team_one_id: i32,
+    team_two_id: i32,
     team_one_name: String,
     team_two_name: String,
     team_one_ratio: String,
@@ -19,6 +22,8 @@ pub struct MatchItemUpcoming {
 pub struct MatchItemUpcomingProps {
     pub id: i32,
     pub game_id: i32,
+    pub team_one_id: i32,
+    pub team_two_id: i32,
     pub team_one_name: String,
     pub team_two_name: String,
     pub team_one_ratio: String,
@@ -35,6 +40,8 @@ impl Component for MatchItemUpcoming {
         let MatchItemUpcomingProps {
             id,
             game_id,
+            team_one_id,
+            team_two_id,
             team_one_name,
             team_two_name,
             team_one_ratio,
@@ -46,6 +53,8 @@ impl Component for MatchItemUpcoming {
         Self {
             id: id.clone(),
             game_id: game_id.clone(),
+            team_one_id: team_one_id.clone(),
+            team_two_id: team_two_id.clone(),
             team_one_name: team_one_name.clone(),
             team_two_name: team_two_name.clone(),
             team_one_ratio: team_one_ratio.clone(),
@@ -95,6 +104,7 @@ impl Component for MatchItemUpcoming {
                             <span>{self.team_two_ratio.clone()}</span>
                         </div>
                     </div>
+                    <MatchStats team_one_id={self.team_one_id} team_two_id={self.team_two_id} />
                 </div>
             </div>
         }
@@ -0,0 +1,96 @@
+use crate::config::GRPC_URL;
+use crate::types::grpc_types::team::{
+    team_service_client, GetHeadToHeadReply, GetHeadToHeadRequest, MatchResult,
+};
+use anyhow;
+use log::error;
+use yew::prelude::*;
+
+pub enum Msg {
+    ReceiveResponse(anyhow::Result<GetHeadToHeadReply>),
+}
+
+/// Recent form of both teams of a match and their head-to-head record
+pub struct MatchStats {
+    stats: Option<GetHeadToHeadReply>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MatchStatsProps {
+    pub team_one_id: i32,
+    pub team_two_id: i32,
+}
+
+/// Letters of the latest results, the newest first
+fn form_letters(form: &[MatchResult]) -> Html {
+    form.iter()
+        .map(|result| {
+            if result.won {
+                html! { <span class="font-bold text-success">{"W"}</span> }
+            } else {
+                html! { <span class="font-bold text-danger">{"L"}</span> }
+            }
+        })
+        .collect::<Html>()
+}
+
+impl Component for MatchStats {
+    type Message = Msg;
+    type Properties = MatchStatsProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let grpc_client = team_service_client::TeamService::new(String::from(GRPC_URL));
+        let team_one_id = ctx.props().team_one_id;
+        let team_two_id = ctx.props().team_two_id;
+        ctx.link().send_future(async move {
+            Msg::ReceiveResponse(
+                grpc_client
+                    .get_head_to_head(GetHeadToHeadRequest {
+                        team_one_id,
+                        team_two_id,
+                        form_length: None,
+                    })
+                    .await,
+            )
+        });
+
+        Self { stats: None }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            Msg::ReceiveResponse(Ok(reply)) => {
+                self.stats = Some(reply);
+                true
+            }
+            Msg::ReceiveResponse(Err(err)) => {
+                error!("{}", err.to_string());
+                false
+            }
+        }
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        let (team_one, team_two, head_to_head) = match &self.stats {
+            Some(GetHeadToHeadReply {
+                team_one: Some(team_one),
+                team_two: Some(team_two),
+                head_to_head: Some(head_to_head),
+            }) => (team_one, team_two, head_to_head),
+            _ => return html! {},
+        };
+
+        html! {
+            <div class="flex gap-5 justify-between text-sm">
+                <div class="flex gap-1">{form_letters(&team_one.form)}</div>
+                <div>
+                    {"Head to head "}
+                    <span class="font-bold font-mono">
+                        {format!("{} : {}", head_to_head.team_one_wins, head_to_head.team_two_wins)}
+                    </span>
+                </div>
+                <div class="flex gap-1">{form_letters(&team_two.form)}</div>
+            </div>
+        }
+    }
+}
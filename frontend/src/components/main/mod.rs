@@ -7,3 +7,4 @@ pub mod game_matches;
 pub mod match_item_ended;
 pub mod match_item_live;
 pub mod match_item_upcoming;
+pub mod match_stats;
@@ -36,6 +36,7 @@ pub struct Ticket {
     user_store: Box<dyn Bridge<StoreWrapper<UserStore>>>,
     ticket_is_loading: bool,
     matches_is_loading: bool,
+    error: Option<String>,
 }
 
 // parse value from event type
@@ -62,6 +63,7 @@ impl Component for Ticket {
             user_store: UserStore::bridge(ctx.link().callback(Msg::UserStore)),
             ticket_is_loading: false,
             matches_is_loading: false,
+            error: None,
         }
     }
 
@@ -105,6 +107,7 @@ impl Component for Ticket {
                 self.ticket_value = state.ticket_value;
                 self.rate = state.rate;
                 self.ticket_is_loading = state.is_loading;
+                self.error = state.error.clone();
             }
 
             Msg::UserStore(_) => {}
@@ -192,6 +195,13 @@ impl Component for Ticket {
                         <span>{"Total ratio: "}{self.rate}</span>
                         <span>{"ev.win: "}{self.ticket_value * self.rate}{"€"}</span>
                     </div>
+                    {
+                        if let Some(error) = &self.error {
+                            html! { <div class="text-sm text-center text-danger bg-danger-light rounded-md p-1 mt-1">{ error.clone() }</div> }
+                        } else {
+                            html! {}
+                        }
+                    }
                 </form>
 
 
@@ -0,0 +1,14 @@
+/// Endpoints of the backend, fixed when the frontend is built,
+/// e.g. `GRPC_URL=https://api.starbet.example WS_URL=wss://ws.starbet.example/ws trunk build`
+
+/// gRPC-web server, the `server.grpc_address` of the backend
+pub const GRPC_URL: &str = match option_env!("GRPC_URL") {
+    Some(url) => url,
+    None => "http://127.0.0.1:5430",
+};
+
+/// Websocket route of the backend, at its `server.ws_address`
+pub const WS_URL: &str = match option_env!("WS_URL") {
+    Some(url) => url,
+    None => "ws://127.0.0.1:50052/ws",
+};
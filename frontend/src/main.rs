@@ -2,6 +2,7 @@ use crate::{
     components::{
         layout::Layout, layout_no_sidebars::LayoutNoSidebars, layout_profile::LayoutProfile,
     },
+    config::WS_URL,
     pages::{
         about_page::AboutPage, administration_page::AdministrationPage, contact_page::ContactPage,
         live_page::LivePage, not_found::NotFoundPage, privacy_policy_page::PrivacyPolicyPage,
@@ -9,11 +10,23 @@ use crate::{
         profile_tickets::ProfileTickets, registration_page::RegistrationPage,
         results_page::ResultsPage, upcoming_page::UpcomingPage,
     },
-    store::{MatchesRequest, MatchesStore, TicketRequest, TicketStore, UserRequest, UserStore},
-    types::{grpc_types::game_match::Match, MainRoute, ProfileRoute},
+    services::get_session_token,
+    store::{
+        GamesStore, MatchesRequest, MatchesStore, TicketRequest, TicketStore, UserRequest,
+        UserStore,
+    },
+    types::{
+        grpc_types::{
+            envelope::{envelope::Payload, Envelope, MatchAlertType},
+            game_match::timeline_event,
+            subscription::{SubscriptionAction, SubscriptionRequest, Topic, TopicType},
+        },
+        MainRoute, ProfileRoute,
+    },
 };
 use bytes::BytesMut;
 use gloo::console::info;
+use gloo_timers::callback::Timeout;
 use prost::{DecodeError, Message as ProstMessage};
 use wasm_sockets::{self, Message, WebSocketError};
 use yew::prelude::*;
@@ -24,18 +37,29 @@ use yew_agent::{
 use yew_router::{prelude::Redirect, BrowserRouter, Switch};
 
 mod components;
+mod config;
 mod pages;
 mod services;
 mod store;
 mod types;
 
+/// Version of the websocket envelope the application understands
+const ENVELOPE_VERSION: u32 = 1;
+
+/// Delay before reconnecting a dropped websocket, in milliseconds
+const WS_RECONNECT_DELAY: u32 = 2000;
+
 enum Msg {
     UserStore(ReadOnly<UserStore>),
     InitUser,
     MatchesStore(ReadOnly<MatchesStore>),
     TicketStore(ReadOnly<TicketStore>),
+    GamesStore(ReadOnly<GamesStore>),
+    WsConnected,
+    WsClosed,
+    WsReconnect,
     FetchMatches,
-    ReceiveMatchUpdate(Result<Match, DecodeError>),
+    ReceiveEnvelope(Result<Envelope, DecodeError>),
 }
 
 struct App {
@@ -43,32 +67,128 @@ struct App {
     user_store: Box<dyn Bridge<StoreWrapper<UserStore>>>,
     matches_store: Box<dyn Bridge<StoreWrapper<MatchesStore>>>,
     ticket_store: Box<dyn Bridge<StoreWrapper<TicketStore>>>,
+    games_store: Box<dyn Bridge<StoreWrapper<GamesStore>>>,
     ws_client: wasm_sockets::EventClient,
+    ws_session_token: Option<String>,
+    ws_connected: bool,
+    /// sequence number of the last received envelope
+    last_sequence: u64,
+    /// whether the next subscription asks for the events missed while disconnected
+    resume: bool,
+    game_ids: Vec<i32>,
+    subscribed_game_ids: Vec<i32>,
 }
 
-impl Component for App {
-    type Message = Msg;
-    type Properties = ();
-
-    fn create(ctx: &Context<Self>) -> Self {
-        ctx.link().send_message(Msg::InitUser);
-
-        let mut client = wasm_sockets::EventClient::new("ws://127.0.0.1:50052/match").unwrap();
+impl App {
+    /// Open the websocket connection, authenticated by the session token if there is one
+    fn connect_ws(
+        ctx: &Context<Self>,
+        session_token: &Option<String>,
+    ) -> wasm_sockets::EventClient {
+        let url = match session_token {
+            Some(token) => format!("{}?token={}", WS_URL, token),
+            None => String::from(WS_URL),
+        };
+        let mut client = wasm_sockets::EventClient::new(&url).unwrap();
 
         let callback = ctx
             .link()
-            .callback(|match_item: Result<Match, DecodeError>| Msg::ReceiveMatchUpdate(match_item));
+            .callback(|envelope: Result<Envelope, DecodeError>| Msg::ReceiveEnvelope(envelope));
+
+        let connected_callback = ctx.link().callback(|_| Msg::WsConnected);
+        client.set_on_connection(Some(Box::new(move |_: &wasm_sockets::EventClient| {
+            connected_callback.emit(());
+        })));
+
+        let closed_callback = ctx.link().callback(|_| Msg::WsClosed);
+        client.set_on_close(Some(Box::new(move || {
+            closed_callback.emit(());
+        })));
 
         client.set_on_message(Some(Box::new(
             move |_: &wasm_sockets::EventClient, message: wasm_sockets::Message| {
                 if let Message::Binary(data) = message {
                     let mut buf = BytesMut::with_capacity(64);
                     buf.extend_from_slice(&data);
-                    callback.emit(Match::decode(buf));
+                    callback.emit(Envelope::decode(buf));
                 };
             },
         )));
 
+        client
+    }
+
+    /// Reconnect the websocket when the user logs in or out,
+    /// so the connection is bound to the current session
+    fn reconnect_ws(&mut self, ctx: &Context<Self>) {
+        let session_token = get_session_token();
+        if session_token == self.ws_session_token {
+            return;
+        }
+        self.replace_ws(ctx, session_token);
+    }
+
+    /// Replace the websocket connection by a new one, which resumes
+    /// from the last received envelope once it subscribes
+    fn replace_ws(&mut self, ctx: &Context<Self>, session_token: Option<String>) {
+        // stop handling the events of the previous connection
+        self.ws_client.set_on_connection(None);
+        self.ws_client.set_on_message(None);
+        self.ws_client.set_on_close(None);
+
+        self.ws_client = Self::connect_ws(ctx, &session_token);
+        self.ws_session_token = session_token;
+        self.ws_connected = false;
+        self.resume = self.last_sequence > 0;
+        self.subscribed_game_ids = Vec::new();
+    }
+
+    /// Subscribe to the match updates of the games we do not receive yet
+    fn subscribe_games(&mut self) {
+        let new_game_ids: Vec<i32> = self
+            .game_ids
+            .iter()
+            .filter(|id| !self.subscribed_game_ids.contains(id))
+            .cloned()
+            .collect();
+        if !self.ws_connected || new_game_ids.is_empty() {
+            return;
+        }
+
+        let request = SubscriptionRequest {
+            action: SubscriptionAction::Subscribe.into(),
+            topics: new_game_ids
+                .iter()
+                .map(|id| Topic {
+                    topic_type: TopicType::Game.into(),
+                    id: *id,
+                })
+                .collect(),
+            // replay the events missed while the connection was down
+            last_sequence: if self.resume { self.last_sequence } else { 0 },
+        };
+        let mut buf = BytesMut::with_capacity(64);
+        let _ = request.encode(&mut buf);
+        match self.ws_client.send_binary(buf.to_vec()) {
+            Ok(()) => {
+                self.subscribed_game_ids.extend(new_game_ids);
+                self.resume = false;
+            }
+            Err(_) => log::error!("WebSocket subscription error"),
+        }
+    }
+}
+
+impl Component for App {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link().send_message(Msg::InitUser);
+
+        let session_token = get_session_token();
+        let client = Self::connect_ws(ctx, &session_token);
+
         ctx.link().send_message(Msg::FetchMatches);
 
         Self {
@@ -76,7 +196,14 @@ impl Component for App {
             user_store: UserStore::bridge(ctx.link().callback(Msg::UserStore)),
             matches_store: MatchesStore::bridge(ctx.link().callback(Msg::MatchesStore)),
             ticket_store: TicketStore::bridge(ctx.link().callback(Msg::TicketStore)),
+            games_store: GamesStore::bridge(ctx.link().callback(Msg::GamesStore)),
             ws_client: client,
+            ws_session_token: session_token,
+            ws_connected: false,
+            last_sequence: 0,
+            resume: false,
+            game_ids: Vec::new(),
+            subscribed_game_ids: Vec::new(),
         }
     }
 
@@ -86,10 +213,76 @@ impl Component for App {
             Msg::FetchMatches => {
                 self.matches_store.send(MatchesRequest::Fetch);
             }
-            Msg::ReceiveMatchUpdate(Ok(match_item)) => {
-                self.matches_store.send(MatchesRequest::Update(match_item));
+            Msg::ReceiveEnvelope(Ok(envelope)) => {
+                if envelope.version != ENVELOPE_VERSION {
+                    log::warn!(
+                        "Unsupported WebSocket envelope version {}",
+                        envelope.version
+                    );
+                    return false;
+                }
+                // replayed envelopes may arrive after newer ones
+                self.last_sequence = self.last_sequence.max(envelope.sequence);
+                match envelope.payload {
+                    Some(Payload::MatchUpdate(match_item)) => {
+                        self.matches_store.send(MatchesRequest::Update(match_item));
+                    }
+                    Some(Payload::MatchDeleted(match_deleted)) => {
+                        self.matches_store
+                            .send(MatchesRequest::Remove(match_deleted.match_id));
+                    }
+                    Some(Payload::OddsChange(odds_change)) => {
+                        self.matches_store
+                            .send(MatchesRequest::UpdateRatios(odds_change));
+                    }
+                    Some(Payload::TimelineEvent(timeline_event)) => {
+                        // only the score is shown, the rest of the timeline is not yet
+                        if let Some(timeline_event::Payload::ScoreChanged(score)) =
+                            timeline_event.payload
+                        {
+                            self.matches_store
+                                .send(MatchesRequest::UpdateScore(timeline_event.match_id, score));
+                        }
+                    }
+                    Some(Payload::BalanceChanged(balance_changed)) => {
+                        self.user_store
+                            .send(UserRequest::SetBalance(balance_changed.balance));
+                    }
+                    Some(Payload::TicketSettled(ticket_settled)) => {
+                        log::info!(
+                            "Ticket #{} has been settled, won: {}",
+                            ticket_settled.ticket_id,
+                            ticket_settled.won
+                        );
+                    }
+                    Some(Payload::OutrightSettled(outright_settled)) => {
+                        log::info!(
+                            "Outright bet #{} has been settled, won: {}",
+                            outright_settled.bet_id,
+                            outright_settled.won
+                        );
+                    }
+                    Some(Payload::MatchAlert(match_alert)) => {
+                        // sent only to the admins
+                        let reason = match MatchAlertType::from_i32(match_alert.alert_type) {
+                            Some(MatchAlertType::RunningLong) => "is running longer than expected",
+                            _ => "has not started at its scheduled time",
+                        };
+                        if let Some(game_match) = match_alert.game_match {
+                            log::warn!("Match #{} {}", game_match.id, reason);
+                        }
+                    }
+                    Some(Payload::ResyncRequired(_)) => {
+                        // the missed events are gone, the sequence continues from the envelope
+                        self.last_sequence = envelope.sequence;
+                        self.matches_store.send(MatchesRequest::Fetch);
+                    }
+                    // the other payloads are not shown by the application yet
+                    Some(_) => {}
+                    None => log::error!("WebSocket envelope without payload"),
+                }
             }
-            Msg::ReceiveMatchUpdate(Err(err)) => {
+            Msg::ReceiveEnvelope(Err(err)) => {
                 log::error!("WebSocket message decode error");
             }
             Msg::UserStore(state) => {
@@ -100,11 +293,32 @@ impl Component for App {
                 } else {
                     self.ticket_store.send(TicketRequest::SetUserId(0));
                 }
+                self.reconnect_ws(ctx);
             }
             Msg::InitUser => {
                 self.user_store.send(UserRequest::InitializeUser);
             }
             Msg::TicketStore(_) => {}
+            Msg::GamesStore(state) => {
+                self.game_ids = state.borrow().games.iter().map(|game| game.id).collect();
+                self.subscribe_games();
+            }
+            Msg::WsConnected => {
+                self.ws_connected = true;
+                self.subscribe_games();
+            }
+            Msg::WsClosed => {
+                log::warn!("WebSocket connection closed, reconnecting");
+                self.ws_connected = false;
+                let link = ctx.link().clone();
+                Timeout::new(WS_RECONNECT_DELAY, move || {
+                    link.send_message(Msg::WsReconnect)
+                })
+                .forget();
+            }
+            Msg::WsReconnect => {
+                self.replace_ws(ctx, get_session_token());
+            }
         }
         false
     }
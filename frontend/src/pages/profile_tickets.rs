@@ -1,5 +1,7 @@
 use crate::{
     components::loading_animation::LoadingAnimation,
+    config::GRPC_URL,
+    services::error_message,
     store::UserStore,
     types::grpc_types::ticket::{
         ticket_service_client, ListTicketsReply, ListTicketsRequest, Ticket,
@@ -47,9 +49,7 @@ impl Component for ProfileTickets {
             Msg::FetchTickets => {
                 ctx.link().send_message(Msg::SetLoading(true));
 
-                let grpc_client = ticket_service_client::TicketService::new(String::from(
-                    "http://127.0.0.1:5430",
-                ));
+                let grpc_client = ticket_service_client::TicketService::new(String::from(GRPC_URL));
                 let user_id = self.user_id;
                 ctx.link().send_future(async move {
                     Msg::ReceiveResponse(
@@ -71,7 +71,7 @@ impl Component for ProfileTickets {
                 true
             }
             Msg::ReceiveResponse(Err(err)) => {
-                self.error = err.to_string();
+                self.error = error_message(&err);
                 true
             }
             Msg::UserStore(state) => {
@@ -6,6 +6,7 @@ use lazy_static::lazy_static;
 use parking_lot::RwLock;
 
 const TOKEN_KEY: &str = "yew.token";
+const SESSION_TOKEN_KEY: &str = "yew.session";
 
 lazy_static! {
     /// Jwt token read from local storage.
@@ -16,6 +17,15 @@ lazy_static! {
             RwLock::new(None)
         }
     };
+
+    /// Session token read from local storage.
+    pub static ref SESSION_TOKEN: RwLock<Option<String>> = {
+        if let Ok(token) = LocalStorage::get(SESSION_TOKEN_KEY) {
+            RwLock::new(Some(token))
+        } else {
+            RwLock::new(None)
+        }
+    };
 }
 
 /// Set jwt token to local storage.
@@ -34,3 +44,20 @@ pub fn get_token() -> Option<String> {
     let token_lock = TOKEN.read();
     token_lock.clone()
 }
+
+/// Set session token to local storage.
+pub fn set_session_token(token: Option<String>) {
+    if let Some(t) = token.clone() {
+        LocalStorage::set(SESSION_TOKEN_KEY, t).expect("failed to set");
+    } else {
+        LocalStorage::delete(SESSION_TOKEN_KEY);
+    }
+    let mut token_lock = SESSION_TOKEN.write();
+    *token_lock = token;
+}
+
+/// Get session token from lazy static.
+pub fn get_session_token() -> Option<String> {
+    let token_lock = SESSION_TOKEN.read();
+    token_lock.clone()
+}
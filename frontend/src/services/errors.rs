@@ -0,0 +1,71 @@
+// messages of the failed requests shown to the users
+
+use crate::types::grpc_types::error::{ErrorDetail, ErrorReason};
+use prost::Message;
+
+/// Name of the field the server sends the encoded `ErrorDetail` in
+const DETAILS_FIELD: &str = "grpc-status-details-bin";
+
+/// Find the details of a failed gRPC call.
+/// The gRPC-web client reports the trailers of a failed call in its error,
+/// the details are the base64 encoded `ErrorDetail` sent by the server.
+///
+/// Params
+/// ---
+/// - err: error returned by the gRPC-web client
+///
+/// Returns
+/// ---
+/// - Some(detail) if the server has described the failure
+/// - None if the call failed without reaching the server, or the details cannot be decoded
+pub fn error_detail(err: &anyhow::Error) -> Option<ErrorDetail> {
+    err.chain().find_map(|cause| {
+        let text = cause.to_string();
+        let start = text.find(DETAILS_FIELD)? + DETAILS_FIELD.len();
+        let encoded: String = text[start..]
+            .trim_start_matches(|c: char| c == ':' || c == '=' || c == '"' || c.is_whitespace())
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '+' || *c == '/')
+            .collect();
+        let bytes = base64::decode_config(&encoded, base64::STANDARD_NO_PAD).ok()?;
+        ErrorDetail::decode(bytes.as_slice()).ok()
+    })
+}
+
+/// Message telling the user what went wrong with their request,
+/// worded by the frontend instead of the server
+///
+/// Params
+/// ---
+/// - err: error returned by the gRPC-web client
+///
+/// Returns
+/// ---
+/// - message for the reason of the failure, filled with the values the server has sent
+pub fn error_message(err: &anyhow::Error) -> String {
+    let detail = match error_detail(err) {
+        Some(detail) => detail,
+        None => return String::from("The server cannot be reached, please try again later."),
+    };
+    let param = |name: &str| detail.params.get(name).cloned().unwrap_or_default();
+
+    match ErrorReason::from_i32(detail.reason) {
+        Some(ErrorReason::NotFound) => {
+            String::from("What you are looking for does not exist anymore.")
+        }
+        Some(ErrorReason::Validation) => String::from("Please check the values you have entered."),
+        Some(ErrorReason::Conflict) => {
+            String::from("This cannot be done right now, please refresh the page and try again.")
+        }
+        Some(ErrorReason::InsufficientFunds) => format!(
+            "Your balance of {} € is not enough, {} € is needed.",
+            param("balance"),
+            param("required")
+        ),
+        Some(ErrorReason::BettingClosed) => String::from("Betting on this is closed."),
+        Some(ErrorReason::Unauthenticated) => String::from("Wrong e-mail or password."),
+        Some(ErrorReason::Internal) | None => {
+            String::from("Something went wrong on our side, please try again later.")
+        }
+    }
+}
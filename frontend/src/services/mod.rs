@@ -1,3 +1,5 @@
 pub mod auth;
+pub mod errors;
 
-pub use auth::{get_token, set_token};
\ No newline at end of file
+pub use auth::{get_session_token, get_token, set_session_token, set_token};
+pub use errors::error_message;
@@ -1,3 +1,4 @@
+use crate::config::GRPC_URL;
 use crate::types::grpc_types::game::{game_service_client, Game, ListGamesReply, ListGamesRequest};
 use anyhow;
 use std::collections::HashMap;
@@ -46,10 +47,15 @@ impl Store for GamesStore {
             GamesRequest::Fetch => {
                 link.send_message(Action::SetLoading(true));
 
-                let grpc_client =
-                    game_service_client::GameService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = game_service_client::GameService::new(String::from(GRPC_URL));
                 link.send_future(async move {
-                    Action::ReceiveResponse(grpc_client.list_games(ListGamesRequest {}).await)
+                    Action::ReceiveResponse(
+                        grpc_client
+                            .list_games(ListGamesRequest {
+                                include_archived: false,
+                            })
+                            .await,
+                    )
                 });
             }
             GamesRequest::FilterAdd(id) => {
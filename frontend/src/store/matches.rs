@@ -1,5 +1,9 @@
-use crate::types::grpc_types::game_match::{
-    match_service_client, GameEventType, ListMatchesReply, ListMatchesRequest, Match,
+use crate::config::GRPC_URL;
+use crate::types::grpc_types::{
+    envelope::OddsChange,
+    game_match::{
+        match_service_client, GameEventType, ListMatchesReply, ListMatchesRequest, Match, Score,
+    },
 };
 use anyhow;
 use gloo::console::info;
@@ -10,7 +14,14 @@ use yew_agent::AgentLink;
 #[derive(Debug)]
 pub enum MatchesRequest {
     Fetch,
+    /// show only the matches followed by the user, or all matches when `None`
+    ShowFavourites(Option<i32>),
     Update(Match),
+    UpdateRatios(OddsChange),
+    /// set the score of the match, by its ID
+    UpdateScore(i32, Score),
+    /// remove the deleted match, by its ID
+    Remove(i32),
 }
 
 #[derive(Debug)]
@@ -19,7 +30,11 @@ pub enum Action {
     ReceiveResponseLive(anyhow::Result<ListMatchesReply>),
     ReceiveResponseEnded(anyhow::Result<ListMatchesReply>),
     SetLoading(bool),
+    SetFavourites(Option<i32>),
     Update(Match),
+    UpdateRatios(OddsChange),
+    UpdateScore(i32, Score),
+    Remove(i32),
 }
 
 pub struct MatchesStore {
@@ -28,6 +43,34 @@ pub struct MatchesStore {
     pub matches_ended: Vec<Match>,
     pub is_loading: bool,
     pub is_error: bool,
+    /// ID of the user whose favourites are shown, all matches are shown when `None`
+    pub favourites_of_user_id: Option<i32>,
+}
+
+/// Fetch the upcoming, live and ended matches
+fn fetch(link: &AgentLink<StoreWrapper<MatchesStore>>, favourites_of_user_id: Option<i32>) {
+    link.send_message(Action::SetLoading(true));
+
+    for game_event_type in [
+        GameEventType::Upcoming,
+        GameEventType::Live,
+        GameEventType::Ended,
+    ] {
+        link.send_future(async move {
+            let grpc_client = match_service_client::MatchService::new(String::from(GRPC_URL));
+            let response = grpc_client
+                .list_matches(ListMatchesRequest {
+                    game_event_type: game_event_type as i32,
+                    favourites_of_user_id,
+                })
+                .await;
+            match game_event_type {
+                GameEventType::Upcoming => Action::ReceiveResponseUpcoming(response),
+                GameEventType::Live => Action::ReceiveResponseLive(response),
+                GameEventType::Ended => Action::ReceiveResponseEnded(response),
+            }
+        });
+    }
 }
 
 impl Store for MatchesStore {
@@ -42,6 +85,7 @@ impl Store for MatchesStore {
             matches_ended: Vec::new(),
             is_loading: false,
             is_error: false,
+            favourites_of_user_id: None,
         }
     }
 
@@ -49,50 +93,24 @@ impl Store for MatchesStore {
     fn handle_input(&self, link: AgentLink<StoreWrapper<Self>>, msg: Self::Input) {
         match msg {
             MatchesRequest::Fetch => {
-                link.send_message(Action::SetLoading(true));
-
-                link.send_future(async move {
-                    let grpc_client = match_service_client::MatchService::new(String::from(
-                        "http://127.0.0.1:5430",
-                    ));
-                    Action::ReceiveResponseUpcoming(
-                        grpc_client
-                            .list_matches(ListMatchesRequest {
-                                game_event_type: GameEventType::Upcoming as i32,
-                            })
-                            .await,
-                    )
-                });
-
-                link.send_future(async move {
-                    let grpc_client = match_service_client::MatchService::new(String::from(
-                        "http://127.0.0.1:5430",
-                    ));
-                    Action::ReceiveResponseLive(
-                        grpc_client
-                            .list_matches(ListMatchesRequest {
-                                game_event_type: GameEventType::Live as i32,
-                            })
-                            .await,
-                    )
-                });
-
-                link.send_future(async move {
-                    let grpc_client = match_service_client::MatchService::new(String::from(
-                        "http://127.0.0.1:5430",
-                    ));
-                    Action::ReceiveResponseEnded(
-                        grpc_client
-                            .list_matches(ListMatchesRequest {
-                                game_event_type: GameEventType::Ended as i32,
-                            })
-                            .await,
-                    )
-                });
+                fetch(&link, self.favourites_of_user_id);
+            }
+            MatchesRequest::ShowFavourites(favourites_of_user_id) => {
+                link.send_message(Action::SetFavourites(favourites_of_user_id));
+                fetch(&link, favourites_of_user_id);
             }
             MatchesRequest::Update(match_item) => {
                 link.send_message(Action::Update(match_item));
             }
+            MatchesRequest::UpdateRatios(odds_change) => {
+                link.send_message(Action::UpdateRatios(odds_change));
+            }
+            MatchesRequest::UpdateScore(match_id, score) => {
+                link.send_message(Action::UpdateScore(match_id, score));
+            }
+            MatchesRequest::Remove(match_id) => {
+                link.send_message(Action::Remove(match_id));
+            }
         }
     }
 
@@ -102,6 +120,9 @@ impl Store for MatchesStore {
             Action::SetLoading(value) => {
                 self.is_loading = true;
             }
+            Action::SetFavourites(favourites_of_user_id) => {
+                self.favourites_of_user_id = favourites_of_user_id;
+            }
 
             Action::ReceiveResponseUpcoming(Ok(result)) => {
                 self.matches_upcoming = result.game_matches;
@@ -142,6 +163,16 @@ impl Store for MatchesStore {
 
             Action::Update(match_item) => {
                 let match_id = match_item.clone().id;
+                // the matches not followed by the user are not shown with the favourites
+                let is_shown = self
+                    .matches_upcoming
+                    .iter()
+                    .chain(self.matches_live.iter())
+                    .chain(self.matches_ended.iter())
+                    .any(|m| m.id == match_id);
+                if self.favourites_of_user_id.is_some() && !is_shown {
+                    return;
+                }
                 self.matches_upcoming = self
                     .matches_upcoming
                     .clone()
@@ -183,6 +214,28 @@ impl Store for MatchesStore {
                     _ => {}
                 }
             }
+            Action::UpdateRatios(odds_change) => {
+                for match_item in self
+                    .matches_upcoming
+                    .iter_mut()
+                    .chain(self.matches_live.iter_mut())
+                    .chain(self.matches_ended.iter_mut())
+                    .filter(|m| m.id == odds_change.match_id)
+                {
+                    match_item.team_one_ratio = odds_change.team_one_ratio.clone();
+                    match_item.team_two_ratio = odds_change.team_two_ratio.clone();
+                }
+            }
+            Action::UpdateScore(match_id, score) => {
+                for match_item in self.matches_live.iter_mut().filter(|m| m.id == match_id) {
+                    match_item.score = Some(score.clone());
+                }
+            }
+            Action::Remove(match_id) => {
+                self.matches_upcoming.retain(|m| m.id != match_id);
+                self.matches_live.retain(|m| m.id != match_id);
+                self.matches_ended.retain(|m| m.id != match_id);
+            }
         }
     }
 }
@@ -1,3 +1,5 @@
+use crate::config::GRPC_URL;
+use crate::services::error_message;
 use crate::types::grpc_types::bet::{
     bet_service_client, Bet, CreateBetReply, CreateBetRequest, DeleteBetReply, DeleteBetRequest,
 };
@@ -42,6 +44,8 @@ pub struct TicketStore {
     pub rate: f32,
 
     pub is_loading: bool,
+    /// message of the last failed change of the ticket, shown to the user
+    pub error: Option<String>,
 }
 
 impl Store for TicketStore {
@@ -58,6 +62,7 @@ impl Store for TicketStore {
             rate: 1.0,
 
             is_loading: false,
+            error: None,
         }
     }
 
@@ -69,9 +74,7 @@ impl Store for TicketStore {
 
                 // reload ticket
                 let user_id = self.user_id.clone();
-                let grpc_client = ticket_service_client::TicketService::new(String::from(
-                    "http://127.0.0.1:5430",
-                ));
+                let grpc_client = ticket_service_client::TicketService::new(String::from(GRPC_URL));
                 link.send_future(async move {
                     Action::LoadTicket(
                         grpc_client
@@ -90,9 +93,7 @@ impl Store for TicketStore {
                 link.send_message(Action::SetIsLoading(true));
 
                 let user_id = self.user_id.clone();
-                let grpc_client = ticket_service_client::TicketService::new(String::from(
-                    "http://127.0.0.1:5430",
-                ));
+                let grpc_client = ticket_service_client::TicketService::new(String::from(GRPC_URL));
                 link.send_future(async move {
                     Action::LoadTicket(
                         grpc_client
@@ -108,8 +109,7 @@ impl Store for TicketStore {
                 };
 
                 let ticket_id = self.id.clone();
-                let grpc_client =
-                    bet_service_client::BetService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = bet_service_client::BetService::new(String::from(GRPC_URL));
                 link.send_future(async move {
                     Action::CreateBetReceiveResponse(
                         grpc_client
@@ -117,6 +117,7 @@ impl Store for TicketStore {
                                 ticket_id,
                                 match_id: match_id.clone(),
                                 team_id: team_id.clone(),
+                                market: None,
                             })
                             .await,
                     )
@@ -124,9 +125,7 @@ impl Store for TicketStore {
 
                 // reload ticket
                 let user_id = self.user_id.clone();
-                let grpc_client = ticket_service_client::TicketService::new(String::from(
-                    "http://127.0.0.1:5430",
-                ));
+                let grpc_client = ticket_service_client::TicketService::new(String::from(GRPC_URL));
                 link.send_future(async move {
                     Action::LoadTicket(
                         grpc_client
@@ -137,8 +136,7 @@ impl Store for TicketStore {
             }
             TicketRequest::DeleteBet(id) => {
                 let ticket_id = self.id.clone();
-                let grpc_client =
-                    bet_service_client::BetService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = bet_service_client::BetService::new(String::from(GRPC_URL));
                 link.send_future(async move {
                     Action::DeleteBetReceiveResponse(
                         grpc_client
@@ -147,14 +145,11 @@ impl Store for TicketStore {
                     )
                 });
                 let ticket_id = self.id.clone();
-                let grpc_client =
-                    bet_service_client::BetService::new(String::from("http://127.0.0.1:5430"));
+                let grpc_client = bet_service_client::BetService::new(String::from(GRPC_URL));
 
                 // reload ticket
                 let user_id = self.user_id.clone();
-                let grpc_client = ticket_service_client::TicketService::new(String::from(
-                    "http://127.0.0.1:5430",
-                ));
+                let grpc_client = ticket_service_client::TicketService::new(String::from(GRPC_URL));
                 link.send_future(async move {
                     Action::LoadTicket(
                         grpc_client
@@ -166,9 +161,7 @@ impl Store for TicketStore {
             TicketRequest::SubmitTicket => {
                 let ticket_id = self.id.clone();
                 let price_paid = self.ticket_value.clone();
-                let grpc_client = ticket_service_client::TicketService::new(String::from(
-                    "http://127.0.0.1:5430",
-                ));
+                let grpc_client = ticket_service_client::TicketService::new(String::from(GRPC_URL));
                 link.send_future(async move {
                     Action::SubmitTicketReceiveResponse(
                         grpc_client
@@ -182,9 +175,7 @@ impl Store for TicketStore {
 
                 // reload ticket
                 let user_id = self.user_id.clone();
-                let grpc_client = ticket_service_client::TicketService::new(String::from(
-                    "http://127.0.0.1:5430",
-                ));
+                let grpc_client = ticket_service_client::TicketService::new(String::from(GRPC_URL));
                 link.send_future(async move {
                     Action::LoadTicket(
                         grpc_client
@@ -222,21 +213,27 @@ impl Store for TicketStore {
             }
             Action::CreateBetReceiveResponse(Ok(_)) => {
                 info!("bet created");
+                self.error = None;
             }
             Action::CreateBetReceiveResponse(Err(err)) => {
                 error!("error create bet", err.to_string());
+                self.error = Some(error_message(&err));
             }
             Action::DeleteBetReceiveResponse(Ok(_)) => {
                 info!("bet deleted");
+                self.error = None;
             }
             Action::DeleteBetReceiveResponse(Err(err)) => {
                 error!("error delete bet", err.to_string());
+                self.error = Some(error_message(&err));
             }
             Action::SubmitTicketReceiveResponse(Ok(_)) => {
                 info!("ticket submited");
+                self.error = None;
             }
             Action::SubmitTicketReceiveResponse(Err(err)) => {
                 error!("error submit ticket", err.to_string());
+                self.error = Some(error_message(&err));
             }
         }
     }
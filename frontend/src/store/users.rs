@@ -1,8 +1,9 @@
 use crate::{
-    services::{get_token, set_token},
+    config::GRPC_URL,
+    services::{get_session_token, get_token, set_session_token, set_token},
     types::grpc_types::user::{
         user_service_client, Address, AuthUserReply, AuthUserRequest, GetUserReply, GetUserRequest,
-        User,
+        LogoutUserRequest, User,
     },
 };
 use log::{error, warn};
@@ -13,14 +14,16 @@ use yew_agent::{
 
 #[derive(Debug)]
 pub enum UserRequest {
-    Login(Option<User>),
+    Login(Option<User>, String),
     Logout,
     InitializeUser,
+    SetBalance(String),
 }
 
 #[derive(Debug)]
 pub enum Action {
-    SetUser(User),
+    SetUser(User, String),
+    SetBalance(String),
     RemoveUser,
     ReceiveResponse(anyhow::Result<GetUserReply>),
 }
@@ -41,8 +44,8 @@ impl Store for UserStore {
     // incoming requests
     fn handle_input(&self, link: AgentLink<StoreWrapper<Self>>, msg: Self::Input) {
         match msg {
-            UserRequest::Login(user) => match user {
-                Some(value) => link.send_message(Action::SetUser(value)),
+            UserRequest::Login(user, session_token) => match user {
+                Some(value) => link.send_message(Action::SetUser(value, session_token)),
                 None => error!("Couldn't set user"),
             },
             UserRequest::InitializeUser => {
@@ -53,9 +56,8 @@ impl Store for UserStore {
                             _ => 0,
                         };
                         // get identity and create user
-                        let grpc_client = user_service_client::UserService::new(String::from(
-                            "http://127.0.0.1:5430",
-                        ));
+                        let grpc_client =
+                            user_service_client::UserService::new(String::from(GRPC_URL));
                         link.send_future(async move {
                             Action::ReceiveResponse(
                                 grpc_client.get_user(GetUserRequest { id }).await,
@@ -65,16 +67,37 @@ impl Store for UserStore {
                     None => {}
                 }
             }
-            UserRequest::Logout => link.send_message(Action::RemoveUser),
+            UserRequest::Logout => match get_session_token() {
+                Some(session_token) => {
+                    let grpc_client = user_service_client::UserService::new(String::from(GRPC_URL));
+                    link.send_future(async move {
+                        if let Err(err) = grpc_client
+                            .logout_user(LogoutUserRequest { session_token })
+                            .await
+                        {
+                            warn!("{}", err.to_string());
+                        }
+                        Action::RemoveUser
+                    });
+                }
+                None => link.send_message(Action::RemoveUser),
+            },
+            UserRequest::SetBalance(balance) => link.send_message(Action::SetBalance(balance)),
         }
     }
 
     // store's operations
     fn reduce(&mut self, msg: Self::Action) {
         match msg {
-            Action::SetUser(user_data) => {
+            Action::SetUser(user_data, session_token) => {
                 self.user = Some(user_data.clone());
                 set_token(Some(user_data.id.to_string()));
+                set_session_token(Some(session_token));
+            }
+            Action::SetBalance(balance) => {
+                if let Some(user) = self.user.as_mut() {
+                    user.balance = balance;
+                }
             }
             Action::ReceiveResponse(Ok(data)) => match data.user {
                 Some(user_data) => {
@@ -89,6 +112,7 @@ impl Store for UserStore {
             Action::RemoveUser => {
                 self.user = None;
                 set_token(None);
+                set_session_token(None);
             }
         }
     }
@@ -16,3 +16,15 @@ pub mod bet {
 pub mod user {
     include!(concat!(env!("OUT_DIR"), concat!("/user.rs")));
 }
+pub mod subscription {
+    include!(concat!(env!("OUT_DIR"), concat!("/subscription.rs")));
+}
+pub mod tournament {
+    include!(concat!(env!("OUT_DIR"), concat!("/tournament.rs")));
+}
+pub mod envelope {
+    include!(concat!(env!("OUT_DIR"), concat!("/envelope.rs")));
+}
+pub mod error {
+    include!(concat!(env!("OUT_DIR"), concat!("/error.rs")));
+}